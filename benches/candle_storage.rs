@@ -0,0 +1,160 @@
+use chrono::Duration;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rust_bot::{
+    indicators::{indicator_type::IndicatorType, populates_candles::PopulatesCandlesWithSelf},
+    models::{
+        candle::Candle, interval::Interval, ma_type::MAType, shared_candles::SharedCandles,
+        timeseries::TimeSeries, timeseries_builder::TimeSeriesBuilder,
+    },
+};
+
+const N_CANDLES: usize = 20_000;
+const N_LATEST: usize = 500;
+const N_WINDOW: usize = 5;
+
+fn time_series() -> TimeSeries {
+    let mut ts = TimeSeriesBuilder::new()
+        .symbol("BENCH".to_string())
+        .interval(Interval::Day1)
+        .max_length(N_CANDLES)
+        .candles(Candle::dummy_data(N_CANDLES, "positive", 100.0))
+        .build();
+
+    IndicatorType::SMA(20).populate_candles(&mut ts).unwrap();
+    IndicatorType::RSI(14, MAType::RMA)
+        .populate_candles(&mut ts)
+        .unwrap();
+
+    ts
+}
+
+fn next_candle(candles: &[Candle]) -> Candle {
+    let mut candle = candles.last().unwrap().clone();
+    candle.timestamp += Duration::days(1);
+    candle.indicators.clear();
+    candle
+}
+
+/// Adding a candle to a full series, compared to shifting the whole Vec on
+/// every candle as before.
+fn add_candle(c: &mut Criterion) {
+    let mut group = c.benchmark_group("add_candle");
+
+    let mut candles = time_series().candles;
+    group.bench_function("vec_remove_first", |b| {
+        b.iter(|| {
+            let candle = next_candle(&candles);
+            candles.push(candle);
+            candles.remove(0);
+        })
+    });
+
+    let mut ts = time_series();
+    group.bench_function("time_series", |b| {
+        b.iter(|| {
+            let candle = next_candle(&ts.candles);
+            ts.add_candle(&candle).unwrap();
+        })
+    });
+
+    group.finish();
+}
+
+/// Handing the latest candles to a strategy, by cloning them or by sharing
+/// the buffer of the series.
+fn latest_candles(c: &mut Criterion) {
+    let mut group = c.benchmark_group("latest_candles");
+    let ts = time_series();
+
+    group.bench_function("clone_vec", |b| {
+        b.iter(|| black_box(ts.candles[N_CANDLES - N_LATEST..].to_vec()))
+    });
+
+    group.bench_function("shared_snapshot", |b| {
+        b.iter(|| black_box(SharedCandles::new(ts.buffer.clone()).latest(N_LATEST)))
+    });
+
+    group.finish();
+}
+
+/// Reading a single column over the whole series.
+fn read_column(c: &mut Criterion) {
+    let mut group = c.benchmark_group("read_column");
+    let ts = time_series();
+
+    group.bench_function("candles", |b| {
+        b.iter(|| black_box(ts.candles.iter().map(|c| c.close).sum::<f64>()))
+    });
+
+    group.bench_function("candle_view", |b| {
+        b.iter(|| black_box(ts.buffer.view().close().iter().sum::<f64>()))
+    });
+
+    let field = "rsi(14)";
+    group.bench_function("candles_indicator", |b| {
+        b.iter(|| {
+            black_box(
+                ts.candles
+                    .iter()
+                    .filter_map(|c| c.value(field))
+                    .sum::<f64>(),
+            )
+        })
+    });
+
+    let view = ts.buffer.view();
+    group.bench_function("candle_view_indicator", |b| {
+        b.iter(|| black_box(view.value(field).unwrap().flatten().sum::<f64>()))
+    });
+
+    group.finish();
+}
+
+/// Checking every window of the series for a setup, as the StrategyTester
+/// does, on slices of candles compared to views into the buffer.
+fn check_windows(c: &mut Criterion) {
+    let mut group = c.benchmark_group("check_windows");
+    let ts = time_series();
+    let sma = IndicatorType::SMA(20);
+
+    group.bench_function("candles", |b| {
+        b.iter(|| {
+            let n_above = ts
+                .candles
+                .windows(N_WINDOW)
+                .filter(|window| {
+                    let last = &window[N_WINDOW - 1];
+                    let sma = last.indicators.get(&sma).and_then(|i| i.field("value"));
+                    sma.is_some_and(|sma| window.iter().all(|c| c.close > sma))
+                })
+                .count();
+            black_box(n_above)
+        })
+    });
+
+    let view = ts.buffer.view();
+    group.bench_function("candle_view", |b| {
+        b.iter(|| {
+            let n_above = (N_WINDOW..=view.len())
+                .map(|end| view.window(end - N_WINDOW..end))
+                .filter(|window| {
+                    let smas = window.indicator(&sma);
+                    let sma = smas.and_then(|smas| smas.last()?.as_ref()?.field("value"));
+                    sma.is_some_and(|sma| window.close().iter().all(|close| *close > sma))
+                })
+                .count();
+            black_box(n_above)
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    add_candle,
+    latest_candles,
+    read_column,
+    check_windows
+);
+criterion_main!(benches);
@@ -0,0 +1,60 @@
+use crate::control_api::control_state::ControlState;
+use actix_web::{
+    dev::Payload, error::ErrorUnauthorized, http::header::AUTHORIZATION, web::Data, Error,
+    FromRequest, HttpRequest,
+};
+use std::future::{ready, Ready};
+
+/// Extractor guarding control API routes. Requests must provide the local
+/// token through an `Authorization: Bearer <token>` header.
+pub struct Authorized;
+
+impl FromRequest for Authorized {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let token = match req.app_data::<Data<ControlState>>() {
+            Some(state) => state.token.clone(),
+            None => return ready(Err(ErrorUnauthorized("Unauthorized"))),
+        };
+
+        let provided = req
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("Bearer "));
+
+        match provided {
+            Some(provided) if token_matches(provided, &token) => ready(Ok(Authorized)),
+            _ => ready(Err(ErrorUnauthorized("Unauthorized"))),
+        }
+    }
+}
+
+/// Compares tokens in constant time to avoid leaking the token through
+/// response timings.
+fn token_matches(provided: &str, token: &str) -> bool {
+    if provided.len() != token.len() || token.is_empty() {
+        return false;
+    }
+
+    provided
+        .bytes()
+        .zip(token.bytes())
+        .fold(0, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::control_api::auth::token_matches;
+
+    #[test]
+    fn compare_tokens() {
+        assert!(token_matches("secret", "secret"));
+        assert!(!token_matches("secreT", "secret"));
+        assert!(!token_matches("secret-longer", "secret"));
+        assert!(!token_matches("", ""));
+    }
+}
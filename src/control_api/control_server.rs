@@ -0,0 +1,272 @@
+use crate::{
+    control_api::{
+        auth::Authorized,
+        control_state::{ControlState, RunningStrategy},
+    },
+    data_sources::datasource::DataSource,
+    models::{
+        interval::Interval,
+        message_payloads::{
+            force_close_payload::ForceClosePayload,
+            pause_payload::PausePayload,
+            request_setup_finder_state_payload::RequestSetupFinderStatePayload,
+            request_timeseries_state_payload::{RequestTimeSeriesStatePayload, TimeSeriesState},
+            request_trade_state_payload::{RequestTradeStatePayload, TradeState},
+        },
+        trade::Trade,
+    },
+};
+use actix::{spawn, Addr};
+use actix_web::{
+    get, post,
+    web::{Data, Path, ServiceConfig},
+    App, HttpResponse, HttpServer, Responder,
+};
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use serde_json::json;
+use std::env;
+use tracing::{error, info, warn};
+
+const DEFAULT_CONTROL_API_ADDR: &str = "127.0.0.1:9185";
+
+#[derive(Debug, Serialize)]
+struct StrategyStatus {
+    id: usize,
+    strategy: String,
+    interval: Interval,
+    paused: bool,
+    live_trading_enabled: bool,
+    trade_ids: Vec<usize>,
+    timeseries: TimeSeriesState,
+}
+
+#[derive(Debug, Serialize)]
+struct TradeStatus {
+    strategy_id: usize,
+    #[serde(flatten)]
+    trade: TradeState,
+}
+
+#[get("/strategies")]
+async fn list_strategies(_auth: Authorized, state: Data<ControlState>) -> impl Responder {
+    let mut statuses = vec![];
+
+    for (id, running) in state.strategies.iter().enumerate() {
+        let status = async {
+            let sf_state = running
+                .setup_finder
+                .send(RequestSetupFinderStatePayload)
+                .await?;
+            let timeseries = running
+                .timeseries
+                .send(RequestTimeSeriesStatePayload)
+                .await?;
+
+            let mut trade_ids = vec![];
+            for trade in &sf_state.trades {
+                trade_ids.push(trade.send(RequestTradeStatePayload).await?.id);
+            }
+
+            Ok::<StrategyStatus, anyhow::Error>(StrategyStatus {
+                id,
+                strategy: sf_state.strategy,
+                interval: sf_state.interval,
+                paused: sf_state.paused,
+                live_trading_enabled: sf_state.live_trading_enabled,
+                trade_ids,
+                timeseries,
+            })
+        };
+
+        match status.await {
+            Ok(status) => statuses.push(status),
+            Err(e) => return internal_error(e),
+        }
+    }
+
+    HttpResponse::Ok().json(statuses)
+}
+
+#[post("/strategies/{id}/pause")]
+async fn pause_strategy(
+    _auth: Authorized,
+    state: Data<ControlState>,
+    id: Path<usize>,
+) -> impl Responder {
+    set_paused(&state, id.into_inner(), true)
+}
+
+#[post("/strategies/{id}/resume")]
+async fn resume_strategy(
+    _auth: Authorized,
+    state: Data<ControlState>,
+    id: Path<usize>,
+) -> impl Responder {
+    set_paused(&state, id.into_inner(), false)
+}
+
+#[get("/trades")]
+async fn list_trades(_auth: Authorized, state: Data<ControlState>) -> impl Responder {
+    match open_trades(&state).await {
+        Ok(trades) => {
+            let trades: Vec<TradeStatus> = trades
+                .into_iter()
+                .map(|(strategy_id, _, trade)| TradeStatus { strategy_id, trade })
+                .collect();
+
+            HttpResponse::Ok().json(trades)
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+#[post("/trades/{id}/close")]
+async fn close_trade(
+    _auth: Authorized,
+    state: Data<ControlState>,
+    id: Path<usize>,
+) -> impl Responder {
+    let id = id.into_inner();
+
+    let trades = match open_trades(&state).await {
+        Ok(trades) => trades,
+        Err(e) => return internal_error(e),
+    };
+
+    let trade = match trades.into_iter().find(|(_, _, t)| t.id == id) {
+        Some((_, trade, _)) => trade,
+        None => return not_found(format!("No open trade with id {}", id)),
+    };
+
+    warn!(trade_id = id, "Force-closing trade through control API");
+
+    match trade.send(ForceClosePayload).await {
+        Ok(Ok(_)) => HttpResponse::Ok().json(json!({ "closed": id })),
+        Ok(Err(e)) => internal_error(e),
+        Err(e) => internal_error(anyhow!(e)),
+    }
+}
+
+#[post("/wallet/sell-all")]
+async fn sell_all(_auth: Authorized, state: Data<ControlState>) -> impl Responder {
+    warn!("Selling all positions through control API");
+
+    match state.source.exit_all_positions().await {
+        Ok(_) => HttpResponse::Ok().json(json!({ "sold": true })),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// Starts the control API. The address is read from `CONTROL_API_ADDR`
+/// (defaults to 127.0.0.1:9185) and requests are authenticated with the
+/// token in `CONTROL_API_TOKEN`. The API is not started without a token.
+pub fn start_control_server(source: DataSource, strategies: Vec<RunningStrategy>) -> Result<()> {
+    let token = env::var("CONTROL_API_TOKEN")
+        .map_err(|_| anyhow!("CONTROL_API_TOKEN must be set to start the control API."))?;
+    let addr = env::var("CONTROL_API_ADDR").unwrap_or(DEFAULT_CONTROL_API_ADDR.to_string());
+
+    let state = Data::new(ControlState {
+        token,
+        source,
+        strategies,
+    });
+
+    let server = HttpServer::new(move || App::new().app_data(state.clone()).configure(routes))
+        .workers(1)
+        .bind(&addr)?
+        .run();
+
+    info!(%addr, "Serving control API");
+
+    spawn(async move {
+        if let Err(e) = server.await {
+            error!(error = ?e, "Control API error");
+        }
+    });
+
+    Ok(())
+}
+
+fn routes(cfg: &mut ServiceConfig) {
+    cfg.service(list_strategies)
+        .service(pause_strategy)
+        .service(resume_strategy)
+        .service(list_trades)
+        .service(close_trade)
+        .service(sell_all);
+}
+
+fn set_paused(state: &ControlState, id: usize, paused: bool) -> HttpResponse {
+    match state.strategies.get(id) {
+        Some(running) => {
+            running.setup_finder.do_send(PausePayload { paused });
+            HttpResponse::Ok().json(json!({ "id": id, "paused": paused }))
+        }
+        None => not_found(format!("No strategy with id {}", id)),
+    }
+}
+
+async fn open_trades(state: &ControlState) -> Result<Vec<(usize, Addr<Trade>, TradeState)>> {
+    let mut trades = vec![];
+
+    for (strategy_id, running) in state.strategies.iter().enumerate() {
+        let sf_state = running
+            .setup_finder
+            .send(RequestSetupFinderStatePayload)
+            .await?;
+
+        for trade in sf_state.trades {
+            let trade_state = trade.send(RequestTradeStatePayload).await?;
+            trades.push((strategy_id, trade, trade_state));
+        }
+    }
+
+    Ok(trades)
+}
+
+fn not_found(message: String) -> HttpResponse {
+    HttpResponse::NotFound().json(json!({ "error": message }))
+}
+
+fn internal_error(e: anyhow::Error) -> HttpResponse {
+    error!(error = ?e, "Control API request failed");
+    HttpResponse::InternalServerError().json(json!({ "error": e.to_string() }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        control_api::{control_server::routes, control_state::ControlState},
+        data_sources::datasource::DataSource,
+    };
+    use actix_web::{http::StatusCode, test, web::Data, App};
+
+    #[actix_web::test]
+    async fn requests_require_token() {
+        let state = Data::new(ControlState {
+            token: "secret".to_string(),
+            source: DataSource::Dummy(1),
+            strategies: vec![],
+        });
+        let app = test::init_service(App::new().app_data(state).configure(routes)).await;
+
+        let req = test::TestRequest::get().uri("/strategies").to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::get()
+            .uri("/strategies")
+            .insert_header(("Authorization", "Bearer secret"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let req = test::TestRequest::post()
+            .uri("/strategies/3/pause")
+            .insert_header(("Authorization", "Bearer secret"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+}
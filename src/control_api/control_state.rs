@@ -0,0 +1,20 @@
+use crate::{
+    data_sources::datasource::DataSource,
+    models::{setups::setup_finder::SetupFinder, timeseries::TimeSeries},
+};
+use actix::Addr;
+
+/// A SetupFinder together with the TimeSeries it observes.
+#[derive(Debug, Clone)]
+pub struct RunningStrategy {
+    pub setup_finder: Addr<SetupFinder>,
+    pub timeseries: Addr<TimeSeries>,
+}
+
+/// Shared state of the control API. Strategies are identified by their index.
+#[derive(Debug, Clone)]
+pub struct ControlState {
+    pub token: String,
+    pub source: DataSource,
+    pub strategies: Vec<RunningStrategy>,
+}
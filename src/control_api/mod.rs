@@ -0,0 +1,3 @@
+pub mod auth;
+pub mod control_server;
+pub mod control_state;
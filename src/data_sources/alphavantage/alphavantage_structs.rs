@@ -95,6 +95,7 @@ impl ApiResponse for AlphaVantageApiResponse {
                     low: ts.low.parse::<f64>()?,
                     volume: ts.volume.parse::<f64>()?,
                     indicators: HashMap::new(),
+                    timeframe_indicators: HashMap::new(),
                 })
             })
             .collect();
@@ -33,11 +33,14 @@ fn generate_url(symbol: &str, interval: &Interval) -> Result<String> {
         Interval::Minute15 => "15m",
         Interval::Minute30 => "30m",
         Interval::Hour1 => "1h",
+        Interval::Minutes(180) => "3h",
         Interval::Hour4 => "4h",
+        Interval::Minutes(360) => "6h",
+        Interval::Hour12 => "12h",
         Interval::Day1 => "1D",
         Interval::Week1 => "7D",
+        Interval::Month1 => "1M",
         _ => return Err(anyhow!("Bitfinex does not support this interval.")),
-        // Interval::Month1 => "1M",
     };
 
     Ok(format!(
@@ -64,6 +67,7 @@ fn generate_timeseries(
                 low: entry[4],
                 volume: entry[5],
                 indicators: HashMap::new(),
+                timeframe_indicators: HashMap::new(),
             })
         })
         .collect::<Result<Vec<Candle>>>()?;
@@ -4,6 +4,7 @@ use actix::Addr;
 use anyhow::Result;
 use futures_util::{SinkExt, StreamExt};
 use tokio_tungstenite::connect_async;
+use tracing::debug;
 use tungstenite::Message;
 
 use crate::models::{
@@ -49,7 +50,7 @@ pub async fn connect_ws(client: &Addr<WebsocketClient>, interval: &Interval) ->
         };
     }
 
-    println!("Done");
+    debug!("Bitfinex websocket example done");
 
     ws_stream.close(None).await?;
     Ok(())
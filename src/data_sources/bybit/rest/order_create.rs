@@ -13,6 +13,7 @@ use crate::{
 use anyhow::{anyhow, Result};
 use reqwest::Client;
 use serde_json::{json, to_string, Map, Value};
+use tracing::debug;
 
 const ORDER_MAX_DECIMALS: i64 = 6;
 
@@ -26,7 +27,7 @@ pub async fn market_buy(symbol: &str, quantity: f64) -> Result<()> {
     params.insert("orderType".to_string(), json!("Market"));
     params.insert("marketUnit".to_string(), json!("quoteCoin"));
     params.insert("qty".to_string(), json!(rounded_quantity.to_string()));
-    println!("buy params: {:#?}", params);
+    debug!(?params, "Placing market buy");
 
     Ok(post_market_order(params, &NetVersion::Mainnet).await?)
 }
@@ -77,7 +78,7 @@ pub async fn market_sell(symbol: &str, quantity: f64) -> Result<()> {
     params.insert("orderType".to_string(), json!("Market"));
     params.insert("qty".to_string(), json!(qty.to_string()));
     params.insert("marketUnit".to_string(), json!("baseCoin"));
-    println!("sell params: {:#?}", params);
+    debug!(?params, "Placing market sell");
 
     post_market_order(params, &NetVersion::Mainnet).await?;
 
@@ -1,36 +1,44 @@
 use crate::models::interval::Interval;
 use anyhow::{anyhow, Result};
 
+/// Kline intervals provided by Bybit.
+const INTERVALS: &[(&str, Interval)] = &[
+    ("1", Interval::Minute1),
+    ("3", Interval::Minutes(3)),
+    ("5", Interval::Minute5),
+    ("15", Interval::Minute15),
+    ("30", Interval::Minute30),
+    ("60", Interval::Hour1),
+    ("120", Interval::Minutes(120)),
+    ("240", Interval::Hour4),
+    ("360", Interval::Minutes(360)),
+    ("720", Interval::Hour12),
+    ("D", Interval::Day1),
+    ("W", Interval::Week1),
+    ("M", Interval::Month1),
+];
+
+pub fn native_intervals() -> Vec<Interval> {
+    INTERVALS
+        .iter()
+        .map(|(_, interval)| interval.clone())
+        .collect()
+}
+
 #[allow(dead_code)]
 pub fn interval_to_str(interval: &Interval) -> Result<String> {
-    let interval = match interval {
-        Interval::Minute1 => "1",
-        Interval::Minute5 => "5",
-        Interval::Minute15 => "15",
-        Interval::Minute30 => "30",
-        Interval::Hour1 => "60",
-        Interval::Hour4 => "240",
-        Interval::Day1 => "D",
-        Interval::Week1 => "W",
-        // Interval::Month1 => "1M",
-        _ => return Err(anyhow!("Bybit does not support this interval.")),
-    };
-
-    Ok(interval.to_string())
+    INTERVALS
+        .iter()
+        .find(|(_, native)| native == interval)
+        .map(|(s, _)| s.to_string())
+        .ok_or(anyhow!("Bybit does not support this interval."))
 }
 
 #[allow(dead_code)]
 pub fn str_to_interval(interval_str: &str) -> Result<Interval> {
-    match interval_str {
-        "1" => Ok(Interval::Minute1),
-        "5" => Ok(Interval::Minute5),
-        "15" => Ok(Interval::Minute15),
-        "30" => Ok(Interval::Minute30),
-        "60" => Ok(Interval::Hour1),
-        "240" => Ok(Interval::Hour4),
-        "D" => Ok(Interval::Day1),
-        "W" => Ok(Interval::Week1),
-        // "1M" => Ok(Interval::Month1),
-        _ => Err(anyhow!("Unsupported interval string.")),
-    }
+    INTERVALS
+        .iter()
+        .find(|(s, _)| *s == interval_str)
+        .map(|(_, interval)| interval.clone())
+        .ok_or(anyhow!("Unsupported interval string."))
 }
@@ -1,7 +1,10 @@
 use crate::{
-    data_sources::bybit::ws::{
-        incoming_message::{IncomingMessage, KlineResponse},
-        outgoing_message::{OutgoingMessage, OutgoingMessageArg},
+    data_sources::bybit::{
+        util::interval_to_str,
+        ws::{
+            incoming_message::{IncomingMessage, KlineResponse},
+            outgoing_message::{OutgoingMessage, OutgoingMessageArg},
+        },
     },
     models::{
         interval::Interval, message_payloads::websocket_payload::WebsocketPayload,
@@ -9,7 +12,7 @@ use crate::{
     },
 };
 use actix::{spawn, Addr};
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use futures_util::{SinkExt, StreamExt};
 use tokio::{
     net::TcpStream,
@@ -20,6 +23,7 @@ use tokio::{
     try_join,
 };
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use tracing::{debug, error};
 use tungstenite::{Error, Message};
 
 pub struct BybitWebsocketApi {
@@ -61,7 +65,7 @@ impl BybitWebsocketApi {
     ) -> Result<()> {
         let args = vec![OutgoingMessageArg {
             stream: "kline".to_string(),
-            interval: self.interval_as_string()?,
+            interval: interval_to_str(&self.interval)?,
             symbol: "BTCUSDT".to_string(),
         }];
         let sub = OutgoingMessage::new("subscribe", args);
@@ -72,21 +76,6 @@ impl BybitWebsocketApi {
         Ok(())
     }
 
-    fn interval_as_string(&self) -> Result<String> {
-        let interval = match self.interval {
-            Interval::Minute1 => "1",
-            Interval::Minute5 => "5",
-            Interval::Minute15 => "15",
-            Interval::Minute30 => "30",
-            Interval::Hour1 => "60",
-            Interval::Day1 => "D",
-            Interval::Week1 => "W",
-            _ => return Err(anyhow!("Bybit does not support this interval.")),
-        };
-
-        Ok(interval.to_string())
-    }
-
     async fn send_ping(
         req_id: Option<String>,
         ws_stream: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
@@ -130,8 +119,7 @@ impl BybitWebsocketApi {
     ) -> Result<(), ()> {
         if let Some(_) = rx.recv().await {
             Self::send_ping(None, ws_stream).await.map_err(|e| {
-                eprintln!("Error in Websockets: {:#?}", e);
-                ()
+                error!(error = ?e, "Unable to send websocket ping");
             })
         } else {
             Ok(())
@@ -144,8 +132,7 @@ impl BybitWebsocketApi {
     ) -> Result<(), ()> {
         if let Some(msg) = ws_msg {
             Self::handle_message(client, msg).await.map_err(|e| {
-                eprintln!("Error in Websockets: {:#?}", e);
-                ()
+                error!(error = ?e, "Unable to handle websocket message");
             })
         } else {
             Ok(())
@@ -164,7 +151,7 @@ impl BybitWebsocketApi {
 
             match parsed {
                 IncomingMessage::Pong(_) => {}
-                IncomingMessage::Subscribe(sub) => println!("Subscribe: {:#?}", sub),
+                IncomingMessage::Subscribe(sub) => debug!(?sub, "Subscribed to kline stream"),
                 IncomingMessage::Kline(kline_response) => {
                     Self::handle_kline(kline_response, client).await?
                 }
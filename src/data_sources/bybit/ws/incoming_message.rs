@@ -109,6 +109,7 @@ impl Kline {
             low: self.low.parse::<f64>()?,
             volume: self.volume.parse::<f64>()?,
             indicators: HashMap::new(),
+            timeframe_indicators: HashMap::new(),
         })
     }
 }
@@ -44,6 +44,7 @@ impl ApiResponse for CryptoCompareApiResponse {
                     low: entry.low,
                     volume: entry.volume_from,
                     indicators: HashMap::new(),
+                    timeframe_indicators: HashMap::new(),
                 })
             })
             .collect();
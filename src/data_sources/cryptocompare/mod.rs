@@ -4,67 +4,92 @@ pub mod websockets;
 use self::cryptocompare_structs::CryptoCompareApiResponse;
 use crate::{
     data_sources::api_response::ApiResponse,
-    models::{interval::Interval, timeseries::TimeSeries},
+    models::{
+        candle::Candle, interval::Interval, timeseries::TimeSeries,
+        timeseries_builder::TimeSeriesBuilder,
+    },
 };
 use anyhow::{anyhow, Result};
 use reqwest::Client;
 use std::env;
 
+/// Most candles returned by a single request.
+const MAX_LIMIT: usize = 2000;
+
+/// Returns the latest `len` candles. Requests are limited to `MAX_LIMIT`
+/// candles, so longer histories are fetched in pages going back in time.
 pub async fn get(
     symbol: &str,
     interval: &Interval,
+    len: usize,
     exchange: Option<String>,
 ) -> Result<TimeSeries> {
     let api_key = env::var("CRYPTOCOMPARE_KEY")?;
-    let url = construct_url(symbol, interval, 2000, exchange);
-
     let client = Client::new();
-    let response = client
-        .get(url)
-        .header("Authorization", format!("Apikey {}", api_key))
-        .send()
-        .await?;
+    let mut candles: Vec<Candle> = vec![];
+
+    while candles.len() < len {
+        // Pages end at the first candle already fetched, which is dropped
+        // from the page like the current candle is from the first one.
+        let to_ts = candles.first().map(|c| c.timestamp.timestamp());
+        let limit = (len - candles.len()).min(MAX_LIMIT);
+        let url = construct_url(symbol, interval, limit, to_ts, exchange.clone())?;
+
+        let response = client
+            .get(url)
+            .header("Authorization", format!("Apikey {}", api_key))
+            .send()
+            .await?;
+
+        let mut response: CryptoCompareApiResponse = match response.status() {
+            reqwest::StatusCode::OK => response.json().await?,
+            _ => return Err(anyhow!("CryptoCompare request failed.")),
+        };
 
-    match response.status() {
-        reqwest::StatusCode::OK => {
-            let mut response: CryptoCompareApiResponse = response.json().await?;
-            let ts = response.to_timeseries(symbol, interval);
-            ts.map(|ts| ts)
+        let mut page = response.to_timeseries(symbol, interval)?.candles;
+        if page.is_empty() {
+            break;
         }
-        _ => Err(anyhow!("CryptoCompare request failed.")),
+
+        page.append(&mut candles);
+        candles = page;
     }
+
+    Ok(TimeSeriesBuilder::new()
+        .symbol(symbol.to_string())
+        .interval(interval.clone())
+        .candles(candles)
+        .build())
 }
 
+/// Only minute, hour and day candles are requested. Other intervals are
+/// resampled from these rather than using the `aggregate` parameter of the
+/// api, so that their candles are aligned the same way for every source.
 fn construct_url(
     symbol: &str,
     interval: &Interval,
-    limit: u32,
+    limit: usize,
+    to_ts: Option<i64>,
     exchange: Option<String>,
-) -> String {
+) -> Result<String> {
     let market = "USD";
-    let minute = "histominute";
-    let hour = "histohour";
-    let day = "histoday";
-
     let exchange = exchange.unwrap_or("CCCAGG".to_string());
 
-    let (interval, aggregate) = match interval {
-        Interval::Minute1 => (minute, 1),
-        Interval::Minute5 => (minute, 5),
-        Interval::Minute15 => (minute, 15),
-        Interval::Minute30 => (minute, 30),
-        Interval::Hour1 => (hour, 1),
-        Interval::Hour4 => (hour, 4),
-        Interval::Hour12 => (hour, 12),
-        Interval::Day1 => (day, 1),
-        Interval::Day5 => (day, 5),
-        Interval::Week1 => (day, 5),
+    let interval = match interval {
+        Interval::Minute1 => "histominute",
+        Interval::Hour1 => "histohour",
+        Interval::Day1 => "histoday",
+        _ => return Err(anyhow!("CryptoCompare does not support this interval.")),
     };
 
-    // TODO: Enable multiples using the aggregate parameter in the api
+    let mut url = format!(
+        "https://min-api.cryptocompare.com/data/v2/{}?fsym={}&tsym={}&limit={}&e={}",
+        interval, symbol, market, limit, exchange
+    );
+
+    if let Some(to_ts) = to_ts {
+        url.push_str(&format!("&toTs={}", to_ts));
+    }
 
-    format!(
-        "https://min-api.cryptocompare.com/data/v2/{}?fsym={}&tsym={}&limit={}&aggregate={}&e={}",
-        interval, symbol, market, limit, aggregate, exchange
-    )
+    Ok(url)
 }
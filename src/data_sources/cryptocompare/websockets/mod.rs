@@ -13,6 +13,7 @@ use serde_json::Value;
 use std::env;
 use subscription::Subscription;
 use tokio_tungstenite::connect_async;
+use tracing::{debug, warn};
 use tungstenite::Message;
 
 #[allow(dead_code)]
@@ -39,13 +40,12 @@ pub async fn dummy_example() -> Result<()> {
                 serde_json::from_str(txt.as_str());
 
             if parsed.is_err() {
-                println!("Value:{:#?}", v);
-                println!("Parsed: {:#?}", parsed);
+                warn!(value = ?v, ?parsed, "Unable to parse CryptoCompare message");
                 let unsub = OutgoingMessage::new(Action::SubRemove, vec![sub.clone()]);
                 let message = Message::Text(unsub.to_string());
                 ws_stream.send(message).await?;
             } else {
-                println!("Handled correctly for iteration: {}", i);
+                debug!(iteration = i, "Handled CryptoCompare message");
             }
         }
 
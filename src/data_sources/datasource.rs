@@ -1,16 +1,20 @@
 use crate::{
     data_sources::{
         alphavantage, bitfinex,
-        bybit::{rest::bybit_rest_api::BybitRestApi, ws::bybit_ws_api::BybitWebsocketApi},
+        bybit::{
+            rest::bybit_rest_api::BybitRestApi, util as bybit_util,
+            ws::bybit_ws_api::BybitWebsocketApi,
+        },
         coinmarketcap, cryptocompare, local,
     },
+    metrics::bot_metrics::BotMetrics,
     models::{
         candle::Candle, interval::Interval, message_payloads::websocket_payload::WebsocketPayload,
-        net_version::NetVersion, timeseries::TimeSeries, wallet::Wallet,
+        net_version::NetVersion, resampler::Resampler, timeseries::TimeSeries, wallet::Wallet,
         websockets::wsclient::WebsocketClient,
     },
 };
-use actix::{spawn, Addr};
+use actix::Addr;
 use anyhow::{anyhow, Result};
 use std::fmt::{Display, Formatter};
 use tokio::time::{sleep, Duration};
@@ -54,17 +58,38 @@ impl DataSource {
         }
     }
 
-    pub async fn get_wallet(&self) -> Result<Wallet> {
+    pub async fn exit_all_positions(&self) -> Result<()> {
         match self {
-            DataSource::Dummy(_) => BybitRestApi::get_wallet_balance().await,
-            DataSource::Bybit => BybitRestApi::get_wallet_balance().await,
+            DataSource::Bybit | DataSource::Dummy(_) => {
+                let wallet = self.get_wallet().await?;
+                BybitRestApi::market_sell_all(&wallet).await
+            }
             _ => Err(anyhow!(format!(
-                "{} does not support fetching wallet balance yet",
+                "{} does not support exiting positions yet",
                 self
             ))),
         }
     }
 
+    pub async fn get_wallet(&self) -> Result<Wallet> {
+        let wallet = match self {
+            DataSource::Dummy(_) => BybitRestApi::get_wallet_balance().await?,
+            DataSource::Bybit => BybitRestApi::get_wallet_balance().await?,
+            _ => {
+                return Err(anyhow!(format!(
+                    "{} does not support fetching wallet balance yet",
+                    self
+                )))
+            }
+        };
+
+        BotMetrics::get()
+            .wallet_balance
+            .set(wallet.total_available_balance);
+
+        Ok(wallet)
+    }
+
     pub async fn get_symbol_price(&self, symbol: &str) -> Result<f64> {
         match self {
             DataSource::Dummy(_) => BybitRestApi::get_symbol_price(symbol).await,
@@ -76,12 +101,84 @@ impl DataSource {
         }
     }
 
+    /// Returns the intervals candles can be requested in from the source, or
+    /// None if the source isn't limited to specific intervals.
+    pub fn native_intervals(&self) -> Option<Vec<Interval>> {
+        let intervals = match self {
+            DataSource::AlphaVantage => vec![Interval::Day1],
+            DataSource::Bitfinex => vec![
+                Interval::Minute5,
+                Interval::Minute15,
+                Interval::Minute30,
+                Interval::Hour1,
+                Interval::Minutes(180),
+                Interval::Hour4,
+                Interval::Minutes(360),
+                Interval::Hour12,
+                Interval::Day1,
+                Interval::Week1,
+                Interval::Month1,
+            ],
+            DataSource::Bybit => bybit_util::native_intervals(),
+            DataSource::CryptoCompare(_) => {
+                vec![Interval::Minute1, Interval::Hour1, Interval::Day1]
+            }
+            DataSource::CoinMarketCap | DataSource::Dummy(_) => return None,
+        };
+
+        Some(intervals)
+    }
+
+    /// Returns the interval to request candles in from the source to get
+    /// candles of the given interval. This is the interval itself if it is
+    /// native to the source, otherwise the largest native interval it can be
+    /// resampled from.
+    pub fn base_interval(&self, interval: &Interval) -> Result<Interval> {
+        let Some(native) = self.native_intervals() else {
+            return Ok(interval.clone());
+        };
+
+        native
+            .into_iter()
+            .filter(|base| Resampler::supports(base, interval))
+            .max_by_key(|base| base.to_millis())
+            .ok_or(anyhow!(
+                "{} does not support the {} interval",
+                self,
+                interval
+            ))
+    }
+
+    /// Returns a TimeSeries of the latest `len` candles, resampled from a
+    /// finer interval if the source doesn't provide the interval.
     pub async fn get_historical_data(
         &self,
         symbol: &str,
         interval: &Interval,
         len: usize,
         net: &NetVersion,
+    ) -> Result<TimeSeries> {
+        let base = self.base_interval(interval)?;
+
+        if base == *interval {
+            return self.get_native_data(symbol, interval, len, net).await;
+        }
+
+        // One extra candle as the first one may only be partially covered
+        let ratio = (interval.to_millis() / base.to_millis()) as usize;
+        let ts = self
+            .get_native_data(symbol, &base, (len + 1) * ratio, net)
+            .await?;
+
+        Resampler::resample_timeseries(&ts, interval)
+    }
+
+    async fn get_native_data(
+        &self,
+        symbol: &str,
+        interval: &Interval,
+        len: usize,
+        net: &NetVersion,
     ) -> Result<TimeSeries> {
         let ts = match self {
             DataSource::AlphaVantage => alphavantage::get(symbol, &interval).await?,
@@ -89,7 +186,7 @@ impl DataSource {
             DataSource::Bybit => BybitRestApi::get_kline(symbol, &interval, len, net).await?,
             DataSource::CoinMarketCap => coinmarketcap::get().await?,
             DataSource::CryptoCompare(exchange) => {
-                cryptocompare::get(symbol, &interval, exchange.clone()).await?
+                cryptocompare::get(symbol, &interval, len, exchange.clone()).await?
             }
             DataSource::Dummy(_duration) => {
                 let mut ts = TimeSeries::dummy();
@@ -114,7 +211,7 @@ impl DataSource {
                 let mut api = BybitWebsocketApi::new(&client, interval);
                 api.connect(net).await?
             }
-            DataSource::Dummy(d) => spawn_dummy_generator(client, d),
+            DataSource::Dummy(d) => run_dummy_generator(client, *d).await,
             _ => {
                 let err = format!("{} does not support websockets", self);
                 return Err(anyhow!(err));
@@ -125,25 +222,21 @@ impl DataSource {
     }
 }
 
-fn spawn_dummy_generator(client: Addr<WebsocketClient>, d: &u64) {
-    let duration = *d;
-
-    let fut = async move {
-        let mut prev = Candle::dummy_from_val(1000.0);
-        loop {
-            sleep(Duration::from_millis(duration)).await;
-            let candle = Candle::dyn_dummy_from_prev(&prev, Interval::Day1);
-            let payload = WebsocketPayload {
-                ok: true,
-                message: None,
-                candle: Some(candle.clone()),
-            };
-            client.do_send(payload);
-            prev = candle;
-        }
-    };
-
-    spawn(fut);
+/// Sends a dummy candle every `duration` milliseconds, like a connection
+/// that never closes.
+async fn run_dummy_generator(client: Addr<WebsocketClient>, duration: u64) {
+    let mut prev = Candle::dummy_from_val(1000.0);
+    loop {
+        sleep(Duration::from_millis(duration)).await;
+        let candle = Candle::dyn_dummy_from_prev(&prev, Interval::Day1);
+        let payload = WebsocketPayload {
+            ok: true,
+            message: None,
+            candle: Some(candle.clone()),
+        };
+        client.do_send(payload);
+        prev = candle;
+    }
 }
 
 impl Display for DataSource {
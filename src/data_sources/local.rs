@@ -76,18 +76,7 @@ fn exchange_path(interval: &Interval, symbol: &str, source: &DataSource) -> Stri
         DataSource::Dummy(_) => "dummy",
     };
 
-    let interval = match interval {
-        Interval::Minute1 => "minute-1",
-        Interval::Minute5 => "minute-5",
-        Interval::Minute15 => "minute-15",
-        Interval::Minute30 => "minute-30",
-        Interval::Hour1 => "hour-1",
-        Interval::Hour4 => "hour-4",
-        Interval::Hour12 => "hour-12",
-        Interval::Day1 => "day-1",
-        Interval::Day5 => "day-5",
-        Interval::Week1 => "week-1",
-    };
+    let (unit, count) = interval.unit_count();
 
-    format!("data/{}/{}/{}", source, symbol, interval)
+    format!("data/{}/{}/{}-{}", source, symbol, unit.name(), count)
 }
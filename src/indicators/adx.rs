@@ -0,0 +1,320 @@
+use crate::{
+    indicators::{
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+        rolling::rolling_ema::RollingEMA,
+    },
+    models::{candle::Candle, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Context, Result};
+
+/// # ADX
+///
+/// Average Directional Index along with the positive and negative
+/// directional indicators (DMI). The +DI and -DI measure upward and downward
+/// movement over the last `di_len` candles relative to the true range, and
+/// the ADX smooths how far apart they are over `adx_len` candles to measure
+/// trend strength regardless of direction.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct ADX {
+    pub adx: Option<f64>,
+    pub plus_di: f64,
+    pub minus_di: f64,
+}
+
+impl PopulatesCandles for ADX {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (di_len, adx_len) = (args.usize(0)?, args.usize(1)?);
+        let indicator_type = IndicatorType::ADX(di_len, adx_len);
+        let mut state = ADXState::new(di_len, adx_len);
+
+        for candle in ts.candles.iter_mut() {
+            let adx = Self::calculate_rolling(&mut state, candle);
+
+            candle
+                .indicators
+                .insert(indicator_type, Indicator::ADX(adx));
+        }
+
+        ts.set_indicator_state(indicator_type, IndicatorState::ADX(state));
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (di_len, adx_len) = (args.usize(0)?, args.usize(1)?);
+        let ctx_err = "Unable to get last candle";
+        let indicator_type = IndicatorType::ADX(di_len, adx_len);
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::ADX(state)) => state,
+            _ => return Self::populate_candles_args(ts, args),
+        };
+
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let adx = Self::calculate_rolling(&mut state, candle);
+
+        candle
+            .indicators
+            .insert(indicator_type, Indicator::ADX(adx));
+
+        ts.set_indicator_state(indicator_type, IndicatorState::ADX(state));
+
+        Ok(())
+    }
+}
+
+impl DefinesIndicator for ADX {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "adx",
+        params: &[
+            ParamSchema {
+                name: "len",
+                default: ParamValue::Int(14),
+            },
+            ParamSchema {
+                name: "smoothing",
+                default: ParamValue::Int(14),
+            },
+        ],
+        outputs: &["adx", "plus_di", "minus_di"],
+        warmup: |args| Ok(args.usize(0)? + args.usize(1)?),
+        lag: |_| Ok(0),
+        build: |args| Ok(IndicatorType::ADX(args.usize(0)?, args.usize(1)?)),
+        args: |indicator_type| match indicator_type {
+            IndicatorType::ADX(a, b) => Some(IndicatorArgs(vec![
+                ParamValue::Int(*a),
+                ParamValue::Int(*b),
+            ])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
+impl IsIndicator for ADX {
+    fn default_args() -> IndicatorArgs {
+        IndicatorType::ADX(14, 14).args()
+    }
+
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::calculate_args(segment, &Self::default_args())
+    }
+
+    /// Calculates the ADX of the last candle with the averages seeded at the
+    /// start of the segment.
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let (di_len, adx_len) = (args.usize(0).ok()?, args.usize(1).ok()?);
+        let mut state = ADXState::new(di_len, adx_len);
+
+        segment
+            .iter()
+            .map(|candle| Self::calculate_rolling(&mut state, candle))
+            .last()?
+    }
+}
+
+impl ADX {
+    fn calculate_rolling(state: &mut ADXState, candle: &Candle) -> Option<Self> {
+        let moves = state.prev.map(|prev| Self::directional_moves(prev, candle));
+        state.prev = Some((candle.high, candle.low, candle.close));
+
+        let tr = state.tr.push(moves.map(|(tr, _, _)| tr));
+        let plus_dm = state.plus_dm.push(moves.map(|(_, plus, _)| plus));
+        let minus_dm = state.minus_dm.push(moves.map(|(_, _, minus)| minus));
+
+        // Keep the previous values if there was no range to compare with
+        if let (Some(tr), Some(plus_dm), Some(minus_dm)) = (tr, plus_dm, minus_dm) {
+            if tr != 0.0 {
+                state.di = Some((100.0 * plus_dm / tr, 100.0 * minus_dm / tr));
+            }
+        }
+
+        let dx = state.di.map(|(plus_di, minus_di)| {
+            let sum = plus_di + minus_di;
+            (plus_di - minus_di).abs() / if sum == 0.0 { 1.0 } else { sum }
+        });
+        let adx = state.adx.push(dx).map(|adx| 100.0 * adx);
+
+        let (plus_di, minus_di) = state.di?;
+        Some(ADX {
+            adx,
+            plus_di,
+            minus_di,
+        })
+    }
+
+    /// Returns the true range along with the positive and negative
+    /// directional movement from the previous high, low and close.
+    fn directional_moves(
+        (prev_high, prev_low, prev_close): (f64, f64, f64),
+        candle: &Candle,
+    ) -> (f64, f64, f64) {
+        let tr = (candle.high - candle.low)
+            .max((candle.high - prev_close).abs())
+            .max((candle.low - prev_close).abs());
+
+        let up = candle.high - prev_high;
+        let down = prev_low - candle.low;
+
+        let plus_dm = if up > down && up > 0.0 { up } else { 0.0 };
+        let minus_dm = if down > up && down > 0.0 { down } else { 0.0 };
+
+        (tr, plus_dm, minus_dm)
+    }
+}
+
+/// Smoothed true range and directional movement along with the previous
+/// high, low and close and directional indicators.
+#[derive(Debug, Clone)]
+pub struct ADXState {
+    prev: Option<(f64, f64, f64)>,
+    tr: RollingEMA,
+    plus_dm: RollingEMA,
+    minus_dm: RollingEMA,
+    di: Option<(f64, f64)>,
+    adx: RollingEMA,
+}
+
+impl ADXState {
+    pub fn new(di_len: usize, adx_len: usize) -> Self {
+        Self {
+            prev: None,
+            tr: RollingEMA::rma(di_len),
+            plus_dm: RollingEMA::rma(di_len),
+            minus_dm: RollingEMA::rma(di_len),
+            di: None,
+            adx: RollingEMA::rma(adx_len),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            adx::ADX, indicator_type::IndicatorType, is_indicator::IsIndicator,
+            populates_candles::PopulatesCandles,
+        },
+        models::{interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::candle_strategy,
+    };
+    use proptest::prelude::*;
+
+    #[test]
+    fn adx_populate_candles() {
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candle_strategy::price_change_candles())
+            .build();
+
+        ADX::populate_candles(&mut ts).unwrap();
+
+        let indicator_type = IndicatorType::ADX(14, 14);
+        let adxs: Vec<Option<ADX>> = ts
+            .candles
+            .iter()
+            .map(|c| c.clone_indicator(&indicator_type).unwrap().as_adx())
+            .collect();
+
+        assert!(adxs[13].is_none());
+        assert!(adxs[14].unwrap().adx.is_none());
+        assert!(adxs[27].unwrap().adx.is_some());
+    }
+
+    #[test]
+    fn adx_worked_example() {
+        // (high, low, close) giving true ranges of 3, 3, 5 and 5, +DMs of
+        // 2, 1, 0 and 0 and -DMs of 0, 0, 3 and 1 from the second candle
+        let candles = candle_strategy::hlc_candles(&[
+            (10.0, 8.0, 9.0),
+            (12.0, 9.0, 11.0),
+            (13.0, 10.0, 12.0),
+            (12.0, 7.0, 8.0),
+            (11.0, 6.0, 7.0),
+        ]);
+        let indicator_type = IndicatorType::ADX(2, 2);
+        let populated = candle_strategy::populated(&candles, indicator_type);
+        let adxs: Vec<Option<ADX>> = populated
+            .iter()
+            .map(|c| c.clone_indicator(&indicator_type).unwrap().as_adx())
+            .collect();
+
+        // Smoothed TR, +DM and -DM are seeded with 3, 1.5 and 0, then
+        // 4, 0.75, 1.5 and 4.5, 0.375, 1.25. DX is 1, 1/3 and 7/13 and the
+        // ADX is seeded with the average of the first two.
+        let expected = [
+            None,
+            None,
+            Some((None, 50.0, 0.0)),
+            Some((Some(200.0 / 3.0), 18.75, 37.5)),
+            Some((Some(100.0 * 47.0 / 78.0), 25.0 / 3.0, 250.0 / 9.0)),
+        ];
+
+        for (adx, expected) in adxs.iter().zip(expected) {
+            let (Some(adx), Some((value, plus_di, minus_di))) = (adx, expected) else {
+                assert_eq!(adx.is_some(), expected.is_some());
+                continue;
+            };
+
+            assert_eq!(adx.adx.is_some(), value.is_some());
+            assert!((adx.adx.unwrap_or(0.0) - value.unwrap_or(0.0)).abs() < 1e-9);
+            assert!((adx.plus_di - plus_di).abs() < 1e-9);
+            assert!((adx.minus_di - minus_di).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn adx_no_candles() {
+        let candles = Vec::new();
+        let adx = ADX::calculate(&candles);
+        assert!(adx.is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn adx_matches_recomputation(
+            candles in candle_strategy::candles(1, 80),
+            di_len in 1..20usize,
+            adx_len in 1..20usize,
+        ) {
+            let indicator_type = IndicatorType::ADX(di_len, adx_len);
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            for candle in populated.iter() {
+                let adx = candle.clone_indicator(&indicator_type).unwrap().as_adx();
+
+                if let Some(adx) = adx {
+                    prop_assert!(adx.plus_di >= 0.0 && adx.minus_di >= 0.0);
+                    prop_assert!(adx.adx.is_none_or(|adx| (0.0..=100.0 + 1e-9).contains(&adx)));
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,11 @@
 use crate::{
     indicators::{
-        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
     },
     models::{candle::Candle, timeseries::TimeSeries},
 };
@@ -20,7 +24,7 @@ impl PopulatesCandles for ATR {
     }
 
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let len = args.len_res()?;
+        let len = args.usize(0)?;
         let indicator_type = IndicatorType::ATR(len);
 
         let mut prev: Option<ATR> = None;
@@ -53,7 +57,7 @@ impl PopulatesCandles for ATR {
     }
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let len = args.len_res()?;
+        let len = args.usize(0)?;
         let indicator_type = IndicatorType::ATR(len);
         let prev = Indicator::get_second_last(ts, &indicator_type)
             .and_then(|indicator| indicator.as_atr());
@@ -96,7 +100,7 @@ impl PopulatesCandles for ATR {
 }
 
 impl ATR {
-    fn calculate_rolling(
+    pub fn calculate_rolling(
         (prev_candle, curr_candle): (&Candle, &Candle),
         prev_atr: f64,
         len: usize,
@@ -108,7 +112,7 @@ impl ATR {
         Some(ATR { len, value: atr })
     }
 
-    fn true_range(prev: f64, curr: &Candle) -> f64 {
+    pub fn true_range(prev: f64, curr: &Candle) -> f64 {
         let a = curr.high - curr.low;
         let b = (curr.high - prev).abs();
         let c = (curr.low - prev).abs();
@@ -117,9 +121,29 @@ impl ATR {
     }
 }
 
+impl DefinesIndicator for ATR {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "atr",
+        params: &[ParamSchema {
+            name: "len",
+            default: ParamValue::Int(14),
+        }],
+        outputs: &["value"],
+        warmup: |args| Ok(args.usize(0)? + 1),
+        lag: |_| Ok(0),
+        build: |args| Ok(IndicatorType::ATR(args.usize(0)?)),
+        args: |indicator_type| match indicator_type {
+            IndicatorType::ATR(a) => Some(IndicatorArgs(vec![ParamValue::Int(*a)])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
 impl IsIndicator for ATR {
     fn default_args() -> IndicatorArgs {
-        IndicatorArgs::LengthArg(14)
+        IndicatorType::ATR(14).args()
     }
 
     /// Segment should be one candle longer than the length of ATR wanted.
@@ -146,7 +170,7 @@ impl IsIndicator for ATR {
     where
         Self: Sized,
     {
-        let arg_len = args.len_opt()?;
+        let arg_len = args.usize(0).ok()?;
         let candle_len = segment.len();
 
         if arg_len >= candle_len {
@@ -161,8 +185,8 @@ impl IsIndicator for ATR {
 mod tests {
     use crate::{
         indicators::{
-            atr::ATR, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-            is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+            atr::ATR, indicator_type::IndicatorType, is_indicator::IsIndicator,
+            populates_candles::PopulatesCandles,
         },
         models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
     };
@@ -170,7 +194,7 @@ mod tests {
     #[test]
     fn atr_calculate() {
         let candles = Candle::dummy_data(6, "positive", 100.0);
-        let args = IndicatorArgs::LengthArg(5);
+        let args = IndicatorType::ATR(5).args();
         let atr = ATR::calculate_args(&candles, &args);
         println!("{:#?}", atr);
         assert!(atr.is_some());
@@ -197,7 +221,7 @@ mod tests {
 
         let _ = ATR::populate_candles(&mut ts);
 
-        let len = ATR::default_args().len_opt().unwrap();
+        let len = ATR::default_args().usize(0).unwrap();
         let indicator_type = IndicatorType::ATR(len);
 
         for (i, candle) in ts.candles.iter().enumerate() {
@@ -233,7 +257,7 @@ mod tests {
         let _ = ATR::populate_candles(&mut ts);
 
         let _ = ts.add_candle(&candle);
-        let len = ATR::default_args().len_opt().unwrap();
+        let len = ATR::default_args().usize(0).unwrap();
         let indicator_type = IndicatorType::ATR(len);
 
         for (i, candle) in ts.candles.iter().enumerate() {
@@ -1,8 +1,14 @@
 use crate::{
     indicators::{
-        bollinger_bands::BollingerBands, indicator::Indicator, indicator_args::IndicatorArgs,
-        indicator_type::IndicatorType, is_indicator::IsIndicator,
+        bollinger_bands::BollingerBands,
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
         populates_candles::PopulatesCandles,
+        rolling::rolling_stats::RollingStats,
     },
     models::{candle::Candle, timeseries::TimeSeries},
 };
@@ -21,22 +27,19 @@ impl PopulatesCandles for BBW {
     }
 
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, _) = args.bb_res()?;
-        let indicator_type = IndicatorType::BBW(len);
+        let (len, std_n) = (args.usize(0)?, args.f64(1)?);
+        let indicator_type = IndicatorType::BBW(len, std_n.into());
+        let mut state = RollingStats::new(len);
 
-        for i in 0..ts.candles.len() {
-            let end = i + 1;
-            let bbw = if end < len {
-                None
-            } else {
-                Self::calculate_args(&ts.candles[end - len..end], &args)
-            };
+        for candle in ts.candles.iter_mut() {
+            let bbw = Self::calculate_rolling(&mut state, candle, len, std_n);
 
-            ts.candles[i]
+            candle
                 .indicators
                 .insert(indicator_type, Indicator::BBW(bbw));
         }
 
+        ts.set_indicator_state(indicator_type, IndicatorState::BBW(state));
         ts.indicators.insert(indicator_type);
 
         Ok(())
@@ -47,37 +50,64 @@ impl PopulatesCandles for BBW {
     }
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, _) = args.bb_res()?;
+        let (len, std_n) = (args.usize(0)?, args.f64(1)?);
         let ctx_err = "Unable to get last candle";
-        let indicator_type = IndicatorType::BBW(len);
-        let end = ts.candles.len();
+        let indicator_type = IndicatorType::BBW(len, std_n.into());
 
-        if end == 0 {
+        if ts.candles.is_empty() {
             return Err(anyhow!("No candle to populate"));
-        } else if end < len {
-            // Not enough candles to populate
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::BBW(None));
-        } else {
-            let new_bbw = Self::calculate_args(&ts.candles[end - len..end], &args);
-
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::BBW(new_bbw));
         }
 
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::BBW(state)) => state,
+            _ => return Self::populate_candles_args(ts, args),
+        };
+
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let bbw = Self::calculate_rolling(&mut state, candle, len, std_n);
+
+        candle
+            .indicators
+            .insert(indicator_type, Indicator::BBW(bbw));
+
+        ts.set_indicator_state(indicator_type, IndicatorState::BBW(state));
+
         Ok(())
     }
 }
 
+impl DefinesIndicator for BBW {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "bbw",
+        params: &[
+            ParamSchema {
+                name: "len",
+                default: ParamValue::Int(20),
+            },
+            ParamSchema {
+                name: "std",
+                default: ParamValue::Float(2.0),
+            },
+        ],
+        outputs: &["value"],
+        warmup: |args| Ok(args.usize(0)? + 1),
+        lag: |_| Ok(0),
+        build: |args| Ok(IndicatorType::BBW(args.usize(0)?, args.f64(1)?.into())),
+        args: |indicator_type| match indicator_type {
+            IndicatorType::BBW(a, b) => Some(IndicatorArgs(vec![
+                ParamValue::Int(*a),
+                ParamValue::Float(b.value()),
+            ])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
 impl IsIndicator for BBW {
     fn default_args() -> IndicatorArgs {
-        IndicatorArgs::BollingerBandArgs(20, 2.0)
+        IndicatorType::BBW(20, 2.0.into()).args()
     }
 
     /// Segment should have the same number of candles as the desired length of
@@ -106,6 +136,24 @@ impl IsIndicator for BBW {
     }
 }
 
+impl BBW {
+    /// Adds the candle to the rolling window and calculates the width of the
+    /// bands once the window holds `len` candles.
+    pub fn calculate_rolling(
+        state: &mut RollingStats,
+        candle: &Candle,
+        len: usize,
+        std_n: f64,
+    ) -> Option<Self> {
+        let bb = BollingerBands::calculate_rolling(state, candle, len, std_n)?;
+
+        Some(BBW {
+            value: (bb.upper - bb.lower) / bb.sma,
+            len,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -114,7 +162,9 @@ mod tests {
             populates_candles::PopulatesCandles,
         },
         models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::candle_strategy,
     };
+    use proptest::prelude::*;
 
     #[test]
     fn bbw_calculate() {
@@ -161,8 +211,10 @@ mod tests {
 
         let _ = BBW::populate_candles(&mut ts);
 
-        let (len, _) = BBW::default_args().bb_opt().unwrap();
-        let indicator_type = IndicatorType::BBW(len);
+        let args = BBW::default_args();
+
+        let (len, std_n) = (args.usize(0).unwrap(), args.f64(1).unwrap());
+        let indicator_type = IndicatorType::BBW(len, std_n.into());
 
         for (i, candle) in ts.candles.iter().enumerate() {
             let indicator = candle.indicators.get(&indicator_type).unwrap();
@@ -197,8 +249,9 @@ mod tests {
         let _ = BBW::populate_candles(&mut ts);
 
         let _ = ts.add_candle(&candle);
-        let (len, _) = BBW::default_args().bb_opt().unwrap();
-        let indicator_type = IndicatorType::BBW(len);
+        let args = BBW::default_args();
+        let (len, std_n) = (args.usize(0).unwrap(), args.f64(1).unwrap());
+        let indicator_type = IndicatorType::BBW(len, std_n.into());
 
         for (i, candle) in ts.candles.iter().enumerate() {
             let indicator = candle.indicators.get(&indicator_type).unwrap();
@@ -220,4 +273,34 @@ mod tests {
 
         assert_eq!(last_bbw.value, 0.9280125149960182);
     }
+
+    proptest! {
+        #[test]
+        fn bbw_matches_recomputation(
+            candles in candle_strategy::candles(1, 80),
+            len in 1..20usize,
+        ) {
+            let args = IndicatorType::BBW(len, 2.0.into()).args();
+            let indicator_type = IndicatorType::BBW(len, 2.0.into());
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            for (i, candle) in populated.iter().enumerate() {
+                let expected = BBW::calculate_args(&candles[..=i], &args).map(|bbw| bbw.value);
+                let actual = candle
+                    .clone_indicator(&indicator_type)
+                    .unwrap()
+                    .as_bbw()
+                    .map(|bbw| bbw.value);
+
+                // Rounding in the rolling deviation scales with the price rather
+                // than the width, so narrow bands need an absolute tolerance
+                match (actual, expected) {
+                    (Some(actual), Some(expected)) => {
+                        prop_assert!((actual - expected).abs() < 1e-6, "{} != {}", actual, expected)
+                    }
+                    (actual, expected) => prop_assert_eq!(actual, expected),
+                }
+            }
+        }
+    }
 }
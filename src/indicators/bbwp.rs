@@ -1,11 +1,17 @@
 use crate::{
     indicators::{
-        bbw::BBW, indicator::Indicator, indicator_args::IndicatorArgs,
-        indicator_type::IndicatorType, is_indicator::IsIndicator,
-        populates_candles::PopulatesCandles, sma::SMA,
+        bbw::BBW,
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+        rolling::{rolling_ma::RollingMA, rolling_rank::RollingRank, rolling_stats::RollingStats},
+        sma::SMA,
     },
-    models::{candle::Candle, timeseries::TimeSeries},
-    utils::math::sma,
+    models::{candle::Candle, ma_type::MAType, timeseries::TimeSeries},
 };
 use anyhow::{anyhow, Context, Result};
 
@@ -32,48 +38,24 @@ impl PopulatesCandles for BBWP {
     }
 
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, lookback, sma_len) = args.bbwp_res()?;
-        let indicator_type = IndicatorType::BBWP(len, lookback);
-
-        for i in 0..ts.candles.len() {
-            let end = i + 1;
-            let bbwp = if end < len {
-                None
-            } else {
-                let start = if end > lookback + len {
-                    end - lookback - len
-                } else {
-                    0
-                };
-
-                Self::calculate_args(&ts.candles[start..end], &args)
-            };
-
-            ts.candles[i]
+        let (len, lookback, sma_len, ma_type) = (
+            args.usize(0)?,
+            args.usize(1)?,
+            args.usize(2)?,
+            args.ma_type(3)?,
+        );
+        let indicator_type = IndicatorType::BBWP(len, lookback, sma_len, ma_type);
+        let mut state = BBWPState::new(len, lookback, sma_len, ma_type);
+
+        for candle in ts.candles.iter_mut() {
+            let bbwp = Self::calculate_rolling(&mut state, candle);
+
+            candle
                 .indicators
                 .insert(indicator_type, Indicator::BBWP(bbwp));
-
-            // Not enough candles to populate bbwp sma so return
-            if end < len + sma_len {
-                continue;
-            }
-
-            let mut bbwp = bbwp.context("Unable to calculate BBWP")?;
-            let sma_segment = &ts.candles[end - sma_len..end];
-            let sma = Self::bbwp_sma(sma_segment, &indicator_type);
-
-            if let Some(sma) = sma {
-                bbwp.sma = Some(SMA {
-                    len: sma_len,
-                    value: sma,
-                });
-
-                ts.candles[i]
-                    .indicators
-                    .insert(indicator_type, Indicator::BBWP(Some(bbwp)));
-            }
         }
 
+        ts.set_indicator_state(indicator_type, IndicatorState::BBWP(Box::new(state)));
         ts.indicators.insert(indicator_type);
 
         Ok(())
@@ -84,69 +66,86 @@ impl PopulatesCandles for BBWP {
     }
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, lookback, sma_len) = args.bbwp_res()?;
+        let (len, lookback, sma_len, ma_type) = (
+            args.usize(0)?,
+            args.usize(1)?,
+            args.usize(2)?,
+            args.ma_type(3)?,
+        );
         let ctx_err = "Unable to get last candle";
-        let indicator_type = IndicatorType::BBWP(len, lookback);
-        let end = ts.candles.len();
+        let indicator_type = IndicatorType::BBWP(len, lookback, sma_len, ma_type);
 
-        if end == 0 {
+        if ts.candles.is_empty() {
             return Err(anyhow!("No candle to populate"));
         }
 
-        // Not enough candles to populate
-        if end < len {
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::BBWP(None));
-
-            return Ok(());
-        }
-
-        let start = if end > lookback + len {
-            end - lookback - len
-        } else {
-            0
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::BBWP(state)) => *state,
+            _ => return Self::populate_candles_args(ts, args),
         };
 
-        let mut new_bbwp = Self::calculate_args(&ts.candles[start..end], &args)
-            .context("Unable to calculate BBWP")?;
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let bbwp = Self::calculate_rolling(&mut state, candle);
 
-        ts.candles
-            .last_mut()
-            .context(ctx_err)?
+        candle
             .indicators
-            .insert(indicator_type, Indicator::BBWP(Some(new_bbwp)));
-
-        // Not enough candles to populate bbwp sma so return
-        if end < len + sma_len {
-            return Ok(());
-        }
+            .insert(indicator_type, Indicator::BBWP(bbwp));
 
-        let sma_segment = &ts.candles[end - sma_len..end];
-        let sma = Self::bbwp_sma(sma_segment, &indicator_type);
-
-        if let Some(sma) = sma {
-            new_bbwp.sma = Some(SMA {
-                len: sma_len,
-                value: sma,
-            });
-
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::BBWP(Some(new_bbwp)));
-        }
+        ts.set_indicator_state(indicator_type, IndicatorState::BBWP(Box::new(state)));
 
         Ok(())
     }
 }
 
+impl DefinesIndicator for BBWP {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "bbwp",
+        params: &[
+            ParamSchema {
+                name: "len",
+                default: ParamValue::Int(13),
+            },
+            ParamSchema {
+                name: "lookback",
+                default: ParamValue::Int(252),
+            },
+            ParamSchema {
+                name: "sma",
+                default: ParamValue::Int(5),
+            },
+            ParamSchema {
+                name: "ma",
+                default: ParamValue::MAType(MAType::SMA),
+            },
+        ],
+        outputs: &["value", "sma"],
+        warmup: |args| Ok(args.usize(1)? + 1),
+        lag: |_| Ok(0),
+        build: |args| {
+            Ok(IndicatorType::BBWP(
+                args.usize(0)?,
+                args.usize(1)?,
+                args.usize(2)?,
+                args.ma_type(3)?,
+            ))
+        },
+        args: |indicator_type| match indicator_type {
+            IndicatorType::BBWP(a, b, c, d) => Some(IndicatorArgs(vec![
+                ParamValue::Int(*a),
+                ParamValue::Int(*b),
+                ParamValue::Int(*c),
+                ParamValue::MAType(*d),
+            ])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
 impl IsIndicator for BBWP {
     fn default_args() -> IndicatorArgs {
-        IndicatorArgs::BBWPArgs(13, 252, 5) // len, lookback, sma_len
+        IndicatorType::BBWP(13, 252, 5, MAType::SMA).args() // len, lookback, sma_len, sma type
     }
 
     /// Note: Due to api based on segment length, the segment needs "len"
@@ -170,7 +169,7 @@ impl IsIndicator for BBWP {
 impl BBWP {
     fn calculate_bbwp(segment: &[Candle], args: &IndicatorArgs) -> Option<Self> {
         let bbws = Self::get_bbws(segment, args).ok()?;
-        let (len, lookback, _) = args.bbwp_opt()?;
+        let (len, lookback) = (args.usize(0).ok()?, args.usize(1).ok()?);
         let new_bbw = bbws.last()?.as_ref()?;
 
         let count = bbws
@@ -190,41 +189,82 @@ impl BBWP {
         })
     }
 
-    fn bbwp_sma(segment: &[Candle], indicator_type: &IndicatorType) -> Option<f64> {
-        let values: Vec<f64> = segment
-            .iter()
-            .filter_map(|c| c.clone_indicator(indicator_type).ok()?.as_bbwp())
-            .map(|bbwp| bbwp.value)
-            .collect();
+    /// Adds the candle to the rolling state and calculates the BBWP of it.
+    /// Gives the same result as calculating over the last `lookback + len`
+    /// candles without having to recalculate every BBW in the lookback.
+    fn calculate_rolling(state: &mut BBWPState, candle: &Candle) -> Option<Self> {
+        let (len, lookback, sma_len) = (state.len, state.lookback, state.sma_len);
+        state.seen += 1;
 
-        (values.len() == segment.len()).then_some(sma(&values))
-    }
+        let bbw = BBW::calculate_rolling(&mut state.bbws, candle, len, BBW_STD_N);
+        let bbwp = bbw.map(|bbw| BBWP {
+            len,
+            lookback,
+            value: (state.ranks.count_below(bbw.value) as f64) / (lookback as f64),
+            sma: None,
+        });
 
-    fn get_bbws(segment: &[Candle], args: &IndicatorArgs) -> Result<Vec<Option<BBW>>> {
-        // TODO: Refactor populates candles trait to include methods accepting
-        // just candles instead of only TimeSeries to not need temporary
-        // TimeSeries below.
+        state.ranks.push(bbw.map(|bbw| bbw.value));
+        let ma = state.values.push(bbwp.map(|bbwp| bbwp.value));
 
-        let temp_segment = segment.to_vec();
-        let mut temp_ts = TimeSeries::dummy();
-        temp_ts.set_candles(&temp_segment);
+        let mut bbwp = bbwp?;
 
-        let (len, _, _) = args.bbwp_res()?;
-        let bbw_args = IndicatorArgs::BollingerBandArgs(len, BBW_STD_N);
-        BBW::populate_candles_args(&mut temp_ts, bbw_args)?;
+        // Not enough candles to populate bbwp sma
+        if state.seen >= len + sma_len {
+            bbwp.sma = ma.map(|value| SMA {
+                len: sma_len,
+                value,
+            });
+        }
 
-        let ind_type = IndicatorType::BBW(len);
+        Some(bbwp)
+    }
 
-        let bbws: Vec<Option<BBW>> = temp_ts
-            .candles
-            .iter()
-            .map(|candle| candle.clone_indicator(&ind_type).unwrap().as_bbw())
+    fn get_bbws(segment: &[Candle], args: &IndicatorArgs) -> Result<Vec<Option<BBW>>> {
+        let len = args.usize(0)?;
+        let bbw_args = IndicatorType::BBW(len, BBW_STD_N.into()).args();
+
+        let bbws = (1..=segment.len())
+            .map(|end| {
+                if end < len {
+                    None
+                } else {
+                    BBW::calculate_args(&segment[end - len..end], &bbw_args)
+                }
+            })
             .collect();
 
         Ok(bbws)
     }
 }
 
+/// Rolling state of BBWP: the BBW window, the previous BBWs within the
+/// lookback and the moving average of the BBWP values.
+#[derive(Debug, Clone)]
+pub struct BBWPState {
+    len: usize,
+    lookback: usize,
+    sma_len: usize,
+    bbws: RollingStats,
+    ranks: RollingRank,
+    values: RollingMA,
+    seen: usize,
+}
+
+impl BBWPState {
+    pub fn new(len: usize, lookback: usize, sma_len: usize, ma_type: MAType) -> Self {
+        Self {
+            len,
+            lookback,
+            sma_len,
+            bbws: RollingStats::new(len),
+            ranks: RollingRank::new(lookback),
+            values: RollingMA::new(ma_type, sma_len),
+            seen: 0,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -232,9 +272,16 @@ mod tests {
             bbwp::BBWP, indicator_type::IndicatorType, is_indicator::IsIndicator,
             populates_candles::PopulatesCandles,
         },
-        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
-        utils::data::dummy_data::PRICE_CHANGES,
+        models::{
+            candle::Candle, interval::Interval, ma_type::MAType,
+            timeseries_builder::TimeSeriesBuilder,
+        },
+        utils::data::{
+            candle_strategy::{self, assert_close},
+            dummy_data::PRICE_CHANGES,
+        },
     };
+    use proptest::prelude::*;
 
     const FINAL_VALUES: &[f64] = &[
         0.5238095238095238,
@@ -258,10 +305,17 @@ mod tests {
 
         let segment = &ts.candles[ts.candles.len() - 5..];
 
-        let (len, lookback, _) = BBWP::default_args().bbwp_opt().unwrap();
+        let args = BBWP::default_args();
+
+        let (len, lookback, sma_len, ma_type) = (
+            args.usize(0).unwrap(),
+            args.usize(1).unwrap(),
+            args.usize(2).unwrap(),
+            args.ma_type(3).unwrap(),
+        );
         for (i, val) in FINAL_VALUES.iter().enumerate() {
             let bbwp = segment[i]
-                .clone_indicator(&IndicatorType::BBWP(len, lookback))
+                .clone_indicator(&IndicatorType::BBWP(len, lookback, sma_len, ma_type))
                 .unwrap()
                 .as_bbwp()
                 .unwrap();
@@ -295,8 +349,15 @@ mod tests {
 
         let _ = BBWP::populate_candles(&mut ts);
 
-        let (len, lookback, _sma_len) = BBWP::default_args().bbwp_opt().unwrap();
-        let indicator_type = IndicatorType::BBWP(len, lookback);
+        let args = BBWP::default_args();
+
+        let (len, lookback, sma_len, ma_type) = (
+            args.usize(0).unwrap(),
+            args.usize(1).unwrap(),
+            args.usize(2).unwrap(),
+            args.ma_type(3).unwrap(),
+        );
+        let indicator_type = IndicatorType::BBWP(len, lookback, sma_len, ma_type);
 
         for (i, candle) in ts.candles.iter().enumerate() {
             let indicator = candle.indicators.get(&indicator_type).unwrap();
@@ -310,10 +371,17 @@ mod tests {
 
         let segment = &ts.candles[ts.candles.len() - 5..];
 
-        let (len, lookback, _) = BBWP::default_args().bbwp_opt().unwrap();
+        let args = BBWP::default_args();
+
+        let (len, lookback, sma_len, ma_type) = (
+            args.usize(0).unwrap(),
+            args.usize(1).unwrap(),
+            args.usize(2).unwrap(),
+            args.ma_type(3).unwrap(),
+        );
         for (i, val) in FINAL_VALUES.iter().enumerate() {
             let bbwp = segment[i]
-                .clone_indicator(&IndicatorType::BBWP(len, lookback))
+                .clone_indicator(&IndicatorType::BBWP(len, lookback, sma_len, ma_type))
                 .unwrap()
                 .as_bbwp()
                 .unwrap();
@@ -334,8 +402,15 @@ mod tests {
         let _ = BBWP::populate_candles(&mut ts);
         let _ = ts.add_candle(&candle);
 
-        let (len, lookback, _sma_len) = BBWP::default_args().bbwp_opt().unwrap();
-        let indicator_type = IndicatorType::BBWP(len, lookback);
+        let args = BBWP::default_args();
+
+        let (len, lookback, sma_len, ma_type) = (
+            args.usize(0).unwrap(),
+            args.usize(1).unwrap(),
+            args.usize(2).unwrap(),
+            args.ma_type(3).unwrap(),
+        );
+        let indicator_type = IndicatorType::BBWP(len, lookback, sma_len, ma_type);
 
         for (i, candle) in ts.candles.iter().enumerate() {
             let indicator = candle.indicators.get(&indicator_type).unwrap();
@@ -357,4 +432,26 @@ mod tests {
 
         assert_eq!(&last_bbwp.value, FINAL_VALUES.last().unwrap());
     }
+
+    proptest! {
+        #[test]
+        fn bbwp_matches_recomputation(
+            candles in candle_strategy::candles(1, 80),
+            len in 1..10usize,
+            lookback in 1..30usize,
+            sma_len in 1..5usize,
+            ma_type in prop_oneof![Just(MAType::SMA), Just(MAType::EMA), Just(MAType::HMA)],
+        ) {
+            let args = IndicatorType::BBWP(len, lookback, sma_len, ma_type).args();
+            let indicator_type = IndicatorType::BBWP(len, lookback, sma_len, ma_type);
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            for (i, candle) in populated.iter().enumerate() {
+                let start = (i + 1).saturating_sub(lookback + len);
+                let expected = BBWP::calculate_args(&candles[start..=i], &args);
+                let actual = candle.clone_indicator(&indicator_type).unwrap().as_bbwp();
+                assert_close(actual.map(|bbwp| bbwp.value), expected.map(|bbwp| bbwp.value))?;
+            }
+        }
+    }
 }
@@ -1,7 +1,13 @@
 use crate::{
     indicators::{
-        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+        rolling::rolling_stats::RollingStats,
     },
     models::{candle::Candle, timeseries::TimeSeries},
     utils::math::{sma, std},
@@ -23,22 +29,19 @@ impl PopulatesCandles for BollingerBands {
     }
 
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, _) = args.bb_res()?;
-        let indicator_type = IndicatorType::BollingerBands(len);
+        let (len, std_n) = (args.usize(0)?, args.f64(1)?);
+        let indicator_type = IndicatorType::BollingerBands(len, std_n.into());
+        let mut state = RollingStats::new(len);
 
-        for i in 0..ts.candles.len() {
-            let end = i + 1;
-            let bb = if end < len {
-                None
-            } else {
-                Self::calculate_args(&ts.candles[end - len..end], &args)
-            };
+        for candle in ts.candles.iter_mut() {
+            let bb = Self::calculate_rolling(&mut state, candle, len, std_n);
 
-            ts.candles[i]
+            candle
                 .indicators
                 .insert(indicator_type, Indicator::BollingerBands(bb));
         }
 
+        ts.set_indicator_state(indicator_type, IndicatorState::BollingerBands(state));
         ts.indicators.insert(indicator_type);
 
         Ok(())
@@ -49,37 +52,69 @@ impl PopulatesCandles for BollingerBands {
     }
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, _) = args.bb_res()?;
+        let (len, std_n) = (args.usize(0)?, args.f64(1)?);
         let ctx_err = "Unable to get last candle";
-        let indicator_type = IndicatorType::BollingerBands(len);
-        let end = ts.candles.len();
+        let indicator_type = IndicatorType::BollingerBands(len, std_n.into());
 
-        if end == 0 {
+        if ts.candles.is_empty() {
             return Err(anyhow!("No candle to populate"));
-        } else if end < len {
-            // Not enough candles to populate
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::BollingerBands(None));
-        } else {
-            let new_bb = Self::calculate_args(&ts.candles[end - len..end], &args);
-
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::BollingerBands(new_bb));
         }
 
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::BollingerBands(state)) => state,
+            _ => return Self::populate_candles_args(ts, args),
+        };
+
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let bb = Self::calculate_rolling(&mut state, candle, len, std_n);
+
+        candle
+            .indicators
+            .insert(indicator_type, Indicator::BollingerBands(bb));
+
+        ts.set_indicator_state(indicator_type, IndicatorState::BollingerBands(state));
+
         Ok(())
     }
 }
 
+impl DefinesIndicator for BollingerBands {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "bb",
+        params: &[
+            ParamSchema {
+                name: "len",
+                default: ParamValue::Int(20),
+            },
+            ParamSchema {
+                name: "std",
+                default: ParamValue::Float(2.0),
+            },
+        ],
+        outputs: &["upper", "lower", "sma", "std"],
+        warmup: |args| Ok(args.usize(0)? + 1),
+        lag: |_| Ok(0),
+        build: |args| {
+            Ok(IndicatorType::BollingerBands(
+                args.usize(0)?,
+                args.f64(1)?.into(),
+            ))
+        },
+        args: |indicator_type| match indicator_type {
+            IndicatorType::BollingerBands(a, b) => Some(IndicatorArgs(vec![
+                ParamValue::Int(*a),
+                ParamValue::Float(b.value()),
+            ])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
 impl IsIndicator for BollingerBands {
     fn default_args() -> IndicatorArgs {
-        IndicatorArgs::BollingerBandArgs(20, 2.0)
+        IndicatorType::BollingerBands(20, 2.0.into()).args()
     }
 
     /// Segment should have the same number of candles as the desired length of
@@ -92,8 +127,8 @@ impl IsIndicator for BollingerBands {
             return None;
         }
 
-        let (_, std_n) = Self::default_args().bb_opt()?;
-        let args = IndicatorArgs::BollingerBandArgs(segment.len(), std_n);
+        let std_n = Self::default_args().f64(1).ok()?;
+        let args = IndicatorType::BollingerBands(segment.len(), std_n.into()).args();
 
         Self::calculate_bb(segment, &args)
     }
@@ -102,7 +137,7 @@ impl IsIndicator for BollingerBands {
     where
         Self: Sized,
     {
-        let (arg_len, _) = args.bb_opt()?;
+        let arg_len = args.usize(0).ok()?;
         let candle_len = segment.len();
 
         if candle_len < arg_len {
@@ -114,11 +149,33 @@ impl IsIndicator for BollingerBands {
 }
 
 impl BollingerBands {
+    /// Adds the candle to the rolling window and calculates the bands once
+    /// the window holds `len` candles.
+    pub fn calculate_rolling(
+        state: &mut RollingStats,
+        candle: &Candle,
+        len: usize,
+        std_n: f64,
+    ) -> Option<Self> {
+        state.push(candle.close);
+
+        let sma = state.mean()?;
+        let std = state.std()?;
+
+        Some(BollingerBands {
+            upper: sma + std_n * std,
+            lower: sma - std_n * std,
+            sma,
+            std,
+            len,
+        })
+    }
+
     fn calculate_bb(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
     where
         Self: Sized,
     {
-        let (_, std_n) = args.bb_opt()?;
+        let std_n = args.f64(1).ok()?;
         let values: Vec<f64> = segment.iter().map(|c| c.close).collect();
 
         let sma = sma(&values);
@@ -145,7 +202,9 @@ mod tests {
             is_indicator::IsIndicator, populates_candles::PopulatesCandles,
         },
         models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::candle_strategy::{self, assert_close},
     };
+    use proptest::prelude::*;
 
     #[test]
     fn bb_calculate() {
@@ -186,8 +245,10 @@ mod tests {
 
         let _ = BollingerBands::populate_candles(&mut ts);
 
-        let (len, _) = BollingerBands::default_args().bb_opt().unwrap();
-        let indicator_type = IndicatorType::BollingerBands(len);
+        let args = BollingerBands::default_args();
+
+        let (len, std_n) = (args.usize(0).unwrap(), args.f64(1).unwrap());
+        let indicator_type = IndicatorType::BollingerBands(len, std_n.into());
 
         for (i, candle) in ts.candles.iter().enumerate() {
             let indicator = candle.indicators.get(&indicator_type).unwrap();
@@ -223,8 +284,10 @@ mod tests {
 
         let _ = ts.add_candle(&candle);
 
-        let (len, _) = BollingerBands::default_args().bb_opt().unwrap();
-        let indicator_type = IndicatorType::BollingerBands(len);
+        let args = BollingerBands::default_args();
+
+        let (len, std_n) = (args.usize(0).unwrap(), args.f64(1).unwrap());
+        let indicator_type = IndicatorType::BollingerBands(len, std_n.into());
 
         for (i, candle) in ts.candles.iter().enumerate() {
             let indicator = candle.indicators.get(&indicator_type).unwrap();
@@ -246,4 +309,24 @@ mod tests {
 
         assert_eq!(last_bb.upper, 373.3215956619923);
     }
+
+    proptest! {
+        #[test]
+        fn bb_matches_recomputation(
+            candles in candle_strategy::candles(1, 80),
+            len in 1..20usize,
+            std_n in 0.5..3.0f64,
+        ) {
+            let args = IndicatorType::BollingerBands(len, std_n.into()).args();
+            let indicator_type = IndicatorType::BollingerBands(len, std_n.into());
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            for (i, candle) in populated.iter().enumerate() {
+                let expected = BollingerBands::calculate_args(&candles[..=i], &args);
+                let actual = candle.clone_indicator(&indicator_type).unwrap().as_bollinger_bands();
+                assert_close(actual.map(|bb| bb.upper), expected.map(|bb| bb.upper))?;
+                assert_close(actual.map(|bb| bb.lower), expected.map(|bb| bb.lower))?;
+            }
+        }
+    }
 }
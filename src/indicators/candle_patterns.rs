@@ -0,0 +1,513 @@
+use crate::{
+    indicators::{
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+    },
+    models::{candle::Candle, strategy_orientation::StrategyOrientation, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Context, Result};
+use std::collections::VecDeque;
+
+/// Candlestick patterns recognized by `CandlePatterns`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CandlePattern {
+    BullishEngulfing,
+    BearishEngulfing,
+    Hammer,
+    ShootingStar,
+    Doji,
+    MorningStar,
+    EveningStar,
+    InsideBar,
+}
+
+impl CandlePattern {
+    pub const ALL: [CandlePattern; 8] = [
+        CandlePattern::BullishEngulfing,
+        CandlePattern::BearishEngulfing,
+        CandlePattern::Hammer,
+        CandlePattern::ShootingStar,
+        CandlePattern::Doji,
+        CandlePattern::MorningStar,
+        CandlePattern::EveningStar,
+        CandlePattern::InsideBar,
+    ];
+
+    /// Name of the pattern, also used as the output field of the indicator.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            CandlePattern::BullishEngulfing => "bullish_engulfing",
+            CandlePattern::BearishEngulfing => "bearish_engulfing",
+            CandlePattern::Hammer => "hammer",
+            CandlePattern::ShootingStar => "shooting_star",
+            CandlePattern::Doji => "doji",
+            CandlePattern::MorningStar => "morning_star",
+            CandlePattern::EveningStar => "evening_star",
+            CandlePattern::InsideBar => "inside_bar",
+        }
+    }
+
+    /// Returns the direction the pattern signals a reversal in, or None for
+    /// patterns signalling indecision.
+    #[allow(dead_code)]
+    pub fn orientation(&self) -> Option<StrategyOrientation> {
+        match self {
+            CandlePattern::BullishEngulfing
+            | CandlePattern::Hammer
+            | CandlePattern::MorningStar => Some(StrategyOrientation::Long),
+            CandlePattern::BearishEngulfing
+            | CandlePattern::ShootingStar
+            | CandlePattern::EveningStar => Some(StrategyOrientation::Short),
+            CandlePattern::Doji | CandlePattern::InsideBar => None,
+        }
+    }
+}
+
+/// # CandlePatterns
+///
+/// Candlestick patterns completed by a candle, each with a strength between
+/// 0 and 1 describing how pronounced it is. Body sizes are compared with the
+/// average body of the `len` preceding candles, so a candle needs to be
+/// large relative to recent candles to engulf or start a star pattern.
+///
+/// Hammers and shooting stars require the previous candle to move against
+/// the reversal they signal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CandlePatterns {
+    pub patterns: Vec<(CandlePattern, f64)>,
+}
+
+impl PopulatesCandles for CandlePatterns {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let len = args.usize(0)?;
+        let indicator_type = IndicatorType::CandlePatterns(len);
+        let mut state = CandlePatternsState::new(len);
+
+        for candle in ts.candles.iter_mut() {
+            let patterns = Self::calculate_rolling(&mut state, candle);
+
+            candle
+                .indicators
+                .insert(indicator_type, Indicator::CandlePatterns(patterns));
+        }
+
+        ts.set_indicator_state(indicator_type, IndicatorState::CandlePatterns(state));
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let len = args.usize(0)?;
+        let ctx_err = "Unable to get last candle";
+        let indicator_type = IndicatorType::CandlePatterns(len);
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::CandlePatterns(state)) => state,
+            _ => return Self::populate_candles_args(ts, args),
+        };
+
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let patterns = Self::calculate_rolling(&mut state, candle);
+
+        candle
+            .indicators
+            .insert(indicator_type, Indicator::CandlePatterns(patterns));
+
+        ts.set_indicator_state(indicator_type, IndicatorState::CandlePatterns(state));
+
+        Ok(())
+    }
+}
+
+impl DefinesIndicator for CandlePatterns {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "patterns",
+        params: &[ParamSchema {
+            name: "len",
+            default: ParamValue::Int(14),
+        }],
+        outputs: &[
+            CandlePattern::BullishEngulfing.name(),
+            CandlePattern::BearishEngulfing.name(),
+            CandlePattern::Hammer.name(),
+            CandlePattern::ShootingStar.name(),
+            CandlePattern::Doji.name(),
+            CandlePattern::MorningStar.name(),
+            CandlePattern::EveningStar.name(),
+            CandlePattern::InsideBar.name(),
+        ],
+        warmup: |args| Ok(args.usize(0)? + 1),
+        lag: |_| Ok(0),
+        build: |args| Ok(IndicatorType::CandlePatterns(args.usize(0)?)),
+        args: |indicator_type| match indicator_type {
+            IndicatorType::CandlePatterns(a) => Some(IndicatorArgs(vec![ParamValue::Int(*a)])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
+impl IsIndicator for CandlePatterns {
+    fn default_args() -> IndicatorArgs {
+        IndicatorType::CandlePatterns(14).args()
+    }
+
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::calculate_args(segment, &Self::default_args())
+    }
+
+    /// Detects the patterns completed by the last candle of the segment.
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let len = args.usize(0).ok()?;
+        let n = segment.len();
+
+        if len == 0 || n < len + 1 {
+            return None;
+        }
+
+        let bodies: Vec<f64> = segment[n - len - 1..n - 1]
+            .iter()
+            .map(|candle| Bar::from(candle).body())
+            .collect();
+        let avg_body = Self::mean(bodies.iter());
+
+        let bars: Vec<Bar> = segment[n.saturating_sub(3)..]
+            .iter()
+            .map(Bar::from)
+            .collect();
+
+        Some(Self::detect(&bars, avg_body))
+    }
+}
+
+impl CandlePatterns {
+    /// Returns whether the pattern was detected.
+    #[allow(dead_code)]
+    pub fn contains(&self, pattern: CandlePattern) -> bool {
+        self.patterns.iter().any(|(p, _)| *p == pattern)
+    }
+
+    /// Returns the strength of the pattern, or 0 if it wasn't detected.
+    pub fn strength(&self, pattern: CandlePattern) -> f64 {
+        self.patterns
+            .iter()
+            .find(|(p, _)| *p == pattern)
+            .map_or(0.0, |(_, strength)| *strength)
+    }
+
+    fn calculate_rolling(state: &mut CandlePatternsState, candle: &Candle) -> Option<Self> {
+        let bar = Bar::from(candle);
+        let avg_body = (state.len > 0 && state.bodies.len() == state.len)
+            .then(|| Self::mean(state.bodies.iter()));
+
+        state.bodies.push_back(bar.body());
+        if state.bodies.len() > state.len {
+            state.bodies.pop_front();
+        }
+
+        state.bars.push_back(bar);
+        if state.bars.len() > 3 {
+            state.bars.pop_front();
+        }
+
+        Some(Self::detect(state.bars.make_contiguous(), avg_body?))
+    }
+
+    // Summed in order rather than rolling so that populating candles one by
+    // one detects exactly the same patterns as recalculating them
+    fn mean<'a>(bodies: impl ExactSizeIterator<Item = &'a f64>) -> f64 {
+        let len = bodies.len();
+        bodies.sum::<f64>() / len as f64
+    }
+
+    /// Detects the patterns completed by the last of up to three bars.
+    fn detect(bars: &[Bar], avg_body: f64) -> Self {
+        let mut patterns = vec![];
+        let n = bars.len();
+        let curr = &bars[n - 1];
+        let prev = (n >= 2).then(|| &bars[n - 2]);
+        let prev_prev = (n >= 3).then(|| &bars[n - 3]);
+        let range = curr.range();
+        let body = curr.body();
+
+        if range > 0.0 && body <= 0.1 * range {
+            patterns.push((CandlePattern::Doji, 1.0 - body / (0.1 * range)));
+        }
+
+        if let Some(prev) = prev {
+            let is_hammer_shape = |wick: f64, other_wick: f64| {
+                range > 0.0
+                    && wick >= 2.0 * body
+                    && wick >= 0.6 * range
+                    && other_wick <= 0.1 * range
+            };
+
+            if prev.is_bearish() && is_hammer_shape(curr.lower_wick(), curr.upper_wick()) {
+                patterns.push((CandlePattern::Hammer, curr.lower_wick() / range));
+            }
+
+            if prev.is_bullish() && is_hammer_shape(curr.upper_wick(), curr.lower_wick()) {
+                patterns.push((CandlePattern::ShootingStar, curr.upper_wick() / range));
+            }
+
+            let engulfs = body > prev.body()
+                && curr.open.min(curr.close) <= prev.open.min(prev.close)
+                && curr.open.max(curr.close) >= prev.open.max(prev.close);
+            let strength = if avg_body > 0.0 {
+                (body / (2.0 * avg_body)).min(1.0)
+            } else {
+                1.0
+            };
+
+            if engulfs && prev.is_bearish() && curr.is_bullish() {
+                patterns.push((CandlePattern::BullishEngulfing, strength));
+            }
+
+            if engulfs && prev.is_bullish() && curr.is_bearish() {
+                patterns.push((CandlePattern::BearishEngulfing, strength));
+            }
+
+            if prev.range() > 0.0
+                && curr.high <= prev.high
+                && curr.low >= prev.low
+                && range < prev.range()
+            {
+                patterns.push((CandlePattern::InsideBar, 1.0 - range / prev.range()));
+            }
+        }
+
+        if let (Some(first), Some(star)) = (prev_prev, prev) {
+            let is_star =
+                avg_body > 0.0 && first.body() >= avg_body && star.body() <= 0.5 * avg_body;
+            let first_mid = (first.open + first.close) / 2.0;
+            let star_mid = (star.open + star.close) / 2.0;
+            // Share of the way from the middle of the first body to its open
+            let recovery = ((curr.close - first_mid) / (first.open - first_mid)).min(1.0);
+
+            if is_star
+                && first.is_bearish()
+                && star_mid < first.close
+                && curr.is_bullish()
+                && curr.close > first_mid
+            {
+                patterns.push((CandlePattern::MorningStar, recovery));
+            }
+
+            if is_star
+                && first.is_bullish()
+                && star_mid > first.close
+                && curr.is_bearish()
+                && curr.close < first_mid
+            {
+                patterns.push((CandlePattern::EveningStar, recovery));
+            }
+        }
+
+        CandlePatterns { patterns }
+    }
+}
+
+/// Prices of a candle needed to detect patterns.
+#[derive(Debug, Copy, Clone)]
+struct Bar {
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+}
+
+impl Bar {
+    fn body(&self) -> f64 {
+        (self.close - self.open).abs()
+    }
+
+    fn range(&self) -> f64 {
+        self.high - self.low
+    }
+
+    fn upper_wick(&self) -> f64 {
+        self.high - self.open.max(self.close)
+    }
+
+    fn lower_wick(&self) -> f64 {
+        self.open.min(self.close) - self.low
+    }
+
+    fn is_bullish(&self) -> bool {
+        self.close > self.open
+    }
+
+    fn is_bearish(&self) -> bool {
+        self.close < self.open
+    }
+}
+
+impl From<&Candle> for Bar {
+    fn from(candle: &Candle) -> Self {
+        Self {
+            open: candle.open,
+            high: candle.high,
+            low: candle.low,
+            close: candle.close,
+        }
+    }
+}
+
+/// Bodies of the previous `len` candles along with the last three candles.
+#[derive(Debug, Clone)]
+pub struct CandlePatternsState {
+    len: usize,
+    bodies: VecDeque<f64>,
+    bars: VecDeque<Bar>,
+}
+
+impl CandlePatternsState {
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            bodies: VecDeque::with_capacity(len + 1),
+            bars: VecDeque::with_capacity(4),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            candle_patterns::{CandlePattern, CandlePatterns},
+            indicator_type::IndicatorType,
+            is_indicator::IsIndicator,
+            populates_candles::PopulatesCandles,
+        },
+        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::candle_strategy,
+    };
+    use chrono::{Duration, TimeZone, Utc};
+    use proptest::prelude::*;
+
+    /// Candles from (open, high, low, close), preceded by three candles with
+    /// bodies of 2.
+    fn candles(ohlc: &[(f64, f64, f64, f64)]) -> Vec<Candle> {
+        let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let warmup = [(100.0, 103.0, 99.0, 102.0); 3];
+
+        warmup
+            .iter()
+            .chain(ohlc)
+            .enumerate()
+            .map(|(i, (open, high, low, close))| {
+                let timestamp = start + Duration::days(i as i64);
+                Candle::new(timestamp, *open, *close, *high, *low, 10.0)
+            })
+            .collect()
+    }
+
+    fn last_patterns(ohlc: &[(f64, f64, f64, f64)]) -> CandlePatterns {
+        CandlePatterns::calculate_args(&candles(ohlc), &IndicatorType::CandlePatterns(3).args())
+            .unwrap()
+    }
+
+    #[test]
+    fn detects_patterns() {
+        let engulfing = last_patterns(&[(102.0, 103.0, 99.5, 100.0), (99.5, 104.0, 99.0, 103.5)]);
+        assert!(engulfing.contains(CandlePattern::BullishEngulfing));
+        assert_eq!(engulfing.strength(CandlePattern::BullishEngulfing), 1.0);
+        assert!(!engulfing.contains(CandlePattern::BearishEngulfing));
+
+        let hammer = last_patterns(&[(102.0, 102.5, 99.5, 100.0), (99.5, 100.1, 95.0, 100.0)]);
+        assert!(hammer.contains(CandlePattern::Hammer));
+        assert!(!hammer.contains(CandlePattern::ShootingStar));
+
+        let doji = last_patterns(&[(100.0, 104.0, 96.0, 100.1)]);
+        assert!(doji.contains(CandlePattern::Doji));
+        assert!((doji.strength(CandlePattern::Doji) - 0.875).abs() < 1e-9);
+
+        let morning_star = last_patterns(&[
+            (104.0, 104.5, 99.5, 100.0),
+            (99.0, 99.5, 98.0, 98.8),
+            (99.0, 103.5, 98.5, 103.0),
+        ]);
+        assert!(morning_star.contains(CandlePattern::MorningStar));
+        assert_eq!(morning_star.strength(CandlePattern::MorningStar), 0.5);
+
+        let inside = last_patterns(&[(100.0, 106.0, 96.0, 104.0), (103.0, 105.0, 99.0, 101.0)]);
+        assert!(inside.contains(CandlePattern::InsideBar));
+        assert!((inside.strength(CandlePattern::InsideBar) - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn populates_candles() {
+        let candles = candles(&[(102.0, 103.0, 99.5, 100.0), (99.5, 104.0, 99.0, 103.5)]);
+        let indicator_type = IndicatorType::CandlePatterns(3);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        CandlePatterns::populate_candles_args(&mut ts, IndicatorType::CandlePatterns(3).args())
+            .unwrap();
+
+        let patterns: Vec<Option<CandlePatterns>> = ts
+            .candles
+            .iter()
+            .map(|c| {
+                c.clone_indicator(&indicator_type)
+                    .unwrap()
+                    .as_candle_patterns()
+            })
+            .collect();
+
+        assert!(patterns[..3].iter().all(|p| p.is_none()));
+        let last = patterns[4].as_ref().unwrap();
+        assert!(last.contains(CandlePattern::BullishEngulfing));
+        assert_eq!(
+            ts.candles[4].value("patterns(3).bullish_engulfing"),
+            Some(1.0)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn patterns_matches_recomputation(
+            candles in candle_strategy::candles(1, 60),
+            len in 1..10usize,
+        ) {
+            let args = IndicatorType::CandlePatterns(len).args();
+            let indicator_type = IndicatorType::CandlePatterns(len);
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            for (i, candle) in populated.iter().enumerate() {
+                let expected = CandlePatterns::calculate_args(&candles[..=i], &args);
+                let actual = candle.clone_indicator(&indicator_type).unwrap().as_candle_patterns();
+                prop_assert_eq!(actual, expected);
+            }
+        }
+    }
+}
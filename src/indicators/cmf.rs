@@ -0,0 +1,273 @@
+use crate::{
+    indicators::{
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+        rolling::rolling_sum::RollingSum,
+    },
+    models::{candle::Candle, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Context, Result};
+
+/// # CMF
+///
+/// Chaikin Money Flow, the sum of money flow volume over the sum of volume
+/// for the last `len` candles. Money flow volume weights each candle's
+/// volume by where it closed within its range, from -1 at the low to 1 at
+/// the high.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct CMF {
+    pub value: f64,
+    pub len: usize,
+}
+
+impl PopulatesCandles for CMF {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let len = args.usize(0)?;
+        let indicator_type = IndicatorType::CMF(len);
+        let mut state = CMFState::new(len);
+
+        for candle in ts.candles.iter_mut() {
+            let cmf = Self::calculate_rolling(&mut state, candle, len);
+
+            candle
+                .indicators
+                .insert(indicator_type, Indicator::CMF(cmf));
+        }
+
+        ts.set_indicator_state(indicator_type, IndicatorState::CMF(state));
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let len = args.usize(0)?;
+        let ctx_err = "Unable to get last candle";
+        let indicator_type = IndicatorType::CMF(len);
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::CMF(state)) => state,
+            _ => return Self::populate_candles_args(ts, args),
+        };
+
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let cmf = Self::calculate_rolling(&mut state, candle, len);
+
+        candle
+            .indicators
+            .insert(indicator_type, Indicator::CMF(cmf));
+
+        ts.set_indicator_state(indicator_type, IndicatorState::CMF(state));
+
+        Ok(())
+    }
+}
+
+impl DefinesIndicator for CMF {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "cmf",
+        params: &[ParamSchema {
+            name: "len",
+            default: ParamValue::Int(20),
+        }],
+        outputs: &["value"],
+        warmup: |args| args.usize(0),
+        lag: |_| Ok(0),
+        build: |args| Ok(IndicatorType::CMF(args.usize(0)?)),
+        args: |indicator_type| match indicator_type {
+            IndicatorType::CMF(a) => Some(IndicatorArgs(vec![ParamValue::Int(*a)])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
+impl IsIndicator for CMF {
+    fn default_args() -> IndicatorArgs {
+        IndicatorType::CMF(20).args()
+    }
+
+    /// Segment should be the same length as the CMF wanted.
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        if segment.is_empty() {
+            return None;
+        }
+
+        let flow_volume: f64 = segment.iter().map(Self::money_flow_volume).sum();
+        let volume: f64 = segment.iter().map(|c| c.volume).sum();
+
+        Some(CMF {
+            value: Self::ratio(flow_volume, volume),
+            len: segment.len(),
+        })
+    }
+
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let len = args.usize(0).ok()?;
+        let candle_len = segment.len();
+
+        if len == 0 || candle_len < len {
+            return None;
+        }
+
+        Self::calculate(&segment[candle_len - len..candle_len])
+    }
+}
+
+impl CMF {
+    fn money_flow_volume(candle: &Candle) -> f64 {
+        let range = candle.high - candle.low;
+
+        if range == 0.0 {
+            return 0.0;
+        }
+
+        let multiplier = ((candle.close - candle.low) - (candle.high - candle.close)) / range;
+        multiplier * candle.volume
+    }
+
+    fn ratio(flow_volume: f64, volume: f64) -> f64 {
+        if volume == 0.0 {
+            0.0
+        } else {
+            flow_volume / volume
+        }
+    }
+
+    fn calculate_rolling(state: &mut CMFState, candle: &Candle, len: usize) -> Option<Self> {
+        state
+            .flow_volume
+            .push(Some(Self::money_flow_volume(candle)));
+        state.volume.push(Some(candle.volume));
+
+        let value = Self::ratio(state.flow_volume.sum()?, state.volume.sum()?);
+
+        Some(CMF { value, len })
+    }
+}
+
+/// Money flow volume and volume over the last `len` candles.
+#[derive(Debug, Clone)]
+pub struct CMFState {
+    flow_volume: RollingSum,
+    volume: RollingSum,
+}
+
+impl CMFState {
+    pub fn new(len: usize) -> Self {
+        Self {
+            flow_volume: RollingSum::new(len),
+            volume: RollingSum::new(len),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            cmf::CMF, indicator_type::IndicatorType, is_indicator::IsIndicator,
+            populates_candles::PopulatesCandles,
+        },
+        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::candle_strategy::{self, assert_close},
+    };
+    use chrono::{Duration, Utc};
+    use proptest::prelude::*;
+
+    fn candles() -> Vec<Candle> {
+        let now = Utc::now();
+
+        // (close, high, low, volume)
+        [
+            (11.0, 12.0, 8.0, 100.0),
+            (8.0, 10.0, 8.0, 200.0),
+            (10.0, 10.0, 10.0, 50.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (close, high, low, volume))| {
+            let timestamp = now + Duration::days(i as i64);
+            Candle::new(timestamp, *close, *close, *high, *low, *volume)
+        })
+        .collect()
+    }
+
+    #[test]
+    fn cmf_populate_candles() {
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles())
+            .build();
+
+        CMF::populate_candles_args(&mut ts, IndicatorType::CMF(2).args()).unwrap();
+
+        let cmfs: Vec<Option<CMF>> = ts
+            .candles
+            .iter()
+            .map(|c| c.clone_indicator(&IndicatorType::CMF(2)).unwrap().as_cmf())
+            .collect();
+
+        assert!(cmfs[0].is_none());
+        assert!((cmfs[1].unwrap().value + 0.5).abs() < 1e-12);
+        assert!((cmfs[2].unwrap().value + 0.8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn cmf_calculate() {
+        let cmf = CMF::calculate(&candles()).unwrap();
+
+        assert_eq!(cmf.len, 3);
+        assert!((cmf.value + 150.0 / 350.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn cmf_no_candles() {
+        let candles = Vec::new();
+        let cmf = CMF::calculate_args(&candles, &CMF::default_args());
+        assert!(cmf.is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn cmf_matches_recomputation(
+            candles in candle_strategy::candles(1, 80),
+            len in 1..20usize,
+        ) {
+            let args = IndicatorType::CMF(len).args();
+            let indicator_type = IndicatorType::CMF(len);
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            for (i, candle) in populated.iter().enumerate() {
+                let expected = CMF::calculate_args(&candles[..=i], &args).map(|c| c.value);
+                let actual = candle.clone_indicator(&indicator_type).unwrap().as_cmf();
+                assert_close(actual.map(|c| c.value), expected)?;
+            }
+        }
+    }
+}
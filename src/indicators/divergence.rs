@@ -0,0 +1,353 @@
+use crate::{
+    indicators::{
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        oscillator::Oscillator,
+        populates_candles::{PopulatesCandles, PopulatesCandlesWithSelf},
+    },
+    models::{
+        candle::Candle, ma_type::MAType, timeseries::TimeSeries,
+        traits::has_min_length::HasMinLength,
+    },
+};
+use anyhow::{anyhow, Context, Result};
+use std::collections::VecDeque;
+
+const DEFAULT_OSCILLATOR: Oscillator = Oscillator::RSI(14, MAType::RMA);
+
+/// # Divergence
+///
+/// Divergences between price and the first output of an oscillator, e.g.
+/// the RSI value or the Stochastic K. Price pivots are found like
+/// `DynamicPivots`, as the highest high or lowest low of the `len` candles
+/// on each side, and each pivot is compared to the previous pivot of the
+/// same side along with the oscillator values at both pivots:
+///
+/// - Regular bullish: lower low in price, higher low in the oscillator.
+/// - Hidden bullish: higher low in price, lower low in the oscillator.
+/// - Regular bearish: higher high in price, lower high in the oscillator.
+/// - Hidden bearish: lower high in price, higher high in the oscillator.
+///
+/// A pivot is only known `len` candles after it occurs, so divergences are
+/// signalled on the candle confirming the pivot rather than on the pivot
+/// itself. This keeps the signals free of lookahead in backtests.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Divergence {
+    pub regular_bullish: bool,
+    pub hidden_bullish: bool,
+    pub regular_bearish: bool,
+    pub hidden_bearish: bool,
+}
+
+impl PopulatesCandles for Divergence {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (oscillator, len) = (Oscillator::try_from(args.indicator(0)?)?, args.usize(1)?);
+        let indicator_type = IndicatorType::Divergence(oscillator, len);
+        let oscillator = oscillator.indicator_type();
+        let mut state = DivergenceState::new(len);
+
+        if !ts.indicators.contains(&oscillator) {
+            oscillator.populate_candles(ts)?;
+        }
+
+        for candle in ts.candles.iter_mut() {
+            let value = Self::oscillator_value(candle, &oscillator);
+            let divergence = Self::calculate_rolling(&mut state, candle, value);
+
+            candle
+                .indicators
+                .insert(indicator_type, Indicator::Divergence(divergence));
+        }
+
+        ts.set_indicator_state(indicator_type, IndicatorState::Divergence(state));
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (oscillator, len) = (Oscillator::try_from(args.indicator(0)?)?, args.usize(1)?);
+        let ctx_err = "Unable to get last candle";
+        let indicator_type = IndicatorType::Divergence(oscillator, len);
+        let oscillator = oscillator.indicator_type();
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::Divergence(state)) => state,
+            _ => return Self::populate_candles_args(ts, args),
+        };
+
+        // The oscillator is added to the series before the divergence, so it
+        // has already been populated for the last candle
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let value = Self::oscillator_value(candle, &oscillator);
+        let divergence = Self::calculate_rolling(&mut state, candle, value);
+
+        candle
+            .indicators
+            .insert(indicator_type, Indicator::Divergence(divergence));
+
+        ts.set_indicator_state(indicator_type, IndicatorState::Divergence(state));
+
+        Ok(())
+    }
+}
+
+impl DefinesIndicator for Divergence {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "divergence",
+        params: &[
+            ParamSchema {
+                name: "oscillator",
+                default: ParamValue::Indicator(DEFAULT_OSCILLATOR.indicator_type()),
+            },
+            ParamSchema {
+                name: "len",
+                default: ParamValue::Int(5),
+            },
+        ],
+        outputs: &[
+            "regular_bullish",
+            "hidden_bullish",
+            "regular_bearish",
+            "hidden_bearish",
+        ],
+        warmup: |args| Ok(args.indicator(0)?.min_length() + 2 * args.usize(1)?),
+        lag: |_| Ok(0),
+        build: |args| {
+            let oscillator = Oscillator::try_from(args.indicator(0)?)?;
+
+            Ok(IndicatorType::Divergence(oscillator, args.usize(1)?))
+        },
+        args: |indicator_type| match indicator_type {
+            IndicatorType::Divergence(a, b) => Some(IndicatorArgs(vec![
+                ParamValue::Indicator(a.indicator_type()),
+                ParamValue::Int(*b),
+            ])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
+impl IsIndicator for Divergence {
+    fn default_args() -> IndicatorArgs {
+        IndicatorType::Divergence(DEFAULT_OSCILLATOR, 5).args()
+    }
+
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::calculate_args(segment, &Self::default_args())
+    }
+
+    /// Calculates the divergence of the last candle from pivots within the
+    /// segment. The oscillator needs to be populated on the candles.
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let (oscillator, len) = (
+            Oscillator::try_from(args.indicator(0).ok()?).ok()?,
+            args.usize(1).ok()?,
+        );
+        let oscillator = oscillator.indicator_type();
+        let mut state = DivergenceState::new(len);
+
+        segment
+            .iter()
+            .map(|candle| {
+                let value = Self::oscillator_value(candle, &oscillator);
+                Self::calculate_rolling(&mut state, candle, value)
+            })
+            .last()?
+    }
+}
+
+impl Divergence {
+    #[allow(dead_code)]
+    pub fn is_bullish(&self) -> bool {
+        self.regular_bullish || self.hidden_bullish
+    }
+
+    #[allow(dead_code)]
+    pub fn is_bearish(&self) -> bool {
+        self.regular_bearish || self.hidden_bearish
+    }
+
+    fn oscillator_value(candle: &Candle, oscillator: &IndicatorType) -> Option<f64> {
+        candle.indicators.get(oscillator)?.fields().first()?.1
+    }
+
+    fn calculate_rolling(
+        state: &mut DivergenceState,
+        candle: &Candle,
+        value: Option<f64>,
+    ) -> Option<Self> {
+        let window_len = 2 * state.len + 1;
+
+        state.window.push_back((candle.high, candle.low, value));
+        if state.window.len() > window_len {
+            state.window.pop_front();
+        }
+
+        if state.window.len() < window_len {
+            return None;
+        }
+
+        let mut divergence = Self::default();
+        let (high, low, value) = state.window[state.len];
+        let Some(value) = value else {
+            return Some(divergence);
+        };
+
+        if state.window.iter().all(|(h, _, _)| *h <= high) {
+            if let Some((prev_high, prev_value)) = state.last_high {
+                divergence.regular_bearish = high > prev_high && value < prev_value;
+                divergence.hidden_bearish = high < prev_high && value > prev_value;
+            }
+            state.last_high = Some((high, value));
+        }
+
+        if state.window.iter().all(|(_, l, _)| *l >= low) {
+            if let Some((prev_low, prev_value)) = state.last_low {
+                divergence.regular_bullish = low < prev_low && value > prev_value;
+                divergence.hidden_bullish = low > prev_low && value < prev_value;
+            }
+            state.last_low = Some((low, value));
+        }
+
+        Some(divergence)
+    }
+}
+
+/// High, low and oscillator value of the last `2 * len + 1` candles along
+/// with the price and oscillator value of the last confirmed pivots.
+#[derive(Debug, Clone)]
+pub struct DivergenceState {
+    len: usize,
+    window: VecDeque<(f64, f64, Option<f64>)>,
+    last_high: Option<(f64, f64)>,
+    last_low: Option<(f64, f64)>,
+}
+
+impl DivergenceState {
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            window: VecDeque::with_capacity(2 * len + 2),
+            last_high: None,
+            last_low: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            divergence::{Divergence, DivergenceState},
+            indicator_registry::IndicatorRegistry,
+            indicator_type::IndicatorType,
+            oscillator::Oscillator,
+            populates_candles::PopulatesCandlesWithSelf,
+        },
+        models::{
+            candle::Candle, interval::Interval, ma_type::MAType,
+            timeseries_builder::TimeSeriesBuilder,
+        },
+        utils::data::candle_strategy,
+    };
+    use chrono::{TimeZone, Utc};
+
+    /// Divergences of candles given as (high, low, oscillator value).
+    fn divergences(bars: &[(f64, f64, f64)], len: usize) -> Vec<Option<Divergence>> {
+        let timestamp = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let mut state = DivergenceState::new(len);
+
+        bars.iter()
+            .map(|(high, low, value)| {
+                let candle = Candle::new(timestamp, *low, *high, *high, *low, 1.0);
+                Divergence::calculate_rolling(&mut state, &candle, Some(*value))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn regular_and_hidden_divergences() {
+        // Lower low in price with a higher low in the oscillator, confirmed
+        // two candles after the second low
+        let bars = [
+            (12.0, 10.0, 40.0),
+            (11.0, 9.0, 30.0),
+            (12.0, 10.0, 40.0),
+            (12.0, 10.0, 45.0),
+            (11.0, 8.0, 35.0),
+            (12.0, 10.0, 45.0),
+            (12.0, 10.0, 45.0),
+        ];
+        let result = divergences(&bars, 1);
+
+        assert!(result[0].is_none());
+        assert_eq!(result[2], Some(Divergence::default()));
+        assert!(!result[4].unwrap().regular_bullish);
+        assert!(result[5].unwrap().regular_bullish);
+        assert!(!result[5].unwrap().hidden_bullish);
+
+        // Lower high in price with a higher high in the oscillator
+        let bars = [
+            (10.0, 8.0, 50.0),
+            (12.0, 9.0, 60.0),
+            (10.0, 8.0, 50.0),
+            (11.0, 8.0, 70.0),
+            (10.0, 8.0, 50.0),
+        ];
+        let result = divergences(&bars, 1);
+
+        assert!(result[4].unwrap().hidden_bearish);
+        assert!(!result[4].unwrap().regular_bearish);
+        assert!(result[4].unwrap().is_bearish());
+    }
+
+    #[test]
+    fn populates_oscillator_and_parses_spec() {
+        let rsi = IndicatorType::RSI(14, MAType::RMA);
+        let indicator_type = IndicatorType::Divergence(Oscillator::RSI(14, MAType::RMA), 3);
+
+        let parsed = IndicatorRegistry::parse("divergence(rsi(14,rma),3)").unwrap();
+        assert_eq!(parsed, indicator_type);
+        assert_eq!(indicator_type.to_string(), "divergence(rsi(14,rma),3)");
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candle_strategy::price_change_candles())
+            .build();
+        indicator_type.populate_candles(&mut ts).unwrap();
+
+        assert!(ts.indicators.contains(&rsi));
+        assert!(ts.candles[5].indicators[&indicator_type]
+            .as_divergence()
+            .is_none());
+        assert!(ts.candles[6].indicators[&indicator_type]
+            .as_divergence()
+            .is_some());
+    }
+}
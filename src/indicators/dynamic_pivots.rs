@@ -1,7 +1,13 @@
 use crate::{
     indicators::{
-        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+        rolling::rolling_extreme::RollingExtreme,
     },
     models::{candle::Candle, timeseries::TimeSeries},
 };
@@ -23,73 +29,67 @@ impl PopulatesCandles for DynamicPivots {
     }
 
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let len = args.len_res()?;
+        let len = args.usize(0)?;
         let indicator_type = IndicatorType::DynamicPivot(len);
-        let min_len = 2 * len + 1;
+        let mut state = DynamicPivotsState::new(len);
 
-        for i in 0..ts.candles.len() {
-            let end = i + 1;
-            let pivot = if end < min_len {
-                None
-            } else {
-                Self::calculate_args(&ts.candles[end - min_len..end], &args)
-            };
-
-            // Since the dynamic pivots are populated for the "len/2"-nth
-            // (by default 15+1 = 16) candle we need extra handling to select
-            // the correct index when populating.
-            let j = if end <= len { i } else { i - len };
-
-            ts.candles[j]
-                .indicators
-                .insert(indicator_type, Indicator::DynamicPivot(pivot));
+        for end in 1..=ts.candles.len() {
+            Self::calculate_rolling(&mut state, &mut ts.candles[..end])?;
         }
 
+        ts.set_indicator_state(indicator_type, IndicatorState::DynamicPivot(state));
         ts.indicators.insert(indicator_type);
 
         Ok(())
     }
 
     fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
-        Self::populate_candles_args(ts, Self::default_args())
+        Self::populate_last_candle_args(ts, Self::default_args())
     }
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let len = args.len_res()?;
-        let ctx_err = "Unable to get last candle";
+        let len = args.usize(0)?;
         let indicator_type = IndicatorType::DynamicPivot(len);
-        let candle_len = ts.candles.len();
-        let min_len = 2 * len + 1;
-        if candle_len == 0 {
-            return Err(anyhow!("No candle to populate"));
-        }
-
-        if candle_len < len {
-            // Not enough candles to populate
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::DynamicPivot(None));
 
-            return Ok(());
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
         }
 
-        let new_pivot = Self::calculate_args(&ts.candles[candle_len - min_len..candle_len], &args);
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::DynamicPivot(state)) => state,
+            _ => return Self::populate_candles_args(ts, args),
+        };
 
-        ts.candles
-            .get_mut(candle_len - (len + 1))
-            .context(ctx_err)?
-            .indicators
-            .insert(indicator_type, Indicator::DynamicPivot(new_pivot));
+        Self::calculate_rolling(&mut state, &mut ts.candles)?;
+        ts.set_indicator_state(indicator_type, IndicatorState::DynamicPivot(state));
 
         Ok(())
     }
 }
 
+impl DefinesIndicator for DynamicPivots {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "pivots",
+        params: &[ParamSchema {
+            name: "len",
+            default: ParamValue::Int(15),
+        }],
+        outputs: &["high", "low"],
+        warmup: |args| Ok(args.usize(0)? + 1),
+        lag: |args| args.usize(0),
+        build: |args| Ok(IndicatorType::DynamicPivot(args.usize(0)?)),
+        args: |indicator_type| match indicator_type {
+            IndicatorType::DynamicPivot(a) => Some(IndicatorArgs(vec![ParamValue::Int(*a)])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
 impl IsIndicator for DynamicPivots {
     fn default_args() -> IndicatorArgs {
-        IndicatorArgs::LengthArg(15)
+        IndicatorType::DynamicPivot(15).args()
     }
 
     fn calculate(segment: &[Candle]) -> Option<Self>
@@ -132,7 +132,7 @@ impl IsIndicator for DynamicPivots {
     where
         Self: Sized,
     {
-        let len = args.len_opt()?;
+        let len = args.usize(0).ok()?;
         let segment_len = segment.len();
 
         if segment_len < 2 * len + 1 {
@@ -151,4 +151,117 @@ impl DynamicPivots {
             low: None,
         }
     }
+
+    /// Adds the last candle to the rolling state and populates the pivot of
+    /// the candle `len` candles before it, which is the center of the
+    /// window.
+    fn calculate_rolling(state: &mut DynamicPivotsState, candles: &mut [Candle]) -> Result<()> {
+        let ctx_err = "Unable to get pivot candle";
+        let len = state.len;
+        let indicator_type = IndicatorType::DynamicPivot(len);
+        let last = candles.len().checked_sub(1).context(ctx_err)?;
+
+        state.highs.push(candles[last].high);
+        state.lows.push(candles[last].low);
+
+        let (Some(high), Some(low)) = (state.highs.value(), state.lows.value()) else {
+            // Since the dynamic pivots are populated for the "len/2"-nth
+            // (by default 15+1 = 16) candle we need extra handling to select
+            // the correct index when populating.
+            let j = if state.highs.pushes() <= len {
+                last
+            } else {
+                last.checked_sub(len).context(ctx_err)?
+            };
+
+            candles[j]
+                .indicators
+                .insert(indicator_type, Indicator::DynamicPivot(None));
+
+            return Ok(());
+        };
+
+        let center = last.checked_sub(len).context(ctx_err)?;
+        let candle = &candles[center];
+
+        let prev = center
+            .checked_sub(1)
+            .and_then(|i| candles[i].indicators.get(&indicator_type))
+            .and_then(|p| p.as_dynamic_pivots());
+
+        let mut pivots = DynamicPivots::new_empty(len);
+
+        pivots.high = if high <= candle.high {
+            Some(candle.high)
+        } else {
+            prev.and_then(|p| p.high)
+        };
+
+        pivots.low = if low >= candle.low {
+            Some(candle.low)
+        } else {
+            prev.and_then(|p| p.low)
+        };
+
+        candles[center]
+            .indicators
+            .insert(indicator_type, Indicator::DynamicPivot(Some(pivots)));
+
+        Ok(())
+    }
+}
+
+/// Rolling state of the dynamic pivots: the highest high and lowest low of
+/// the last `2 * len + 1` candles.
+#[derive(Debug, Clone)]
+pub struct DynamicPivotsState {
+    len: usize,
+    highs: RollingExtreme,
+    lows: RollingExtreme,
+}
+
+impl DynamicPivotsState {
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            highs: RollingExtreme::max(2 * len + 1),
+            lows: RollingExtreme::min(2 * len + 1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            dynamic_pivots::DynamicPivots, indicator_type::IndicatorType, is_indicator::IsIndicator,
+        },
+        utils::data::candle_strategy,
+    };
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn pivots_matches_recomputation(
+            candles in candle_strategy::candles(1, 80),
+            len in 1..10usize,
+        ) {
+            let args = IndicatorType::DynamicPivot(len).args();
+            let indicator_type = IndicatorType::DynamicPivot(len);
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            // Each pivot depends on the previous one so compare against the
+            // window calculation on the populated candles.
+            for center in len..candles.len().saturating_sub(len) {
+                let segment = &populated[center - len..=center + len];
+                let expected = DynamicPivots::calculate_args(segment, &args);
+                let actual = populated[center]
+                    .clone_indicator(&indicator_type)
+                    .unwrap()
+                    .as_dynamic_pivots();
+
+                prop_assert_eq!(actual.map(|p| (p.high, p.low)), expected.map(|p| (p.high, p.low)));
+            }
+        }
+    }
 }
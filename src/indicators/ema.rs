@@ -1,7 +1,11 @@
 use crate::{
     indicators::{
-        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
     },
     models::{candle::Candle, timeseries::TimeSeries},
     utils::math::{ema_rolling, sma},
@@ -21,7 +25,7 @@ impl PopulatesCandles for EMA {
     }
 
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let len = args.len_res()?;
+        let len = args.usize(0)?;
         let indicator_type = IndicatorType::EMA(len);
 
         let mut prev_ema: Option<EMA> = None;
@@ -54,7 +58,7 @@ impl PopulatesCandles for EMA {
     }
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let len = args.len_res()?;
+        let len = args.usize(0)?;
         let indicator_type = IndicatorType::EMA(len);
         let end = ts.candles.len();
         let ctx_err = "Unable to get last candle";
@@ -64,7 +68,7 @@ impl PopulatesCandles for EMA {
         }
 
         // Not enough candles to get new EMA
-        if end < len {
+        if end <= len {
             ts.candles
                 .last_mut()
                 .context(ctx_err)?
@@ -79,9 +83,8 @@ impl PopulatesCandles for EMA {
             .and_then(|indicator| indicator.as_ema());
 
         let new_ema = if prev.is_none() {
-            let start = end - len;
-            let end = end - 1;
-            Self::calculate_args(&ts.candles[start..end], &args)
+            // Seed from the same len + 1 candles as when populating all
+            Self::calculate_args(&ts.candles[end - len - 1..end], &args)
         } else {
             let prev = prev.unwrap();
             let current = ts.candles.last().unwrap().close;
@@ -98,9 +101,29 @@ impl PopulatesCandles for EMA {
     }
 }
 
+impl DefinesIndicator for EMA {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "ema",
+        params: &[ParamSchema {
+            name: "len",
+            default: ParamValue::Int(8),
+        }],
+        outputs: &["value"],
+        warmup: |args| Ok(args.usize(0)? + 1),
+        lag: |_| Ok(0),
+        build: |args| Ok(IndicatorType::EMA(args.usize(0)?)),
+        args: |indicator_type| match indicator_type {
+            IndicatorType::EMA(a) => Some(IndicatorArgs(vec![ParamValue::Int(*a)])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
 impl IsIndicator for EMA {
     fn default_args() -> IndicatorArgs {
-        IndicatorArgs::LengthArg(8)
+        IndicatorType::EMA(8).args()
     }
 
     /// Segment should be one candle longer than the length of EMA wanted.
@@ -127,7 +150,7 @@ impl IsIndicator for EMA {
     where
         Self: Sized,
     {
-        let len = args.len_opt()?;
+        let len = args.usize(0).ok()?;
         let candle_len = segment.len();
 
         if candle_len < len + 1 {
@@ -151,8 +174,8 @@ impl EMA {
 mod tests {
     use crate::{
         indicators::{
-            ema::EMA, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-            is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+            ema::EMA, indicator_type::IndicatorType, is_indicator::IsIndicator,
+            populates_candles::PopulatesCandles,
         },
         models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
     };
@@ -170,7 +193,7 @@ mod tests {
     #[test]
     fn ema_calculate_args() {
         let candles = Candle::dummy_data(8, "positive", 100.0);
-        let args = IndicatorArgs::LengthArg(7);
+        let args = IndicatorType::EMA(7).args();
         let ema = EMA::calculate_args(&candles, &args);
         assert!(ema.is_some());
 
@@ -204,7 +227,7 @@ mod tests {
 
         let _ = EMA::populate_candles(&mut ts);
 
-        let len = EMA::default_args().len_opt().unwrap();
+        let len = EMA::default_args().usize(0).unwrap();
         let indicator_type = IndicatorType::EMA(len);
 
         for (i, candle) in ts.candles.iter().enumerate() {
@@ -241,7 +264,7 @@ mod tests {
         let _ = EMA::populate_candles(&mut ts);
         let _ = ts.add_candle(&candle);
 
-        let len = EMA::default_args().len_opt().unwrap();
+        let len = EMA::default_args().usize(0).unwrap();
         let indicator_type = IndicatorType::EMA(len);
 
         for (i, candle) in ts.candles.iter().enumerate() {
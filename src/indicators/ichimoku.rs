@@ -0,0 +1,402 @@
+use std::collections::VecDeque;
+
+use crate::{
+    indicators::{
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+        rolling::rolling_extreme::RollingExtreme,
+    },
+    models::{candle::Candle, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Context, Result};
+
+/// # Ichimoku
+///
+/// Ichimoku Cloud. The conversion and base lines are the midpoints of the
+/// highest high and lowest low over their lengths. Leading span A is the
+/// average of the two lines and leading span B the midpoint over the span B
+/// length. The leading spans are plotted `displacement - 1` candles ahead, so
+/// `span_a` and `span_b` hold the cloud at the current candle while `lead_a`
+/// and `lead_b` hold the cloud being projected forward from it.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct Ichimoku {
+    pub conversion: f64,
+    pub base: Option<f64>,
+    pub span_a: Option<f64>,
+    pub span_b: Option<f64>,
+    pub lead_a: Option<f64>,
+    pub lead_b: Option<f64>,
+}
+
+impl PopulatesCandles for Ichimoku {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (conversion, base, span_b, displacement) = (
+            args.usize(0)?,
+            args.usize(1)?,
+            args.usize(2)?,
+            args.usize(3)?,
+        );
+        let indicator_type = IndicatorType::Ichimoku(conversion, base, span_b, displacement);
+        let mut state = IchimokuState::new(conversion, base, span_b, displacement);
+
+        for candle in ts.candles.iter_mut() {
+            let ichimoku = Self::calculate_rolling(&mut state, candle);
+
+            candle
+                .indicators
+                .insert(indicator_type, Indicator::Ichimoku(ichimoku));
+        }
+
+        ts.set_indicator_state(indicator_type, IndicatorState::Ichimoku(Box::new(state)));
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (conversion, base, span_b, displacement) = (
+            args.usize(0)?,
+            args.usize(1)?,
+            args.usize(2)?,
+            args.usize(3)?,
+        );
+        let ctx_err = "Unable to get last candle";
+        let indicator_type = IndicatorType::Ichimoku(conversion, base, span_b, displacement);
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::Ichimoku(state)) => state,
+            _ => return Self::populate_candles_args(ts, args),
+        };
+
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let ichimoku = Self::calculate_rolling(&mut state, candle);
+
+        candle
+            .indicators
+            .insert(indicator_type, Indicator::Ichimoku(ichimoku));
+
+        ts.set_indicator_state(indicator_type, IndicatorState::Ichimoku(state));
+
+        Ok(())
+    }
+}
+
+impl DefinesIndicator for Ichimoku {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "ichimoku",
+        params: &[
+            ParamSchema {
+                name: "conversion",
+                default: ParamValue::Int(9),
+            },
+            ParamSchema {
+                name: "base",
+                default: ParamValue::Int(26),
+            },
+            ParamSchema {
+                name: "span_b",
+                default: ParamValue::Int(52),
+            },
+            ParamSchema {
+                name: "displacement",
+                default: ParamValue::Int(26),
+            },
+        ],
+        outputs: &["conversion", "base", "span_a", "span_b", "lead_a", "lead_b"],
+        warmup: |args| {
+            let (conversion, base, span_b) = (args.usize(0)?, args.usize(1)?, args.usize(2)?);
+            Ok(conversion.max(base).max(span_b) + args.usize(3)?.saturating_sub(1))
+        },
+        lag: |_| Ok(0),
+        build: |args| {
+            Ok(IndicatorType::Ichimoku(
+                args.usize(0)?,
+                args.usize(1)?,
+                args.usize(2)?,
+                args.usize(3)?,
+            ))
+        },
+        args: |indicator_type| match indicator_type {
+            IndicatorType::Ichimoku(a, b, c, d) => Some(IndicatorArgs(vec![
+                ParamValue::Int(*a),
+                ParamValue::Int(*b),
+                ParamValue::Int(*c),
+                ParamValue::Int(*d),
+            ])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
+impl IsIndicator for Ichimoku {
+    fn default_args() -> IndicatorArgs {
+        IndicatorType::Ichimoku(9, 26, 52, 26).args()
+    }
+
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::calculate_args(segment, &Self::default_args())
+    }
+
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let (conversion, base, span_b, displacement) = (
+            args.usize(0).ok()?,
+            args.usize(1).ok()?,
+            args.usize(2).ok()?,
+            args.usize(3).ok()?,
+        );
+        let mut state = IchimokuState::new(conversion, base, span_b, displacement);
+
+        segment
+            .iter()
+            .map(|candle| Self::calculate_rolling(&mut state, candle))
+            .last()?
+    }
+}
+
+impl Ichimoku {
+    fn calculate_rolling(state: &mut IchimokuState, candle: &Candle) -> Option<Self> {
+        let conversion = state.conversion.push(candle);
+        let base = state.base.push(candle);
+        let lead_b = state.span_b.push(candle);
+        let lead_a = conversion
+            .zip(base)
+            .map(|(conversion, base)| (conversion + base) / 2.0);
+
+        // The cloud at this candle was projected `displacement - 1` candles ago
+        state.leads.push_back((lead_a, lead_b));
+        let (span_a, span_b) = if state.leads.len() > state.displacement.saturating_sub(1) {
+            state.leads.pop_front().unwrap_or((None, None))
+        } else {
+            (None, None)
+        };
+
+        Some(Ichimoku {
+            conversion: conversion?,
+            base,
+            span_a,
+            span_b,
+            lead_a,
+            lead_b,
+        })
+    }
+}
+
+/// Highest high and lowest low over a window, used for the midpoint lines.
+#[derive(Debug, Clone)]
+struct Midpoint {
+    high: RollingExtreme,
+    low: RollingExtreme,
+}
+
+impl Midpoint {
+    fn new(len: usize) -> Self {
+        Self {
+            high: RollingExtreme::max(len),
+            low: RollingExtreme::min(len),
+        }
+    }
+
+    fn push(&mut self, candle: &Candle) -> Option<f64> {
+        self.high.push(candle.high);
+        self.low.push(candle.low);
+
+        Some((self.high.value()? + self.low.value()?) / 2.0)
+    }
+}
+
+/// Midpoint windows of the Ichimoku lines along with the leading spans not
+/// yet reached.
+#[derive(Debug, Clone)]
+pub struct IchimokuState {
+    conversion: Midpoint,
+    base: Midpoint,
+    span_b: Midpoint,
+    displacement: usize,
+    leads: VecDeque<(Option<f64>, Option<f64>)>,
+}
+
+impl IchimokuState {
+    pub fn new(conversion: usize, base: usize, span_b: usize, displacement: usize) -> Self {
+        Self {
+            conversion: Midpoint::new(conversion),
+            base: Midpoint::new(base),
+            span_b: Midpoint::new(span_b),
+            displacement,
+            leads: VecDeque::with_capacity(displacement),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            ichimoku::Ichimoku, indicator_type::IndicatorType, is_indicator::IsIndicator,
+            populates_candles::PopulatesCandles,
+        },
+        models::{interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::candle_strategy,
+    };
+    use proptest::prelude::*;
+
+    #[test]
+    fn ichimoku_populate_candles() {
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candle_strategy::price_change_candles())
+            .build();
+
+        Ichimoku::populate_candles(&mut ts).unwrap();
+
+        let indicator_type = IndicatorType::Ichimoku(9, 26, 52, 26);
+        let ichimokus: Vec<Option<Ichimoku>> = ts
+            .candles
+            .iter()
+            .map(|c| c.clone_indicator(&indicator_type).unwrap().as_ichimoku())
+            .collect();
+
+        assert!(ichimokus[7].is_none());
+        assert!(ichimokus[8].unwrap().base.is_none());
+        assert!(ichimokus[25].unwrap().lead_a.is_some());
+        assert!(ichimokus[49].unwrap().span_a.is_none());
+        assert!(ichimokus[50].unwrap().span_a.is_some());
+        assert!(ichimokus[75].unwrap().span_b.is_none());
+        assert!(ichimokus[76].unwrap().span_b.is_some());
+    }
+
+    #[test]
+    fn ichimoku_worked_example() {
+        let candles = candle_strategy::hlc_candles(&[
+            (10.0, 8.0, 9.0),
+            (12.0, 9.0, 11.0),
+            (11.0, 7.0, 8.0),
+            (14.0, 10.0, 13.0),
+            (13.0, 11.0, 12.0),
+            (15.0, 12.0, 14.0),
+        ]);
+        let indicator_type = IndicatorType::Ichimoku(2, 3, 4, 2);
+        let populated = candle_strategy::populated(&candles, indicator_type);
+        let ichimokus: Vec<Option<Ichimoku>> = populated
+            .iter()
+            .map(|c| c.clone_indicator(&indicator_type).unwrap().as_ichimoku())
+            .collect();
+
+        // Midpoints of the highest high and lowest low over 2, 3 and 4
+        // candles, with the leads of each candle becoming the spans of the
+        // next one
+        let expected = [
+            None,
+            Some(Ichimoku {
+                conversion: 10.0,
+                base: None,
+                span_a: None,
+                span_b: None,
+                lead_a: None,
+                lead_b: None,
+            }),
+            Some(Ichimoku {
+                conversion: 9.5,
+                base: Some(9.5),
+                span_a: None,
+                span_b: None,
+                lead_a: Some(9.5),
+                lead_b: None,
+            }),
+            Some(Ichimoku {
+                conversion: 10.5,
+                base: Some(10.5),
+                span_a: Some(9.5),
+                span_b: None,
+                lead_a: Some(10.5),
+                lead_b: Some(10.5),
+            }),
+            Some(Ichimoku {
+                conversion: 12.0,
+                base: Some(10.5),
+                span_a: Some(10.5),
+                span_b: Some(10.5),
+                lead_a: Some(11.25),
+                lead_b: Some(10.5),
+            }),
+            Some(Ichimoku {
+                conversion: 13.0,
+                base: Some(12.5),
+                span_a: Some(11.25),
+                span_b: Some(10.5),
+                lead_a: Some(12.75),
+                lead_b: Some(11.0),
+            }),
+        ];
+
+        for (ichimoku, expected) in ichimokus.iter().zip(expected) {
+            let (Some(ichimoku), Some(expected)) = (ichimoku, expected) else {
+                assert_eq!(ichimoku.is_some(), expected.is_some());
+                continue;
+            };
+
+            assert!((ichimoku.conversion - expected.conversion).abs() < 1e-9);
+            for (a, b) in [
+                (ichimoku.base, expected.base),
+                (ichimoku.span_a, expected.span_a),
+                (ichimoku.span_b, expected.span_b),
+                (ichimoku.lead_a, expected.lead_a),
+                (ichimoku.lead_b, expected.lead_b),
+            ] {
+                assert_eq!(a.is_some(), b.is_some());
+                assert!((a.unwrap_or(0.0) - b.unwrap_or(0.0)).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn ichimoku_no_candles() {
+        let candles = Vec::new();
+        let ichimoku = Ichimoku::calculate(&candles);
+        assert!(ichimoku.is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn ichimoku_matches_recomputation(
+            candles in candle_strategy::candles(1, 80),
+            conversion in 1..10usize,
+            base in 1..20usize,
+            span_b in 1..30usize,
+            displacement in 1..20usize,
+        ) {
+            let args = IndicatorType::Ichimoku(conversion, base, span_b, displacement).args();
+            let indicator_type = IndicatorType::Ichimoku(conversion, base, span_b, displacement);
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            let last = populated.last().unwrap();
+            let actual = last.clone_indicator(&indicator_type).unwrap().as_ichimoku();
+            prop_assert_eq!(actual, Ichimoku::calculate_args(&candles, &args));
+        }
+    }
+}
@@ -1,8 +1,30 @@
 use crate::{
     indicators::{
-        atr::ATR, bbw::BBW, bbwp::BBWP, bollinger_bands::BollingerBands,
-        dynamic_pivots::DynamicPivots, ema::EMA, indicator_type::IndicatorType, pmar::PMAR,
-        pmarp::PMARP, rsi::RSI, sma::SMA, stochastic::Stochastic,
+        adx::ADX,
+        atr::ATR,
+        bbw::BBW,
+        bbwp::BBWP,
+        bollinger_bands::BollingerBands,
+        candle_patterns::{CandlePattern, CandlePatterns},
+        cmf::CMF,
+        divergence::Divergence,
+        dynamic_pivots::DynamicPivots,
+        ema::EMA,
+        ichimoku::Ichimoku,
+        indicator_type::IndicatorType,
+        macd::MACD,
+        mfi::MFI,
+        obv::OBV,
+        pmar::PMAR,
+        pmarp::PMARP,
+        psar::PSAR,
+        rsi::RSI,
+        sma::SMA,
+        sr_zones::SRZones,
+        stochastic::Stochastic,
+        supertrend::Supertrend,
+        volume_profile::VolumeProfile,
+        vwap::VWAP,
     },
     models::timeseries::TimeSeries,
 };
@@ -21,6 +43,19 @@ pub enum Indicator {
     PMAR(Option<PMAR>),
     PMARP(Option<PMARP>),
     Stochastic(Option<Stochastic>),
+    VWAP(Option<VWAP>),
+    OBV(Option<OBV>),
+    MFI(Option<MFI>),
+    CMF(Option<CMF>),
+    VolumeProfile(Option<VolumeProfile>),
+    MACD(Option<MACD>),
+    ADX(Option<ADX>),
+    Supertrend(Option<Supertrend>),
+    PSAR(Option<PSAR>),
+    Ichimoku(Option<Ichimoku>),
+    CandlePatterns(Option<CandlePatterns>),
+    SRZones(Option<SRZones>),
+    Divergence(Option<Divergence>),
 }
 
 impl Indicator {
@@ -59,6 +94,116 @@ impl Indicator {
         Self::get_nth_last(ts, indicator_type, 2)
     }
 
+    /// Returns the value of the named output field, e.g. `upper` for
+    /// Bollinger Bands or `d` for the Stochastic. Fields are listed in the
+    /// `outputs` of the indicator definition.
+    pub fn field(&self, name: &str) -> Option<f64> {
+        self.fields()
+            .into_iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .and_then(|(_, value)| value)
+    }
+
+    /// Returns all output fields of the indicator along with their values.
+    /// Fields are returned in the same order as the `outputs` of the
+    /// indicator definition.
+    pub fn fields(&self) -> Vec<(&'static str, Option<f64>)> {
+        match self {
+            Indicator::SMA(i) => vec![("value", i.map(|i| i.value))],
+            Indicator::EMA(i) => vec![("value", i.map(|i| i.value))],
+            Indicator::RSI(i) => vec![("value", i.map(|i| i.value))],
+            Indicator::ATR(i) => vec![("value", i.map(|i| i.value))],
+            Indicator::BollingerBands(i) => vec![
+                ("upper", i.map(|i| i.upper)),
+                ("lower", i.map(|i| i.lower)),
+                ("sma", i.map(|i| i.sma)),
+                ("std", i.map(|i| i.std)),
+            ],
+            Indicator::BBW(i) => vec![("value", i.map(|i| i.value))],
+            Indicator::BBWP(i) => vec![
+                ("value", i.map(|i| i.value)),
+                ("sma", i.and_then(|i| i.sma).map(|sma| sma.value)),
+            ],
+            Indicator::DynamicPivot(i) => vec![
+                ("high", i.and_then(|i| i.high)),
+                ("low", i.and_then(|i| i.low)),
+            ],
+            Indicator::PMAR(i) => vec![("value", i.map(|i| i.value)), ("ma", i.and_then(|i| i.ma))],
+            Indicator::PMARP(i) => {
+                vec![("value", i.map(|i| i.value)), ("ma", i.and_then(|i| i.ma))]
+            }
+            Indicator::Stochastic(i) => vec![("k", i.map(|i| i.k)), ("d", i.map(|i| i.d))],
+            Indicator::VWAP(i) => vec![
+                ("value", i.map(|i| i.value)),
+                ("upper", i.map(|i| i.upper)),
+                ("lower", i.map(|i| i.lower)),
+                ("std", i.map(|i| i.std)),
+            ],
+            Indicator::OBV(i) => vec![
+                ("value", i.map(|i| i.value)),
+                ("signal", i.and_then(|i| i.signal)),
+            ],
+            Indicator::MFI(i) => vec![("value", i.map(|i| i.value))],
+            Indicator::CMF(i) => vec![("value", i.map(|i| i.value))],
+            Indicator::VolumeProfile(i) => vec![
+                ("poc", i.map(|i| i.poc)),
+                ("vah", i.map(|i| i.vah)),
+                ("val", i.map(|i| i.val)),
+            ],
+            Indicator::MACD(i) => vec![
+                ("macd", i.map(|i| i.macd)),
+                ("signal", i.and_then(|i| i.signal)),
+                ("histogram", i.and_then(|i| i.histogram)),
+            ],
+            Indicator::ADX(i) => vec![
+                ("adx", i.and_then(|i| i.adx)),
+                ("plus_di", i.map(|i| i.plus_di)),
+                ("minus_di", i.map(|i| i.minus_di)),
+            ],
+            Indicator::Supertrend(i) => vec![
+                ("value", i.map(|i| i.value)),
+                ("direction", i.map(|i| i.direction())),
+            ],
+            Indicator::PSAR(i) => vec![
+                ("value", i.map(|i| i.value)),
+                ("direction", i.map(|i| i.direction())),
+            ],
+            Indicator::Ichimoku(i) => vec![
+                ("conversion", i.map(|i| i.conversion)),
+                ("base", i.and_then(|i| i.base)),
+                ("span_a", i.and_then(|i| i.span_a)),
+                ("span_b", i.and_then(|i| i.span_b)),
+                ("lead_a", i.and_then(|i| i.lead_a)),
+                ("lead_b", i.and_then(|i| i.lead_b)),
+            ],
+            Indicator::CandlePatterns(i) => CandlePattern::ALL
+                .iter()
+                .map(|p| (p.name(), i.as_ref().map(|i| i.strength(*p))))
+                .collect(),
+            Indicator::SRZones(i) => {
+                let support = i.as_ref().and_then(|i| i.support);
+                let resistance = i.as_ref().and_then(|i| i.resistance);
+
+                vec![
+                    ("support", support.map(|z| z.high)),
+                    ("support_score", support.map(|z| z.score)),
+                    ("resistance", resistance.map(|z| z.low)),
+                    ("resistance_score", resistance.map(|z| z.score)),
+                ]
+            }
+            Indicator::Divergence(i) => {
+                let flag = |set: bool| if set { 1.0 } else { 0.0 };
+
+                vec![
+                    ("regular_bullish", i.map(|i| flag(i.regular_bullish))),
+                    ("hidden_bullish", i.map(|i| flag(i.hidden_bullish))),
+                    ("regular_bearish", i.map(|i| flag(i.regular_bearish))),
+                    ("hidden_bearish", i.map(|i| flag(i.hidden_bearish))),
+                ]
+            }
+        }
+    }
+
     #[allow(dead_code)] // TODO: Remove once used
     pub fn as_sma(&self) -> Option<SMA> {
         if let Indicator::SMA(sma) = self {
@@ -109,6 +254,7 @@ impl Indicator {
         }
     }
 
+    #[allow(dead_code)] // TODO: Remove once used
     pub fn as_bbw(&self) -> Option<BBW> {
         if let Indicator::BBW(bbw) = self {
             bbw.clone()
@@ -125,6 +271,7 @@ impl Indicator {
         }
     }
 
+    #[allow(dead_code)] // TODO: Remove once used
     pub fn as_pmar(&self) -> Option<PMAR> {
         if let Indicator::PMAR(pmar) = self {
             pmar.clone()
@@ -149,4 +296,121 @@ impl Indicator {
             None
         }
     }
+
+    #[allow(dead_code)]
+    pub fn as_vwap(&self) -> Option<VWAP> {
+        if let Indicator::VWAP(vwap) = self {
+            *vwap
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_obv(&self) -> Option<OBV> {
+        if let Indicator::OBV(obv) = self {
+            *obv
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_mfi(&self) -> Option<MFI> {
+        if let Indicator::MFI(mfi) = self {
+            *mfi
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_cmf(&self) -> Option<CMF> {
+        if let Indicator::CMF(cmf) = self {
+            *cmf
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_volume_profile(&self) -> Option<VolumeProfile> {
+        if let Indicator::VolumeProfile(volume_profile) = self {
+            *volume_profile
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_macd(&self) -> Option<MACD> {
+        if let Indicator::MACD(macd) = self {
+            *macd
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_adx(&self) -> Option<ADX> {
+        if let Indicator::ADX(adx) = self {
+            *adx
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_supertrend(&self) -> Option<Supertrend> {
+        if let Indicator::Supertrend(supertrend) = self {
+            *supertrend
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_psar(&self) -> Option<PSAR> {
+        if let Indicator::PSAR(psar) = self {
+            *psar
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_ichimoku(&self) -> Option<Ichimoku> {
+        if let Indicator::Ichimoku(ichimoku) = self {
+            *ichimoku
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_candle_patterns(&self) -> Option<CandlePatterns> {
+        if let Indicator::CandlePatterns(patterns) = self {
+            patterns.clone()
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_sr_zones(&self) -> Option<SRZones> {
+        if let Indicator::SRZones(zones) = self {
+            zones.clone()
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_divergence(&self) -> Option<Divergence> {
+        if let Indicator::Divergence(divergence) = self {
+            *divergence
+        } else {
+            None
+        }
+    }
 }
@@ -0,0 +1,192 @@
+use crate::{
+    indicators::{indicator_args::IndicatorArgs, indicator_type::IndicatorType},
+    models::{ma_type::MAType, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Context, Result};
+use std::fmt::{Display, Formatter};
+
+/// A single indicator parameter value.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ParamValue {
+    Int(usize),
+    Float(f64),
+    MAType(MAType),
+    /// Another indicator, given as a spec such as `rsi(14)`.
+    Indicator(IndicatorType),
+}
+
+impl ParamValue {
+    /// Parses a string into a value of the same kind as `self`.
+    pub fn parse_like(&self, s: &str) -> Result<ParamValue> {
+        let value = match self {
+            ParamValue::Int(_) => ParamValue::Int(s.parse()?),
+            ParamValue::Float(_) => ParamValue::Float(s.parse()?),
+            ParamValue::MAType(_) => ParamValue::MAType(s.parse()?),
+            ParamValue::Indicator(_) => ParamValue::Indicator(s.parse()?),
+        };
+
+        Ok(value)
+    }
+}
+
+impl Display for ParamValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamValue::Int(n) => write!(f, "{}", n),
+            ParamValue::Float(n) => write!(f, "{}", n),
+            ParamValue::MAType(ma_type) => write!(f, "{}", ma_type),
+            ParamValue::Indicator(indicator_type) => write!(f, "{}", indicator_type),
+        }
+    }
+}
+
+/// Name and default value of an indicator parameter. The default also
+/// determines what kind of value the parameter accepts.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ParamSchema {
+    pub name: &'static str,
+    pub default: ParamValue,
+}
+
+/// # IndicatorDefinition
+///
+/// Describes an indicator: its name, parameter schema with defaults, the
+/// output fields it produces and how many candles it needs before producing
+/// values. Also holds the functions used to build and populate it, and to
+/// read the arguments back from an IndicatorType, so that registering the
+/// definition is all an indicator needs to be looked up, parsed and printed.
+#[derive(Debug, Copy, Clone)]
+pub struct IndicatorDefinition {
+    pub name: &'static str,
+    pub params: &'static [ParamSchema],
+    pub outputs: &'static [&'static str],
+    pub warmup: fn(&IndicatorArgs) -> Result<usize>,
+    /// Number of candles before the last one that may change when the last
+    /// candle is populated, e.g. pivots confirmed `len` candles later.
+    pub lag: fn(&IndicatorArgs) -> Result<usize>,
+    pub build: fn(&IndicatorArgs) -> Result<IndicatorType>,
+    /// Inverse of `build`, None for IndicatorTypes of other indicators.
+    pub args: fn(&IndicatorType) -> Option<IndicatorArgs>,
+    pub populate_candles: fn(&mut TimeSeries, IndicatorArgs) -> Result<()>,
+    pub populate_last_candle: fn(&mut TimeSeries, IndicatorArgs) -> Result<()>,
+}
+
+impl IndicatorDefinition {
+    /// Returns the default arguments of the indicator.
+    pub fn default_args(&self) -> IndicatorArgs {
+        IndicatorArgs(self.params.iter().map(|p| p.default).collect())
+    }
+
+    /// Builds an IndicatorType using the default arguments.
+    #[allow(dead_code)]
+    pub fn build_default(&self) -> Result<IndicatorType> {
+        (self.build)(&self.default_args())
+    }
+
+    /// Builds an IndicatorType from the arguments of a spec such as
+    /// `13,252,sma=5`. Positional arguments come first, followed by named
+    /// ones. Parameters not given use their defaults. Indicator parameters
+    /// are given as nested specs, e.g. `rsi(14),5`.
+    pub fn build_from_args(&self, args: &str) -> Result<IndicatorType> {
+        let mut params = self.default_args();
+        let mut assigned = vec![false; self.params.len()];
+        let mut named_seen = false;
+
+        // Empty arguments are rejected rather than skipped, as skipping would
+        // shift the positions of the arguments following them
+        let parts = match args.trim() {
+            "" => vec![],
+            args => split_args(args).into_iter().map(str::trim).collect(),
+        };
+
+        for (i, part) in parts.into_iter().enumerate() {
+            if part.is_empty() {
+                return Err(anyhow!("Empty argument {} for {}.", i + 1, self.name));
+            }
+
+            // Names can't contain parentheses, which separates a named nested
+            // spec like `oscillator=bbwp(13,sma=5)` from a positional one
+            let named = part.split_once('=').filter(|(name, _)| !name.contains('('));
+
+            let (index, raw) = match named {
+                Some((name, raw)) => {
+                    named_seen = true;
+                    let name = name.trim();
+                    let index = self
+                        .params
+                        .iter()
+                        .position(|p| p.name.eq_ignore_ascii_case(name))
+                        .ok_or(anyhow!("Unknown parameter '{}' for {}.", name, self.name))?;
+
+                    (index, raw.trim())
+                }
+                None => {
+                    if named_seen {
+                        return Err(anyhow!(
+                            "Positional argument '{}' after named arguments for {}.",
+                            part,
+                            self.name
+                        ));
+                    }
+                    if i >= self.params.len() {
+                        return Err(anyhow!(
+                            "Too many arguments for {}, expected at most {}.",
+                            self.name,
+                            self.params.len()
+                        ));
+                    }
+
+                    (i, part)
+                }
+            };
+
+            if assigned[index] {
+                return Err(anyhow!(
+                    "Parameter '{}' given more than once for {}.",
+                    self.params[index].name,
+                    self.name
+                ));
+            }
+
+            params.0[index] = self.params[index]
+                .default
+                .parse_like(raw)
+                .with_context(|| {
+                    format!(
+                        "Invalid value '{}' for parameter '{}' of {}.",
+                        raw, self.params[index].name, self.name
+                    )
+                })?;
+            assigned[index] = true;
+        }
+
+        (self.build)(&params)
+    }
+}
+
+/// Splits arguments on the commas outside of nested parentheses.
+fn split_args(args: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, c) in args.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&args[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    parts.push(&args[start..]);
+
+    parts
+}
+
+/// Implemented by indicators to register their definition.
+pub trait DefinesIndicator {
+    const DEFINITION: IndicatorDefinition;
+}
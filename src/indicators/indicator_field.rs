@@ -0,0 +1,124 @@
+use crate::indicators::{indicator_registry::IndicatorRegistry, indicator_type::IndicatorType};
+use anyhow::{anyhow, Error, Result};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+/// # IndicatorField
+///
+/// Reference to a single output of an indicator, parsed from specs such as
+/// `bb(20).upper` or `stoch(14,3,3).d`. When the field is left out the first
+/// output of the indicator is used, so `rsi(14)` refers to `rsi(14).value`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct IndicatorField {
+    pub indicator_type: IndicatorType,
+    pub field: &'static str,
+}
+
+impl IndicatorField {
+    pub fn new(indicator_type: IndicatorType, field: &str) -> Result<Self> {
+        let definition = indicator_type.definition();
+        let field = definition
+            .outputs
+            .iter()
+            .find(|output| output.eq_ignore_ascii_case(field.trim()))
+            .ok_or(anyhow!(
+                "Unknown field '{}' for {}, expected one of: {}.",
+                field.trim(),
+                definition.name,
+                definition.outputs.join(", ")
+            ))?;
+
+        Ok(Self {
+            indicator_type,
+            field,
+        })
+    }
+
+    /// Returns all fields of the given indicator type.
+    pub fn all(indicator_type: IndicatorType) -> Vec<IndicatorField> {
+        indicator_type
+            .definition()
+            .outputs
+            .iter()
+            .map(|field| IndicatorField {
+                indicator_type,
+                field,
+            })
+            .collect()
+    }
+}
+
+impl FromStr for IndicatorField {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+
+        // Fields follow the closing parenthesis, or the name if the spec
+        // has no arguments.
+        let field_start = s.rfind(')').unwrap_or(0);
+        let (spec, field) = match s[field_start..].find('.') {
+            Some(i) => (&s[..field_start + i], Some(&s[field_start + i + 1..])),
+            None => (s, None),
+        };
+
+        let indicator_type = IndicatorRegistry::parse(spec)?;
+
+        match field {
+            Some(field) => Self::new(indicator_type, field),
+            None => Self::new(indicator_type, indicator_type.definition().outputs[0]),
+        }
+    }
+}
+
+impl Display for IndicatorField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.indicator_type, self.field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{indicator_field::IndicatorField, indicator_type::IndicatorType},
+        models::ma_type::MAType,
+    };
+
+    #[test]
+    fn parse_fields() {
+        let field: IndicatorField = "bb(20).upper".parse().unwrap();
+        assert_eq!(
+            field.indicator_type,
+            IndicatorType::BollingerBands(20, 2.0.into())
+        );
+        assert_eq!(field.field, "upper");
+
+        let field: IndicatorField = "stoch(14,3,3).d".parse().unwrap();
+        assert_eq!(
+            field.indicator_type,
+            IndicatorType::Stochastic(14, 3, 3, MAType::SMA)
+        );
+        assert_eq!(field.field, "d");
+
+        let field: IndicatorField = "pmarp(ma=ema).ma".parse().unwrap();
+        assert_eq!(
+            field.indicator_type,
+            IndicatorType::PMARP(20, 350, MAType::EMA, MAType::SMA)
+        );
+        assert_eq!(field.field, "ma");
+
+        let field: IndicatorField = "bb(std=2.5)".parse().unwrap();
+        assert_eq!(field.field, "upper");
+
+        let field: IndicatorField = "rsi.value".parse().unwrap();
+        assert_eq!(field.indicator_type, IndicatorType::RSI(14, MAType::RMA));
+
+        let field: IndicatorField = "rsi".parse().unwrap();
+        assert_eq!(field.to_string(), "rsi(14,rma).value");
+
+        assert!("rsi(14).upper".parse::<IndicatorField>().is_err());
+        assert!("foo(14).value".parse::<IndicatorField>().is_err());
+    }
+}
@@ -0,0 +1,444 @@
+use crate::indicators::{
+    adx::ADX,
+    atr::ATR,
+    bbw::BBW,
+    bbwp::BBWP,
+    bollinger_bands::BollingerBands,
+    candle_patterns::CandlePatterns,
+    cmf::CMF,
+    divergence::Divergence,
+    dynamic_pivots::DynamicPivots,
+    ema::EMA,
+    ichimoku::Ichimoku,
+    indicator_definition::{DefinesIndicator, IndicatorDefinition},
+    indicator_type::IndicatorType,
+    macd::MACD,
+    mfi::MFI,
+    obv::OBV,
+    pmar::PMAR,
+    pmarp::PMARP,
+    psar::PSAR,
+    rsi::RSI,
+    sma::SMA,
+    sr_zones::SRZones,
+    stochastic::Stochastic,
+    supertrend::Supertrend,
+    volume_profile::VolumeProfile,
+    vwap::VWAP,
+};
+use anyhow::{anyhow, Result};
+
+const DEFINITIONS: &[IndicatorDefinition] = &[
+    SMA::DEFINITION,
+    EMA::DEFINITION,
+    RSI::DEFINITION,
+    ATR::DEFINITION,
+    BollingerBands::DEFINITION,
+    BBW::DEFINITION,
+    BBWP::DEFINITION,
+    DynamicPivots::DEFINITION,
+    PMAR::DEFINITION,
+    PMARP::DEFINITION,
+    Stochastic::DEFINITION,
+    VWAP::DEFINITION,
+    OBV::DEFINITION,
+    MFI::DEFINITION,
+    CMF::DEFINITION,
+    VolumeProfile::DEFINITION,
+    MACD::DEFINITION,
+    ADX::DEFINITION,
+    Supertrend::DEFINITION,
+    PSAR::DEFINITION,
+    Ichimoku::DEFINITION,
+    CandlePatterns::DEFINITION,
+    SRZones::DEFINITION,
+    Divergence::DEFINITION,
+];
+
+/// # IndicatorRegistry
+///
+/// Lookup of all available indicators by name. Indicators can be constructed
+/// from string specs such as `bbwp(13,252,sma=5)`, which makes it possible
+/// to request any indicator with full parameters from strategies and config
+/// files.
+pub struct IndicatorRegistry;
+
+impl IndicatorRegistry {
+    /// Returns the definitions of all registered indicators.
+    #[allow(dead_code)]
+    pub fn all() -> &'static [IndicatorDefinition] {
+        DEFINITIONS
+    }
+
+    /// Returns the definition of the indicator with the given name.
+    pub fn get(name: &str) -> Option<&'static IndicatorDefinition> {
+        DEFINITIONS
+            .iter()
+            .find(|d| d.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Returns the definition of the indicator of the given type. Panics
+    /// for indicators whose definition isn't registered.
+    pub fn definition_of(indicator_type: &IndicatorType) -> &'static IndicatorDefinition {
+        DEFINITIONS
+            .iter()
+            .find(|d| (d.args)(indicator_type).is_some())
+            .unwrap_or_else(|| panic!("No indicator registered for {:?}.", indicator_type))
+    }
+
+    /// Parses an indicator spec into an IndicatorType. Specs consist of the
+    /// indicator name optionally followed by arguments in parentheses, for
+    /// example `rsi`, `rsi(14)` or `bbwp(13,252,sma=5)`.
+    pub fn parse(spec: &str) -> Result<IndicatorType> {
+        let spec = spec.trim();
+
+        let (name, args) = match spec.split_once('(') {
+            Some((name, rest)) => {
+                let args = rest
+                    .strip_suffix(')')
+                    .ok_or(anyhow!("Missing closing parenthesis in '{}'.", spec))?;
+                (name, args)
+            }
+            None => (spec, ""),
+        };
+
+        let definition = Self::get(name).ok_or(anyhow!("Unknown indicator '{}'.", name.trim()))?;
+
+        definition.build_from_args(args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            adx::ADX, atr::ATR, bbw::BBW, bbwp::BBWP, bollinger_bands::BollingerBands,
+            candle_patterns::CandlePatterns, cmf::CMF, divergence::Divergence,
+            dynamic_pivots::DynamicPivots, ema::EMA, ichimoku::Ichimoku,
+            indicator_registry::IndicatorRegistry, indicator_type::IndicatorType,
+            is_indicator::IsIndicator, macd::MACD, mfi::MFI, obv::OBV, oscillator::Oscillator,
+            pmar::PMAR, pmarp::PMARP, psar::PSAR, rsi::RSI, sma::SMA, sr_zones::SRZones,
+            stochastic::Stochastic, supertrend::Supertrend, volume_profile::VolumeProfile,
+            vwap::VWAP,
+        },
+        models::{ma_type::MAType, traits::has_min_length::HasMinLength},
+        utils::data::candle_strategy,
+    };
+    use chrono::{TimeZone, Utc};
+    use proptest::{
+        prelude::*,
+        strategy::BoxedStrategy,
+        test_runner::{Config, TestRunner},
+    };
+
+    #[test]
+    fn parse_specs() {
+        let bbwp = IndicatorRegistry::parse("bbwp(13,252,sma=5)").unwrap();
+        assert_eq!(bbwp, IndicatorType::BBWP(13, 252, 5, MAType::SMA));
+
+        let bbwp = IndicatorRegistry::parse("BBWP(10, sma = 8)").unwrap();
+        assert_eq!(bbwp, IndicatorType::BBWP(10, 252, 8, MAType::SMA));
+
+        let bb = IndicatorRegistry::parse("bb(std=2.5)").unwrap();
+        assert_eq!(bb, IndicatorType::BollingerBands(20, 2.5.into()));
+
+        let pmarp = IndicatorRegistry::parse("pmarp(ma=ema)").unwrap();
+        assert_eq!(
+            pmarp,
+            IndicatorType::PMARP(20, 350, MAType::EMA, MAType::SMA)
+        );
+
+        let rsi: IndicatorType = "rsi".parse().unwrap();
+        assert_eq!(rsi, IndicatorType::RSI(14, MAType::RMA));
+
+        let divergence = IndicatorRegistry::parse("divergence(oscillator=bbwp(13,sma=5),3)");
+        assert!(divergence.is_err());
+
+        let divergence =
+            IndicatorRegistry::parse("divergence(oscillator=bbwp(13,sma=5),len=3)").unwrap();
+        assert_eq!(
+            divergence,
+            IndicatorType::Divergence(Oscillator::BBWP(13, 252, 5, MAType::SMA), 3)
+        );
+    }
+
+    #[test]
+    fn spec_round_trip() {
+        for definition in IndicatorRegistry::all() {
+            let indicator_type = definition.build_default().unwrap();
+            let parsed: IndicatorType = indicator_type.to_string().parse().unwrap();
+
+            assert_eq!(parsed, indicator_type);
+        }
+    }
+
+    #[test]
+    fn invalid_specs() {
+        let specs = [
+            "foo(1)",
+            "rsi(14",
+            "rsi(14,2)",
+            "rsi(length=14)",
+            "rsi(abc)",
+            "bbwp(sma=5,13)",
+            "bbwp(13,len=13)",
+            "pmar(20,foo)",
+            "bbwp(13,,5)",
+            "bbwp(13,252,)",
+            "rsi(,14)",
+            "divergence(sma(20),3)",
+            "divergence(divergence,3)",
+        ];
+
+        for spec in specs {
+            assert!(IndicatorRegistry::parse(spec).is_err(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn defaults_match_indicators() {
+        let defaults = [
+            ("sma", SMA::default_args()),
+            ("ema", EMA::default_args()),
+            ("rsi", RSI::default_args()),
+            ("atr", ATR::default_args()),
+            ("bb", BollingerBands::default_args()),
+            ("bbw", BBW::default_args()),
+            ("bbwp", BBWP::default_args()),
+            ("pivots", DynamicPivots::default_args()),
+            ("pmar", PMAR::default_args()),
+            ("pmarp", PMARP::default_args()),
+            ("stoch", Stochastic::default_args()),
+            ("vwap", VWAP::default_args()),
+            ("obv", OBV::default_args()),
+            ("mfi", MFI::default_args()),
+            ("cmf", CMF::default_args()),
+            ("vp", VolumeProfile::default_args()),
+            ("macd", MACD::default_args()),
+            ("adx", ADX::default_args()),
+            ("supertrend", Supertrend::default_args()),
+            ("psar", PSAR::default_args()),
+            ("ichimoku", Ichimoku::default_args()),
+            ("patterns", CandlePatterns::default_args()),
+            ("sr", SRZones::default_args()),
+            ("divergence", Divergence::default_args()),
+        ];
+
+        assert_eq!(defaults.len(), IndicatorRegistry::all().len());
+
+        for (name, args) in defaults {
+            let definition = IndicatorRegistry::get(name).unwrap();
+            assert_eq!(definition.build_default().unwrap().args(), args);
+        }
+    }
+
+    #[test]
+    fn warmup_lengths() {
+        assert_eq!(IndicatorType::SMA(8).min_length(), 9);
+        assert_eq!(
+            IndicatorType::BBWP(13, 252, 5, MAType::SMA).min_length(),
+            253
+        );
+        assert_eq!(
+            IndicatorType::Stochastic(14, 1, 3, MAType::SMA).min_length(),
+            18
+        );
+    }
+
+    fn ma_types(ma_types: &[MAType]) -> BoxedStrategy<MAType> {
+        prop::sample::select(ma_types.to_vec()).boxed()
+    }
+
+    // Arguments to test each registered indicator with, small enough for the
+    // indicators to warm up within the generated candles.
+    fn indicator_types() -> Vec<(&'static str, BoxedStrategy<IndicatorType>)> {
+        // VWAP anchors within the first days of the generated candles
+        let start = Utc
+            .with_ymd_and_hms(2023, 1, 2, 0, 0, 0)
+            .unwrap()
+            .timestamp() as usize;
+
+        vec![
+            ("sma", (1..30usize).prop_map(IndicatorType::SMA).boxed()),
+            ("ema", (1..20usize).prop_map(IndicatorType::EMA).boxed()),
+            (
+                "rsi",
+                (
+                    1..20usize,
+                    ma_types(&[
+                        MAType::RMA,
+                        MAType::SMA,
+                        MAType::EMA,
+                        MAType::WMA,
+                        MAType::HMA,
+                        MAType::TEMA,
+                        MAType::KAMA,
+                    ]),
+                )
+                    .prop_map(|(len, ma_type)| IndicatorType::RSI(len, ma_type))
+                    .boxed(),
+            ),
+            ("atr", (1..20usize).prop_map(IndicatorType::ATR).boxed()),
+            (
+                "bb",
+                (1..20usize, 0.5..3.0f64)
+                    .prop_map(|(len, std_n)| IndicatorType::BollingerBands(len, std_n.into()))
+                    .boxed(),
+            ),
+            (
+                "bbw",
+                (1..20usize)
+                    .prop_map(|len| IndicatorType::BBW(len, 2.0.into()))
+                    .boxed(),
+            ),
+            (
+                "bbwp",
+                (
+                    1..10usize,
+                    1..30usize,
+                    1..5usize,
+                    ma_types(&[MAType::SMA, MAType::EMA, MAType::HMA]),
+                )
+                    .prop_map(|(len, lookback, sma, ma_type)| {
+                        IndicatorType::BBWP(len, lookback, sma, ma_type)
+                    })
+                    .boxed(),
+            ),
+            (
+                "pivots",
+                (1..10usize).prop_map(IndicatorType::DynamicPivot).boxed(),
+            ),
+            (
+                "pmar",
+                (1..20usize, ma_types(&[MAType::SMA, MAType::VWMA]))
+                    .prop_map(|(len, ma_type)| IndicatorType::PMAR(len, ma_type))
+                    .boxed(),
+            ),
+            (
+                "pmarp",
+                (
+                    1..10usize,
+                    1..30usize,
+                    ma_types(&[MAType::SMA, MAType::VWMA]),
+                    ma_types(&[MAType::SMA, MAType::EMA, MAType::WMA]),
+                )
+                    .prop_map(|(len, lookback, ma_type, signal_ma_type)| {
+                        IndicatorType::PMARP(len, lookback, ma_type, signal_ma_type)
+                    })
+                    .boxed(),
+            ),
+            (
+                "stoch",
+                (
+                    1..15usize,
+                    1..5usize,
+                    1..5usize,
+                    ma_types(&[MAType::SMA, MAType::EMA, MAType::WMA, MAType::RMA]),
+                )
+                    .prop_map(|(k_len, k_smoothing, d_smoothing, ma_type)| {
+                        IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing, ma_type)
+                    })
+                    .boxed(),
+            ),
+            (
+                "vwap",
+                (
+                    prop::sample::select(vec![0usize, 1440, 4320, 10080]),
+                    0..20usize,
+                )
+                    .prop_map(move |(session, offset)| {
+                        IndicatorType::VWAP(session, start + offset * 86400, 2.0.into())
+                    })
+                    .boxed(),
+            ),
+            ("obv", (0..20usize).prop_map(IndicatorType::OBV).boxed()),
+            ("mfi", (1..20usize).prop_map(IndicatorType::MFI).boxed()),
+            ("cmf", (1..20usize).prop_map(IndicatorType::CMF).boxed()),
+            (
+                "vp",
+                (1..20usize, 1..30usize)
+                    .prop_map(|(len, bins)| IndicatorType::VolumeProfile(len, bins, 0.7.into()))
+                    .boxed(),
+            ),
+            (
+                "macd",
+                (1..10usize, 1..20usize, 1..10usize)
+                    .prop_map(|(fast, slow, signal)| IndicatorType::MACD(fast, slow, signal))
+                    .boxed(),
+            ),
+            (
+                "adx",
+                (1..20usize, 1..20usize)
+                    .prop_map(|(di_len, adx_len)| IndicatorType::ADX(di_len, adx_len))
+                    .boxed(),
+            ),
+            (
+                "supertrend",
+                (1..20usize, 0.5..5.0f64)
+                    .prop_map(|(atr_len, factor)| IndicatorType::Supertrend(atr_len, factor.into()))
+                    .boxed(),
+            ),
+            (
+                "psar",
+                (0.01..0.05f64, 0.01..0.05f64, 0.1..0.3f64)
+                    .prop_map(|(start, increment, max)| {
+                        IndicatorType::PSAR(start.into(), increment.into(), max.into())
+                    })
+                    .boxed(),
+            ),
+            (
+                "ichimoku",
+                (1..10usize, 1..20usize, 1..30usize, 1..20usize)
+                    .prop_map(|(conversion, base, span_b, displacement)| {
+                        IndicatorType::Ichimoku(conversion, base, span_b, displacement)
+                    })
+                    .boxed(),
+            ),
+            (
+                "patterns",
+                (1..10usize).prop_map(IndicatorType::CandlePatterns).boxed(),
+            ),
+            (
+                "sr",
+                (1..6usize, 1..40usize, 0.005..0.05f64)
+                    .prop_map(|(len, lookback, width)| {
+                        IndicatorType::SRZones(len, lookback, width.into())
+                    })
+                    .boxed(),
+            ),
+            (
+                "divergence",
+                (1..6usize)
+                    .prop_map(|len| IndicatorType::Divergence(Oscillator::RSI(6, MAType::RMA), len))
+                    .boxed(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn incremental_matches_batch() {
+        let indicator_types = indicator_types();
+
+        for definition in IndicatorRegistry::all() {
+            assert!(
+                indicator_types
+                    .iter()
+                    .any(|(name, _)| *name == definition.name),
+                "No test arguments for {}",
+                definition.name
+            );
+        }
+
+        for (name, strategy) in indicator_types {
+            let mut runner = TestRunner::new(Config::with_cases(64));
+            let cases = (candle_strategy::candles(1, 80), 0..80usize, strategy);
+
+            runner
+                .run(&cases, |(candles, split, indicator_type)| {
+                    prop_assert_eq!(indicator_type.definition().name, name);
+                    candle_strategy::assert_incremental_matches(&candles, split, indicator_type)
+                })
+                .unwrap_or_else(|e| panic!("{}: {}", name, e));
+        }
+    }
+}
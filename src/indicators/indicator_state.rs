@@ -0,0 +1,49 @@
+use crate::indicators::{
+    adx::ADXState,
+    bbwp::BBWPState,
+    candle_patterns::CandlePatternsState,
+    cmf::CMFState,
+    divergence::DivergenceState,
+    dynamic_pivots::DynamicPivotsState,
+    ichimoku::IchimokuState,
+    macd::MACDState,
+    mfi::MFIState,
+    obv::OBVState,
+    pmar::PMARState,
+    pmarp::PMARPState,
+    psar::PSARState,
+    rolling::{rolling_stats::RollingStats, rolling_sum::RollingSum},
+    rsi::RSIState,
+    stochastic::StochasticState,
+    supertrend::SupertrendState,
+    vwap::VWAPState,
+};
+
+/// Rolling state kept by a TimeSeries for an indicator between candles, so
+/// that each new candle can be populated in constant or logarithmic time
+/// instead of recomputing the indicator over its whole window. Indicators
+/// updating from their previous value (EMA, ATR) need no extra state. The RSI
+/// only keeps state when averaging with another MA than Wilder's.
+#[derive(Debug, Clone)]
+pub enum IndicatorState {
+    SMA(RollingSum),
+    RSI(RSIState),
+    BollingerBands(RollingStats),
+    BBW(RollingStats),
+    BBWP(Box<BBWPState>),
+    DynamicPivot(DynamicPivotsState),
+    PMAR(Box<PMARState>),
+    PMARP(Box<PMARPState>),
+    Stochastic(StochasticState),
+    VWAP(VWAPState),
+    OBV(OBVState),
+    MFI(MFIState),
+    CMF(CMFState),
+    MACD(MACDState),
+    ADX(ADXState),
+    Supertrend(SupertrendState),
+    PSAR(PSARState),
+    Ichimoku(Box<IchimokuState>),
+    CandlePatterns(CandlePatternsState),
+    Divergence(DivergenceState),
+}
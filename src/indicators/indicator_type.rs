@@ -1,156 +1,100 @@
 use crate::{
     indicators::{
-        atr::ATR,
-        bbw::BBW,
-        bbwp::BBWP,
-        bollinger_bands::BollingerBands,
-        dynamic_pivots::DynamicPivots,
-        ema::EMA,
-        indicator_args::IndicatorArgs,
-        pmar::PMAR,
-        pmarp::PMARP,
-        populates_candles::{PopulatesCandles, PopulatesCandlesWithSelf},
-        rsi::RSI,
-        sma::SMA,
-        stochastic::Stochastic,
+        indicator_args::IndicatorArgs, indicator_definition::IndicatorDefinition,
+        indicator_registry::IndicatorRegistry, oscillator::Oscillator,
+        populates_candles::PopulatesCandlesWithSelf,
+    },
+    models::{
+        hashable_f64::HashableF64, ma_type::MAType, timeseries::TimeSeries,
+        traits::has_min_length::HasMinLength,
     },
-    models::{ma_type::MAType, timeseries::TimeSeries, traits::has_min_length::HasMinLength},
 };
-use anyhow::Result;
+use anyhow::{Error, Result};
 use serde::Serialize;
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
 
 #[allow(dead_code)]
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Serialize)]
 pub enum IndicatorType {
     SMA(usize),
     EMA(usize),
-    RSI(usize),
+    RSI(usize, MAType),
     ATR(usize),
-    BollingerBands(usize),
-    BBW(usize),
-    BBWP(usize, usize), // length, lookback
+    BollingerBands(usize, HashableF64), // length, n-standard deviations
+    BBW(usize, HashableF64),            // length, n-standard deviations
+    BBWP(usize, usize, usize, MAType),  // length, lookback, ma-length, ma type
     DynamicPivot(usize),
     PMAR(usize, MAType),
-    PMARP(usize, usize, MAType),     // length, lookback
-    Stochastic(usize, usize, usize), // K-lenght, K-smoothing, D-smoothing
+    PMARP(usize, usize, MAType, MAType), // length, lookback, ma type, signal ma type
+    Stochastic(usize, usize, usize, MAType), // K-lenght, K-smoothing, D-smoothing
+    VWAP(usize, usize, HashableF64),     // session minutes, anchor timestamp, n-standard deviations
+    OBV(usize),                          // signal length
+    MFI(usize),
+    CMF(usize),
+    VolumeProfile(usize, usize, HashableF64), // length, bins, value area share
+    MACD(usize, usize, usize),                // fast length, slow length, signal length
+    ADX(usize, usize),                        // DI length, ADX smoothing
+    Supertrend(usize, HashableF64),           // ATR length, ATR factor
+    PSAR(HashableF64, HashableF64, HashableF64), // start, increment, max acceleration
+    Ichimoku(usize, usize, usize, usize),     // conversion, base, span B, displacement
+    CandlePatterns(usize),                    // average body length
+    SRZones(usize, usize, HashableF64),       // pivot length, lookback, zone width
+    Divergence(Oscillator, usize),            // oscillator, pivot length
+}
+
+impl IndicatorType {
+    /// Returns the registered definition of the indicator.
+    pub fn definition(&self) -> IndicatorDefinition {
+        *IndicatorRegistry::definition_of(self)
+    }
+
+    /// Returns the arguments of the indicator in schema order.
+    pub fn args(&self) -> IndicatorArgs {
+        (self.definition().args)(self).expect("Definition doesn't match IndicatorType.")
+    }
+
+    /// Returns how many candles before the last one may change when the last
+    /// candle is populated.
+    pub fn lag(&self) -> usize {
+        (self.definition().lag)(&self.args()).unwrap_or(0)
+    }
 }
 
 impl PopulatesCandlesWithSelf for IndicatorType {
     fn populate_candles(&self, ts: &mut TimeSeries) -> Result<()> {
-        match self {
-            IndicatorType::ATR(n) => {
-                let args = IndicatorArgs::LengthArg(*n);
-                ATR::populate_candles_args(ts, args)
-            }
-            IndicatorType::BBW(len) => {
-                let args = IndicatorArgs::BollingerBandArgs(*len, 2.0);
-                BBW::populate_candles_args(ts, args)
-            }
-            IndicatorType::BBWP(len, lookback) => {
-                let args = IndicatorArgs::BBWPArgs(*len, *lookback, 5);
-                BBWP::populate_candles_args(ts, args)
-            }
-            IndicatorType::BollingerBands(len) => {
-                let args = IndicatorArgs::BollingerBandArgs(*len, 2.0);
-                BollingerBands::populate_candles_args(ts, args)
-            }
-            IndicatorType::DynamicPivot(len) => {
-                let args = IndicatorArgs::LengthArg(*len);
-                DynamicPivots::populate_candles_args(ts, args)
-            }
-            IndicatorType::EMA(len) => {
-                let args = IndicatorArgs::LengthArg(*len);
-                EMA::populate_candles_args(ts, args)
-            }
-            IndicatorType::RSI(len) => {
-                let args = IndicatorArgs::LengthArg(*len);
-                RSI::populate_candles_args(ts, args)
-            }
-            IndicatorType::SMA(len) => {
-                let args = IndicatorArgs::LengthArg(*len);
-                SMA::populate_candles_args(ts, args)
-            }
-            IndicatorType::PMAR(len, ma_type) => {
-                let args = IndicatorArgs::PMARArgs(*len, *ma_type);
-                PMAR::populate_candles_args(ts, args)
-            }
-            IndicatorType::PMARP(len, lookback, ma_type) => {
-                let args = IndicatorArgs::PMARPArgs(*len, *lookback, *ma_type);
-                PMARP::populate_candles_args(ts, args)
-            }
-            IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing) => {
-                let args = IndicatorArgs::StochasticArgs(*k_len, *k_smoothing, *d_smoothing);
-                Stochastic::populate_candles_args(ts, args)
-            }
-        }
+        (self.definition().populate_candles)(ts, self.args())?;
+        ts.sync_buffer_indicator(*self);
+
+        Ok(())
     }
 
     fn populate_last_candle(&self, ts: &mut TimeSeries) -> Result<()> {
-        match self {
-            IndicatorType::ATR(len) => {
-                let args = IndicatorArgs::LengthArg(*len);
-                ATR::populate_last_candle_args(ts, args)
-            }
-            IndicatorType::BBW(len) => {
-                let args = IndicatorArgs::BollingerBandArgs(*len, 2.0);
-                BBW::populate_last_candle_args(ts, args)
-            }
-            IndicatorType::BBWP(len, lookback) => {
-                let args = IndicatorArgs::BBWPArgs(*len, *lookback, 5);
-                BBWP::populate_last_candle_args(ts, args)
-            }
-            IndicatorType::BollingerBands(len) => {
-                let args = IndicatorArgs::BollingerBandArgs(*len, 2.0);
-                BollingerBands::populate_last_candle_args(ts, args)
-            }
-            IndicatorType::DynamicPivot(len) => {
-                let args = IndicatorArgs::LengthArg(*len);
-                DynamicPivots::populate_last_candle_args(ts, args)
-            }
-            IndicatorType::EMA(len) => {
-                let args = IndicatorArgs::LengthArg(*len);
-                EMA::populate_last_candle_args(ts, args)
-            }
-            IndicatorType::RSI(len) => {
-                let args = IndicatorArgs::LengthArg(*len);
-                RSI::populate_last_candle_args(ts, args)
-            }
-            IndicatorType::SMA(len) => {
-                let args = IndicatorArgs::LengthArg(*len);
-                SMA::populate_last_candle_args(ts, args)
-            }
-            IndicatorType::PMAR(len, ma_type) => {
-                let args = IndicatorArgs::PMARArgs(*len, *ma_type);
-                PMAR::populate_last_candle_args(ts, args)
-            }
-            IndicatorType::PMARP(len, lookback, ma_type) => {
-                let args = IndicatorArgs::PMARPArgs(*len, *lookback, *ma_type);
-                PMARP::populate_last_candle_args(ts, args)
-            }
-            IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing) => {
-                let args = IndicatorArgs::StochasticArgs(*k_len, *k_smoothing, *d_smoothing);
-                Stochastic::populate_last_candle_args(ts, args)
-            }
-        }
+        (self.definition().populate_last_candle)(ts, self.args())
     }
 }
 
 impl HasMinLength for IndicatorType {
     fn min_length(&self) -> usize {
-        match self {
-            IndicatorType::SMA(n) => n + 1,
-            IndicatorType::EMA(n) => n + 1,
-            IndicatorType::RSI(n) => n + 1,
-            IndicatorType::ATR(n) => n + 1,
-            IndicatorType::BollingerBands(n) => n + 1,
-            IndicatorType::BBW(n) => n + 1,
-            IndicatorType::BBWP(_, n) => n + 1,
-            IndicatorType::DynamicPivot(n) => n + 1,
-            IndicatorType::PMAR(n, _) => n + 1,
-            IndicatorType::PMARP(_, n, _) => n + 1,
-            IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing) => {
-                k_len + k_smoothing + d_smoothing
-            }
-        }
+        (self.definition().warmup)(&self.args()).unwrap_or(0)
+    }
+}
+
+impl FromStr for IndicatorType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        IndicatorRegistry::parse(s)
+    }
+}
+
+/// Formats the indicator as a spec which can be parsed back, e.g.
+/// `bbwp(13,252,5)`.
+impl Display for IndicatorType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let args: Vec<String> = self.args().0.iter().map(|p| p.to_string()).collect();
+        write!(f, "{}({})", self.definition().name, args.join(","))
     }
 }
@@ -0,0 +1,289 @@
+use crate::{
+    indicators::{
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+        rolling::rolling_ema::RollingEMA,
+    },
+    models::{candle::Candle, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Context, Result};
+
+/// # MACD
+///
+/// Moving Average Convergence Divergence, the difference between a fast and
+/// a slow EMA of the close. The signal line is an EMA of the MACD line and
+/// the histogram the difference between the two.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct MACD {
+    pub macd: f64,
+    pub signal: Option<f64>,
+    pub histogram: Option<f64>,
+}
+
+impl PopulatesCandles for MACD {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (fast, slow, signal) = (args.usize(0)?, args.usize(1)?, args.usize(2)?);
+        let indicator_type = IndicatorType::MACD(fast, slow, signal);
+        let mut state = MACDState::new(fast, slow, signal);
+
+        for candle in ts.candles.iter_mut() {
+            let macd = Self::calculate_rolling(&mut state, candle);
+
+            candle
+                .indicators
+                .insert(indicator_type, Indicator::MACD(macd));
+        }
+
+        ts.set_indicator_state(indicator_type, IndicatorState::MACD(state));
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (fast, slow, signal) = (args.usize(0)?, args.usize(1)?, args.usize(2)?);
+        let ctx_err = "Unable to get last candle";
+        let indicator_type = IndicatorType::MACD(fast, slow, signal);
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::MACD(state)) => state,
+            _ => return Self::populate_candles_args(ts, args),
+        };
+
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let macd = Self::calculate_rolling(&mut state, candle);
+
+        candle
+            .indicators
+            .insert(indicator_type, Indicator::MACD(macd));
+
+        ts.set_indicator_state(indicator_type, IndicatorState::MACD(state));
+
+        Ok(())
+    }
+}
+
+impl DefinesIndicator for MACD {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "macd",
+        params: &[
+            ParamSchema {
+                name: "fast",
+                default: ParamValue::Int(12),
+            },
+            ParamSchema {
+                name: "slow",
+                default: ParamValue::Int(26),
+            },
+            ParamSchema {
+                name: "signal",
+                default: ParamValue::Int(9),
+            },
+        ],
+        outputs: &["macd", "signal", "histogram"],
+        warmup: |args| Ok(args.usize(0)?.max(args.usize(1)?) + args.usize(2)?),
+        lag: |_| Ok(0),
+        build: |args| {
+            Ok(IndicatorType::MACD(
+                args.usize(0)?,
+                args.usize(1)?,
+                args.usize(2)?,
+            ))
+        },
+        args: |indicator_type| match indicator_type {
+            IndicatorType::MACD(a, b, c) => Some(IndicatorArgs(vec![
+                ParamValue::Int(*a),
+                ParamValue::Int(*b),
+                ParamValue::Int(*c),
+            ])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
+impl IsIndicator for MACD {
+    fn default_args() -> IndicatorArgs {
+        IndicatorType::MACD(12, 26, 9).args()
+    }
+
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::calculate_args(segment, &Self::default_args())
+    }
+
+    /// Calculates the MACD of the last candle with the EMAs seeded at the
+    /// start of the segment.
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let (fast, slow, signal) = (
+            args.usize(0).ok()?,
+            args.usize(1).ok()?,
+            args.usize(2).ok()?,
+        );
+        let mut state = MACDState::new(fast, slow, signal);
+
+        segment
+            .iter()
+            .map(|candle| Self::calculate_rolling(&mut state, candle))
+            .last()?
+    }
+}
+
+impl MACD {
+    fn calculate_rolling(state: &mut MACDState, candle: &Candle) -> Option<Self> {
+        let fast = state.fast.push(Some(candle.close));
+        let slow = state.slow.push(Some(candle.close));
+        let macd = fast.zip(slow).map(|(fast, slow)| fast - slow);
+        let signal = state.signal.push(macd);
+
+        let macd = macd?;
+        Some(MACD {
+            macd,
+            signal,
+            histogram: signal.map(|signal| macd - signal),
+        })
+    }
+}
+
+/// Fast, slow and signal EMAs of the MACD.
+#[derive(Debug, Clone)]
+pub struct MACDState {
+    fast: RollingEMA,
+    slow: RollingEMA,
+    signal: RollingEMA,
+}
+
+impl MACDState {
+    pub fn new(fast: usize, slow: usize, signal: usize) -> Self {
+        Self {
+            fast: RollingEMA::ema(fast),
+            slow: RollingEMA::ema(slow),
+            signal: RollingEMA::ema(signal),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_type::IndicatorType, is_indicator::IsIndicator, macd::MACD,
+            populates_candles::PopulatesCandles,
+        },
+        models::{interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::candle_strategy,
+    };
+    use proptest::prelude::*;
+
+    #[test]
+    fn macd_populate_candles() {
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candle_strategy::price_change_candles())
+            .build();
+
+        MACD::populate_candles(&mut ts).unwrap();
+
+        let indicator_type = IndicatorType::MACD(12, 26, 9);
+        let macds: Vec<Option<MACD>> = ts
+            .candles
+            .iter()
+            .map(|c| c.clone_indicator(&indicator_type).unwrap().as_macd())
+            .collect();
+
+        assert!(macds[24].is_none());
+        assert!(macds[25].unwrap().signal.is_none());
+        assert!(macds[33].unwrap().signal.is_some());
+    }
+
+    #[test]
+    fn macd_worked_example() {
+        let candles = candle_strategy::hlc_candles(&[
+            (1.0, 1.0, 1.0),
+            (2.0, 2.0, 2.0),
+            (3.0, 3.0, 3.0),
+            (5.0, 5.0, 5.0),
+            (4.0, 4.0, 4.0),
+        ]);
+        let indicator_type = IndicatorType::MACD(2, 3, 2);
+        let populated = candle_strategy::populated(&candles, indicator_type);
+        let macds: Vec<Option<MACD>> = populated
+            .iter()
+            .map(|c| c.clone_indicator(&indicator_type).unwrap().as_macd())
+            .collect();
+
+        // The fast EMA is 1.5, 2.5, 25/6 and 73/18 and the slow EMA 2, 3.5
+        // and 3.75, both seeded with the average of their first closes. The
+        // signal is seeded with the average of the first two MACD values.
+        let expected = [
+            None,
+            None,
+            Some((0.5, None)),
+            Some((2.0 / 3.0, Some(7.0 / 12.0))),
+            Some((11.0 / 36.0, Some(43.0 / 108.0))),
+        ];
+
+        for (macd, expected) in macds.iter().zip(expected) {
+            let (Some(macd), Some((line, signal))) = (macd, expected) else {
+                assert_eq!(macd.is_some(), expected.is_some());
+                continue;
+            };
+
+            assert!((macd.macd - line).abs() < 1e-9);
+            assert_eq!(macd.signal.is_some(), signal.is_some());
+            if let Some(signal) = signal {
+                assert!((macd.signal.unwrap() - signal).abs() < 1e-9);
+                assert!((macd.histogram.unwrap() - (line - signal)).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn macd_no_candles() {
+        let candles = Vec::new();
+        let macd = MACD::calculate(&candles);
+        assert!(macd.is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn macd_matches_recomputation(
+            candles in candle_strategy::candles(1, 80),
+            fast in 1..10usize,
+            slow in 1..20usize,
+            signal in 1..10usize,
+        ) {
+            let args = IndicatorType::MACD(fast, slow, signal).args();
+            let indicator_type = IndicatorType::MACD(fast, slow, signal);
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            let last = populated.last().unwrap();
+            let actual = last.clone_indicator(&indicator_type).unwrap().as_macd();
+            prop_assert_eq!(actual, MACD::calculate_args(&candles, &args));
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use crate::{
+    indicators::{
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+        rolling::rolling_sum::RollingSum,
+    },
+    models::{candle::Candle, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Context, Result};
+
+/// # MFI
+///
+/// Money Flow Index, a volume weighted RSI. Money flow is the typical price
+/// times volume, counted as positive when the typical price rose and as
+/// negative when it fell. Ranges from 0 to 100.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct MFI {
+    pub value: f64,
+    pub len: usize,
+}
+
+impl PopulatesCandles for MFI {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let len = args.usize(0)?;
+        let indicator_type = IndicatorType::MFI(len);
+        let mut state = MFIState::new(len);
+
+        for candle in ts.candles.iter_mut() {
+            let mfi = Self::calculate_rolling(&mut state, candle, len);
+
+            candle
+                .indicators
+                .insert(indicator_type, Indicator::MFI(mfi));
+        }
+
+        ts.set_indicator_state(indicator_type, IndicatorState::MFI(state));
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let len = args.usize(0)?;
+        let ctx_err = "Unable to get last candle";
+        let indicator_type = IndicatorType::MFI(len);
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::MFI(state)) => state,
+            _ => return Self::populate_candles_args(ts, args),
+        };
+
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let mfi = Self::calculate_rolling(&mut state, candle, len);
+
+        candle
+            .indicators
+            .insert(indicator_type, Indicator::MFI(mfi));
+
+        ts.set_indicator_state(indicator_type, IndicatorState::MFI(state));
+
+        Ok(())
+    }
+}
+
+impl DefinesIndicator for MFI {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "mfi",
+        params: &[ParamSchema {
+            name: "len",
+            default: ParamValue::Int(14),
+        }],
+        outputs: &["value"],
+        warmup: |args| Ok(args.usize(0)? + 1),
+        lag: |_| Ok(0),
+        build: |args| Ok(IndicatorType::MFI(args.usize(0)?)),
+        args: |indicator_type| match indicator_type {
+            IndicatorType::MFI(a) => Some(IndicatorArgs(vec![ParamValue::Int(*a)])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
+impl IsIndicator for MFI {
+    fn default_args() -> IndicatorArgs {
+        IndicatorType::MFI(14).args()
+    }
+
+    /// Segment should be one candle longer than the length of MFI wanted.
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        if segment.len() < 2 {
+            return None;
+        }
+
+        let len = segment.len() - 1;
+        let (positive, negative) = segment.windows(2).fold((0.0, 0.0), |(pos, neg), pair| {
+            let (flow_pos, flow_neg) = Self::money_flow(pair[0].typical_price(), &pair[1]);
+            (pos + flow_pos, neg + flow_neg)
+        });
+
+        Some(MFI {
+            value: Self::index(positive, negative),
+            len,
+        })
+    }
+
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let len = args.usize(0).ok()?;
+        let candle_len = segment.len();
+
+        if len == 0 || len >= candle_len {
+            return None;
+        }
+
+        Self::calculate(&segment[candle_len - len - 1..candle_len])
+    }
+}
+
+impl MFI {
+    /// Returns the positive and negative money flow of a candle.
+    fn money_flow(prev_price: f64, candle: &Candle) -> (f64, f64) {
+        let price = candle.typical_price();
+        let flow = price * candle.volume;
+
+        if price > prev_price {
+            (flow, 0.0)
+        } else if price < prev_price {
+            (0.0, flow)
+        } else {
+            (0.0, 0.0)
+        }
+    }
+
+    fn index(positive: f64, negative: f64) -> f64 {
+        let total = positive + negative;
+
+        if total <= 0.0 {
+            50.0
+        } else {
+            100.0 * positive / total
+        }
+    }
+
+    fn calculate_rolling(state: &mut MFIState, candle: &Candle, len: usize) -> Option<Self> {
+        let prev_price = state.prev_price.replace(candle.typical_price());
+        let (positive, negative) = Self::money_flow(prev_price?, candle);
+
+        state.positive.push(Some(positive));
+        state.negative.push(Some(negative));
+
+        let value = Self::index(state.positive.sum()?, state.negative.sum()?);
+
+        Some(MFI { value, len })
+    }
+}
+
+/// Positive and negative money flow over the last `len` candles along with
+/// the previous typical price.
+#[derive(Debug, Clone)]
+pub struct MFIState {
+    prev_price: Option<f64>,
+    positive: RollingSum,
+    negative: RollingSum,
+}
+
+impl MFIState {
+    pub fn new(len: usize) -> Self {
+        Self {
+            prev_price: None,
+            positive: RollingSum::new(len),
+            negative: RollingSum::new(len),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_type::IndicatorType, is_indicator::IsIndicator, mfi::MFI,
+            populates_candles::PopulatesCandles,
+        },
+        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::candle_strategy::{self, assert_close},
+    };
+    use chrono::{Duration, Utc};
+    use proptest::prelude::*;
+
+    fn candles() -> Vec<Candle> {
+        let now = Utc::now();
+
+        [(10.0, 100.0), (11.0, 200.0), (10.5, 100.0), (12.0, 100.0)]
+            .iter()
+            .enumerate()
+            .map(|(i, (price, volume))| {
+                Candle::from_val(now + Duration::days(i as i64), *price, *volume)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn mfi_calculate() {
+        let mfi = MFI::calculate(&candles()).unwrap();
+
+        assert_eq!(mfi.len, 3);
+        assert!((mfi.value - 100.0 * 3400.0 / 4450.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn mfi_populate_candles() {
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles())
+            .build();
+
+        MFI::populate_candles_args(&mut ts, IndicatorType::MFI(2).args()).unwrap();
+
+        let mfis: Vec<Option<MFI>> = ts
+            .candles
+            .iter()
+            .map(|c| c.clone_indicator(&IndicatorType::MFI(2)).unwrap().as_mfi())
+            .collect();
+
+        assert!(mfis[0].is_none());
+        assert!(mfis[1].is_none());
+        assert!((mfis[2].unwrap().value - 100.0 * 2200.0 / 3250.0).abs() < 1e-12);
+        assert!((mfis[3].unwrap().value - 100.0 * 1200.0 / 2250.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn mfi_no_candles() {
+        let candles = Vec::new();
+        let mfi = MFI::calculate_args(&candles, &MFI::default_args());
+        assert!(mfi.is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn mfi_matches_recomputation(
+            candles in candle_strategy::candles(1, 80),
+            len in 1..20usize,
+        ) {
+            let args = IndicatorType::MFI(len).args();
+            let indicator_type = IndicatorType::MFI(len);
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            for (i, candle) in populated.iter().enumerate() {
+                let expected = MFI::calculate_args(&candles[..=i], &args).map(|m| m.value);
+                let actual = candle.clone_indicator(&indicator_type).unwrap().as_mfi();
+                assert_close(actual.map(|m| m.value), expected)?;
+            }
+        }
+    }
+}
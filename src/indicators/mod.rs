@@ -1,16 +1,36 @@
+pub mod adx;
 pub mod atr;
 pub mod bbw;
 pub mod bbwp;
 pub mod bollinger_bands;
+pub mod candle_patterns;
+pub mod cmf;
+pub mod divergence;
 pub mod dynamic_pivots;
 pub mod ema;
+pub mod ichimoku;
 pub mod indicator;
 pub mod indicator_args;
+pub mod indicator_definition;
+pub mod indicator_field;
+pub mod indicator_registry;
+pub mod indicator_state;
 pub mod indicator_type;
 pub mod is_indicator;
+pub mod macd;
+pub mod mfi;
+pub mod obv;
+pub mod oscillator;
 pub mod pmar;
 pub mod pmarp;
 pub mod populates_candles;
+pub mod psar;
+pub mod rolling;
 pub mod rsi;
 pub mod sma;
+pub mod sr_zones;
 pub mod stochastic;
+pub mod supertrend;
+pub mod timeframe_indicator;
+pub mod volume_profile;
+pub mod vwap;
@@ -0,0 +1,270 @@
+use crate::{
+    indicators::{
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+        rolling::rolling_sum::RollingSum,
+    },
+    models::{candle::Candle, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Context, Result};
+
+/// # OBV
+///
+/// On-Balance Volume, the running total of volume added on up closes and
+/// subtracted on down closes, counted from the first candle of the series.
+/// The signal is a simple moving average of the OBV.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct OBV {
+    pub value: f64,
+    pub signal: Option<f64>,
+}
+
+impl PopulatesCandles for OBV {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let signal_len = args.usize(0)?;
+        let indicator_type = IndicatorType::OBV(signal_len);
+        let mut state = OBVState::new(signal_len);
+
+        for candle in ts.candles.iter_mut() {
+            let obv = Self::calculate_rolling(&mut state, candle);
+
+            candle
+                .indicators
+                .insert(indicator_type, Indicator::OBV(Some(obv)));
+        }
+
+        ts.set_indicator_state(indicator_type, IndicatorState::OBV(state));
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let signal_len = args.usize(0)?;
+        let ctx_err = "Unable to get last candle";
+        let indicator_type = IndicatorType::OBV(signal_len);
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::OBV(state)) => state,
+            _ => return Self::populate_candles_args(ts, args),
+        };
+
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let obv = Self::calculate_rolling(&mut state, candle);
+
+        candle
+            .indicators
+            .insert(indicator_type, Indicator::OBV(Some(obv)));
+
+        ts.set_indicator_state(indicator_type, IndicatorState::OBV(state));
+
+        Ok(())
+    }
+}
+
+impl DefinesIndicator for OBV {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "obv",
+        params: &[ParamSchema {
+            name: "signal",
+            default: ParamValue::Int(20),
+        }],
+        outputs: &["value", "signal"],
+        warmup: |args| args.usize(0),
+        lag: |_| Ok(0),
+        build: |args| Ok(IndicatorType::OBV(args.usize(0)?)),
+        args: |indicator_type| match indicator_type {
+            IndicatorType::OBV(a) => Some(IndicatorArgs(vec![ParamValue::Int(*a)])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
+impl IsIndicator for OBV {
+    fn default_args() -> IndicatorArgs {
+        IndicatorType::OBV(20).args()
+    }
+
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::calculate_args(segment, &Self::default_args())
+    }
+
+    /// Calculates the OBV of the last candle counting from the first candle
+    /// of the segment.
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let signal_len = args.usize(0).ok()?;
+
+        if segment.is_empty() {
+            return None;
+        }
+
+        let mut obvs = vec![0.0];
+        for pair in segment.windows(2) {
+            let prev = *obvs.last()?;
+            obvs.push(prev + Self::signed_volume(pair[0].close, &pair[1]));
+        }
+
+        let value = *obvs.last()?;
+        let signal = (signal_len > 0 && obvs.len() >= signal_len)
+            .then(|| obvs[obvs.len() - signal_len..].iter().sum::<f64>() / signal_len as f64);
+
+        Some(OBV { value, signal })
+    }
+}
+
+impl OBV {
+    fn signed_volume(prev_close: f64, curr: &Candle) -> f64 {
+        if curr.close > prev_close {
+            curr.volume
+        } else if curr.close < prev_close {
+            -curr.volume
+        } else {
+            0.0
+        }
+    }
+
+    fn calculate_rolling(state: &mut OBVState, candle: &Candle) -> Self {
+        if let Some(prev_close) = state.prev_close {
+            state.value += Self::signed_volume(prev_close, candle);
+        }
+
+        state.prev_close = Some(candle.close);
+        state.signal.push(Some(state.value));
+
+        OBV {
+            value: state.value,
+            signal: state.signal.mean(),
+        }
+    }
+}
+
+/// Running OBV along with the previous close and the signal window.
+#[derive(Debug, Clone)]
+pub struct OBVState {
+    prev_close: Option<f64>,
+    value: f64,
+    signal: RollingSum,
+}
+
+impl OBVState {
+    pub fn new(signal_len: usize) -> Self {
+        Self {
+            prev_close: None,
+            value: 0.0,
+            signal: RollingSum::new(signal_len),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_type::IndicatorType, is_indicator::IsIndicator, obv::OBV,
+            populates_candles::PopulatesCandles,
+        },
+        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::candle_strategy::{self, assert_close},
+    };
+    use chrono::{Duration, Utc};
+    use proptest::prelude::*;
+
+    #[test]
+    fn obv_populate_candles() {
+        let now = Utc::now();
+        let candles: Vec<Candle> = [
+            (10.0, 100.0),
+            (11.0, 200.0),
+            (10.5, 150.0),
+            (10.5, 100.0),
+            (12.0, 300.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (close, volume))| {
+            Candle::from_val(now + Duration::days(i as i64), *close, *volume)
+        })
+        .collect();
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        OBV::populate_candles_args(&mut ts, IndicatorType::OBV(3).args()).unwrap();
+
+        let obvs: Vec<OBV> = ts
+            .candles
+            .iter()
+            .map(|c| {
+                c.clone_indicator(&IndicatorType::OBV(3))
+                    .unwrap()
+                    .as_obv()
+                    .unwrap()
+            })
+            .collect();
+
+        let values: Vec<f64> = obvs.iter().map(|obv| obv.value).collect();
+        assert_eq!(values, vec![0.0, 200.0, 50.0, 50.0, 350.0]);
+
+        assert!(obvs[1].signal.is_none());
+        assert!((obvs[2].signal.unwrap() - 250.0 / 3.0).abs() < 1e-12);
+        assert_eq!(obvs[3].signal, Some(100.0));
+        assert_eq!(obvs[4].signal, Some(150.0));
+    }
+
+    #[test]
+    fn obv_no_candles() {
+        let candles = Vec::new();
+        let obv = OBV::calculate(&candles);
+        assert!(obv.is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn obv_matches_recomputation(
+            candles in candle_strategy::candles(1, 80),
+            signal_len in 0..20usize,
+        ) {
+            let args = IndicatorType::OBV(signal_len).args();
+            let indicator_type = IndicatorType::OBV(signal_len);
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            for (i, candle) in populated.iter().enumerate() {
+                let expected = OBV::calculate_args(&candles[..=i], &args);
+                let actual = candle.clone_indicator(&indicator_type).unwrap().as_obv();
+                assert_close(actual.map(|o| o.value), expected.map(|o| o.value))?;
+                assert_close(
+                    actual.and_then(|o| o.signal),
+                    expected.and_then(|o| o.signal),
+                )?;
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{indicators::indicator_type::IndicatorType, models::ma_type::MAType};
+use anyhow::{anyhow, Error, Result};
+use serde::Serialize;
+
+/// # Oscillator
+///
+/// Indicators a `Divergence` can compare price to, with the same arguments
+/// as their `IndicatorType`. Kept separate from IndicatorType so that the
+/// divergence can hold its oscillator while both stay `Copy`.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Serialize)]
+pub enum Oscillator {
+    RSI(usize, MAType),
+    Stochastic(usize, usize, usize, MAType),
+    BBWP(usize, usize, usize, MAType),
+    PMARP(usize, usize, MAType, MAType),
+    OBV(usize),
+    MFI(usize),
+    CMF(usize),
+    MACD(usize, usize, usize),
+}
+
+impl Oscillator {
+    pub const fn indicator_type(&self) -> IndicatorType {
+        match *self {
+            Oscillator::RSI(len, ma_type) => IndicatorType::RSI(len, ma_type),
+            Oscillator::Stochastic(k_len, k_smoothing, d_smoothing, ma_type) => {
+                IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing, ma_type)
+            }
+            Oscillator::BBWP(len, lookback, ma_len, ma_type) => {
+                IndicatorType::BBWP(len, lookback, ma_len, ma_type)
+            }
+            Oscillator::PMARP(len, lookback, ma_type, signal_ma_type) => {
+                IndicatorType::PMARP(len, lookback, ma_type, signal_ma_type)
+            }
+            Oscillator::OBV(len) => IndicatorType::OBV(len),
+            Oscillator::MFI(len) => IndicatorType::MFI(len),
+            Oscillator::CMF(len) => IndicatorType::CMF(len),
+            Oscillator::MACD(fast, slow, signal) => IndicatorType::MACD(fast, slow, signal),
+        }
+    }
+}
+
+impl TryFrom<IndicatorType> for Oscillator {
+    type Error = Error;
+
+    fn try_from(indicator_type: IndicatorType) -> Result<Self> {
+        let oscillator = match indicator_type {
+            IndicatorType::RSI(len, ma_type) => Oscillator::RSI(len, ma_type),
+            IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing, ma_type) => {
+                Oscillator::Stochastic(k_len, k_smoothing, d_smoothing, ma_type)
+            }
+            IndicatorType::BBWP(len, lookback, ma_len, ma_type) => {
+                Oscillator::BBWP(len, lookback, ma_len, ma_type)
+            }
+            IndicatorType::PMARP(len, lookback, ma_type, signal_ma_type) => {
+                Oscillator::PMARP(len, lookback, ma_type, signal_ma_type)
+            }
+            IndicatorType::OBV(len) => Oscillator::OBV(len),
+            IndicatorType::MFI(len) => Oscillator::MFI(len),
+            IndicatorType::CMF(len) => Oscillator::CMF(len),
+            IndicatorType::MACD(fast, slow, signal) => Oscillator::MACD(fast, slow, signal),
+            _ => {
+                return Err(anyhow!(
+                    "Divergences need an oscillator to compare to, {} is not one.",
+                    indicator_type
+                ))
+            }
+        };
+
+        Ok(oscillator)
+    }
+}
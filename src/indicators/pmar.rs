@@ -1,7 +1,13 @@
 use crate::{
     indicators::{
-        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+        rolling::{rolling_ma::RollingMA, rolling_sum::RollingSum},
     },
     models::{candle::Candle, ma_type::MAType, timeseries::TimeSeries},
     utils::math::{sma, vwma},
@@ -35,45 +41,19 @@ impl PopulatesCandles for PMAR {
     }
 
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, ma_type) = args.pmar_res()?;
+        let (len, ma_type) = (args.usize(0)?, args.ma_type(1)?);
         let indicator_type = IndicatorType::PMAR(len, ma_type);
+        let mut state = PMARState::new(len, ma_type);
 
-        for i in 0..ts.candles.len() {
-            let end = i + 1;
-            let pmar = if end < len {
-                None
-            } else {
-                let start = end - len;
-                Self::calculate_args(&ts.candles[start..end], &args)
-            };
+        for candle in ts.candles.iter_mut() {
+            let pmar = Self::calculate_rolling(&mut state, candle)?;
 
-            ts.candles[i]
+            candle
                 .indicators
                 .insert(indicator_type, Indicator::PMAR(pmar));
-
-            // If ma type is EMA one extra candle is needed to proceed with
-            // calculations (EMA always needs one extra previous value as
-            // compared to SMA and VWMA.
-            let needed_len = match ma_type {
-                MAType::EMA => len + 1,
-                _ => len,
-            };
-            if end < needed_len {
-                continue;
-            }
-
-            let mut pmar = pmar.context("Unable to calculate PMAR")?;
-            let sma = Self::pmar_sma(&ts.candles[end - len..end], &indicator_type);
-
-            if let Some(sma) = sma {
-                pmar.ma = Some(sma);
-
-                ts.candles[i]
-                    .indicators
-                    .insert(indicator_type, Indicator::PMAR(Some(pmar)));
-            }
         }
 
+        ts.set_indicator_state(indicator_type, IndicatorState::PMAR(Box::new(state)));
         ts.indicators.insert(indicator_type);
 
         Ok(())
@@ -84,55 +64,64 @@ impl PopulatesCandles for PMAR {
     }
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, ma_type) = args.pmar_res()?;
-        let end = ts.candles.len();
+        let (len, ma_type) = (args.usize(0)?, args.ma_type(1)?);
         let ctx_err = "Failed to get last candle";
         let indicator_type = IndicatorType::PMAR(len, ma_type);
 
-        if end == 0 {
+        if ts.candles.is_empty() {
             return Err(anyhow!("No candle to populate"));
         }
 
-        if end < len {
-            // Not enough candles to populate
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::PMAR(None));
-
-            return Ok(());
-        }
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::PMAR(state)) => *state,
+            _ => return Self::populate_candles_args(ts, args),
+        };
 
-        let new_pmar = Self::calculate_args(&ts.candles[end - len..end], &args);
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let pmar = Self::calculate_rolling(&mut state, candle)?;
 
-        // Insert pmar without moving average
-        ts.candles
-            .last_mut()
-            .context(ctx_err)?
+        candle
             .indicators
-            .insert(indicator_type, Indicator::PMAR(new_pmar));
-
-        // Attempt to calculate and insert pmar moving average
-        let sma_segment = &ts.candles[end - len..end];
-        let sma = Self::pmar_sma(sma_segment, &indicator_type);
+            .insert(indicator_type, Indicator::PMAR(pmar));
 
-        ts.candles
-            .last_mut()
-            .and_then(|candle| candle.indicators.get_mut(&indicator_type))
-            .and_then(|indicator| match indicator {
-                Indicator::PMAR(pmar) => pmar.as_mut(),
-                _ => None,
-            })
-            .map(|pmar| pmar.ma = sma);
+        ts.set_indicator_state(indicator_type, IndicatorState::PMAR(Box::new(state)));
 
         Ok(())
     }
 }
 
+impl DefinesIndicator for PMAR {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "pmar",
+        params: &[
+            ParamSchema {
+                name: "len",
+                default: ParamValue::Int(20),
+            },
+            ParamSchema {
+                name: "ma",
+                default: ParamValue::MAType(MAType::SMA),
+            },
+        ],
+        outputs: &["value", "ma"],
+        warmup: |args| Ok(args.usize(0)? + 1),
+        lag: |_| Ok(0),
+        build: |args| Ok(IndicatorType::PMAR(args.usize(0)?, args.ma_type(1)?)),
+        args: |indicator_type| match indicator_type {
+            IndicatorType::PMAR(a, b) => Some(IndicatorArgs(vec![
+                ParamValue::Int(*a),
+                ParamValue::MAType(*b),
+            ])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
 impl IsIndicator for PMAR {
     fn default_args() -> IndicatorArgs {
-        IndicatorArgs::PMARArgs(20, MAType::SMA)
+        IndicatorType::PMAR(20, MAType::SMA).args()
     }
 
     /// Segment should be the same length as the PMAR wanted.
@@ -140,8 +129,8 @@ impl IsIndicator for PMAR {
     where
         Self: Sized,
     {
-        let (_, ma_type) = Self::default_args().pmar_opt()?;
-        let args = IndicatorArgs::PMARArgs(segment.len(), ma_type);
+        let ma_type = Self::default_args().ma_type(1).ok()?;
+        let args = IndicatorType::PMAR(segment.len(), ma_type).args();
 
         Self::calculate_pmar(segment, &args)
     }
@@ -150,7 +139,7 @@ impl IsIndicator for PMAR {
     where
         Self: Sized,
     {
-        let (len, _) = args.pmar_opt()?;
+        let len = args.usize(0).ok()?;
         let candle_len = segment.len();
 
         if candle_len < len {
@@ -163,7 +152,7 @@ impl IsIndicator for PMAR {
 
 impl PMAR {
     fn calculate_pmar(segment: &[Candle], args: &IndicatorArgs) -> Option<Self> {
-        let (len, ma_type) = args.pmar_opt()?;
+        let (len, ma_type) = (args.usize(0).ok()?, args.ma_type(1).ok()?);
         let segment_len = segment.len();
 
         if segment_len == 0 || segment_len < len {
@@ -198,6 +187,18 @@ impl PMAR {
                 let values: Vec<(f64, f64)> = segment.iter().map(|c| (c.close, c.volume)).collect();
                 segment.last()?.close / vwma(&values)
             }
+            _ => {
+                // Averages depending on more history than the window, such as
+                // the DEMA, are seeded within it and only match the populated
+                // values once they have converged.
+                let mut ma = RollingMA::new(ma_type, len);
+                let ma = segment
+                    .iter()
+                    .map(|c| ma.push_with_volume(Some(c.close), c.volume))
+                    .last()??;
+
+                segment.last()?.close / ma
+            }
         };
 
         Some(PMAR::new(pmar, segment_len))
@@ -211,14 +212,77 @@ impl PMAR {
         }
     }
 
-    fn pmar_sma(segment: &[Candle], indicator_type: &IndicatorType) -> Option<f64> {
-        let values: Vec<f64> = segment
-            .iter()
-            .filter_map(|c| c.clone_indicator(indicator_type).ok()?.as_pmar())
-            .map(|pmar| pmar.value)
-            .collect();
+    /// Adds the candle to the rolling state and calculates the PMAR of it
+    /// along with the moving average of the last `len` PMAR values.
+    fn calculate_rolling(state: &mut PMARState, candle: &Candle) -> Result<Option<Self>> {
+        let value = state.next_value(candle);
+        state.values.push(value);
+
+        let mut pmar = value.map(|value| PMAR::new(value, state.len));
+
+        // If ma type is EMA one extra candle is needed to proceed with
+        // calculations (EMA always needs one extra previous value as
+        // compared to SMA and VWMA.
+        let needed_len = match state.ma_type {
+            MAType::EMA => state.len + 1,
+            _ => state.len,
+        };
 
-        (values.len() == segment.len()).then_some(sma(&values))
+        if state.seen >= needed_len {
+            pmar.as_mut().context("Unable to calculate PMAR")?.ma = state.values.mean();
+        }
+
+        Ok(pmar)
+    }
+}
+
+/// Rolling state of PMAR: the moving average of the closes along with the
+/// last PMAR values for the moving average of the PMAR.
+#[derive(Debug, Clone)]
+pub struct PMARState {
+    len: usize,
+    ma_type: MAType,
+    closes: RollingMA,
+    values: RollingSum,
+    seen: usize,
+}
+
+impl PMARState {
+    pub fn new(len: usize, ma_type: MAType) -> Self {
+        Self {
+            len,
+            ma_type,
+            closes: RollingMA::new(ma_type, len),
+            values: RollingSum::new(len),
+            seen: 0,
+        }
+    }
+
+    /// Adds the candle to the window and returns its PMAR value, without the
+    /// moving average. Used by PMARP which only needs the values.
+    pub fn next_value(&mut self, candle: &Candle) -> Option<f64> {
+        self.seen += 1;
+        let ma = self
+            .closes
+            .push_with_volume(Some(candle.close), candle.volume);
+
+        if self.len == 0 || self.seen < self.len {
+            return None;
+        }
+
+        if self.len == 1 {
+            return Some(1.0);
+        }
+
+        let ma = match self.ma_type {
+            MAType::EMA => {
+                let ema = candle.indicators.get(&IndicatorType::EMA(self.len))?;
+                ema.as_ema()?.value
+            }
+            _ => ma?,
+        };
+
+        Some(candle.close / ma)
     }
 }
 
@@ -226,15 +290,19 @@ impl PMAR {
 mod tests {
     use crate::{
         indicators::{
-            indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-            is_indicator::IsIndicator, pmar::PMAR, populates_candles::PopulatesCandles,
+            indicator_type::IndicatorType, is_indicator::IsIndicator, pmar::PMAR,
+            populates_candles::PopulatesCandles,
         },
         models::{
             candle::Candle, interval::Interval, ma_type::MAType,
             timeseries_builder::TimeSeriesBuilder,
         },
-        utils::data::dummy_data::PRICE_CHANGES,
+        utils::data::{
+            candle_strategy::{self, assert_close},
+            dummy_data::PRICE_CHANGES,
+        },
     };
+    use proptest::prelude::*;
 
     const FINAL_VALUE: f64 = 1.0150163271810746;
 
@@ -251,7 +319,7 @@ mod tests {
     #[test]
     fn pmar_calculate_args() {
         let candles = Candle::dummy_from_increments(&PRICE_CHANGES);
-        let args = IndicatorArgs::PMARArgs(3, MAType::SMA);
+        let args = IndicatorType::PMAR(3, MAType::SMA).args();
         let pmar = PMAR::calculate_args(&candles[1..4], &args);
         assert!(pmar.is_some());
 
@@ -295,7 +363,9 @@ mod tests {
 
         let _ = PMAR::populate_candles(&mut ts);
 
-        let (len, ma_type) = PMAR::default_args().pmar_opt().unwrap();
+        let args = PMAR::default_args();
+
+        let (len, ma_type) = (args.usize(0).unwrap(), args.ma_type(1).unwrap());
         let indicator_type = IndicatorType::PMAR(len, ma_type);
 
         for (i, candle) in ts.candles.iter().enumerate() {
@@ -331,7 +401,9 @@ mod tests {
         let _ = PMAR::populate_candles(&mut ts);
         let _ = ts.add_candle(&candle);
 
-        let (len, ma_type) = PMAR::default_args().pmar_opt().unwrap();
+        let args = PMAR::default_args();
+
+        let (len, ma_type) = (args.usize(0).unwrap(), args.ma_type(1).unwrap());
         let indicator_type = IndicatorType::PMAR(len, ma_type);
 
         for (i, candle) in ts.candles.iter().enumerate() {
@@ -354,4 +426,23 @@ mod tests {
 
         assert_eq!(last_pmar.value, FINAL_VALUE);
     }
+
+    proptest! {
+        #[test]
+        fn pmar_matches_recomputation(
+            candles in candle_strategy::candles(1, 80),
+            len in 1..20usize,
+            ma_type in prop_oneof![Just(MAType::SMA), Just(MAType::VWMA)],
+        ) {
+            let args = IndicatorType::PMAR(len, ma_type).args();
+            let indicator_type = IndicatorType::PMAR(len, ma_type);
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            for (i, candle) in populated.iter().enumerate() {
+                let expected = PMAR::calculate_args(&candles[..=i], &args).map(|pmar| pmar.value);
+                let actual = candle.clone_indicator(&indicator_type).unwrap().as_pmar();
+                assert_close(actual.map(|pmar| pmar.value), expected)?;
+            }
+        }
+    }
 }
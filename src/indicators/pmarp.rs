@@ -1,10 +1,16 @@
 use crate::{
     indicators::{
-        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-        is_indicator::IsIndicator, pmar::PMAR, populates_candles::PopulatesCandles,
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        pmar::{PMARState, PMAR},
+        populates_candles::PopulatesCandles,
+        rolling::{rolling_ma::RollingMA, rolling_rank::RollingRank},
     },
     models::{candle::Candle, ma_type::MAType, timeseries::TimeSeries},
-    utils::math::sma,
 };
 use anyhow::{anyhow, Context, Result};
 
@@ -28,46 +34,24 @@ impl PopulatesCandles for PMARP {
     }
 
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, lookback, ma_type) = args.pmarp_res()?;
-        let sma_len = len; // TODO: Change argument type so this is provided
-        let indicator_type = IndicatorType::PMARP(len, lookback, ma_type);
-
-        for i in 0..ts.candles.len() {
-            let end = i + 1;
-            let pmarp = if end < len {
-                None
-            } else {
-                let start = if end > lookback + len {
-                    end - lookback - len
-                } else {
-                    0
-                };
-
-                Self::calculate_args(&ts.candles[start..end], &args)
-            };
-
-            ts.candles[i]
+        let (len, lookback, ma_type, signal_ma_type) = (
+            args.usize(0)?,
+            args.usize(1)?,
+            args.ma_type(2)?,
+            args.ma_type(3)?,
+        );
+        let indicator_type = IndicatorType::PMARP(len, lookback, ma_type, signal_ma_type);
+        let mut state = PMARPState::new(len, lookback, ma_type, signal_ma_type);
+
+        for candle in ts.candles.iter_mut() {
+            let pmarp = Self::calculate_rolling(&mut state, candle)?;
+
+            candle
                 .indicators
                 .insert(indicator_type, Indicator::PMARP(pmarp));
-
-            // Not enough candles to populate pmarp sma so return
-            if end < len + sma_len {
-                continue;
-            }
-
-            let mut pmarp = pmarp.context("Unable to calculate PMARP")?;
-            let sma_segment = &ts.candles[end - sma_len..end];
-            let sma = Self::pmarp_sma(sma_segment, &indicator_type);
-
-            if let Some(sma) = sma {
-                pmarp.ma = Some(sma);
-
-                ts.candles[i]
-                    .indicators
-                    .insert(indicator_type, Indicator::PMARP(Some(pmarp)));
-            }
         }
 
+        ts.set_indicator_state(indicator_type, IndicatorState::PMARP(Box::new(state)));
         ts.indicators.insert(indicator_type);
 
         Ok(())
@@ -78,67 +62,86 @@ impl PopulatesCandles for PMARP {
     }
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, lookback, ma_type) = args.pmarp_res()?;
-        let sma_len = len; // TODO: Change argument type so this is provided
+        let (len, lookback, ma_type, signal_ma_type) = (
+            args.usize(0)?,
+            args.usize(1)?,
+            args.ma_type(2)?,
+            args.ma_type(3)?,
+        );
         let ctx_err = "Unable to get last candle";
-        let indicator_type = IndicatorType::PMARP(len, lookback, ma_type);
-        let end = ts.candles.len();
+        let indicator_type = IndicatorType::PMARP(len, lookback, ma_type, signal_ma_type);
 
-        if end == 0 {
+        if ts.candles.is_empty() {
             return Err(anyhow!("No candle to populate"));
         }
 
-        // Not enough candles to populate
-        if end < len {
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::PMARP(None));
-
-            return Ok(());
-        }
-
-        let start = if end > lookback + len {
-            end - lookback - len
-        } else {
-            0
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::PMARP(state)) => *state,
+            _ => return Self::populate_candles_args(ts, args),
         };
 
-        let mut new_pmarp = Self::calculate_args(&ts.candles[start..end], &args)
-            .context("Unable to calculate PMARP")?;
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let pmarp = Self::calculate_rolling(&mut state, candle)?;
 
-        ts.candles
-            .last_mut()
-            .context(ctx_err)?
+        candle
             .indicators
-            .insert(indicator_type, Indicator::PMARP(Some(new_pmarp)));
-
-        // Not enough candles to populate pmarp sma so return
-        if end < len + sma_len {
-            return Ok(());
-        }
+            .insert(indicator_type, Indicator::PMARP(pmarp));
 
-        let sma_segment = &ts.candles[end - sma_len..end];
-        let sma = Self::pmarp_sma(sma_segment, &indicator_type);
-
-        if let Some(sma) = sma {
-            new_pmarp.ma = Some(sma);
-
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::PMARP(Some(new_pmarp)));
-        }
+        ts.set_indicator_state(indicator_type, IndicatorState::PMARP(Box::new(state)));
 
         Ok(())
     }
 }
 
+impl DefinesIndicator for PMARP {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "pmarp",
+        params: &[
+            ParamSchema {
+                name: "len",
+                default: ParamValue::Int(20),
+            },
+            ParamSchema {
+                name: "lookback",
+                default: ParamValue::Int(350),
+            },
+            ParamSchema {
+                name: "ma",
+                default: ParamValue::MAType(MAType::VWMA),
+            },
+            ParamSchema {
+                name: "signal_ma",
+                default: ParamValue::MAType(MAType::SMA),
+            },
+        ],
+        outputs: &["value", "ma"],
+        warmup: |args| Ok(args.usize(1)? + 1),
+        lag: |_| Ok(0),
+        build: |args| {
+            Ok(IndicatorType::PMARP(
+                args.usize(0)?,
+                args.usize(1)?,
+                args.ma_type(2)?,
+                args.ma_type(3)?,
+            ))
+        },
+        args: |indicator_type| match indicator_type {
+            IndicatorType::PMARP(a, b, c, d) => Some(IndicatorArgs(vec![
+                ParamValue::Int(*a),
+                ParamValue::Int(*b),
+                ParamValue::MAType(*c),
+                ParamValue::MAType(*d),
+            ])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
 impl IsIndicator for PMARP {
     fn default_args() -> IndicatorArgs {
-        IndicatorArgs::PMARPArgs(20, 350, MAType::VWMA)
+        IndicatorType::PMARP(20, 350, MAType::VWMA, MAType::SMA).args()
     }
 
     fn calculate(segment: &[Candle]) -> Option<Self>
@@ -154,7 +157,7 @@ impl IsIndicator for PMARP {
     {
         let mut pmars = Self::get_pmars(segment, args).ok()?;
         let new_pmar = pmars.pop()??;
-        let (len, lookback, _) = args.pmarp_opt()?;
+        let (len, lookback) = (args.usize(0).ok()?, args.usize(1).ok()?);
 
         let count = pmars
             .iter()
@@ -183,38 +186,77 @@ impl PMARP {
         }
     }
 
-    fn pmarp_sma(segment: &[Candle], indicator_type: &IndicatorType) -> Option<f64> {
-        let values: Vec<f64> = segment
-            .iter()
-            .filter_map(|c| c.clone_indicator(indicator_type).ok()?.as_pmarp())
-            .map(|pmarp| pmarp.value)
-            .collect();
+    /// Adds the candle to the rolling state and calculates the PMARP of it.
+    /// Gives the same result as calculating over the last `lookback + len`
+    /// candles without having to recalculate every PMAR in the lookback.
+    fn calculate_rolling(state: &mut PMARPState, candle: &Candle) -> Result<Option<Self>> {
+        let (len, lookback, sma_len) = (state.len, state.lookback, state.sma_len);
+        state.seen += 1;
+
+        let pmar = state.pmar.next_value(candle);
+        let pmarp = pmar.map(|pmar| {
+            let count = state.ranks.count_below(pmar);
+            PMARP::new((count as f64) / (lookback as f64), len, lookback)
+        });
+
+        state.ranks.push(pmar);
+        let ma = state.values.push(pmarp.map(|pmarp| pmarp.value));
+
+        // Not enough candles to populate pmarp sma
+        if state.seen < len + sma_len {
+            return Ok(pmarp);
+        }
+
+        let mut pmarp = pmarp.context("Unable to calculate PMARP")?;
+        pmarp.ma = ma;
 
-        (values.len() == segment.len()).then_some(sma(&values))
+        Ok(Some(pmarp))
     }
 
     fn get_pmars(segment: &[Candle], args: &IndicatorArgs) -> Result<Vec<Option<PMAR>>> {
-        // TODO: Refactor populates candles trait to include methods accepting
-        // just candles instead of only TimeSeries to not need temporary
-        // TimeSeries below.
+        let (len, ma_type) = (args.usize(0)?, args.ma_type(2)?);
+        let pmar_args = IndicatorType::PMAR(len, ma_type).args();
 
-        let temp_segment = segment.to_vec();
-        let mut temp_ts = TimeSeries::dummy();
-        temp_ts.set_candles(&temp_segment);
+        let pmars = (1..=segment.len())
+            .map(|end| {
+                if end < len {
+                    None
+                } else {
+                    PMAR::calculate_args(&segment[end - len..end], &pmar_args)
+                }
+            })
+            .collect();
 
-        let (len, _, ma_type) = args.pmarp_res()?;
-        let pmar_args = IndicatorArgs::PMARArgs(len, ma_type);
-        PMAR::populate_candles_args(&mut temp_ts, pmar_args)?;
+        Ok(pmars)
+    }
+}
 
-        let ind_type = IndicatorType::PMAR(len, ma_type);
+/// Rolling state of PMARP: the PMAR window, the previous PMAR values within
+/// the lookback and the signal line moving average of the PMARP values.
+#[derive(Debug, Clone)]
+pub struct PMARPState {
+    len: usize,
+    lookback: usize,
+    sma_len: usize,
+    pmar: PMARState,
+    ranks: RollingRank,
+    values: RollingMA,
+    seen: usize,
+}
 
-        let pmars: Vec<Option<PMAR>> = temp_ts
-            .candles
-            .iter()
-            .map(|candle| candle.clone_indicator(&ind_type).unwrap().as_pmar())
-            .collect();
+impl PMARPState {
+    pub fn new(len: usize, lookback: usize, ma_type: MAType, signal_ma_type: MAType) -> Self {
+        let sma_len = len; // TODO: Change argument type so this is provided
 
-        Ok(pmars)
+        Self {
+            len,
+            lookback,
+            sma_len,
+            pmar: PMARState::new(len, ma_type),
+            ranks: RollingRank::new(lookback),
+            values: RollingMA::new(signal_ma_type, sma_len),
+            seen: 0,
+        }
     }
 }
 
@@ -222,15 +264,19 @@ impl PMARP {
 mod tests {
     use crate::{
         indicators::{
-            indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-            is_indicator::IsIndicator, pmarp::PMARP, populates_candles::PopulatesCandles,
+            indicator_type::IndicatorType, is_indicator::IsIndicator, pmarp::PMARP,
+            populates_candles::PopulatesCandles,
         },
         models::{
             candle::Candle, interval::Interval, ma_type::MAType,
             timeseries_builder::TimeSeriesBuilder,
         },
-        utils::data::dummy_data::PRICE_CHANGES,
+        utils::data::{
+            candle_strategy::{self, assert_close},
+            dummy_data::PRICE_CHANGES,
+        },
     };
+    use proptest::prelude::*;
 
     const FINAL_VALUE: f64 = 0.3314285714285714;
 
@@ -247,8 +293,13 @@ mod tests {
     #[test]
     fn pmarp_calculate_args_vwma() {
         let candles = Candle::dummy_from_increments(&PRICE_CHANGES);
-        let (len, lookback, _) = PMARP::default_args().pmarp_opt().unwrap();
-        let args = IndicatorArgs::PMARPArgs(len, lookback, MAType::VWMA);
+        let args = PMARP::default_args();
+        let (len, lookback, signal_ma_type) = (
+            args.usize(0).unwrap(),
+            args.usize(1).unwrap(),
+            args.ma_type(3).unwrap(),
+        );
+        let args = IndicatorType::PMARP(len, lookback, MAType::VWMA, signal_ma_type).args();
         let pmarp = PMARP::calculate_args(&candles, &args);
         assert!(pmarp.is_some());
 
@@ -259,8 +310,13 @@ mod tests {
     #[test]
     fn pmarp_calculate_args_sma() {
         let candles = Candle::dummy_from_increments(&PRICE_CHANGES);
-        let (len, lookback, _) = PMARP::default_args().pmarp_opt().unwrap();
-        let args = IndicatorArgs::PMARPArgs(len, lookback, MAType::SMA);
+        let args = PMARP::default_args();
+        let (len, lookback, signal_ma_type) = (
+            args.usize(0).unwrap(),
+            args.usize(1).unwrap(),
+            args.ma_type(3).unwrap(),
+        );
+        let args = IndicatorType::PMARP(len, lookback, MAType::SMA, signal_ma_type).args();
         let pmarp = PMARP::calculate_args(&candles, &args);
         assert!(pmarp.is_some());
 
@@ -315,8 +371,15 @@ mod tests {
 
         let _ = PMARP::populate_candles(&mut ts);
 
-        let (len, lookback, ma_type) = PMARP::default_args().pmarp_opt().unwrap();
-        let indicator_type = IndicatorType::PMARP(len, lookback, ma_type);
+        let args = PMARP::default_args();
+
+        let (len, lookback, ma_type, signal_ma_type) = (
+            args.usize(0).unwrap(),
+            args.usize(1).unwrap(),
+            args.ma_type(2).unwrap(),
+            args.ma_type(3).unwrap(),
+        );
+        let indicator_type = IndicatorType::PMARP(len, lookback, ma_type, signal_ma_type);
 
         for (i, candle) in ts.candles.iter().enumerate() {
             let indicator = candle.indicators.get(&indicator_type).unwrap();
@@ -348,12 +411,18 @@ mod tests {
             .candles(candles)
             .build();
 
-        let (len, lookback, _) = PMARP::default_args().pmarp_opt().unwrap();
+        let args = PMARP::default_args();
+
+        let (len, lookback, signal_ma_type) = (
+            args.usize(0).unwrap(),
+            args.usize(1).unwrap(),
+            args.ma_type(3).unwrap(),
+        );
         let ma_type = MAType::VWMA;
-        let args = IndicatorArgs::PMARPArgs(len, lookback, ma_type);
+        let args = IndicatorType::PMARP(len, lookback, ma_type, signal_ma_type).args();
         let _ = PMARP::populate_candles_args(&mut ts, args);
 
-        let indicator_type = IndicatorType::PMARP(len, lookback, ma_type);
+        let indicator_type = IndicatorType::PMARP(len, lookback, ma_type, signal_ma_type);
 
         for (i, candle) in ts.candles.iter().enumerate() {
             let indicator = candle.indicators.get(&indicator_type).unwrap();
@@ -374,7 +443,7 @@ mod tests {
             .unwrap();
 
         assert_eq!(last_pmarp.value, FINAL_VALUE);
-        assert_eq!(last_pmarp.ma, Some(0.22428571428571428));
+        assert!((last_pmarp.ma.unwrap() - 0.22428571428571428).abs() < 1e-12);
     }
 
     #[test]
@@ -390,8 +459,15 @@ mod tests {
         let _ = PMARP::populate_candles(&mut ts);
         let _ = ts.add_candle(&candle);
 
-        let (len, lookback, ma_type) = PMARP::default_args().pmarp_opt().unwrap();
-        let indicator_type = IndicatorType::PMARP(len, lookback, ma_type);
+        let args = PMARP::default_args();
+
+        let (len, lookback, ma_type, signal_ma_type) = (
+            args.usize(0).unwrap(),
+            args.usize(1).unwrap(),
+            args.ma_type(2).unwrap(),
+            args.ma_type(3).unwrap(),
+        );
+        let indicator_type = IndicatorType::PMARP(len, lookback, ma_type, signal_ma_type);
 
         for (i, candle) in ts.candles.iter().enumerate() {
             let indicator = candle.indicators.get(&indicator_type).unwrap();
@@ -413,4 +489,26 @@ mod tests {
 
         assert_eq!(last_pmarp.value, FINAL_VALUE);
     }
+
+    proptest! {
+        #[test]
+        fn pmarp_matches_recomputation(
+            candles in candle_strategy::candles(1, 80),
+            len in 1..10usize,
+            lookback in 1..30usize,
+            ma_type in prop_oneof![Just(MAType::SMA), Just(MAType::VWMA)],
+            signal_ma_type in prop_oneof![Just(MAType::SMA), Just(MAType::EMA), Just(MAType::WMA)],
+        ) {
+            let args = IndicatorType::PMARP(len, lookback, ma_type, signal_ma_type).args();
+            let indicator_type = IndicatorType::PMARP(len, lookback, ma_type, signal_ma_type);
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            for (i, candle) in populated.iter().enumerate() {
+                let start = (i + 1).saturating_sub(lookback + len);
+                let expected = PMARP::calculate_args(&candles[start..=i], &args);
+                let actual = candle.clone_indicator(&indicator_type).unwrap().as_pmarp();
+                assert_close(actual.map(|pmarp| pmarp.value), expected.map(|pmarp| pmarp.value))?;
+            }
+        }
+    }
 }
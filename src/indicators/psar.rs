@@ -0,0 +1,346 @@
+use crate::{
+    indicators::{
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+    },
+    models::{candle::Candle, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Context, Result};
+
+/// # PSAR
+///
+/// Parabolic Stop and Reverse. The stop trails the extreme point of the
+/// trend, accelerating by `increment` each time a new extreme is made up to
+/// `max`. When price crosses the stop the trend reverses and the stop is
+/// placed at the extreme point of the previous trend.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct PSAR {
+    pub value: f64,
+    pub uptrend: bool,
+}
+
+impl PopulatesCandles for PSAR {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (start, increment, max) = (args.f64(0)?, args.f64(1)?, args.f64(2)?);
+        let indicator_type = IndicatorType::PSAR(start.into(), increment.into(), max.into());
+        let mut state = PSARState::default();
+
+        for candle in ts.candles.iter_mut() {
+            let psar = Self::calculate_rolling(&mut state, candle, (start, increment, max));
+
+            candle
+                .indicators
+                .insert(indicator_type, Indicator::PSAR(psar));
+        }
+
+        ts.set_indicator_state(indicator_type, IndicatorState::PSAR(state));
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (start, increment, max) = (args.f64(0)?, args.f64(1)?, args.f64(2)?);
+        let ctx_err = "Unable to get last candle";
+        let indicator_type = IndicatorType::PSAR(start.into(), increment.into(), max.into());
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::PSAR(state)) => state,
+            _ => return Self::populate_candles_args(ts, args),
+        };
+
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let psar = Self::calculate_rolling(&mut state, candle, (start, increment, max));
+
+        candle
+            .indicators
+            .insert(indicator_type, Indicator::PSAR(psar));
+
+        ts.set_indicator_state(indicator_type, IndicatorState::PSAR(state));
+
+        Ok(())
+    }
+}
+
+impl DefinesIndicator for PSAR {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "psar",
+        params: &[
+            ParamSchema {
+                name: "start",
+                default: ParamValue::Float(0.02),
+            },
+            ParamSchema {
+                name: "increment",
+                default: ParamValue::Float(0.02),
+            },
+            ParamSchema {
+                name: "max",
+                default: ParamValue::Float(0.2),
+            },
+        ],
+        outputs: &["value", "direction"],
+        warmup: |_| Ok(2),
+        lag: |_| Ok(0),
+        build: |args| {
+            Ok(IndicatorType::PSAR(
+                args.f64(0)?.into(),
+                args.f64(1)?.into(),
+                args.f64(2)?.into(),
+            ))
+        },
+        args: |indicator_type| match indicator_type {
+            IndicatorType::PSAR(a, b, c) => Some(IndicatorArgs(vec![
+                ParamValue::Float(a.value()),
+                ParamValue::Float(b.value()),
+                ParamValue::Float(c.value()),
+            ])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
+impl IsIndicator for PSAR {
+    fn default_args() -> IndicatorArgs {
+        IndicatorType::PSAR(0.02.into(), 0.02.into(), 0.2.into()).args()
+    }
+
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::calculate_args(segment, &Self::default_args())
+    }
+
+    /// Calculates the PSAR of the last candle with the trend started at the
+    /// beginning of the segment.
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let factors = (args.f64(0).ok()?, args.f64(1).ok()?, args.f64(2).ok()?);
+        let mut state = PSARState::default();
+
+        segment
+            .iter()
+            .map(|candle| Self::calculate_rolling(&mut state, candle, factors))
+            .last()?
+    }
+}
+
+impl PSAR {
+    /// Returns 1 for an uptrend and -1 for a downtrend.
+    pub fn direction(&self) -> f64 {
+        if self.uptrend {
+            1.0
+        } else {
+            -1.0
+        }
+    }
+
+    fn calculate_rolling(
+        state: &mut PSARState,
+        candle: &Candle,
+        (start, increment, max): (f64, f64, f64),
+    ) -> Option<Self> {
+        let prev = state.prev.replace((candle.high, candle.low, candle.close));
+        let prev_prev = state.prev_prev;
+        let (prev_high, prev_low, prev_close) = prev?;
+        state.prev_prev = Some((prev_high, prev_low));
+
+        let mut reversed = false;
+        let (mut value, mut extreme, mut uptrend, mut acceleration) = match state.trend {
+            Some(trend) => trend,
+            None => {
+                // The first trend follows the direction of the second close
+                reversed = true;
+                if candle.close > prev_close {
+                    (prev_low, candle.high, true, start)
+                } else {
+                    (prev_high, candle.low, false, start)
+                }
+            }
+        };
+
+        value += acceleration * (extreme - value);
+
+        if uptrend && value > candle.low {
+            reversed = true;
+            uptrend = false;
+            value = candle.high.max(extreme);
+            extreme = candle.low;
+            acceleration = start;
+        } else if !uptrend && value < candle.high {
+            reversed = true;
+            uptrend = true;
+            value = candle.low.min(extreme);
+            extreme = candle.high;
+            acceleration = start;
+        }
+
+        if !reversed {
+            if uptrend && candle.high > extreme {
+                extreme = candle.high;
+                acceleration = (acceleration + increment).min(max);
+            } else if !uptrend && candle.low < extreme {
+                extreme = candle.low;
+                acceleration = (acceleration + increment).min(max);
+            }
+        }
+
+        // The stop may not move into the range of the two previous candles
+        if uptrend {
+            value = value.min(prev_low);
+            if let Some((_, low)) = prev_prev {
+                value = value.min(low);
+            }
+        } else {
+            value = value.max(prev_high);
+            if let Some((high, _)) = prev_prev {
+                value = value.max(high);
+            }
+        }
+
+        state.trend = Some((value, extreme, uptrend, acceleration));
+
+        Some(PSAR { value, uptrend })
+    }
+}
+
+/// Stop, extreme point, direction and acceleration of the current trend along
+/// with the two previous candles.
+#[derive(Debug, Clone, Default)]
+pub struct PSARState {
+    trend: Option<(f64, f64, bool, f64)>,
+    prev: Option<(f64, f64, f64)>,
+    prev_prev: Option<(f64, f64)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_type::IndicatorType, is_indicator::IsIndicator,
+            populates_candles::PopulatesCandles, psar::PSAR,
+        },
+        models::{interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::candle_strategy,
+    };
+    use proptest::prelude::*;
+
+    #[test]
+    fn psar_populate_candles() {
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candle_strategy::price_change_candles())
+            .build();
+
+        PSAR::populate_candles(&mut ts).unwrap();
+
+        let indicator_type = IndicatorType::PSAR(0.02.into(), 0.02.into(), 0.2.into());
+        let psars: Vec<Option<PSAR>> = ts
+            .candles
+            .iter()
+            .map(|c| c.clone_indicator(&indicator_type).unwrap().as_psar())
+            .collect();
+
+        assert!(psars[0].is_none());
+        assert!(psars[1].is_some());
+    }
+
+    #[test]
+    fn psar_worked_example() {
+        let candles = candle_strategy::hlc_candles(&[
+            (10.0, 9.0, 9.5),
+            (11.0, 10.0, 10.5),
+            (12.0, 11.0, 11.5),
+            (13.0, 12.0, 12.5),
+            (14.0, 13.0, 13.5),
+            (12.0, 10.0, 10.5),
+            (11.0, 9.0, 9.5),
+            (10.0, 8.0, 8.5),
+        ]);
+        let indicator_type = IndicatorType::PSAR(0.1.into(), 0.1.into(), 0.2.into());
+        let populated = candle_strategy::populated(&candles, indicator_type);
+        let psars: Vec<Option<(f64, bool)>> = populated
+            .iter()
+            .map(|c| c.clone_indicator(&indicator_type).unwrap().as_psar())
+            .map(|psar| psar.map(|psar| (psar.value, psar.uptrend)))
+            .collect();
+
+        // Rising close starts an uptrend from the first low. The stop is held
+        // at the low two candles back, then accelerates at the maximum 0.2
+        // towards the highs: 9 + 0.2 * (12 - 9) and 9.6 + 0.2 * (13 - 9.6).
+        // 10.28 + 0.2 * (14 - 10.28) is above the low of 10, so the trend
+        // reverses at the extreme of 14, which is held by the high two candles
+        // back before falling to 14 + 0.2 * (9 - 14).
+        let expected = [
+            None,
+            Some((9.0, true)),
+            Some((9.0, true)),
+            Some((9.6, true)),
+            Some((10.28, true)),
+            Some((14.0, false)),
+            Some((14.0, false)),
+            Some((13.0, false)),
+        ];
+
+        for (psar, expected) in psars.iter().zip(expected) {
+            assert_eq!(psar.map(|p| p.1), expected.map(|e| e.1));
+            assert!((psar.map_or(0.0, |p| p.0) - expected.map_or(0.0, |e| e.0)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn psar_no_candles() {
+        let candles = Vec::new();
+        let psar = PSAR::calculate(&candles);
+        assert!(psar.is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn psar_matches_recomputation(
+            candles in candle_strategy::candles(1, 80),
+            start in 0.01..0.05f64,
+            increment in 0.01..0.05f64,
+            max in 0.1..0.3f64,
+        ) {
+            let indicator_type = IndicatorType::PSAR(start.into(), increment.into(), max.into());
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            // The stop is below the candle in an uptrend and above it otherwise
+            for candle in populated.iter() {
+                let psar = candle.clone_indicator(&indicator_type).unwrap().as_psar();
+
+                if let Some(psar) = psar {
+                    if psar.uptrend {
+                        prop_assert!(psar.value <= candle.low);
+                    } else {
+                        prop_assert!(psar.value >= candle.high);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod order_statistic_tree;
+pub mod rolling_ema;
+pub mod rolling_extreme;
+pub mod rolling_ma;
+pub mod rolling_rank;
+pub mod rolling_stats;
+pub mod rolling_sum;
@@ -0,0 +1,261 @@
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone)]
+struct Node {
+    value: f64,
+    priority: u64,
+    count: usize,
+    size: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+/// # OrderStatisticTree
+///
+/// Multiset of floats backed by a treap where every node tracks the size of
+/// its subtree. Inserting, removing and counting the values below a given
+/// value all take logarithmic time. Values are ordered by `f64::total_cmp`.
+#[derive(Debug, Clone)]
+pub struct OrderStatisticTree {
+    nodes: Vec<Node>,
+    free: Vec<usize>,
+    root: Option<usize>,
+    seed: u64,
+}
+
+impl Default for OrderStatisticTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrderStatisticTree {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![],
+            free: vec![],
+            root: None,
+            seed: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn insert(&mut self, value: f64) {
+        let (less, rest) = self.split(self.root, value, false);
+        let (equal, greater) = self.split(rest, value, true);
+
+        let equal = match equal {
+            Some(i) => {
+                self.nodes[i].count += 1;
+                self.nodes[i].size += 1;
+                Some(i)
+            }
+            None => Some(self.new_node(value)),
+        };
+
+        let left = self.merge(less, equal);
+        self.root = self.merge(left, greater);
+    }
+
+    /// Removes one occurrence of the value. Returns false if the value is not
+    /// present.
+    pub fn remove(&mut self, value: f64) -> bool {
+        let (less, rest) = self.split(self.root, value, false);
+        let (equal, greater) = self.split(rest, value, true);
+
+        let (removed, equal) = match equal {
+            Some(i) if self.nodes[i].count > 1 => {
+                self.nodes[i].count -= 1;
+                self.nodes[i].size -= 1;
+                (true, Some(i))
+            }
+            Some(i) => {
+                self.free.push(i);
+                (true, None)
+            }
+            None => (false, None),
+        };
+
+        let left = self.merge(less, equal);
+        self.root = self.merge(left, greater);
+
+        removed
+    }
+
+    /// Number of values strictly less than the given value.
+    pub fn count_less(&self, value: f64) -> usize {
+        let mut count = 0;
+        let mut current = self.root;
+
+        while let Some(i) = current {
+            let node = &self.nodes[i];
+
+            if node.value.total_cmp(&value) == Ordering::Less {
+                count += self.size(node.left) + node.count;
+                current = node.right;
+            } else {
+                current = node.left;
+            }
+        }
+
+        count
+    }
+
+    fn new_node(&mut self, value: f64) -> usize {
+        // xorshift64
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+
+        let node = Node {
+            value,
+            priority: self.seed,
+            count: 1,
+            size: 1,
+            left: None,
+            right: None,
+        };
+
+        match self.free.pop() {
+            Some(i) => {
+                self.nodes[i] = node;
+                i
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn size(&self, node: Option<usize>) -> usize {
+        node.map_or(0, |i| self.nodes[i].size)
+    }
+
+    fn update(&mut self, i: usize) {
+        let node = &self.nodes[i];
+        self.nodes[i].size = node.count + self.size(node.left) + self.size(node.right);
+    }
+
+    /// Splits the tree into values less than `value` and the rest. If
+    /// `inclusive` is set values equal to `value` go to the left tree.
+    fn split(
+        &mut self,
+        node: Option<usize>,
+        value: f64,
+        inclusive: bool,
+    ) -> (Option<usize>, Option<usize>) {
+        let Some(i) = node else {
+            return (None, None);
+        };
+
+        let goes_left = match self.nodes[i].value.total_cmp(&value) {
+            Ordering::Less => true,
+            Ordering::Equal => inclusive,
+            Ordering::Greater => false,
+        };
+
+        if goes_left {
+            let (left, right) = self.split(self.nodes[i].right, value, inclusive);
+            self.nodes[i].right = left;
+            self.update(i);
+            (Some(i), right)
+        } else {
+            let (left, right) = self.split(self.nodes[i].left, value, inclusive);
+            self.nodes[i].left = right;
+            self.update(i);
+            (left, Some(i))
+        }
+    }
+
+    /// Merges two trees where all values of `left` are less than those of
+    /// `right`.
+    fn merge(&mut self, left: Option<usize>, right: Option<usize>) -> Option<usize> {
+        match (left, right) {
+            (None, right) => right,
+            (left, None) => left,
+            (Some(l), Some(r)) => {
+                if self.nodes[l].priority > self.nodes[r].priority {
+                    self.nodes[l].right = self.merge(self.nodes[l].right, Some(r));
+                    self.update(l);
+                    Some(l)
+                } else {
+                    self.nodes[r].left = self.merge(Some(l), self.nodes[r].left);
+                    self.update(r);
+                    Some(r)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::indicators::rolling::order_statistic_tree::OrderStatisticTree;
+    use proptest::prelude::*;
+
+    #[test]
+    fn insert_remove_and_count() {
+        let mut tree = OrderStatisticTree::new();
+
+        for value in [5.0, 1.0, 3.0, 3.0, 9.0, 7.0] {
+            tree.insert(value);
+        }
+
+        assert_eq!(tree.len(), 6);
+        assert_eq!(tree.count_less(0.0), 0);
+        assert_eq!(tree.count_less(3.0), 1);
+        assert_eq!(tree.count_less(3.5), 3);
+        assert_eq!(tree.count_less(100.0), 6);
+
+        assert!(tree.remove(3.0));
+        assert_eq!(tree.count_less(4.0), 2);
+        assert!(!tree.remove(4.0));
+
+        assert!(tree.remove(3.0));
+        assert_eq!(tree.count_less(4.0), 1);
+        assert_eq!(tree.len(), 4);
+    }
+
+    proptest! {
+        #[test]
+        fn matches_sorted_vec(
+            ops in prop::collection::vec((any::<bool>(), 0..50u8), 1..300),
+            probe in 0..50u8,
+        ) {
+            let mut tree = OrderStatisticTree::new();
+            let mut values: Vec<f64> = vec![];
+
+            for (insert, value) in ops {
+                let value = value as f64;
+
+                if insert {
+                    tree.insert(value);
+                    values.push(value);
+                } else {
+                    let position = values.iter().position(|v| *v == value);
+                    prop_assert_eq!(tree.remove(value), position.is_some());
+
+                    if let Some(i) = position {
+                        values.remove(i);
+                    }
+                }
+            }
+
+            let probe = probe as f64;
+            let expected = values.iter().filter(|v| **v < probe).count();
+
+            prop_assert_eq!(tree.len(), values.len());
+            prop_assert_eq!(tree.count_less(probe), expected);
+        }
+    }
+}
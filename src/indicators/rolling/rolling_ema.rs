@@ -0,0 +1,76 @@
+use crate::indicators::rolling::rolling_sum::RollingSum;
+
+/// # RollingEMA
+///
+/// Exponential moving average seeded with the simple average of the first
+/// `len` values, as TradingView's `ta.ema` and `ta.rma` do. The seed is taken
+/// from the first `len` consecutive values that are not missing, so averages
+/// of series which start out missing can be chained. Missing values after the
+/// seed leave the average unchanged.
+#[derive(Debug, Clone)]
+pub struct RollingEMA {
+    alpha: f64,
+    seed: RollingSum,
+    value: Option<f64>,
+}
+
+impl RollingEMA {
+    /// Exponential moving average with smoothing `2 / (len + 1)`.
+    pub fn ema(len: usize) -> Self {
+        Self::with_alpha(len, 2.0 / (len as f64 + 1.0))
+    }
+
+    /// Wilder's moving average with smoothing `1 / len`, used by the ATR,
+    /// RSI and ADX.
+    pub fn rma(len: usize) -> Self {
+        Self::with_alpha(len, 1.0 / len as f64)
+    }
+
+    fn with_alpha(len: usize, alpha: f64) -> Self {
+        Self {
+            alpha,
+            seed: RollingSum::new(len),
+            value: None,
+        }
+    }
+
+    pub fn push(&mut self, value: Option<f64>) -> Option<f64> {
+        self.value = match (self.value, value) {
+            (Some(prev), Some(value)) => Some(self.alpha * value + (1.0 - self.alpha) * prev),
+            (Some(prev), None) => Some(prev),
+            (None, value) => {
+                self.seed.push(value);
+                self.seed.mean()
+            }
+        };
+
+        self.value
+    }
+
+    #[allow(dead_code)]
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::indicators::rolling::rolling_ema::RollingEMA;
+
+    #[test]
+    fn seeds_with_average() {
+        let mut ema = RollingEMA::ema(3);
+
+        assert_eq!(ema.push(None), None);
+        assert_eq!(ema.push(Some(1.0)), None);
+        assert_eq!(ema.push(Some(2.0)), None);
+        assert_eq!(ema.push(Some(3.0)), Some(2.0));
+        assert_eq!(ema.push(Some(4.0)), Some(3.0));
+
+        let mut rma = RollingEMA::rma(2);
+        rma.push(Some(2.0));
+        rma.push(Some(4.0));
+        assert_eq!(rma.push(Some(5.0)), Some(4.0));
+        assert_eq!(rma.value(), Some(4.0));
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::VecDeque;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Extreme {
+    Max,
+    Min,
+}
+
+/// # RollingExtreme
+///
+/// Maximum or minimum of the last `len` values using a monotonic queue, so
+/// each push costs amortized constant time.
+#[derive(Debug, Clone)]
+pub struct RollingExtreme {
+    len: usize,
+    extreme: Extreme,
+    queue: VecDeque<(usize, f64)>,
+    pushes: usize,
+}
+
+impl RollingExtreme {
+    pub fn new(len: usize, extreme: Extreme) -> Self {
+        Self {
+            len,
+            extreme,
+            queue: VecDeque::new(),
+            pushes: 0,
+        }
+    }
+
+    pub fn max(len: usize) -> Self {
+        Self::new(len, Extreme::Max)
+    }
+
+    pub fn min(len: usize) -> Self {
+        Self::new(len, Extreme::Min)
+    }
+
+    pub fn push(&mut self, value: f64) {
+        let i = self.pushes;
+        self.pushes += 1;
+
+        while let Some((_, last)) = self.queue.back() {
+            let dominated = match self.extreme {
+                Extreme::Max => *last <= value,
+                Extreme::Min => *last >= value,
+            };
+
+            if !dominated {
+                break;
+            }
+
+            self.queue.pop_back();
+        }
+
+        self.queue.push_back((i, value));
+
+        while let Some((j, _)) = self.queue.front() {
+            if j + self.len > i {
+                break;
+            }
+
+            self.queue.pop_front();
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len > 0 && self.pushes >= self.len
+    }
+
+    /// Number of values pushed since the extreme was created.
+    pub fn pushes(&self) -> usize {
+        self.pushes
+    }
+
+    /// Extreme of the window, available once it holds `len` values.
+    pub fn value(&self) -> Option<f64> {
+        if !self.is_full() {
+            return None;
+        }
+
+        self.queue.front().map(|(_, value)| *value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::indicators::rolling::rolling_extreme::RollingExtreme;
+
+    #[test]
+    fn rolling_max_and_min() {
+        let values = [5.0, 3.0, 8.0, 1.0, 1.0, 6.0, 2.0, 9.0];
+        let mut max = RollingExtreme::max(3);
+        let mut min = RollingExtreme::min(3);
+
+        for (i, value) in values.iter().enumerate() {
+            max.push(*value);
+            min.push(*value);
+
+            if i < 2 {
+                assert!(max.value().is_none());
+                continue;
+            }
+
+            let window = &values[i - 2..=i];
+            let expected_max = window.iter().cloned().fold(f64::MIN, f64::max);
+            let expected_min = window.iter().cloned().fold(f64::MAX, f64::min);
+
+            assert_eq!(max.value(), Some(expected_max));
+            assert_eq!(min.value(), Some(expected_min));
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::VecDeque;
+
+use crate::{
+    indicators::rolling::{rolling_ema::RollingEMA, rolling_sum::RollingSum},
+    models::ma_type::MAType,
+    utils::math::{dema, efficiency_ratio, hma_lens, kama_rolling, tema, wma},
+};
+
+/// # RollingMA
+///
+/// Moving average of any `MAType` over a stream of values, matching the
+/// TradingView implementations. Averages over a window (SMA, VWMA, WMA, HMA
+/// and KAMA) need `len` consecutive values that are not missing, while the
+/// exponential averages (EMA, RMA, DEMA and TEMA) are seeded from the first
+/// `len` values that are not missing and keep their value when one is.
+#[derive(Debug, Clone)]
+pub struct RollingMA {
+    average: Average,
+}
+
+#[derive(Debug, Clone)]
+enum Average {
+    Sum(RollingSum),
+    Volume(RollingSum, RollingSum),
+    Exponential(Vec<RollingEMA>),
+    Weighted(Window),
+    Hull(Window, Window, Window),
+    Kaufman(Window, Option<f64>),
+}
+
+impl RollingMA {
+    pub fn new(ma_type: MAType, len: usize) -> Self {
+        let average = match ma_type {
+            MAType::SMA => Average::Sum(RollingSum::new(len)),
+            MAType::VWMA => Average::Volume(RollingSum::new(len), RollingSum::new(len)),
+            MAType::EMA => Average::Exponential(vec![RollingEMA::ema(len)]),
+            MAType::RMA => Average::Exponential(vec![RollingEMA::rma(len)]),
+            MAType::DEMA => Average::Exponential(vec![RollingEMA::ema(len); 2]),
+            MAType::TEMA => Average::Exponential(vec![RollingEMA::ema(len); 3]),
+            MAType::WMA => Average::Weighted(Window::new(len)),
+            MAType::HMA => {
+                let (half, sqrt) = hma_lens(len);
+                Average::Hull(Window::new(half), Window::new(len), Window::new(sqrt))
+            }
+            MAType::KAMA => Average::Kaufman(Window::new(len + 1), None),
+        };
+
+        Self { average }
+    }
+
+    pub fn push(&mut self, value: Option<f64>) -> Option<f64> {
+        self.push_with_volume(value, 1.0)
+    }
+
+    /// Adds the value to the average. The volume is only used by the VWMA.
+    pub fn push_with_volume(&mut self, value: Option<f64>, volume: f64) -> Option<f64> {
+        match &mut self.average {
+            Average::Sum(sum) => {
+                sum.push(value);
+                sum.mean()
+            }
+            Average::Volume(weighted, volumes) => {
+                weighted.push(value.map(|value| value * volume));
+                volumes.push(value.map(|_| volume));
+                Some(weighted.sum()? / volumes.sum()?)
+            }
+            Average::Exponential(emas) => {
+                let mut values = Vec::with_capacity(emas.len());
+                let mut next = value;
+
+                // Each EMA averages the one before it
+                for ema in emas.iter_mut() {
+                    next = ema.push(next);
+                    values.push(next);
+                }
+
+                match values[..] {
+                    [ema] => ema,
+                    [ema, ema2] => Some(dema(ema?, ema2?)),
+                    [ema, ema2, ema3] => Some(tema(ema?, ema2?, ema3?)),
+                    _ => None,
+                }
+            }
+            Average::Weighted(window) => window.push(value).map(wma),
+            Average::Hull(half, full, hull) => {
+                let half = half.push(value).map(wma);
+                let full = full.push(value).map(wma);
+                let raw = half.zip(full).map(|(half, full)| 2.0 * half - full);
+
+                hull.push(raw).map(wma)
+            }
+            Average::Kaufman(window, kama) => {
+                let Some(value) = value else {
+                    window.push(None);
+                    *kama = None;
+                    return None;
+                };
+
+                let er = window.push(Some(value)).map(efficiency_ratio)?;
+                let prev = kama.unwrap_or(value);
+                *kama = Some(kama_rolling(prev, value, er));
+
+                *kama
+            }
+        }
+    }
+}
+
+/// Last `len` consecutive values, cleared by a missing value.
+#[derive(Debug, Clone)]
+struct Window {
+    len: usize,
+    values: VecDeque<f64>,
+}
+
+impl Window {
+    fn new(len: usize) -> Self {
+        Self {
+            len,
+            values: VecDeque::with_capacity(len + 1),
+        }
+    }
+
+    /// Adds the value and returns the window once it holds `len` values.
+    fn push(&mut self, value: Option<f64>) -> Option<&[f64]> {
+        let Some(value) = value else {
+            self.values.clear();
+            return None;
+        };
+
+        self.values.push_back(value);
+
+        if self.values.len() > self.len {
+            self.values.pop_front();
+        }
+
+        if self.len == 0 || self.values.len() < self.len {
+            return None;
+        }
+
+        Some(self.values.make_contiguous())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::rolling::rolling_ma::RollingMA,
+        models::ma_type::MAType,
+        utils::math::{sma, wma},
+    };
+
+    fn averages(ma_type: MAType, len: usize, values: &[f64]) -> Vec<Option<f64>> {
+        let mut ma = RollingMA::new(ma_type, len);
+        values.iter().map(|value| ma.push(Some(*value))).collect()
+    }
+
+    #[test]
+    fn window_averages() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+
+        let smas = averages(MAType::SMA, 3, &values);
+        assert_eq!(smas[1], None);
+        assert_eq!(smas[5], Some(sma(&values[3..])));
+
+        let wmas = averages(MAType::WMA, 3, &values);
+        assert_eq!(wmas[2], Some((1.0 + 4.0 + 9.0) / 6.0));
+        assert_eq!(wmas[5], Some(wma(&values[3..])));
+
+        // Hull of a straight line has no lag
+        let hmas = averages(MAType::HMA, 4, &values);
+        assert_eq!(hmas[3], None);
+        assert!((hmas[4].unwrap() - 5.0).abs() < 1e-12);
+        assert!((hmas[5].unwrap() - 6.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn volume_weighted_average() {
+        let mut vwma = RollingMA::new(MAType::VWMA, 2);
+
+        assert_eq!(vwma.push_with_volume(Some(10.0), 1.0), None);
+        assert_eq!(vwma.push_with_volume(Some(20.0), 3.0), Some(17.5));
+    }
+
+    #[test]
+    fn exponential_averages() {
+        let values = [2.0, 4.0, 6.0, 8.0, 10.0];
+
+        let emas = averages(MAType::EMA, 2, &values);
+        assert_eq!(emas[1], Some(3.0));
+        assert_eq!(emas[2], Some(5.0));
+
+        let rmas = averages(MAType::RMA, 2, &values);
+        assert_eq!(rmas[2], Some(4.5));
+
+        // The second EMA is seeded from the first two EMA values
+        let demas = averages(MAType::DEMA, 2, &values);
+        assert_eq!(demas[1], None);
+        assert_eq!(demas[2], Some(2.0 * 5.0 - 4.0));
+
+        let temas = averages(MAType::TEMA, 2, &values);
+        assert_eq!(temas[2], None);
+        assert!(temas[3].is_some());
+    }
+
+    #[test]
+    fn kaufman_adaptive_average() {
+        let flat = averages(MAType::KAMA, 3, &[5.0; 6]);
+        assert_eq!(flat[2], None);
+        assert_eq!(flat[3], Some(5.0));
+        assert_eq!(flat[5], Some(5.0));
+
+        // A perfectly efficient trend follows with the fast smoothing
+        let trend = averages(MAType::KAMA, 2, &[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(trend[2], Some(3.0));
+        let expected = 3.0 + (2.0_f64 / 3.0).powi(2) * (4.0 - 3.0);
+        assert!((trend[3].unwrap() - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn missing_values() {
+        let mut wma = RollingMA::new(MAType::WMA, 2);
+        wma.push(Some(1.0));
+        wma.push(Some(2.0));
+        assert_eq!(wma.push(None), None);
+        assert_eq!(wma.push(Some(3.0)), None);
+
+        let mut ema = RollingMA::new(MAType::EMA, 2);
+        assert_eq!(ema.push(None), None);
+        ema.push(Some(1.0));
+        assert_eq!(ema.push(Some(3.0)), Some(2.0));
+        assert_eq!(ema.push(None), Some(2.0));
+    }
+}
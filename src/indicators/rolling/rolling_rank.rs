@@ -0,0 +1,54 @@
+use crate::indicators::rolling::order_statistic_tree::OrderStatisticTree;
+use std::collections::VecDeque;
+
+/// # RollingRank
+///
+/// Keeps the last `len` values in an order-statistic tree to count how many
+/// of them are below a new value in logarithmic time. Used for percentile
+/// based indicators such as BBWP and PMARP. Missing and NaN values take up a
+/// slot in the window but are never counted.
+#[derive(Debug, Clone)]
+pub struct RollingRank {
+    len: usize,
+    window: VecDeque<Option<f64>>,
+    tree: OrderStatisticTree,
+}
+
+impl RollingRank {
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            window: VecDeque::with_capacity(len + 1),
+            tree: OrderStatisticTree::new(),
+        }
+    }
+
+    pub fn push(&mut self, value: Option<f64>) {
+        if self.len == 0 {
+            return;
+        }
+
+        let value = value.filter(|v| !v.is_nan());
+
+        if self.window.len() == self.len {
+            if let Some(old) = self.window.pop_front().flatten() {
+                self.tree.remove(old);
+            }
+        }
+
+        if let Some(value) = value {
+            self.tree.insert(value);
+        }
+
+        self.window.push_back(value);
+    }
+
+    /// Number of values in the window strictly less than the given value.
+    pub fn count_below(&self, value: f64) -> usize {
+        if value.is_nan() {
+            return 0;
+        }
+
+        self.tree.count_less(value)
+    }
+}
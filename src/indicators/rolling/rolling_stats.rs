@@ -0,0 +1,121 @@
+use std::collections::VecDeque;
+
+/// Number of pushes after which the mean and squared deviations are
+/// recomputed from the window to keep floating point drift bounded.
+const RESYNC_INTERVAL: usize = 1024;
+
+/// # RollingStats
+///
+/// Mean and sample standard deviation over the last `len` values, updated in
+/// constant time using Welford's algorithm for adding and removing values.
+#[derive(Debug, Clone)]
+pub struct RollingStats {
+    len: usize,
+    window: VecDeque<f64>,
+    mean: f64,
+    m2: f64,
+    pushes: usize,
+}
+
+impl RollingStats {
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            window: VecDeque::with_capacity(len + 1),
+            mean: 0.0,
+            m2: 0.0,
+            pushes: 0,
+        }
+    }
+
+    pub fn push(&mut self, value: f64) {
+        if self.len == 0 {
+            return;
+        }
+
+        if self.window.len() == self.len {
+            if let Some(old) = self.window.pop_front() {
+                self.remove(old);
+            }
+        }
+
+        self.window.push_back(value);
+
+        let n = self.window.len() as f64;
+        let delta = value - self.mean;
+        self.mean += delta / n;
+        self.m2 += delta * (value - self.mean);
+
+        self.pushes += 1;
+        if self.pushes.is_multiple_of(RESYNC_INTERVAL) {
+            self.resync();
+        }
+    }
+
+    fn remove(&mut self, value: f64) {
+        let n = self.window.len() as f64;
+
+        if n == 0.0 {
+            self.mean = 0.0;
+            self.m2 = 0.0;
+            return;
+        }
+
+        let delta = value - self.mean;
+        self.mean -= delta / n;
+        self.m2 -= delta * (value - self.mean);
+    }
+
+    fn resync(&mut self) {
+        let n = self.window.len() as f64;
+        self.mean = self.window.iter().sum::<f64>() / n;
+        self.m2 = self.window.iter().map(|x| (x - self.mean).powi(2)).sum();
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len > 0 && self.window.len() == self.len
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        self.is_full().then_some(self.mean)
+    }
+
+    /// Sample standard deviation of the window, 0 for a window of one value.
+    pub fn std(&self) -> Option<f64> {
+        if !self.is_full() {
+            return None;
+        }
+
+        if self.len == 1 {
+            return Some(0.0);
+        }
+
+        Some((self.m2.max(0.0) / (self.len as f64 - 1.0)).sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{indicators::rolling::rolling_stats::RollingStats, utils::math::std};
+
+    #[test]
+    fn rolling_std() {
+        let values = [3.0, 8.0, 1.0, 9.0, 4.0, 4.0, 12.0, 2.0];
+        let mut stats = RollingStats::new(4);
+
+        for (i, value) in values.iter().enumerate() {
+            stats.push(*value);
+
+            if i < 3 {
+                assert!(stats.std().is_none());
+                continue;
+            }
+
+            let window = &values[i - 3..=i];
+            let mean = window.iter().sum::<f64>() / 4.0;
+
+            assert!((stats.mean().unwrap() - mean).abs() < 1e-12);
+            assert!((stats.std().unwrap() - std(window, mean)).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::VecDeque;
+
+/// Number of pushes after which the running sum is recomputed from the
+/// window to keep floating point drift bounded.
+const RESYNC_INTERVAL: usize = 1024;
+
+/// # RollingSum
+///
+/// Running sum over the last `len` values. Missing values can be pushed as
+/// `None`, in which case no sum or mean is available until they have left the
+/// window.
+#[derive(Debug, Clone)]
+pub struct RollingSum {
+    len: usize,
+    window: VecDeque<Option<f64>>,
+    sum: f64,
+    missing: usize,
+    pushes: usize,
+}
+
+impl RollingSum {
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            window: VecDeque::with_capacity(len + 1),
+            sum: 0.0,
+            missing: 0,
+            pushes: 0,
+        }
+    }
+
+    pub fn push(&mut self, value: Option<f64>) {
+        if self.len == 0 {
+            return;
+        }
+
+        if self.window.len() == self.len {
+            match self.window.pop_front().flatten() {
+                Some(old) => self.sum -= old,
+                None => self.missing -= 1,
+            }
+        }
+
+        match value {
+            Some(value) => self.sum += value,
+            None => self.missing += 1,
+        }
+
+        self.window.push_back(value);
+        self.pushes += 1;
+
+        if self.pushes.is_multiple_of(RESYNC_INTERVAL) {
+            self.sum = self.window.iter().flatten().sum();
+        }
+    }
+
+    /// Returns true if the window holds `len` values, none of them missing.
+    pub fn is_full(&self) -> bool {
+        self.len > 0 && self.window.len() == self.len && self.missing == 0
+    }
+
+    pub fn sum(&self) -> Option<f64> {
+        self.is_full().then_some(self.sum)
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        self.sum().map(|sum| sum / self.len as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::indicators::rolling::rolling_sum::RollingSum;
+
+    #[test]
+    fn rolling_mean() {
+        let mut sum = RollingSum::new(3);
+
+        sum.push(Some(1.0));
+        sum.push(Some(2.0));
+        assert_eq!(sum.mean(), None);
+
+        sum.push(Some(3.0));
+        assert_eq!(sum.mean(), Some(2.0));
+
+        sum.push(Some(7.0));
+        assert_eq!(sum.sum(), Some(12.0));
+
+        sum.push(None);
+        assert_eq!(sum.mean(), None);
+
+        sum.push(Some(1.0));
+        sum.push(Some(1.0));
+        assert_eq!(sum.mean(), None);
+
+        sum.push(Some(1.0));
+        assert_eq!(sum.mean(), Some(1.0));
+    }
+}
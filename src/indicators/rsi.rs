@@ -1,13 +1,25 @@
 use crate::{
     indicators::{
-        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+        rolling::rolling_ma::RollingMA,
     },
-    models::{candle::Candle, timeseries::TimeSeries},
+    models::{candle::Candle, ma_type::MAType, timeseries::TimeSeries},
 };
 use anyhow::{anyhow, Context, Result};
 use serde::Serialize;
 
+/// # RSI
+///
+/// Relative Strength Index, the share of the average gain out of the average
+/// absolute change over the last `len` candles. The averages are Wilder's
+/// moving averages (RMA) by default, as in TradingView, but any `MAType` can
+/// be used, e.g. SMA for Cutler's RSI.
 #[derive(Debug, Copy, Clone, Serialize, PartialEq, PartialOrd)]
 pub struct RSI {
     pub value: f64,
@@ -22,8 +34,12 @@ impl PopulatesCandles for RSI {
     }
 
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let len = args.len_res()?;
-        let indicator_type = IndicatorType::RSI(len);
+        let (len, ma_type) = (args.usize(0)?, args.ma_type(1)?);
+        let indicator_type = IndicatorType::RSI(len, ma_type);
+
+        if ma_type != MAType::RMA {
+            return Self::populate_candles_smoothed(ts, len, ma_type);
+        }
 
         let mut prev: Option<RSI> = None;
 
@@ -55,8 +71,8 @@ impl PopulatesCandles for RSI {
     }
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let len = args.len_res()?;
-        let indicator_type = IndicatorType::RSI(len);
+        let (len, ma_type) = (args.usize(0)?, args.ma_type(1)?);
+        let indicator_type = IndicatorType::RSI(len, ma_type);
         let ctx_err = "Unable to get last candle";
         let candle_len = ts.candles.len();
 
@@ -64,8 +80,26 @@ impl PopulatesCandles for RSI {
             return Err(anyhow!("No candle to populate"));
         }
 
+        if ma_type != MAType::RMA {
+            let mut state = match ts.take_indicator_state(&indicator_type) {
+                Some(IndicatorState::RSI(state)) => state,
+                _ => return Self::populate_candles_args(ts, args),
+            };
+
+            let candle = ts.candles.last_mut().context(ctx_err)?;
+            let rsi = Self::calculate_smoothed(&mut state, candle);
+
+            candle
+                .indicators
+                .insert(indicator_type, Indicator::RSI(rsi));
+
+            ts.set_indicator_state(indicator_type, IndicatorState::RSI(state));
+
+            return Ok(());
+        }
+
         // Not enough candles to populate
-        if candle_len < len {
+        if candle_len <= len {
             ts.candles
                 .last_mut()
                 .context(ctx_err)?
@@ -79,9 +113,8 @@ impl PopulatesCandles for RSI {
         let prev_rsi = Indicator::get_second_last(ts, &indicator_type).and_then(|rsi| rsi.as_rsi());
 
         let new_rsi = if prev_rsi.is_none() {
-            let start = candle_len - len;
-            let end = candle_len - 1;
-            Self::calculate_args(&ts.candles[start..end], &args)
+            // Seed from the same len + 1 candles as when populating all
+            Self::calculate_args(&ts.candles[candle_len - len - 1..candle_len], &args)
         } else {
             let candles = (&ts.candles[candle_len - 2], &ts.candles[candle_len - 1]);
             Self::calculate_rolling(candles, prev_rsi.unwrap(), len)
@@ -97,9 +130,38 @@ impl PopulatesCandles for RSI {
     }
 }
 
+impl DefinesIndicator for RSI {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "rsi",
+        params: &[
+            ParamSchema {
+                name: "len",
+                default: ParamValue::Int(14),
+            },
+            ParamSchema {
+                name: "ma",
+                default: ParamValue::MAType(MAType::RMA),
+            },
+        ],
+        outputs: &["value"],
+        warmup: |args| Ok(args.usize(0)? + 1),
+        lag: |_| Ok(0),
+        build: |args| Ok(IndicatorType::RSI(args.usize(0)?, args.ma_type(1)?)),
+        args: |indicator_type| match indicator_type {
+            IndicatorType::RSI(a, b) => Some(IndicatorArgs(vec![
+                ParamValue::Int(*a),
+                ParamValue::MAType(*b),
+            ])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
 impl IsIndicator for RSI {
     fn default_args() -> IndicatorArgs {
-        IndicatorArgs::LengthArg(14)
+        IndicatorType::RSI(14, MAType::RMA).args()
     }
 
     /// Segment should be the length + 1 for the RSI wanted.
@@ -127,9 +189,19 @@ impl IsIndicator for RSI {
     where
         Self: Sized,
     {
-        let len = args.len_opt()?;
+        let (len, ma_type) = (args.usize(0).ok()?, args.ma_type(1).ok()?);
         let candle_len = segment.len();
 
+        // Other averages than Wilder's are seeded at the start of the segment
+        if ma_type != MAType::RMA {
+            let mut state = RSIState::new(len, ma_type);
+
+            return segment
+                .iter()
+                .map(|candle| Self::calculate_smoothed(&mut state, candle))
+                .last()?;
+        }
+
         if candle_len < len + 1 {
             return None;
         }
@@ -179,6 +251,43 @@ impl RSI {
         Self::calculate_rsi(rs, len, (gains / f_len, losses / f_len))
     }
 
+    fn populate_candles_smoothed(ts: &mut TimeSeries, len: usize, ma_type: MAType) -> Result<()> {
+        let indicator_type = IndicatorType::RSI(len, ma_type);
+        let mut state = RSIState::new(len, ma_type);
+
+        for candle in ts.candles.iter_mut() {
+            let rsi = Self::calculate_smoothed(&mut state, candle);
+
+            candle
+                .indicators
+                .insert(indicator_type, Indicator::RSI(rsi));
+        }
+
+        ts.set_indicator_state(indicator_type, IndicatorState::RSI(state));
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    /// Adds the change in close to the average gain and loss and calculates
+    /// the RSI from them.
+    fn calculate_smoothed(state: &mut RSIState, candle: &Candle) -> Option<Self> {
+        let change = state.prev_close.map(|prev| candle.close - prev);
+        state.prev_close = Some(candle.close);
+
+        let avg_gain = state.gains.push(change.map(|change| change.max(0.0)));
+        let avg_loss = state.losses.push(change.map(|change| (-change).max(0.0)));
+        let (avg_gain, avg_loss) = avg_gain.zip(avg_loss)?;
+
+        let rs = if avg_loss != 0.0 {
+            avg_gain / avg_loss
+        } else {
+            f64::INFINITY
+        };
+
+        Self::calculate_rsi(rs, state.len, (avg_gain, avg_loss))
+    }
+
     fn get_outcomes(segment: &[Candle]) -> (f64, f64) {
         let mut gains = 0.0;
         let mut losses = 0.0;
@@ -202,14 +311,38 @@ impl RSI {
     }
 }
 
+/// Previous close along with the average gain and loss of an RSI using
+/// another average than Wilder's.
+#[derive(Debug, Clone)]
+pub struct RSIState {
+    len: usize,
+    prev_close: Option<f64>,
+    gains: RollingMA,
+    losses: RollingMA,
+}
+
+impl RSIState {
+    pub fn new(len: usize, ma_type: MAType) -> Self {
+        Self {
+            len,
+            prev_close: None,
+            gains: RollingMA::new(ma_type, len),
+            losses: RollingMA::new(ma_type, len),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
         indicators::{
-            indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-            is_indicator::IsIndicator, populates_candles::PopulatesCandles, rsi::RSI,
+            indicator_type::IndicatorType, is_indicator::IsIndicator,
+            populates_candles::PopulatesCandles, rsi::RSI,
+        },
+        models::{
+            candle::Candle, interval::Interval, ma_type::MAType,
+            timeseries_builder::TimeSeriesBuilder,
         },
-        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
     };
 
     #[test]
@@ -225,7 +358,7 @@ mod tests {
     #[test]
     fn rsi_calculate_args() {
         let candles = Candle::dummy_data(14, "alternating", 100.0);
-        let args = IndicatorArgs::LengthArg(13);
+        let args = IndicatorType::RSI(13, MAType::RMA).args();
         let rsi = RSI::calculate_args(&candles, &args);
 
         assert!(rsi.is_some());
@@ -259,8 +392,10 @@ mod tests {
 
         let _ = RSI::populate_candles(&mut ts);
 
-        let len = RSI::default_args().len_opt().unwrap();
-        let indicator_type = IndicatorType::RSI(len);
+        let args = RSI::default_args();
+
+        let (len, ma_type) = (args.usize(0).unwrap(), args.ma_type(1).unwrap());
+        let indicator_type = IndicatorType::RSI(len, ma_type);
 
         for (i, candle) in ts.candles.iter().enumerate() {
             let indicator = candle.indicators.get(&indicator_type).unwrap();
@@ -297,8 +432,10 @@ mod tests {
 
         let _ = ts.add_candle(&candle);
 
-        let len = RSI::default_args().len_opt().unwrap();
-        let indicator_type = IndicatorType::RSI(len);
+        let args = RSI::default_args();
+
+        let (len, ma_type) = (args.usize(0).unwrap(), args.ma_type(1).unwrap());
+        let indicator_type = IndicatorType::RSI(len, ma_type);
 
         for (i, candle) in ts.candles.iter().enumerate() {
             let indicator = candle.indicators.get(&indicator_type).unwrap();
@@ -320,4 +457,19 @@ mod tests {
 
         assert!(last_rsi.value - 70.6923842589078 < 0.0001);
     }
+
+    #[test]
+    fn rsi_sma_average() {
+        let candles = Candle::dummy_data(20, "alternating", 100.0);
+        let args = IndicatorType::RSI(4, MAType::SMA).args();
+
+        // Cutler's RSI only depends on the last len changes
+        let rsi = RSI::calculate_args(&candles, &args).unwrap();
+        let window = RSI::calculate_args(&candles[15..], &args).unwrap();
+        let (avg_gain, avg_loss) = RSI::get_outcomes(&candles[15..]);
+
+        assert!((rsi.value - window.value).abs() < 1e-9);
+        assert!((rsi.avg_gain - avg_gain).abs() < 1e-9);
+        assert!((rsi.avg_loss - avg_loss).abs() < 1e-9);
+    }
 }
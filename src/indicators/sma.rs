@@ -1,7 +1,13 @@
 use crate::{
     indicators::{
-        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+        rolling::rolling_sum::RollingSum,
     },
     models::{candle::Candle, timeseries::TimeSeries},
     utils::math::sma,
@@ -20,23 +26,19 @@ impl PopulatesCandles for SMA {
     }
 
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let len = args.len_res()?;
+        let len = args.usize(0)?;
         let indicator_type = IndicatorType::SMA(len);
+        let mut state = RollingSum::new(len);
 
-        for i in 0..ts.candles.len() {
-            let end = i + 1;
-            let sma = if end < len {
-                None
-            } else {
-                let start = end - len;
-                Self::calculate_args(&ts.candles[start..end], &args)
-            };
+        for candle in ts.candles.iter_mut() {
+            let sma = Self::calculate_rolling(&mut state, candle, len);
 
-            ts.candles[i]
+            candle
                 .indicators
                 .insert(indicator_type, Indicator::SMA(sma));
         }
 
+        ts.set_indicator_state(indicator_type, IndicatorState::SMA(state));
         ts.indicators.insert(indicator_type);
 
         Ok(())
@@ -47,37 +49,55 @@ impl PopulatesCandles for SMA {
     }
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let len = args.len_res()?;
-        let end = ts.candles.len();
+        let len = args.usize(0)?;
         let ctx_err = "Failed to get last candle";
         let indicator_type = IndicatorType::SMA(len);
 
-        if end == 0 {
+        if ts.candles.is_empty() {
             return Err(anyhow!("No candle to populate"));
-        } else if end < len {
-            // Not enough candles to populate
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::SMA(None));
-        } else {
-            let new_sma = Self::calculate_args(&ts.candles[end - len..end], &args);
-
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::SMA(new_sma));
         }
 
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::SMA(state)) => state,
+            _ => return Self::populate_candles_args(ts, args),
+        };
+
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let sma = Self::calculate_rolling(&mut state, candle, len);
+
+        candle
+            .indicators
+            .insert(indicator_type, Indicator::SMA(sma));
+
+        ts.set_indicator_state(indicator_type, IndicatorState::SMA(state));
+
         Ok(())
     }
 }
 
+impl DefinesIndicator for SMA {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "sma",
+        params: &[ParamSchema {
+            name: "len",
+            default: ParamValue::Int(8),
+        }],
+        outputs: &["value"],
+        warmup: |args| Ok(args.usize(0)? + 1),
+        lag: |_| Ok(0),
+        build: |args| Ok(IndicatorType::SMA(args.usize(0)?)),
+        args: |indicator_type| match indicator_type {
+            IndicatorType::SMA(a) => Some(IndicatorArgs(vec![ParamValue::Int(*a)])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
 impl IsIndicator for SMA {
     fn default_args() -> IndicatorArgs {
-        IndicatorArgs::LengthArg(8)
+        IndicatorType::SMA(8).args()
     }
 
     /// Segment should be the same length as the of SMA wanted.
@@ -102,7 +122,7 @@ impl IsIndicator for SMA {
     where
         Self: Sized,
     {
-        let len = args.len_opt()?;
+        let len = args.usize(0).ok()?;
         let candle_len = segment.len();
 
         if candle_len < len {
@@ -113,15 +133,24 @@ impl IsIndicator for SMA {
     }
 }
 
+impl SMA {
+    fn calculate_rolling(state: &mut RollingSum, candle: &Candle, len: usize) -> Option<Self> {
+        state.push(Some(candle.close));
+        state.mean().map(|value| SMA { len, value })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
         indicators::{
-            indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-            is_indicator::IsIndicator, populates_candles::PopulatesCandles, sma::SMA,
+            indicator_type::IndicatorType, is_indicator::IsIndicator,
+            populates_candles::PopulatesCandles, sma::SMA,
         },
         models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::candle_strategy::{self, assert_close},
     };
+    use proptest::prelude::*;
 
     #[test]
     fn sma_calculate() {
@@ -135,7 +164,7 @@ mod tests {
     #[test]
     fn sma_calculate_args() {
         let candles = Candle::dummy_data(4, "positive", 100.0);
-        let args = IndicatorArgs::LengthArg(3);
+        let args = IndicatorType::SMA(3).args();
         let sma = SMA::calculate_args(&candles[1..4], &args);
         assert!(sma.is_some());
         let sma = sma.unwrap();
@@ -154,7 +183,7 @@ mod tests {
     #[test]
     fn calculate_args_sma_single() {
         let candles = Candle::dummy_data(1, "positive", 100.0);
-        let args = IndicatorArgs::LengthArg(1);
+        let args = IndicatorType::SMA(1).args();
         let sma = SMA::calculate_args(&candles, &args);
         assert!(sma.is_some());
         let sma = sma.unwrap();
@@ -172,7 +201,7 @@ mod tests {
 
         let _ = SMA::populate_candles(&mut ts);
 
-        let len = SMA::default_args().len_opt().unwrap();
+        let len = SMA::default_args().usize(0).unwrap();
         let indicator_type = IndicatorType::SMA(len);
 
         for (i, candle) in ts.candles.iter().enumerate() {
@@ -223,7 +252,7 @@ mod tests {
         let _ = SMA::populate_candles(&mut ts);
         let _ = ts.add_candle(&candle);
 
-        let len = SMA::default_args().len_opt().unwrap();
+        let len = SMA::default_args().usize(0).unwrap();
         let indicator_type = IndicatorType::SMA(len);
 
         for (i, candle) in ts.candles.iter().enumerate() {
@@ -246,4 +275,22 @@ mod tests {
 
         assert_eq!(last_sma.value, 165.0);
     }
+
+    proptest! {
+        #[test]
+        fn sma_matches_recomputation(
+            candles in candle_strategy::candles(1, 150),
+            len in 1..30usize,
+        ) {
+            let args = IndicatorType::SMA(len).args();
+            let indicator_type = IndicatorType::SMA(len);
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            for (i, candle) in populated.iter().enumerate() {
+                let expected = SMA::calculate_args(&candles[..=i], &args).map(|sma| sma.value);
+                let actual = candle.clone_indicator(&indicator_type).unwrap().as_sma();
+                assert_close(actual.map(|sma| sma.value), expected)?;
+            }
+        }
+    }
 }
@@ -0,0 +1,439 @@
+use crate::{
+    indicators::{
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+    },
+    models::{candle::Candle, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Context, Result};
+
+/// Side of price a zone formed on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SRZoneKind {
+    Support,
+    Resistance,
+}
+
+/// # SRZone
+///
+/// Price zone formed by pivots within the zone width of each other.
+///
+/// The score weighs the number of touches by how recent the last touch is
+/// and by the volume of the pivot candles relative to the average pivot
+/// volume of the window, so a zone touched 3 times recently on high volume
+/// outscores one touched 3 times long ago.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SRZone {
+    pub low: f64,
+    pub high: f64,
+    pub kind: SRZoneKind,
+    /// Number of pivots in the zone.
+    pub touches: usize,
+    /// Total volume of the pivot candles.
+    pub volume: f64,
+    /// Number of candles since the last pivot in the zone.
+    pub age: usize,
+    pub score: f64,
+    /// Whether price has closed through the zone since it formed, e.g. above
+    /// a resistance zone.
+    pub broken: bool,
+    /// Whether price returned to the zone after breaking it and closed on the
+    /// broken side, turning resistance into support or vice versa.
+    pub retested: bool,
+}
+
+impl SRZone {
+    pub fn mid(&self) -> f64 {
+        (self.low + self.high) / 2.0
+    }
+}
+
+/// # SRZones
+///
+/// Support and resistance zones built by clustering the `DynamicPivots`
+/// style pivot highs and lows of the last `lookback` candles. Pivots are
+/// candles with the highest high or lowest low of the `len` candles on each
+/// side, and pivots within `width` (share of the price) of the lowest pivot
+/// of a zone join that zone.
+///
+/// The nearest zone below the close of the candle is its support and the
+/// nearest zone above its resistance, regardless of the side the zones
+/// formed on. Pivots are confirmed `len` candles after they occur, so every
+/// candle is calculated from its window instead of from a rolling state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SRZones {
+    /// Zones ordered by price.
+    pub zones: Vec<SRZone>,
+    pub support: Option<SRZone>,
+    pub resistance: Option<SRZone>,
+}
+
+impl PopulatesCandles for SRZones {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (len, lookback, width) = (args.usize(0)?, args.usize(1)?, args.f64(2)?);
+        let indicator_type = IndicatorType::SRZones(len, lookback, width.into());
+
+        let zones: Vec<Option<SRZones>> = (0..ts.candles.len())
+            .map(|i| Self::calculate_args(&ts.candles[..i + 1], &args))
+            .collect();
+
+        for (candle, zones) in ts.candles.iter_mut().zip(zones) {
+            candle
+                .indicators
+                .insert(indicator_type, Indicator::SRZones(zones));
+        }
+
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (len, lookback, width) = (args.usize(0)?, args.usize(1)?, args.f64(2)?);
+        let ctx_err = "Unable to get last candle";
+        let indicator_type = IndicatorType::SRZones(len, lookback, width.into());
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let zones = Self::calculate_args(&ts.candles, &args);
+
+        ts.candles
+            .last_mut()
+            .context(ctx_err)?
+            .indicators
+            .insert(indicator_type, Indicator::SRZones(zones));
+
+        Ok(())
+    }
+}
+
+impl DefinesIndicator for SRZones {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "sr",
+        params: &[
+            ParamSchema {
+                name: "len",
+                default: ParamValue::Int(5),
+            },
+            ParamSchema {
+                name: "lookback",
+                default: ParamValue::Int(200),
+            },
+            ParamSchema {
+                name: "width",
+                default: ParamValue::Float(0.005),
+            },
+        ],
+        outputs: &["support", "support_score", "resistance", "resistance_score"],
+        warmup: |args| args.usize(1),
+        lag: |_| Ok(0),
+        build: |args| {
+            Ok(IndicatorType::SRZones(
+                args.usize(0)?,
+                args.usize(1)?,
+                args.f64(2)?.into(),
+            ))
+        },
+        args: |indicator_type| match indicator_type {
+            IndicatorType::SRZones(a, b, c) => Some(IndicatorArgs(vec![
+                ParamValue::Int(*a),
+                ParamValue::Int(*b),
+                ParamValue::Float(c.value()),
+            ])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
+impl IsIndicator for SRZones {
+    fn default_args() -> IndicatorArgs {
+        IndicatorType::SRZones(5, 200, 0.005.into()).args()
+    }
+
+    /// Calculates the zones of the whole segment using the default pivot
+    /// length and zone width.
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let args = Self::default_args();
+        let (len, width) = (args.usize(0).ok()?, args.f64(2).ok()?);
+        Self::calculate_zones(segment, len, width)
+    }
+
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let (len, lookback, width) = (args.usize(0).ok()?, args.usize(1).ok()?, args.f64(2).ok()?);
+        let candle_len = segment.len();
+
+        if lookback == 0 || candle_len < lookback {
+            return None;
+        }
+
+        Self::calculate_zones(&segment[candle_len - lookback..candle_len], len, width)
+    }
+}
+
+/// Pivot high or low of a window.
+struct Pivot {
+    price: f64,
+    index: usize,
+    volume: f64,
+    kind: SRZoneKind,
+}
+
+impl SRZones {
+    fn calculate_zones(window: &[Candle], len: usize, width: f64) -> Option<Self> {
+        let close = window.last()?.close;
+        let mut pivots = Self::pivots(window, len);
+
+        if pivots.is_empty() {
+            return Some(Self {
+                zones: vec![],
+                support: None,
+                resistance: None,
+            });
+        }
+
+        let mean_volume = pivots.iter().map(|p| p.volume).sum::<f64>() / pivots.len() as f64;
+        pivots.sort_by(|a, b| a.price.total_cmp(&b.price));
+
+        let mut zones = vec![];
+        let mut start = 0;
+        for i in 1..=pivots.len() {
+            let ends_zone = pivots
+                .get(i)
+                .is_none_or(|p| p.price - pivots[start].price > width * p.price);
+
+            if ends_zone {
+                zones.push(Self::zone(&pivots[start..i], window, len, mean_volume));
+                start = i;
+            }
+        }
+
+        let support = zones
+            .iter()
+            .filter(|zone| zone.mid() <= close)
+            .max_by(|a, b| a.high.total_cmp(&b.high))
+            .copied();
+
+        let resistance = zones
+            .iter()
+            .filter(|zone| zone.mid() > close)
+            .min_by(|a, b| a.low.total_cmp(&b.low))
+            .copied();
+
+        Some(Self {
+            zones,
+            support,
+            resistance,
+        })
+    }
+
+    fn pivots(window: &[Candle], len: usize) -> Vec<Pivot> {
+        let mut pivots = vec![];
+
+        for i in len..window.len().saturating_sub(len) {
+            let candle = &window[i];
+            let around = &window[i - len..=i + len];
+
+            if around.iter().all(|c| c.high <= candle.high) {
+                pivots.push(Pivot {
+                    price: candle.high,
+                    index: i,
+                    volume: candle.volume,
+                    kind: SRZoneKind::Resistance,
+                });
+            }
+
+            if around.iter().all(|c| c.low >= candle.low) {
+                pivots.push(Pivot {
+                    price: candle.low,
+                    index: i,
+                    volume: candle.volume,
+                    kind: SRZoneKind::Support,
+                });
+            }
+        }
+
+        pivots
+    }
+
+    fn zone(pivots: &[Pivot], window: &[Candle], len: usize, mean_volume: f64) -> SRZone {
+        let low = pivots[0].price;
+        let high = pivots[pivots.len() - 1].price;
+        let touches = pivots.len();
+        let volume: f64 = pivots.iter().map(|p| p.volume).sum();
+        let first = pivots.iter().min_by_key(|p| p.index).map_or(0, |p| p.index);
+        let last = pivots.iter().map(|p| p.index).max().unwrap_or(0);
+        let age = window.len() - 1 - last;
+
+        // The zone takes the side of the pivot it started from
+        let kind = pivots
+            .iter()
+            .find(|p| p.index == first)
+            .map_or(SRZoneKind::Support, |p| p.kind);
+
+        let recency = 1.0 - age as f64 / window.len() as f64;
+        let relative_volume = if mean_volume > 0.0 {
+            volume / touches as f64 / mean_volume
+        } else {
+            1.0
+        };
+
+        let is_beyond = |c: &Candle| match kind {
+            SRZoneKind::Resistance => c.close > high,
+            SRZoneKind::Support => c.close < low,
+        };
+        let reaches = |c: &Candle| match kind {
+            SRZoneKind::Resistance => c.low <= high,
+            SRZoneKind::Support => c.high >= low,
+        };
+
+        // Pivots are only known once confirmed
+        let confirmed = (first + len + 1).min(window.len());
+        let broken_at = window[confirmed..]
+            .iter()
+            .position(is_beyond)
+            .map(|i| confirmed + i);
+        let retested =
+            broken_at.is_some_and(|i| window[i + 1..].iter().any(|c| reaches(c) && is_beyond(c)));
+
+        SRZone {
+            low,
+            high,
+            kind,
+            touches,
+            volume,
+            age,
+            score: touches as f64 * recency * relative_volume,
+            broken: broken_at.is_some(),
+            retested,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_type::IndicatorType,
+            is_indicator::IsIndicator,
+            populates_candles::PopulatesCandles,
+            sr_zones::{SRZoneKind, SRZones},
+        },
+        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+    };
+    use chrono::{Duration, TimeZone, Utc};
+
+    /// Candles with the given closes, each ranging 0.5 around its close.
+    fn candles(closes: &[f64]) -> Vec<Candle> {
+        let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| {
+                let timestamp = start + Duration::days(i as i64);
+                Candle::new(timestamp, *close, *close, close + 0.5, close - 0.5, 10.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn clusters_pivots_into_zones() {
+        // Tops at 110 and 110.2 with a bottom at 100 in between
+        let closes = [
+            104.0, 107.0, 110.0, 107.0, 104.0, 100.0, 104.0, 107.0, 110.2, 107.0, 104.0,
+        ];
+        let zones = SRZones::calculate_args(
+            &candles(&closes),
+            &IndicatorType::SRZones(2, 11, 0.01.into()).args(),
+        )
+        .unwrap();
+
+        assert_eq!(zones.zones.len(), 2);
+
+        let resistance = zones.resistance.unwrap();
+        assert_eq!(resistance.kind, SRZoneKind::Resistance);
+        assert_eq!((resistance.low, resistance.high), (110.5, 110.7));
+        assert_eq!(resistance.touches, 2);
+        assert_eq!(resistance.age, 2);
+        assert!(!resistance.broken);
+
+        let support = zones.support.unwrap();
+        assert_eq!(support.kind, SRZoneKind::Support);
+        assert_eq!(support.low, 99.5);
+        assert_eq!(support.touches, 1);
+        assert!(resistance.score > support.score);
+    }
+
+    #[test]
+    fn tracks_breaks_and_retests() {
+        // Breaks above the top at 110, then dips back into it and holds
+        let closes = [
+            104.0, 107.0, 110.0, 107.0, 104.0, 108.0, 112.0, 114.0, 110.8, 113.0,
+        ];
+        let zones = SRZones::calculate_args(
+            &candles(&closes),
+            &IndicatorType::SRZones(2, 10, 0.01.into()).args(),
+        )
+        .unwrap();
+
+        let zone = zones
+            .zones
+            .iter()
+            .find(|zone| zone.kind == SRZoneKind::Resistance && zone.high == 110.5)
+            .unwrap();
+        assert!(zone.broken);
+        assert!(zone.retested);
+
+        // The broken resistance is now the nearest support
+        assert_eq!(zones.support.unwrap().high, 110.5);
+    }
+
+    #[test]
+    fn populates_candles() {
+        let closes: Vec<f64> = (0..40).map(|i| 100.0 + ((i * 7) % 11) as f64).collect();
+        let candles = candles(&closes);
+        let args = IndicatorType::SRZones(2, 20, 0.01.into()).args();
+        let indicator_type = IndicatorType::SRZones(2, 20, 0.01.into());
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles.clone())
+            .build();
+
+        SRZones::populate_candles_args(&mut ts, args.clone()).unwrap();
+
+        for (i, candle) in ts.candles.iter().enumerate() {
+            let expected = SRZones::calculate_args(&candles[..=i], &args);
+            let actual = candle
+                .clone_indicator(&indicator_type)
+                .unwrap()
+                .as_sr_zones();
+
+            assert_eq!(actual, expected);
+            assert_eq!(actual.is_some(), i >= 19);
+        }
+    }
+}
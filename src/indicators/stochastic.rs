@@ -1,9 +1,15 @@
 use crate::{
     indicators::{
-        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+        rolling::{rolling_extreme::RollingExtreme, rolling_ma::RollingMA},
     },
-    models::{candle::Candle, timeseries::TimeSeries},
+    models::{candle::Candle, ma_type::MAType, timeseries::TimeSeries},
 };
 use anyhow::{anyhow, Context, Result};
 
@@ -16,7 +22,7 @@ pub struct Stochastic {
 impl Stochastic {
     #[allow(dead_code)]
     pub fn krown_args() -> IndicatorArgs {
-        IndicatorArgs::StochasticArgs(14, 3, 6)
+        IndicatorType::Stochastic(14, 3, 6, MAType::SMA).args()
     }
 }
 
@@ -26,23 +32,24 @@ impl PopulatesCandles for Stochastic {
     }
 
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (k_len, k_smoothing, d_smoothing) = args.stochastic_res()?;
-        let indicator_type = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing);
-        let needed_candles = Self::needed_candles(k_len, k_smoothing, d_smoothing);
-
-        for i in 0..ts.candles.len() {
-            let end = i + 1;
-            let stoch = if end < needed_candles {
-                None
-            } else {
-                Self::calculate_args(&ts.candles[end - needed_candles..end], &args)
-            };
-
-            ts.candles[i]
+        let (k_len, k_smoothing, d_smoothing, ma_type) = (
+            args.usize(0)?,
+            args.usize(1)?,
+            args.usize(2)?,
+            args.ma_type(3)?,
+        );
+        let indicator_type = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing, ma_type);
+        let mut state = StochasticState::new(k_len, k_smoothing, d_smoothing, ma_type);
+
+        for candle in ts.candles.iter_mut() {
+            let stoch = Self::calculate_rolling(&mut state, candle);
+
+            candle
                 .indicators
                 .insert(indicator_type, Indicator::Stochastic(stoch));
         }
 
+        ts.set_indicator_state(indicator_type, IndicatorState::Stochastic(state));
         ts.indicators.insert(indicator_type);
 
         Ok(())
@@ -53,38 +60,86 @@ impl PopulatesCandles for Stochastic {
     }
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (k_len, k_smoothing, d_smoothing) = args.stochastic_res()?;
-        let end = ts.candles.len();
+        let (k_len, k_smoothing, d_smoothing, ma_type) = (
+            args.usize(0)?,
+            args.usize(1)?,
+            args.usize(2)?,
+            args.ma_type(3)?,
+        );
         let ctx_err = "Failed to get last candle";
-        let indicator_type = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing);
-        let needed_candles = Self::needed_candles(k_len, k_smoothing, d_smoothing);
+        let indicator_type = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing, ma_type);
 
-        if end == 0 {
+        if ts.candles.is_empty() {
             return Err(anyhow!("No candle to populate"));
-        } else if end < needed_candles {
-            // Not enough candles to populate
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::Stochastic(None));
-        } else {
-            let new_stoch = Self::calculate_args(&ts.candles[end - needed_candles..end], &args);
-
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::Stochastic(new_stoch));
         }
 
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::Stochastic(state)) => state,
+            _ => return Self::populate_candles_args(ts, args),
+        };
+
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let stoch = Self::calculate_rolling(&mut state, candle);
+
+        candle
+            .indicators
+            .insert(indicator_type, Indicator::Stochastic(stoch));
+
+        ts.set_indicator_state(indicator_type, IndicatorState::Stochastic(state));
+
         Ok(())
     }
 }
 
+impl DefinesIndicator for Stochastic {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "stoch",
+        params: &[
+            ParamSchema {
+                name: "k",
+                default: ParamValue::Int(14),
+            },
+            ParamSchema {
+                name: "k_smoothing",
+                default: ParamValue::Int(1),
+            },
+            ParamSchema {
+                name: "d_smoothing",
+                default: ParamValue::Int(3),
+            },
+            ParamSchema {
+                name: "ma",
+                default: ParamValue::MAType(MAType::SMA),
+            },
+        ],
+        outputs: &["k", "d"],
+        warmup: |args| Ok(args.usize(0)? + args.usize(1)? + args.usize(2)?),
+        lag: |_| Ok(0),
+        build: |args| {
+            Ok(IndicatorType::Stochastic(
+                args.usize(0)?,
+                args.usize(1)?,
+                args.usize(2)?,
+                args.ma_type(3)?,
+            ))
+        },
+        args: |indicator_type| match indicator_type {
+            IndicatorType::Stochastic(a, b, c, d) => Some(IndicatorArgs(vec![
+                ParamValue::Int(*a),
+                ParamValue::Int(*b),
+                ParamValue::Int(*c),
+                ParamValue::MAType(*d),
+            ])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
 impl IsIndicator for Stochastic {
     fn default_args() -> IndicatorArgs {
-        IndicatorArgs::StochasticArgs(14, 1, 3)
+        IndicatorType::Stochastic(14, 1, 3, MAType::SMA).args()
     }
 
     fn calculate(segment: &[Candle]) -> Option<Self>
@@ -98,7 +153,23 @@ impl IsIndicator for Stochastic {
     where
         Self: Sized,
     {
-        let (k_len, k_smoothing, d_smoothing) = args.stochastic_opt()?;
+        let (k_len, k_smoothing, d_smoothing, ma_type) = (
+            args.usize(0).ok()?,
+            args.usize(1).ok()?,
+            args.usize(2).ok()?,
+            args.ma_type(3).ok()?,
+        );
+
+        // Other averages than the SMA are seeded at the start of the segment
+        if ma_type != MAType::SMA {
+            let mut state = StochasticState::new(k_len, k_smoothing, d_smoothing, ma_type);
+
+            return segment
+                .iter()
+                .map(|candle| Self::calculate_rolling(&mut state, candle))
+                .last()?;
+        }
+
         let needed_candles = Self::needed_candles(k_len, k_smoothing, d_smoothing);
 
         if segment.len() < needed_candles {
@@ -152,6 +223,45 @@ impl Stochastic {
     fn needed_candles(k_len: usize, k_smoothing: usize, d_smoothing: usize) -> usize {
         k_len + k_smoothing + d_smoothing - 2
     }
+
+    /// Adds the candle to the rolling state and calculates the smoothed %K
+    /// and %D once enough candles have been seen.
+    fn calculate_rolling(state: &mut StochasticState, candle: &Candle) -> Option<Self> {
+        state.highs.push(candle.high);
+        state.lows.push(candle.low);
+
+        let raw_k = match (state.highs.value(), state.lows.value()) {
+            (Some(high), Some(low)) if high == low => Some(0.5),
+            (Some(high), Some(low)) => Some((candle.close - low) / (high - low)),
+            _ => None,
+        };
+
+        let k = state.ks.push(raw_k);
+        let d = state.ds.push(k);
+
+        Some(Self { k: k?, d: d? })
+    }
+}
+
+/// Rolling state of the stochastic: the highest high and lowest low of the
+/// last `k_len` candles along with the averages smoothing %K and %D.
+#[derive(Debug, Clone)]
+pub struct StochasticState {
+    highs: RollingExtreme,
+    lows: RollingExtreme,
+    ks: RollingMA,
+    ds: RollingMA,
+}
+
+impl StochasticState {
+    pub fn new(k_len: usize, k_smoothing: usize, d_smoothing: usize, ma_type: MAType) -> Self {
+        Self {
+            highs: RollingExtreme::max(k_len),
+            lows: RollingExtreme::min(k_len),
+            ks: RollingMA::new(ma_type, k_smoothing),
+            ds: RollingMA::new(ma_type, d_smoothing),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -161,9 +271,16 @@ mod tests {
             indicator_type::IndicatorType, is_indicator::IsIndicator,
             populates_candles::PopulatesCandles, stochastic::Stochastic,
         },
-        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
-        utils::data::dummy_data::PRICE_CHANGES,
+        models::{
+            candle::Candle, interval::Interval, ma_type::MAType,
+            timeseries_builder::TimeSeriesBuilder,
+        },
+        utils::data::{
+            candle_strategy::{self, assert_close},
+            dummy_data::PRICE_CHANGES,
+        },
     };
+    use proptest::prelude::*;
 
     const FINAL_VALUES: &[(f64, f64)] = &[
         (0.6542362500479966, 0.3932293973692202),
@@ -184,19 +301,29 @@ mod tests {
             .build();
 
         let args = Stochastic::krown_args();
-        let _ = Stochastic::populate_candles_args(&mut ts, args);
+        let _ = Stochastic::populate_candles_args(&mut ts, args.clone());
 
         let segment = &ts.candles[ts.candles.len() - 5..];
 
-        let (k_len, k_smoothing, d_smoothing) = args.stochastic_opt().unwrap();
+        let (k_len, k_smoothing, d_smoothing, ma_type) = (
+            args.usize(0).unwrap(),
+            args.usize(1).unwrap(),
+            args.usize(2).unwrap(),
+            args.ma_type(3).unwrap(),
+        );
         for (i, (k_val, d_val)) in FINAL_VALUES.iter().enumerate() {
             let stochastic = segment[i]
-                .clone_indicator(&IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing))
+                .clone_indicator(&IndicatorType::Stochastic(
+                    k_len,
+                    k_smoothing,
+                    d_smoothing,
+                    ma_type,
+                ))
                 .unwrap()
                 .as_stochastic()
                 .unwrap();
-            assert_eq!(*k_val, stochastic.k);
-            assert_eq!(*d_val, stochastic.d);
+            assert!((k_val - stochastic.k).abs() < 1e-12);
+            assert!((d_val - stochastic.d).abs() < 1e-12);
         }
     }
 
@@ -225,10 +352,15 @@ mod tests {
             .build();
 
         let args = Stochastic::krown_args();
-        let _ = Stochastic::populate_candles_args(&mut ts, args);
+        let _ = Stochastic::populate_candles_args(&mut ts, args.clone());
 
-        let (k_len, k_smoothing, d_smoothing) = args.stochastic_opt().unwrap();
-        let indicator_type = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing);
+        let (k_len, k_smoothing, d_smoothing, ma_type) = (
+            args.usize(0).unwrap(),
+            args.usize(1).unwrap(),
+            args.usize(2).unwrap(),
+            args.ma_type(3).unwrap(),
+        );
+        let indicator_type = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing, ma_type);
 
         for (i, candle) in ts.candles.iter().enumerate() {
             let indicator = candle.indicators.get(&indicator_type).unwrap();
@@ -244,12 +376,17 @@ mod tests {
 
         for (i, (k_val, d_val)) in FINAL_VALUES.iter().enumerate() {
             let stoch = segment[i]
-                .clone_indicator(&IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing))
+                .clone_indicator(&IndicatorType::Stochastic(
+                    k_len,
+                    k_smoothing,
+                    d_smoothing,
+                    ma_type,
+                ))
                 .unwrap()
                 .as_stochastic()
                 .unwrap();
-            assert_eq!(*k_val, stoch.k);
-            assert_eq!(*d_val, stoch.d);
+            assert!((k_val - stoch.k).abs() < 1e-12);
+            assert!((d_val - stoch.d).abs() < 1e-12);
         }
     }
 
@@ -264,11 +401,16 @@ mod tests {
             .candles(candles)
             .build();
         let args = Stochastic::krown_args();
-        let _ = Stochastic::populate_candles_args(&mut ts, args);
+        let _ = Stochastic::populate_candles_args(&mut ts, args.clone());
         let _ = ts.add_candle(&candle);
 
-        let (k_len, k_smoothing, d_smoothing) = args.stochastic_opt().unwrap();
-        let indicator_type = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing);
+        let (k_len, k_smoothing, d_smoothing, ma_type) = (
+            args.usize(0).unwrap(),
+            args.usize(1).unwrap(),
+            args.usize(2).unwrap(),
+            args.ma_type(3).unwrap(),
+        );
+        let indicator_type = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing, ma_type);
 
         for (i, candle) in ts.candles.iter().enumerate() {
             let indicator = candle.indicators.get(&indicator_type).unwrap();
@@ -289,7 +431,46 @@ mod tests {
             .unwrap();
 
         let (k_val, d_val) = FINAL_VALUES.last().unwrap();
-        assert_eq!(&last_stoch.k, k_val);
-        assert_eq!(&last_stoch.d, d_val);
+        assert!((last_stoch.k - k_val).abs() < 1e-12);
+        assert!((last_stoch.d - d_val).abs() < 1e-12);
+    }
+
+    proptest! {
+        #[test]
+        fn stochastic_matches_recomputation(
+            candles in candle_strategy::candles(1, 80),
+            k_len in 1..15usize,
+            k_smoothing in 1..5usize,
+            d_smoothing in 1..5usize,
+        ) {
+            let ma_type = MAType::SMA;
+            let args = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing, ma_type).args();
+            let indicator_type = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing, ma_type);
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            for (i, candle) in populated.iter().enumerate() {
+                let expected = Stochastic::calculate_args(&candles[..=i], &args);
+                let actual = candle.clone_indicator(&indicator_type).unwrap().as_stochastic();
+                assert_close(actual.map(|s| s.k), expected.map(|s| s.k))?;
+                assert_close(actual.map(|s| s.d), expected.map(|s| s.d))?;
+            }
+        }
+
+        #[test]
+        fn stochastic_smoothing_matches_recomputation(
+            candles in candle_strategy::candles(1, 80),
+            k_len in 1..15usize,
+            k_smoothing in 1..5usize,
+            d_smoothing in 1..5usize,
+            ma_type in prop_oneof![Just(MAType::EMA), Just(MAType::WMA), Just(MAType::RMA)],
+        ) {
+            let args = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing, ma_type).args();
+            let indicator_type = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing, ma_type);
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            let last = populated.last().unwrap();
+            let actual = last.clone_indicator(&indicator_type).unwrap().as_stochastic();
+            prop_assert_eq!(actual, Stochastic::calculate_args(&candles, &args));
+        }
     }
 }
@@ -0,0 +1,357 @@
+use crate::{
+    indicators::{
+        atr::ATR,
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+        rolling::rolling_sum::RollingSum,
+    },
+    models::{candle::Candle, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Context, Result};
+
+/// # Supertrend
+///
+/// Trailing stop placed `factor` ATRs from the middle of the candle. While in
+/// an uptrend the lower band is followed and may only rise, while in a
+/// downtrend the upper band is followed and may only fall. The trend flips
+/// when the close crosses the band being followed.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct Supertrend {
+    pub value: f64,
+    pub uptrend: bool,
+}
+
+impl PopulatesCandles for Supertrend {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (atr_len, factor) = (args.usize(0)?, args.f64(1)?);
+        let indicator_type = IndicatorType::Supertrend(atr_len, factor.into());
+        let mut state = SupertrendState::new(atr_len);
+
+        for candle in ts.candles.iter_mut() {
+            let supertrend = Self::calculate_rolling(&mut state, candle, factor);
+
+            candle
+                .indicators
+                .insert(indicator_type, Indicator::Supertrend(supertrend));
+        }
+
+        ts.set_indicator_state(indicator_type, IndicatorState::Supertrend(state));
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (atr_len, factor) = (args.usize(0)?, args.f64(1)?);
+        let ctx_err = "Unable to get last candle";
+        let indicator_type = IndicatorType::Supertrend(atr_len, factor.into());
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::Supertrend(state)) => state,
+            _ => return Self::populate_candles_args(ts, args),
+        };
+
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let supertrend = Self::calculate_rolling(&mut state, candle, factor);
+
+        candle
+            .indicators
+            .insert(indicator_type, Indicator::Supertrend(supertrend));
+
+        ts.set_indicator_state(indicator_type, IndicatorState::Supertrend(state));
+
+        Ok(())
+    }
+}
+
+impl DefinesIndicator for Supertrend {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "supertrend",
+        params: &[
+            ParamSchema {
+                name: "len",
+                default: ParamValue::Int(10),
+            },
+            ParamSchema {
+                name: "factor",
+                default: ParamValue::Float(3.0),
+            },
+        ],
+        outputs: &["value", "direction"],
+        warmup: |args| Ok(args.usize(0)? + 1),
+        lag: |_| Ok(0),
+        build: |args| {
+            Ok(IndicatorType::Supertrend(
+                args.usize(0)?,
+                args.f64(1)?.into(),
+            ))
+        },
+        args: |indicator_type| match indicator_type {
+            IndicatorType::Supertrend(a, b) => Some(IndicatorArgs(vec![
+                ParamValue::Int(*a),
+                ParamValue::Float(b.value()),
+            ])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
+impl IsIndicator for Supertrend {
+    fn default_args() -> IndicatorArgs {
+        IndicatorType::Supertrend(10, 3.0.into()).args()
+    }
+
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::calculate_args(segment, &Self::default_args())
+    }
+
+    /// Calculates the Supertrend of the last candle following the bands from
+    /// the start of the segment.
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let (atr_len, factor) = (args.usize(0).ok()?, args.f64(1).ok()?);
+        let mut state = SupertrendState::new(atr_len);
+
+        segment
+            .iter()
+            .map(|candle| Self::calculate_rolling(&mut state, candle, factor))
+            .last()?
+    }
+}
+
+impl Supertrend {
+    /// Returns 1 for an uptrend and -1 for a downtrend.
+    pub fn direction(&self) -> f64 {
+        if self.uptrend {
+            1.0
+        } else {
+            -1.0
+        }
+    }
+
+    fn calculate_rolling(
+        state: &mut SupertrendState,
+        candle: &Candle,
+        factor: f64,
+    ) -> Option<Self> {
+        let prev_close = state.prev.as_ref().map(|prev| prev.close);
+
+        if let Some(prev) = &state.prev {
+            state.atr = match state.atr {
+                Some(atr) => ATR::calculate_rolling((prev, candle), atr.value, state.len),
+                None => {
+                    state.seed.push(Some(ATR::true_range(prev.close, candle)));
+                    state.seed.mean().map(|value| ATR {
+                        len: state.len,
+                        value,
+                    })
+                }
+            };
+        }
+
+        state.prev = Some(Candle::new(
+            candle.timestamp,
+            candle.open,
+            candle.close,
+            candle.high,
+            candle.low,
+            candle.volume,
+        ));
+
+        let atr = state.atr?.value;
+        let middle = (candle.high + candle.low) / 2.0;
+        let mut upper = middle + factor * atr;
+        let mut lower = middle - factor * atr;
+
+        // Bands only move against the trend once the close has crossed them
+        if let (Some(prev_upper), Some(prev_lower), Some(prev_close)) =
+            (state.upper, state.lower, prev_close)
+        {
+            if lower <= prev_lower && prev_close >= prev_lower {
+                lower = prev_lower;
+            }
+
+            if upper >= prev_upper && prev_close <= prev_upper {
+                upper = prev_upper;
+            }
+        }
+
+        let uptrend = match state.uptrend {
+            None => false,
+            Some(false) => candle.close > upper,
+            Some(true) => candle.close >= lower,
+        };
+
+        state.upper = Some(upper);
+        state.lower = Some(lower);
+        state.uptrend = Some(uptrend);
+
+        Some(Supertrend {
+            value: if uptrend { lower } else { upper },
+            uptrend,
+        })
+    }
+}
+
+/// ATR of the Supertrend along with the previous candle, bands and trend.
+#[derive(Debug, Clone)]
+pub struct SupertrendState {
+    len: usize,
+    seed: RollingSum,
+    atr: Option<ATR>,
+    prev: Option<Candle>,
+    upper: Option<f64>,
+    lower: Option<f64>,
+    uptrend: Option<bool>,
+}
+
+impl SupertrendState {
+    pub fn new(atr_len: usize) -> Self {
+        Self {
+            len: atr_len,
+            seed: RollingSum::new(atr_len),
+            atr: None,
+            prev: None,
+            upper: None,
+            lower: None,
+            uptrend: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            atr::ATR, indicator_type::IndicatorType, is_indicator::IsIndicator,
+            populates_candles::PopulatesCandles, supertrend::Supertrend,
+        },
+        models::{interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::candle_strategy,
+    };
+    use proptest::prelude::*;
+
+    #[test]
+    fn supertrend_populate_candles() {
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candle_strategy::price_change_candles())
+            .build();
+
+        Supertrend::populate_candles(&mut ts).unwrap();
+
+        let indicator_type = IndicatorType::Supertrend(10, 3.0.into());
+        let supertrends: Vec<Option<Supertrend>> = ts
+            .candles
+            .iter()
+            .map(|c| c.clone_indicator(&indicator_type).unwrap().as_supertrend())
+            .collect();
+
+        assert!(supertrends[9].is_none());
+        assert!(supertrends[10].is_some());
+    }
+
+    #[test]
+    fn supertrend_worked_example() {
+        let candles = candle_strategy::hlc_candles(&[
+            (10.0, 8.0, 9.0),
+            (11.0, 9.0, 10.0),
+            (12.0, 10.0, 11.0),
+            (14.0, 12.0, 14.0),
+            (13.0, 11.0, 12.0),
+            (11.0, 9.0, 9.5),
+        ]);
+        let indicator_type = IndicatorType::Supertrend(2, 1.0.into());
+        let populated = candle_strategy::populated(&candles, indicator_type);
+        let supertrends: Vec<Option<(f64, bool)>> = populated
+            .iter()
+            .map(|c| c.clone_indicator(&indicator_type).unwrap().as_supertrend())
+            .map(|s| s.map(|s| (s.value, s.uptrend)))
+            .collect();
+
+        // The ATR is seeded with 2 from true ranges of 2 and 2, then follows
+        // true ranges of 3 to 2.5, 2.75 and 2.875. The close of 14 crosses
+        // the upper band of 11 + 2, which is held instead of rising to 15.5,
+        // and the lower band of 13 - 2.5 is held until the close drops below
+        // it and the trend follows the upper band of 10 + 2.875.
+        let expected = [
+            None,
+            None,
+            Some((13.0, false)),
+            Some((10.5, true)),
+            Some((10.5, true)),
+            Some((12.875, false)),
+        ];
+
+        for (supertrend, expected) in supertrends.iter().zip(expected) {
+            assert_eq!(supertrend.map(|s| s.1), expected.map(|e| e.1));
+            assert!((supertrend.map_or(0.0, |s| s.0) - expected.map_or(0.0, |e| e.0)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn supertrend_uses_atr() {
+        let candles = candle_strategy::price_change_candles();
+        let args = IndicatorType::Supertrend(10, 3.0.into()).args();
+
+        let atr = ATR::calculate_args(&candles[..11], &IndicatorType::ATR(10).args()).unwrap();
+        let supertrend = Supertrend::calculate_args(&candles[..11], &args).unwrap();
+        let middle = (candles[10].high + candles[10].low) / 2.0;
+
+        assert!(!supertrend.uptrend);
+        assert!((supertrend.value - (middle + 3.0 * atr.value)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn supertrend_no_candles() {
+        let candles = Vec::new();
+        let supertrend = Supertrend::calculate(&candles);
+        assert!(supertrend.is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn supertrend_matches_recomputation(
+            candles in candle_strategy::candles(1, 80),
+            atr_len in 1..20usize,
+            factor in 0.5..5.0f64,
+        ) {
+            let indicator_type = IndicatorType::Supertrend(atr_len, factor.into());
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            for candle in populated.iter() {
+                let supertrend = candle.clone_indicator(&indicator_type).unwrap().as_supertrend();
+
+                // The lower band followed in an uptrend is never above the close
+                if let Some(supertrend) = supertrend.filter(|s| s.uptrend) {
+                    prop_assert!(supertrend.value <= candle.close);
+                }
+            }
+        }
+    }
+}
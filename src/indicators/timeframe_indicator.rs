@@ -0,0 +1,52 @@
+use crate::{
+    indicators::indicator_type::IndicatorType,
+    models::{interval::Interval, traits::has_min_length::HasMinLength},
+};
+
+/// # TimeframeIndicator
+///
+/// Indicator calculated on candles of a higher Interval than the TimeSeries
+/// it is added to, e.g. the 4 hour RSI on a 15 minute series. Each candle of
+/// the series holds the value of the last higher interval candle that had
+/// closed by the time the candle itself closed, so no values are taken from
+/// the future.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TimeframeIndicator {
+    pub interval: Interval,
+    pub indicator_type: IndicatorType,
+}
+
+impl TimeframeIndicator {
+    pub fn new(interval: Interval, indicator_type: IndicatorType) -> Self {
+        Self {
+            interval,
+            indicator_type,
+        }
+    }
+
+    /// Returns the number of candles of the given lower interval needed for
+    /// the indicator to have a value. Includes one extra higher interval
+    /// candle since the series may start in the middle of one.
+    pub fn min_length_on(&self, interval: &Interval) -> usize {
+        let ratio = (self.interval.to_millis() / interval.to_millis()).max(1) as usize;
+
+        (self.indicator_type.min_length() + 1) * ratio
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{indicator_type::IndicatorType, timeframe_indicator::TimeframeIndicator},
+        models::{interval::Interval, ma_type::MAType},
+    };
+
+    #[test]
+    fn min_length_on_lower_interval() {
+        let indicator =
+            TimeframeIndicator::new(Interval::Hour4, IndicatorType::RSI(14, MAType::RMA));
+
+        assert_eq!(indicator.min_length_on(&Interval::Hour1), 16 * 4);
+        assert_eq!(indicator.min_length_on(&Interval::Minute15), 16 * 16);
+    }
+}
@@ -0,0 +1,361 @@
+use crate::{
+    indicators::{
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+    },
+    models::{candle::Candle, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Context, Result};
+
+/// # VolumeProfile
+///
+/// Distribution of volume over price for the last `len` candles. The price
+/// range of the window is split into `bins` equal bins and the volume of each
+/// candle is spread over the bins its range covers.
+///
+/// * `poc` - Point of control, the middle of the bin with the most volume.
+/// * `vah`/`val` - High and low of the value area, the bins around the point
+///   of control holding `value_area` of the total volume.
+///
+/// The bins move with the price range of the window, so every candle is
+/// calculated from its window instead of from a rolling state.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct VolumeProfile {
+    pub poc: f64,
+    pub vah: f64,
+    pub val: f64,
+}
+
+impl PopulatesCandles for VolumeProfile {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (len, bins, value_area) = (args.usize(0)?, args.usize(1)?, args.f64(2)?);
+        let indicator_type = IndicatorType::VolumeProfile(len, bins, value_area.into());
+
+        let profiles: Vec<Option<VolumeProfile>> = (0..ts.candles.len())
+            .map(|i| Self::calculate_args(&ts.candles[..i + 1], &args))
+            .collect();
+
+        for (candle, profile) in ts.candles.iter_mut().zip(profiles) {
+            candle
+                .indicators
+                .insert(indicator_type, Indicator::VolumeProfile(profile));
+        }
+
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (len, bins, value_area) = (args.usize(0)?, args.usize(1)?, args.f64(2)?);
+        let ctx_err = "Unable to get last candle";
+        let indicator_type = IndicatorType::VolumeProfile(len, bins, value_area.into());
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let profile = Self::calculate_args(&ts.candles, &args);
+
+        ts.candles
+            .last_mut()
+            .context(ctx_err)?
+            .indicators
+            .insert(indicator_type, Indicator::VolumeProfile(profile));
+
+        Ok(())
+    }
+}
+
+impl DefinesIndicator for VolumeProfile {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "vp",
+        params: &[
+            ParamSchema {
+                name: "len",
+                default: ParamValue::Int(100),
+            },
+            ParamSchema {
+                name: "bins",
+                default: ParamValue::Int(24),
+            },
+            ParamSchema {
+                name: "va",
+                default: ParamValue::Float(0.7),
+            },
+        ],
+        outputs: &["poc", "vah", "val"],
+        warmup: |args| args.usize(0),
+        lag: |_| Ok(0),
+        build: |args| {
+            Ok(IndicatorType::VolumeProfile(
+                args.usize(0)?,
+                args.usize(1)?,
+                args.f64(2)?.into(),
+            ))
+        },
+        args: |indicator_type| match indicator_type {
+            IndicatorType::VolumeProfile(a, b, c) => Some(IndicatorArgs(vec![
+                ParamValue::Int(*a),
+                ParamValue::Int(*b),
+                ParamValue::Float(c.value()),
+            ])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
+impl IsIndicator for VolumeProfile {
+    fn default_args() -> IndicatorArgs {
+        IndicatorType::VolumeProfile(100, 24, 0.7.into()).args()
+    }
+
+    /// Calculates the profile of the whole segment using the default number
+    /// of bins and value area.
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let args = Self::default_args();
+        let (bins, value_area) = (args.usize(1).ok()?, args.f64(2).ok()?);
+        Self::calculate_profile(segment, bins, value_area)
+    }
+
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let (len, bins, value_area) = (args.usize(0).ok()?, args.usize(1).ok()?, args.f64(2).ok()?);
+        let candle_len = segment.len();
+
+        if len == 0 || candle_len < len {
+            return None;
+        }
+
+        Self::calculate_profile(&segment[candle_len - len..candle_len], bins, value_area)
+    }
+}
+
+impl VolumeProfile {
+    fn calculate_profile(segment: &[Candle], bins: usize, value_area: f64) -> Option<Self> {
+        if segment.is_empty() || bins == 0 {
+            return None;
+        }
+
+        let low = segment.iter().map(|c| c.low).fold(f64::MAX, f64::min);
+        let high = segment.iter().map(|c| c.high).fold(f64::MIN, f64::max);
+
+        if high == low {
+            return Some(VolumeProfile {
+                poc: low,
+                vah: high,
+                val: low,
+            });
+        }
+
+        let volumes = Self::bin_volumes(segment, bins, low, high);
+        let total: f64 = volumes.iter().sum();
+
+        if total <= 0.0 {
+            return None;
+        }
+
+        let poc = volumes
+            .iter()
+            .enumerate()
+            .fold(0, |max, (i, v)| if *v > volumes[max] { i } else { max });
+        let (bottom, top) = Self::value_area(&volumes, poc, value_area * total);
+
+        let width = (high - low) / bins as f64;
+        Some(VolumeProfile {
+            poc: low + (poc as f64 + 0.5) * width,
+            vah: low + (top + 1) as f64 * width,
+            val: low + bottom as f64 * width,
+        })
+    }
+
+    /// Spreads the volume of each candle over the bins covered by its range,
+    /// proportionally to how much of the range falls in each bin.
+    fn bin_volumes(segment: &[Candle], bins: usize, low: f64, high: f64) -> Vec<f64> {
+        let width = (high - low) / bins as f64;
+        let bin = |price: f64| (((price - low) / width) as usize).min(bins - 1);
+        let mut volumes = vec![0.0; bins];
+
+        for candle in segment {
+            let range = candle.high - candle.low;
+
+            if range <= 0.0 {
+                volumes[bin(candle.close)] += candle.volume;
+                continue;
+            }
+
+            for (i, volume) in volumes
+                .iter_mut()
+                .enumerate()
+                .take(bin(candle.high) + 1)
+                .skip(bin(candle.low))
+            {
+                let bin_low = low + i as f64 * width;
+                let bin_high = if i == bins - 1 { high } else { bin_low + width };
+                let overlap = candle.high.min(bin_high) - candle.low.max(bin_low);
+
+                if overlap > 0.0 {
+                    *volume += candle.volume * overlap / range;
+                }
+            }
+        }
+
+        volumes
+    }
+
+    /// Expands from the point of control towards the neighbouring bin with
+    /// the most volume until `target` volume is covered. Returns the lowest
+    /// and highest bin of the value area.
+    fn value_area(volumes: &[f64], poc: usize, target: f64) -> (usize, usize) {
+        let (mut bottom, mut top) = (poc, poc);
+        let mut covered = volumes[poc];
+
+        while covered < target {
+            let above = volumes.get(top + 1);
+            let below = bottom.checked_sub(1).map(|i| volumes[i]);
+
+            match (above, below) {
+                (Some(above), Some(below)) if below > *above => {
+                    bottom -= 1;
+                    covered += below;
+                }
+                (Some(above), _) => {
+                    top += 1;
+                    covered += above;
+                }
+                (None, Some(below)) => {
+                    bottom -= 1;
+                    covered += below;
+                }
+                (None, None) => break,
+            }
+        }
+
+        (bottom, top)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_type::IndicatorType, is_indicator::IsIndicator, volume_profile::VolumeProfile,
+        },
+        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::candle_strategy,
+    };
+    use chrono::{Duration, Utc};
+    use proptest::prelude::*;
+
+    fn candles() -> Vec<Candle> {
+        let now = Utc::now();
+
+        // (high, low, volume)
+        [
+            (12.0, 10.0, 100.0),
+            (14.0, 11.0, 300.0),
+            (13.0, 13.0, 500.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (high, low, volume))| {
+            let timestamp = now + Duration::days(i as i64);
+            Candle::new(timestamp, *low, *high, *high, *low, *volume)
+        })
+        .collect()
+    }
+
+    #[test]
+    fn volume_profile_calculate() {
+        let args = IndicatorType::VolumeProfile(2, 4, 0.7.into()).args();
+        let candles = candles();
+
+        // Bins of 1 between 10 and 14 with volumes 50, 150, 100 and 100
+        let profile = VolumeProfile::calculate_args(&candles[..2], &args).unwrap();
+        assert_eq!(profile.poc, 11.5);
+        assert_eq!(profile.vah, 14.0);
+        assert_eq!(profile.val, 11.0);
+
+        // Bins of 0.75 between 11 and 14 with volumes 75, 75, 575 and 75
+        let profile = VolumeProfile::calculate_args(&candles, &args).unwrap();
+        assert_eq!(profile.poc, 12.875);
+        assert_eq!(profile.vah, 13.25);
+        assert_eq!(profile.val, 12.5);
+    }
+
+    #[test]
+    fn volume_profile_populate_last_candle() {
+        let mut candles = candles();
+        let candle = candles.pop().unwrap();
+        let indicator_type = IndicatorType::VolumeProfile(2, 4, 0.7.into());
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        ts.add_indicator(indicator_type).unwrap();
+        ts.add_candle(&candle).unwrap();
+
+        let profiles: Vec<Option<VolumeProfile>> = ts
+            .candles
+            .iter()
+            .map(|c| {
+                c.clone_indicator(&indicator_type)
+                    .unwrap()
+                    .as_volume_profile()
+            })
+            .collect();
+
+        assert!(profiles[0].is_none());
+        assert_eq!(profiles[1].unwrap().poc, 11.5);
+        assert_eq!(profiles[2].unwrap().poc, 12.875);
+    }
+
+    #[test]
+    fn volume_profile_no_candles() {
+        let candles = Vec::new();
+        let profile = VolumeProfile::calculate(&candles);
+        assert!(profile.is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn volume_profile_matches_recomputation(
+            candles in candle_strategy::candles(1, 60),
+            len in 1..20usize,
+            bins in 1..30usize,
+        ) {
+            let indicator_type = IndicatorType::VolumeProfile(len, bins, 0.7.into());
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            for candle in populated.iter() {
+                let profile = candle.clone_indicator(&indicator_type).unwrap().as_volume_profile();
+
+                if let Some(profile) = profile {
+                    prop_assert!(profile.val <= profile.poc && profile.poc <= profile.vah);
+                }
+            }
+        }
+    }
+}
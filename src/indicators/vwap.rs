@@ -0,0 +1,347 @@
+use crate::{
+    indicators::{
+        indicator::Indicator,
+        indicator_args::IndicatorArgs,
+        indicator_definition::{DefinesIndicator, IndicatorDefinition, ParamSchema, ParamValue},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+    },
+    models::{candle::Candle, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Context, Result};
+
+/// # VWAP
+///
+/// Volume weighted average of the typical price since the start of the
+/// current session, with bands `std_n` volume weighted standard deviations
+/// away from it.
+///
+/// Sessions are `session` minutes long and start at the `anchor` unix
+/// timestamp, so `vwap(1440)` resets at midnight UTC. A session length of 0
+/// never resets, which gives a VWAP anchored at `anchor`. Candles before the
+/// anchor have no value.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct VWAP {
+    pub value: f64,
+    pub upper: f64,
+    pub lower: f64,
+    pub std: f64,
+}
+
+impl PopulatesCandles for VWAP {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (session, anchor, std_n) = (args.usize(0)?, args.usize(1)?, args.f64(2)?);
+        let indicator_type = IndicatorType::VWAP(session, anchor, std_n.into());
+        let mut state = VWAPState::default();
+
+        for candle in ts.candles.iter_mut() {
+            let vwap = Self::calculate_rolling(&mut state, candle, session, anchor, std_n);
+
+            candle
+                .indicators
+                .insert(indicator_type, Indicator::VWAP(vwap));
+        }
+
+        ts.set_indicator_state(indicator_type, IndicatorState::VWAP(state));
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (session, anchor, std_n) = (args.usize(0)?, args.usize(1)?, args.f64(2)?);
+        let ctx_err = "Unable to get last candle";
+        let indicator_type = IndicatorType::VWAP(session, anchor, std_n.into());
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let mut state = match ts.take_indicator_state(&indicator_type) {
+            Some(IndicatorState::VWAP(state)) => state,
+            _ => return Self::populate_candles_args(ts, args),
+        };
+
+        let candle = ts.candles.last_mut().context(ctx_err)?;
+        let vwap = Self::calculate_rolling(&mut state, candle, session, anchor, std_n);
+
+        candle
+            .indicators
+            .insert(indicator_type, Indicator::VWAP(vwap));
+
+        ts.set_indicator_state(indicator_type, IndicatorState::VWAP(state));
+
+        Ok(())
+    }
+}
+
+impl DefinesIndicator for VWAP {
+    const DEFINITION: IndicatorDefinition = IndicatorDefinition {
+        name: "vwap",
+        params: &[
+            ParamSchema {
+                name: "session",
+                default: ParamValue::Int(1440),
+            },
+            ParamSchema {
+                name: "anchor",
+                default: ParamValue::Int(0),
+            },
+            ParamSchema {
+                name: "std",
+                default: ParamValue::Float(1.0),
+            },
+        ],
+        outputs: &["value", "upper", "lower", "std"],
+        warmup: |_| Ok(1),
+        lag: |_| Ok(0),
+        build: |args| {
+            Ok(IndicatorType::VWAP(
+                args.usize(0)?,
+                args.usize(1)?,
+                args.f64(2)?.into(),
+            ))
+        },
+        args: |indicator_type| match indicator_type {
+            IndicatorType::VWAP(a, b, c) => Some(IndicatorArgs(vec![
+                ParamValue::Int(*a),
+                ParamValue::Int(*b),
+                ParamValue::Float(c.value()),
+            ])),
+            _ => None,
+        },
+        populate_candles: Self::populate_candles_args,
+        populate_last_candle: Self::populate_last_candle_args,
+    };
+}
+
+impl IsIndicator for VWAP {
+    fn default_args() -> IndicatorArgs {
+        IndicatorType::VWAP(1440, 0, 1.0.into()).args()
+    }
+
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::calculate_args(segment, &Self::default_args())
+    }
+
+    /// Calculates the VWAP of the last candle in the segment from the
+    /// candles of its session.
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let (session, anchor, std_n) =
+            (args.usize(0).ok()?, args.usize(1).ok()?, args.f64(2).ok()?);
+        let last = segment.last()?;
+        let current = Self::session_index(last, session, anchor)?;
+
+        let start = segment
+            .iter()
+            .rposition(|c| Self::session_index(c, session, anchor) != Some(current))
+            .map_or(0, |i| i + 1);
+
+        let mut state = VWAPState::default();
+        for candle in &segment[start..] {
+            state.add(candle);
+        }
+
+        state.vwap(std_n)
+    }
+}
+
+impl VWAP {
+    /// Returns the index of the session the candle belongs to, or None if
+    /// the candle is before the anchor.
+    fn session_index(candle: &Candle, session: usize, anchor: usize) -> Option<i64> {
+        let elapsed = candle.timestamp.timestamp() - anchor as i64;
+
+        if elapsed < 0 {
+            None
+        } else if session == 0 {
+            Some(0)
+        } else {
+            Some(elapsed / (session as i64 * 60))
+        }
+    }
+
+    fn calculate_rolling(
+        state: &mut VWAPState,
+        candle: &Candle,
+        session: usize,
+        anchor: usize,
+        std_n: f64,
+    ) -> Option<Self> {
+        let index = Self::session_index(candle, session, anchor);
+
+        if state.session != index {
+            *state = VWAPState {
+                session: index,
+                ..Default::default()
+            };
+        }
+
+        index?;
+        state.add(candle);
+        state.vwap(std_n)
+    }
+}
+
+/// Running volume weighted sums of the typical price over the current
+/// session.
+#[derive(Debug, Clone, Default)]
+pub struct VWAPState {
+    session: Option<i64>,
+    volume: f64,
+    price_volume: f64,
+    price_sq_volume: f64,
+}
+
+impl VWAPState {
+    fn add(&mut self, candle: &Candle) {
+        let price = candle.typical_price();
+
+        self.volume += candle.volume;
+        self.price_volume += price * candle.volume;
+        self.price_sq_volume += price * price * candle.volume;
+    }
+
+    fn vwap(&self, std_n: f64) -> Option<VWAP> {
+        if self.volume <= 0.0 {
+            return None;
+        }
+
+        let value = self.price_volume / self.volume;
+        let variance = self.price_sq_volume / self.volume - value * value;
+        let std = variance.max(0.0).sqrt();
+
+        Some(VWAP {
+            value,
+            upper: value + std_n * std,
+            lower: value - std_n * std,
+            std,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_args::IndicatorArgs, indicator_type::IndicatorType,
+            is_indicator::IsIndicator, populates_candles::PopulatesCandles, vwap::VWAP,
+        },
+        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::candle_strategy::{self, assert_close},
+    };
+    use chrono::{TimeZone, Utc};
+    use proptest::prelude::*;
+
+    fn candles() -> Vec<Candle> {
+        let candle = |hour: u32, day: u32, price: f64, volume: f64| {
+            let timestamp = Utc.with_ymd_and_hms(2023, 1, day, hour, 0, 0).unwrap();
+            Candle::new(timestamp, price, price, price + 1.0, price - 1.0, volume)
+        };
+
+        vec![
+            candle(21, 1, 10.0, 100.0),
+            candle(22, 1, 12.0, 300.0),
+            candle(23, 1, 14.0, 100.0),
+            candle(0, 2, 20.0, 100.0),
+        ]
+    }
+
+    fn vwaps(args: IndicatorArgs) -> Vec<Option<VWAP>> {
+        let (session, anchor, std_n) = (
+            args.usize(0).unwrap(),
+            args.usize(1).unwrap(),
+            args.f64(2).unwrap(),
+        );
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Hour1)
+            .candles(candles())
+            .build();
+
+        VWAP::populate_candles_args(&mut ts, args).unwrap();
+
+        let indicator_type = IndicatorType::VWAP(session, anchor, std_n.into());
+        ts.candles
+            .iter()
+            .map(|c| c.clone_indicator(&indicator_type).unwrap().as_vwap())
+            .collect()
+    }
+
+    #[test]
+    fn session_vwap() {
+        let vwaps = vwaps(IndicatorType::VWAP(1440, 0, 2.0.into()).args());
+
+        let vwap = vwaps[1].unwrap();
+        assert!((vwap.value - 11.5).abs() < 1e-12);
+        assert!((vwap.std - 0.75f64.sqrt()).abs() < 1e-12);
+
+        let vwap = vwaps[2].unwrap();
+        assert!((vwap.value - 12.0).abs() < 1e-12);
+        assert!((vwap.std - 1.6f64.sqrt()).abs() < 1e-12);
+        assert!((vwap.upper - (12.0 + 2.0 * 1.6f64.sqrt())).abs() < 1e-12);
+        assert!((vwap.lower - (12.0 - 2.0 * 1.6f64.sqrt())).abs() < 1e-12);
+
+        // New session at midnight
+        let vwap = vwaps[3].unwrap();
+        assert_eq!(vwap.value, 20.0);
+        assert_eq!(vwap.std, 0.0);
+    }
+
+    #[test]
+    fn anchored_vwap() {
+        let anchor = candles()[1].timestamp.timestamp() as usize;
+        let vwaps = vwaps(IndicatorType::VWAP(0, anchor, 1.0.into()).args());
+
+        assert!(vwaps[0].is_none());
+        assert_eq!(vwaps[1].unwrap().value, 12.0);
+
+        let vwap = vwaps[3].unwrap();
+        assert!((vwap.value - 14.0).abs() < 1e-12);
+        assert!((vwap.std - 9.6f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn vwap_no_candles() {
+        let candles = Vec::new();
+        let vwap = VWAP::calculate(&candles);
+        assert!(vwap.is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn vwap_matches_recomputation(
+            candles in candle_strategy::candles(1, 80),
+            session in prop::sample::select(vec![0usize, 1440, 4320, 10080]),
+            anchor_offset in 0..20usize,
+        ) {
+            let anchor = (candles[0].timestamp.timestamp() as usize) + anchor_offset * 86400;
+            let args = IndicatorType::VWAP(session, anchor, 2.0.into()).args();
+            let indicator_type = IndicatorType::VWAP(session, anchor, 2.0.into());
+            let populated = candle_strategy::populated(&candles, indicator_type);
+
+            for (i, candle) in populated.iter().enumerate() {
+                let expected = VWAP::calculate_args(&candles[..=i], &args);
+                let actual = candle.clone_indicator(&indicator_type).unwrap().as_vwap();
+                assert_close(actual.map(|v| v.value), expected.map(|v| v.value))?;
+                assert_close(actual.map(|v| v.upper), expected.map(|v| v.upper))?;
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
+mod control_api;
 mod data_sources;
-mod indicators;
-mod models;
+pub mod indicators;
+mod metrics;
+pub mod models;
 mod notifications;
 mod resolution_strategies;
 mod strategy_testing;
@@ -8,9 +10,12 @@ mod trading_strategies;
 mod utils;
 
 use crate::{
+    control_api::{control_server::start_control_server, control_state::RunningStrategy},
     data_sources::bybit::rest::bybit_rest_api::BybitRestApi,
     indicators::{atr::ATR, populates_candles::PopulatesCandles, rsi::RSI, stochastic::Stochastic},
+    metrics::metrics_server::start_metrics_server,
     models::{net_version::NetVersion, websockets::wsclient::WebsocketClient},
+    notifications::notification_center::NotificationCenter,
     trading_strategies::private::jb_2::JB2,
 };
 use actix::Actor;
@@ -20,67 +25,94 @@ use dotenv::dotenv;
 use indicators::{indicator_type::IndicatorType, populates_candles::PopulatesCandlesWithSelf};
 use models::{
     candle::Candle,
+    candle_buffer::CandleBuffer,
     interval::Interval,
     message_payloads::{
         ts_subscribe_payload::TSSubscribePayload, websocket_payload::WebsocketPayload,
     },
-    setups::setup_finder_builder::SetupFinderBuilder,
+    regime_classifier::RegimeClassifier,
+    setups::{setup_finder_builder::SetupFinderBuilder, strategy_state_store::StrategyStateStore},
     strategy_orientation::StrategyOrientation,
     timeseries::TimeSeries,
     timeseries_builder::TimeSeriesBuilder,
-    traits::trading_strategy::TradingStrategy,
+    traits::{requires_indicators::RequiresIndicators, trading_strategy::TradingStrategy},
 };
 use strategy_testing::strategy_tester::StrategyTester;
 use tokio::time::{sleep, Duration};
+use tracing::{info, warn};
 use trading_strategies::{
     private::kq_14::KQ14,
     public::{always_true_strategy::AlwaysTrueStrategy, rsi_basic::RsiBasic},
 };
 
+pub use utils::logging::init_logging;
+
 pub async fn run_dummy() -> Result<()> {
     todo!()
 }
 
 pub async fn run_actual_strategy() -> Result<()> {
     let strategy: Box<dyn TradingStrategy> = Box::new(KQ14::new());
+    let symbol = "BTCUSDT";
     let interval = strategy.interval();
     let source = DataSource::Bybit;
     let net = NetVersion::Mainnet;
 
-    println!("Starting strategy {} on interval {}", strategy, interval);
+    info!(%strategy, symbol, %interval, "Starting strategy");
+
+    start_metrics_server()?;
 
     // Initialize timeseries and indicators
     let mut ts = source
-        .get_historical_data("BTCUSDT", &interval, strategy.min_length() + 300, &net)
+        .get_historical_data(symbol, &interval, strategy.history_length() + 300, &net)
         .await?;
 
     for indicator_type in strategy.required_indicators() {
         ts.add_indicator(indicator_type)?;
     }
 
+    for indicator in strategy.required_timeframe_indicators() {
+        ts.add_timeframe_indicator(indicator)?;
+    }
+
     let ts_addr = ts.start();
 
-    // Create setup finder
+    // Create setup finder, resuming the state of the strategy
+    let state_store = StrategyStateStore::for_strategy(symbol, strategy.as_ref());
     let setup_finder = SetupFinderBuilder::new()
         .strategy(strategy)
         .ts(ts_addr.clone())
         .notifications_enabled(true)
         .live_trading_enabled(true)
         .source(source.clone())
+        .state_store(state_store)
         .build()?;
 
     // Subscribe SetupFinder to TimeSeries
     let sf_addr = setup_finder.start();
     let payload = TSSubscribePayload {
-        observer: sf_addr.recipient(),
+        observer: sf_addr.clone().recipient(),
     };
     ts_addr.do_send(payload);
 
+    // Expose control API for the running strategy
+    let running = RunningStrategy {
+        setup_finder: sf_addr,
+        timeseries: ts_addr.clone(),
+    };
+
+    if let Err(e) = start_control_server(source.clone(), vec![running]) {
+        warn!(error = %e, "Control API not started");
+    }
+
     // Start websocket client
     let mut wsclient = WebsocketClient::new(source, interval, net);
     wsclient.add_observer(ts_addr);
+    wsclient.set_notifications_enabled(true);
     wsclient.start();
 
+    NotificationCenter::start_daily_digest();
+
     // Run program indefinately
     loop {
         sleep(Duration::from_secs(1)).await;
@@ -96,7 +128,7 @@ pub async fn run_always_true_buys() -> Result<()> {
 
     // Initialize timeseries and indicators
     let mut ts = source
-        .get_historical_data("BTCUSDT", &interval, strategy.min_length() + 300, &net)
+        .get_historical_data("BTCUSDT", &interval, strategy.history_length() + 300, &net)
         .await?;
     ts.validate_candles_on_add = false;
 
@@ -104,6 +136,10 @@ pub async fn run_always_true_buys() -> Result<()> {
         ts.add_indicator(indicator_type)?;
     }
 
+    for indicator in strategy.required_timeframe_indicators() {
+        ts.add_timeframe_indicator(indicator)?;
+    }
+
     let ts_addr = ts.start();
 
     // Create setup finder and subscribe to timeseries
@@ -137,11 +173,11 @@ pub async fn run_always_true_buys() -> Result<()> {
 pub async fn run_market_buy() -> Result<()> {
     let time = BybitRestApi::get_server_time().await?;
 
-    println!("Time: {:#?}", time);
+    info!(?time, "Fetched server time");
 
     let wallet = BybitRestApi::get_wallet_balance().await?;
 
-    println!("Wallet: {:#?}", wallet);
+    info!(?wallet, "Fetched wallet");
 
     let buy = false;
 
@@ -159,11 +195,11 @@ pub async fn run_market_buy() -> Result<()> {
 pub async fn run_market_sell_all() -> Result<()> {
     let time = BybitRestApi::get_server_time().await?;
 
-    println!("Time: {:#?}", time);
+    info!(?time, "Fetched server time");
 
     let wallet = BybitRestApi::get_wallet_balance().await?;
 
-    println!("Wallet: {:#?}", wallet);
+    info!(?wallet, "Fetched wallet");
 
     BybitRestApi::market_sell_all(&wallet).await?;
 
@@ -171,8 +207,10 @@ pub async fn run_market_sell_all() -> Result<()> {
 }
 
 pub async fn run_single_indicator() -> Result<()> {
-    let (k_len, k_smoothing, d_smoothing) = Stochastic::krown_args().stochastic_res()?;
-    let indicator_type = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing);
+    let args = Stochastic::krown_args();
+    let (k_len, k_smoothing, d_smoothing) = (args.usize(0)?, args.usize(1)?, args.usize(2)?);
+    let indicator_type =
+        IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing, args.ma_type(3)?);
 
     let interval = Interval::Minute1;
     let source = DataSource::Bybit;
@@ -183,7 +221,7 @@ pub async fn run_single_indicator() -> Result<()> {
         .await?;
 
     indicator_type.populate_candles(&mut ts)?;
-    println!("Ts:{:#?}", ts);
+    info!(?ts, "Populated TimeSeries");
 
     let mut client = WebsocketClient::new(source, interval, net);
     let addr = ts.start();
@@ -205,7 +243,7 @@ pub async fn run_strategy() -> Result<()> {
 
     // Initialize timeseries and indicators
     let mut ts = source
-        .get_historical_data("BTCUSDT", &interval, strategy.min_length() + 300, &net)
+        .get_historical_data("BTCUSDT", &interval, strategy.history_length() + 300, &net)
         .await?;
 
     // ts.save_to_local(&source).await?;
@@ -215,6 +253,10 @@ pub async fn run_strategy() -> Result<()> {
         ts.add_indicator(indicator_type)?;
     }
 
+    for indicator in strategy.required_timeframe_indicators() {
+        ts.add_timeframe_indicator(indicator)?;
+    }
+
     let ts_addr = ts.start();
 
     // Create setup finder and subscribe to timeseries
@@ -263,7 +305,12 @@ pub async fn run_double_strategies() -> Result<()> {
 
     // Initialize timeseries and indicators
     let mut ts = source
-        .get_historical_data("BTCUSDT", &interval, long_strategy.min_length() + 300, &net)
+        .get_historical_data(
+            "BTCUSDT",
+            &interval,
+            long_strategy.history_length() + 300,
+            &net,
+        )
         .await?;
     // ts.save_to_local(&source).await?;
     // let ts = source.load_local_data(symbol, &interval).await?;
@@ -272,6 +319,10 @@ pub async fn run_double_strategies() -> Result<()> {
         ts.add_indicator(indicator_type)?;
     }
 
+    for indicator in long_strategy.required_timeframe_indicators() {
+        ts.add_timeframe_indicator(indicator)?;
+    }
+
     let ts_addr = ts.start();
 
     // Create setup finder and subscribe to timeseries
@@ -323,7 +374,7 @@ pub async fn run_historical() -> Result<()> {
 
     RSI::populate_candles(&mut ts)?;
 
-    println!("Candles:{:#?}", ts.candles);
+    info!(candles = ?ts.candles, "Populated candles");
 
     Ok(())
 }
@@ -332,7 +383,7 @@ pub async fn run_local() -> Result<()> {
     let mut ts = local::read_dummy_data("src/utils/data/atr_dummy_data.csv").await?;
     ATR::populate_candles(&mut ts)?;
 
-    println!("{:#?}", ts);
+    info!(?ts, "Populated TimeSeries");
 
     Ok(())
 }
@@ -418,23 +469,55 @@ pub async fn run_strategy_tester() -> Result<()> {
     let interval = strategy.interval();
     let net = NetVersion::Mainnet;
 
-    println!("Fetching Timeseries data.");
+    info!("Fetching Timeseries data.");
     let mut ts = source
-        .get_historical_data("BTCUSDT", &interval, 20000, &net)
+        .get_historical_data(
+            "BTCUSDT",
+            &interval,
+            strategy.history_length() + 20000,
+            &net,
+        )
         .await?;
 
     // Calculate indicators for TimeSeries
     // Implement Strategy to analyze TimeSeries
 
-    println!("Starting indicator calculations.");
+    info!("Starting indicator calculations.");
     for indicator in strategy.required_indicators() {
-        println!("Populating indicator: {:#?}", indicator);
+        info!(?indicator, "Populating indicator");
         indicator.populate_candles(&mut ts)?;
     }
 
-    let result = StrategyTester::test_strategy(&mut strategy, &ts.candles[300..])?;
+    for indicator in strategy.required_timeframe_indicators() {
+        info!(?indicator, "Populating timeframe indicator");
+        ts.add_timeframe_indicator(indicator)?;
+    }
 
-    println!("{:#?}", result);
+    // Break results down by the market regime of each setup
+    let classifier = RegimeClassifier::new();
+    for indicator in classifier.required_indicators() {
+        if !strategy.required_indicators().contains(&indicator) {
+            indicator.populate_candles(&mut ts)?;
+        }
+    }
+
+    // Columnar copy of every candle past the warmup, as the TimeSeries only
+    // buffers the last `max_length` candles
+    let buffer = CandleBuffer::from(&ts.candles[300..]);
+    let result =
+        StrategyTester::test_strategy_by_regime(&mut strategy, buffer.view(), &classifier)?;
+
+    info!(?result, "Strategy test complete");
+    for (regime, result) in &result.by_regime {
+        info!(%regime, n_setups = result.n_setups, accuracy = result.accuracy, "Regime results");
+    }
+    if let Some(result) = &result.unclassified {
+        info!(
+            n_setups = result.n_setups,
+            accuracy = result.accuracy,
+            "Unclassified results"
+        );
+    }
 
     Ok(())
 }
@@ -6,6 +6,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let filename = env::var("RUSTBOT_ENV")
         .unwrap_or(".env.dev".to_string());
     from_filename(filename).ok();
+    let _guard = rust_bot::init_logging()?;
 
     rust_bot::run_actual_strategy().await?;
 
@@ -0,0 +1,141 @@
+use anyhow::Result;
+use prometheus::{Encoder, Gauge, GaugeVec, IntCounterVec, IntGauge, Opts, Registry, TextEncoder};
+use std::sync::OnceLock;
+
+static METRICS: OnceLock<BotMetrics> = OnceLock::new();
+
+/// # BotMetrics
+///
+/// Prometheus metrics of the running bot. Access the process wide instance
+/// through `BotMetrics::get()` and expose it with `BotMetrics::encode()`.
+pub struct BotMetrics {
+    registry: Registry,
+    pub candles_received: IntCounterVec,
+    pub timeseries_gaps: IntCounterVec,
+    pub backfills: IntCounterVec,
+    pub backfilled_candles: IntCounterVec,
+    pub setups_found: IntCounterVec,
+    pub orders_placed: IntCounterVec,
+    pub orders_failed: IntCounterVec,
+    pub websocket_reconnects: IntCounterVec,
+    pub open_trades: IntGauge,
+    pub wallet_balance: Gauge,
+    pub indicator_latency: GaugeVec,
+}
+
+impl BotMetrics {
+    pub fn get() -> &'static BotMetrics {
+        METRICS.get_or_init(|| Self::new().expect("Unable to register metrics."))
+    }
+
+    fn new() -> Result<Self> {
+        let registry = Registry::new_custom(Some("rustbot".to_string()), None)?;
+
+        let counter = |name: &str, help: &str, labels: &[&str]| -> Result<IntCounterVec> {
+            let counter = IntCounterVec::new(Opts::new(name, help), labels)?;
+            registry.register(Box::new(counter.clone()))?;
+            Ok(counter)
+        };
+
+        let candles_received = counter(
+            "candles_received_total",
+            "Candles received from websockets",
+            &["source", "interval"],
+        )?;
+        let timeseries_gaps = counter(
+            "timeseries_gaps_total",
+            "Gaps detected when validating TimeSeries integrity",
+            &["symbol", "interval"],
+        )?;
+        let backfills = counter(
+            "backfills_total",
+            "Historical candle fetches performed to fill gaps",
+            &["symbol", "interval"],
+        )?;
+        let backfilled_candles = counter(
+            "backfilled_candles_total",
+            "Candles added through backfills",
+            &["symbol", "interval"],
+        )?;
+        let setups_found = counter(
+            "setups_found_total",
+            "Setups found per strategy",
+            &["strategy"],
+        )?;
+        let orders_placed = counter(
+            "orders_placed_total",
+            "Orders successfully placed",
+            &["order"],
+        )?;
+        let orders_failed = counter("orders_failed_total", "Orders that failed", &["order"])?;
+        let websocket_reconnects = counter(
+            "websocket_reconnects_total",
+            "Websocket reconnection attempts",
+            &["source"],
+        )?;
+
+        let open_trades = IntGauge::new("open_trades", "Number of currently open trades")?;
+        registry.register(Box::new(open_trades.clone()))?;
+
+        let wallet_balance = Gauge::new(
+            "wallet_balance_usd",
+            "Total available wallet balance in USD",
+        )?;
+        registry.register(Box::new(wallet_balance.clone()))?;
+
+        let indicator_latency = GaugeVec::new(
+            Opts::new(
+                "indicator_latency_seconds",
+                "Time spent populating the last candle per indicator",
+            ),
+            &["indicator"],
+        )?;
+        registry.register(Box::new(indicator_latency.clone()))?;
+
+        Ok(Self {
+            registry,
+            candles_received,
+            timeseries_gaps,
+            backfills,
+            backfilled_candles,
+            setups_found,
+            orders_placed,
+            orders_failed,
+            websocket_reconnects,
+            open_trades,
+            wallet_balance,
+            indicator_latency,
+        })
+    }
+
+    /// Encodes all metrics in the Prometheus text exposition format.
+    pub fn encode(&self) -> Result<String> {
+        let mut buffer = vec![];
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::metrics::bot_metrics::BotMetrics;
+
+    #[test]
+    fn encode_metrics() {
+        let metrics = BotMetrics::get();
+        metrics
+            .candles_received
+            .with_label_values(&["Bybit", "Daily"])
+            .inc();
+        metrics.wallet_balance.set(1234.5);
+
+        let encoded = metrics.encode().unwrap();
+
+        assert!(
+            encoded.contains(r#"rustbot_candles_received_total{interval="Daily",source="Bybit"}"#)
+        );
+        assert!(encoded.contains("rustbot_wallet_balance_usd 1234.5"));
+        assert!(encoded.contains("# TYPE rustbot_open_trades gauge"));
+    }
+}
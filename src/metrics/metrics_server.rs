@@ -0,0 +1,39 @@
+use crate::metrics::bot_metrics::BotMetrics;
+use actix::spawn;
+use actix_web::{get, App, HttpResponse, HttpServer, Responder};
+use anyhow::Result;
+use std::env;
+use tracing::{error, info};
+
+const DEFAULT_METRICS_ADDR: &str = "127.0.0.1:9184";
+
+#[get("/metrics")]
+async fn metrics() -> impl Responder {
+    match BotMetrics::get().encode() {
+        Ok(body) => HttpResponse::Ok()
+            .content_type("text/plain; version=0.0.4")
+            .body(body),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+    }
+}
+
+/// Starts an HTTP server exposing the bot metrics at `/metrics`. The address
+/// is read from `METRICS_ADDR` and defaults to 127.0.0.1:9184.
+pub fn start_metrics_server() -> Result<()> {
+    let addr = env::var("METRICS_ADDR").unwrap_or(DEFAULT_METRICS_ADDR.to_string());
+
+    let server = HttpServer::new(|| App::new().service(metrics))
+        .workers(1)
+        .bind(&addr)?
+        .run();
+
+    info!(%addr, "Serving metrics at /metrics");
+
+    spawn(async move {
+        if let Err(e) = server.await {
+            error!(error = ?e, "Metrics server error");
+        }
+    });
+
+    Ok(())
+}
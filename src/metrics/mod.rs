@@ -0,0 +1,2 @@
+pub mod bot_metrics;
+pub mod metrics_server;
@@ -0,0 +1,351 @@
+use crate::models::{
+    bar_type::BarType, candle::Candle, timeseries::TimeSeries,
+    timeseries_builder::TimeSeriesBuilder,
+};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+
+/// # BarBuilder
+///
+/// Transforms time based candles into bars of a `BarType`. Bars are regular
+/// Candles so indicators and strategies can use them like any other candles.
+/// Since bars are built from candles rather than trades, range and volume
+/// bars may overshoot their size by the move of the candle closing them.
+///
+/// Bars are timestamped by the candle that opened them, offset by a
+/// millisecond when several bars are opened by the same candle so that the
+/// timestamps of the series stay unique.
+#[derive(Debug, Clone)]
+pub struct BarBuilder {
+    bar_type: BarType,
+    /// Last Heikin-Ashi candle or Renko brick built.
+    last: Option<Candle>,
+    /// Range or volume bar currently being built, or the candles not yet
+    /// included in a Renko brick.
+    pending: Option<Candle>,
+    last_timestamp: Option<DateTime<Utc>>,
+}
+
+impl BarBuilder {
+    #[allow(dead_code)]
+    pub fn new(bar_type: BarType) -> Result<Self> {
+        let size = match bar_type {
+            BarType::HeikinAshi => None,
+            BarType::Renko(size) | BarType::Range(size) | BarType::Volume(size) => {
+                Some(size.value())
+            }
+        };
+
+        if size.is_some_and(|size| size.is_nan() || size <= 0.0) {
+            return Err(anyhow!(
+                "Unable to build {} bars, size needs to be positive.",
+                bar_type
+            ));
+        }
+
+        Ok(Self {
+            bar_type,
+            last: None,
+            pending: None,
+            last_timestamp: None,
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn bar_type(&self) -> BarType {
+        self.bar_type
+    }
+
+    /// Adds a candle, returning the bars completed by it.
+    pub fn push(&mut self, candle: &Candle) -> Vec<Candle> {
+        match self.bar_type {
+            BarType::HeikinAshi => vec![self.push_heikin_ashi(candle)],
+            BarType::Renko(size) => self.push_renko(candle, size.value()),
+            BarType::Range(size) => self
+                .push_aggregate(candle, |bar| bar.high - bar.low >= size.value())
+                .into_iter()
+                .collect(),
+            BarType::Volume(size) => self
+                .push_aggregate(candle, |bar| bar.volume >= size.value())
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Adds the candles in order and returns the bars completed by them.
+    #[allow(dead_code)]
+    pub fn build(&mut self, candles: &[Candle]) -> Vec<Candle> {
+        candles
+            .iter()
+            .flat_map(|candle| self.push(candle))
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.pending = None;
+        self.last_timestamp = None;
+    }
+
+    /// Builds a TimeSeries of bars from the candles of a time based series.
+    /// Indicators are not carried over. The builder can then be used to
+    /// continue the series from the live candle stream.
+    #[allow(dead_code)]
+    pub fn build_timeseries(&mut self, ts: &TimeSeries) -> TimeSeries {
+        let bars = self.build(&ts.candles);
+
+        // Bars aren't evenly spaced in time
+        TimeSeriesBuilder::new()
+            .symbol(ts.symbol.clone())
+            .interval(ts.interval.clone())
+            .max_length(ts.max_length)
+            .net(ts.net)
+            .validate_candles_on_add(false)
+            .candles(bars)
+            .build()
+    }
+
+    fn push_heikin_ashi(&mut self, candle: &Candle) -> Candle {
+        let close = (candle.open + candle.high + candle.low + candle.close) / 4.0;
+        let open = match &self.last {
+            Some(last) => (last.open + last.close) / 2.0,
+            None => (candle.open + candle.close) / 2.0,
+        };
+
+        let bar = Candle::new(
+            self.timestamp(candle.timestamp),
+            open,
+            close,
+            candle.high.max(open).max(close),
+            candle.low.min(open).min(close),
+            candle.volume,
+        );
+        self.last = Some(bar.clone());
+
+        bar
+    }
+
+    fn push_renko(&mut self, candle: &Candle, size: f64) -> Vec<Candle> {
+        // Volume and start of the candles since the last brick
+        let (start, volume) = match self.pending.take() {
+            Some(pending) => (pending.timestamp, pending.volume + candle.volume),
+            None => (candle.timestamp, candle.volume),
+        };
+
+        // The first close anchors the bricks
+        let Some(last) = &self.last else {
+            self.last = Some(Candle::from_val(candle.timestamp, candle.close, 0.0));
+            return vec![];
+        };
+
+        let mut top = last.open.max(last.close);
+        let mut bottom = last.open.min(last.close);
+        let mut bricks = vec![];
+
+        loop {
+            let (open, close) = if candle.close >= top + size {
+                (top, top + size)
+            } else if candle.close <= bottom - size {
+                (bottom, bottom - size)
+            } else {
+                break;
+            };
+
+            let volume = if bricks.is_empty() { volume } else { 0.0 };
+            let brick = Candle::new(
+                self.timestamp(start),
+                open,
+                close,
+                open.max(close),
+                open.min(close),
+                volume,
+            );
+
+            top = brick.high;
+            bottom = brick.low;
+            bricks.push(brick);
+        }
+
+        match bricks.last() {
+            Some(brick) => self.last = Some(brick.clone()),
+            None => self.pending = Some(Candle::from_val(start, candle.close, volume)),
+        }
+
+        bricks
+    }
+
+    fn push_aggregate(
+        &mut self,
+        candle: &Candle,
+        is_full: impl Fn(&Candle) -> bool,
+    ) -> Option<Candle> {
+        match self.pending.as_mut() {
+            Some(pending) => {
+                pending.high = pending.high.max(candle.high);
+                pending.low = pending.low.min(candle.low);
+                pending.close = candle.close;
+                pending.volume += candle.volume;
+            }
+            None => {
+                let timestamp = self.timestamp(candle.timestamp);
+                self.pending = Some(Candle::new(
+                    timestamp,
+                    candle.open,
+                    candle.close,
+                    candle.high,
+                    candle.low,
+                    candle.volume,
+                ));
+            }
+        }
+
+        if self.pending.as_ref().is_some_and(is_full) {
+            self.pending.take()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the timestamp for a new bar, ensuring timestamps increase.
+    fn timestamp(&mut self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        let timestamp = match self.last_timestamp {
+            Some(last) if timestamp <= last => last + Duration::milliseconds(1),
+            _ => timestamp,
+        };
+        self.last_timestamp = Some(timestamp);
+
+        timestamp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{indicator::Indicator, indicator_type::IndicatorType, sma::SMA},
+        models::{
+            bar_builder::BarBuilder, bar_type::BarType, candle::Candle, interval::Interval,
+            timeseries_builder::TimeSeriesBuilder,
+        },
+    };
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn candles(ohlc: &[(f64, f64, f64, f64)]) -> Vec<Candle> {
+        let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+
+        ohlc.iter()
+            .enumerate()
+            .map(|(i, (open, high, low, close))| {
+                let timestamp = start + Duration::minutes(i as i64);
+                Candle::new(timestamp, *open, *close, *high, *low, 10.0)
+            })
+            .collect()
+    }
+
+    fn closes(closes: &[f64]) -> Vec<Candle> {
+        let ohlc: Vec<_> = closes.iter().map(|c| (*c, *c, *c, *c)).collect();
+        candles(&ohlc)
+    }
+
+    #[test]
+    fn heikin_ashi() {
+        let candles = candles(&[(10.0, 14.0, 8.0, 12.0), (12.0, 16.0, 11.0, 15.0)]);
+        let bars = BarBuilder::new(BarType::HeikinAshi)
+            .unwrap()
+            .build(&candles);
+
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].open, 11.0);
+        assert_eq!(bars[0].close, 11.0);
+        assert_eq!(bars[1].open, 11.0);
+        assert_eq!(bars[1].close, 13.5);
+        assert_eq!(bars[1].high, 16.0);
+        assert_eq!(bars[1].low, 11.0);
+        assert_eq!(bars[1].timestamp, candles[1].timestamp);
+    }
+
+    #[test]
+    fn renko_bricks_and_reversal() {
+        let candles = closes(&[100.0, 104.0, 121.0, 115.0, 95.0]);
+        let bars = BarBuilder::new(BarType::Renko(10.0.into()))
+            .unwrap()
+            .build(&candles);
+
+        let bricks: Vec<_> = bars.iter().map(|b| (b.open, b.close)).collect();
+        assert_eq!(bricks, vec![(100.0, 110.0), (110.0, 120.0), (110.0, 100.0)]);
+
+        // Both bricks of the third candle are unique in time, and only the
+        // first one holds the volume of the candles since the last brick
+        assert_eq!(bars[0].timestamp, candles[1].timestamp);
+        assert_eq!(
+            bars[1].timestamp,
+            bars[0].timestamp + Duration::milliseconds(1)
+        );
+        assert_eq!(bars[0].volume, 20.0);
+        assert_eq!(bars[1].volume, 0.0);
+        assert_eq!(bars[2].volume, 20.0);
+    }
+
+    #[test]
+    fn range_and_volume_bars() {
+        let mut candles = candles(&[
+            (10.0, 11.0, 9.0, 10.0),
+            (10.0, 12.0, 10.0, 11.0),
+            (11.0, 11.5, 10.5, 11.0),
+            (11.0, 15.0, 11.0, 14.0),
+        ]);
+        let sma = Indicator::SMA(Some(SMA {
+            value: 10.0,
+            len: 1,
+        }));
+        candles[0].indicators.insert(IndicatorType::SMA(1), sma);
+
+        let bars = BarBuilder::new(BarType::Range(3.0.into()))
+            .unwrap()
+            .build(&candles);
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].open, 10.0);
+        assert_eq!(bars[0].close, 11.0);
+        assert_eq!((bars[0].high, bars[0].low), (12.0, 9.0));
+        assert_eq!((bars[1].open, bars[1].close), (11.0, 14.0));
+        assert!(bars[0].indicators.is_empty());
+
+        let bars = BarBuilder::new(BarType::Volume(20.0.into()))
+            .unwrap()
+            .build(&candles);
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[1].timestamp, candles[2].timestamp);
+        assert_eq!(bars[1].close, 14.0);
+        assert_eq!(bars[1].volume, 20.0);
+
+        assert!(BarBuilder::new(BarType::Volume(0.0.into())).is_err());
+    }
+
+    #[test]
+    fn continues_timeseries_from_stream() {
+        let candles = closes(&[100.0, 112.0, 125.0, 131.0, 98.0, 101.0, 87.0]);
+        let bar_type = BarType::Renko(5.0.into());
+
+        let ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Minute1)
+            .candles(candles[..3].to_vec())
+            .build();
+
+        let mut builder = BarBuilder::new(bar_type).unwrap();
+        let mut bars = builder.build_timeseries(&ts).candles;
+        assert!(!bars.is_empty());
+
+        for candle in candles[3..].iter() {
+            bars.extend(builder.push(candle));
+        }
+
+        let expected = BarBuilder::new(bar_type).unwrap().build(&candles);
+        let key = |bars: &[_]| -> Vec<_> {
+            bars.iter()
+                .map(|b: &Candle| (b.timestamp, b.open, b.close, b.volume))
+                .collect()
+        };
+        assert_eq!(key(&bars), key(&expected));
+    }
+}
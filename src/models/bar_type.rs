@@ -0,0 +1,32 @@
+use crate::models::hashable_f64::HashableF64;
+use std::fmt::{Display, Formatter, Result};
+
+/// # BarType
+///
+/// Alternative to time based candles that a TimeSeries can be transformed
+/// into by a `BarBuilder`.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BarType {
+    /// Candles smoothed by averaging with the previous Heikin-Ashi candle.
+    HeikinAshi,
+    /// Bricks of the given price size, formed by the close of the candles.
+    /// Reversing the direction requires a move of two bricks.
+    Renko(HashableF64),
+    /// Bars closed once the range between their high and low reaches the
+    /// given size.
+    Range(HashableF64),
+    /// Bars closed once their volume reaches the given amount.
+    Volume(HashableF64),
+}
+
+impl Display for BarType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            BarType::HeikinAshi => write!(f, "Heikin-Ashi"),
+            BarType::Renko(size) => write!(f, "Renko {}", size),
+            BarType::Range(size) => write!(f, "Range {}", size),
+            BarType::Volume(size) => write!(f, "Volume {}", size),
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use crate::{
-    indicators::{indicator::Indicator, indicator_type::IndicatorType},
+    indicators::{
+        indicator::Indicator, indicator_field::IndicatorField, indicator_type::IndicatorType,
+        timeframe_indicator::TimeframeIndicator,
+    },
     models::interval::Interval,
 };
 use anyhow::{Context, Result};
@@ -8,6 +11,10 @@ use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Names of the price and volume fields of a candle.
+#[allow(dead_code)]
+pub const CANDLE_FIELDS: &[&str] = &["open", "high", "low", "close", "volume"];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Candle {
     pub timestamp: DateTime<Utc>,
@@ -19,6 +26,11 @@ pub struct Candle {
 
     #[serde(skip_serializing, skip_deserializing)]
     pub indicators: HashMap<IndicatorType, Indicator>,
+
+    /// Values of indicators calculated on a higher interval, as of the last
+    /// higher interval candle closed by this candle.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub timeframe_indicators: HashMap<TimeframeIndicator, Indicator>,
 }
 
 impl Candle {
@@ -38,6 +50,7 @@ impl Candle {
             low,
             volume,
             indicators: HashMap::new(),
+            timeframe_indicators: HashMap::new(),
         }
     }
 
@@ -53,6 +66,7 @@ impl Candle {
             low: val,
             volume: 1000.0,
             indicators: HashMap::new(),
+            timeframe_indicators: HashMap::new(),
         }
     }
 
@@ -66,6 +80,7 @@ impl Candle {
             low: val,
             volume,
             indicators: HashMap::new(),
+            timeframe_indicators: HashMap::new(),
         }
     }
 
@@ -85,6 +100,7 @@ impl Candle {
                     low: val,
                     volume: 1000.0,
                     indicators: HashMap::new(),
+                    timeframe_indicators: HashMap::new(),
                 }
             })
             .collect()
@@ -107,6 +123,7 @@ impl Candle {
                     low: val,
                     volume: 1000.0,
                     indicators: HashMap::new(),
+                    timeframe_indicators: HashMap::new(),
                 }
             })
             .collect()
@@ -134,13 +151,14 @@ impl Candle {
         }
 
         Candle {
-            timestamp: candle.timestamp + interval.to_duration(),
+            timestamp: interval.next_start(candle.timestamp),
             open,
             close,
             high,
             low,
             volume: rng.gen_range(200..1500) as f64,
             indicators: HashMap::new(),
+            timeframe_indicators: HashMap::new(),
         }
     }
 
@@ -175,6 +193,7 @@ impl Candle {
                     low,
                     volume: rng.gen_range(200..1500) as f64,
                     indicators: HashMap::new(),
+                    timeframe_indicators: HashMap::new(),
                 }
             })
             .collect()
@@ -208,15 +227,66 @@ impl Candle {
                     low: val,
                     volume: 1000.0,
                     indicators: HashMap::new(),
+                    timeframe_indicators: HashMap::new(),
                 }
             })
             .collect()
     }
 
+    /// Returns a candle field or indicator output by spec, e.g. `close`,
+    /// `rsi(14)`, `bb(20).upper` or `stoch(14,3,3).d`. Returns None if the
+    /// spec is invalid, the indicator has not been populated on the candle
+    /// or the value is not yet available.
+    #[allow(dead_code)]
+    pub fn value(&self, spec: &str) -> Option<f64> {
+        if let Some(value) = self.candle_field(spec) {
+            return Some(value);
+        }
+
+        let field: IndicatorField = spec.parse().ok()?;
+        self.field_value(&field)
+    }
+
+    /// Returns the value of an indicator output. Prefer this over `value`
+    /// when reading the same field repeatedly, as the spec is only parsed
+    /// once.
+    pub fn field_value(&self, field: &IndicatorField) -> Option<f64> {
+        self.indicators
+            .get(&field.indicator_type)?
+            .field(field.field)
+    }
+
+    /// Returns one of the `CANDLE_FIELDS` by name.
+    #[allow(dead_code)]
+    pub fn candle_field(&self, name: &str) -> Option<f64> {
+        match name.trim().to_lowercase().as_str() {
+            "open" => Some(self.open),
+            "high" => Some(self.high),
+            "low" => Some(self.low),
+            "close" => Some(self.close),
+            "volume" => Some(self.volume),
+            _ => None,
+        }
+    }
+
+    /// Average of the high, low and close, used by volume weighted
+    /// indicators.
+    pub fn typical_price(&self) -> f64 {
+        (self.high + self.low + self.close) / 3.0
+    }
+
     pub fn clone_indicator(&self, key: &IndicatorType) -> Result<Indicator> {
         self.indicators
             .get(key)
             .context(format!("Unable to find indicator with type: {:#?}", key))
             .cloned()
     }
+
+    #[allow(dead_code)]
+    pub fn clone_timeframe_indicator(&self, key: &TimeframeIndicator) -> Result<Indicator> {
+        self.timeframe_indicators
+            .get(key)
+            .context(format!("Unable to find timeframe indicator: {:#?}", key))
+            .cloned()
+    }
 }
@@ -0,0 +1,574 @@
+use crate::{
+    indicators::{
+        indicator::Indicator, indicator_field::IndicatorField, indicator_type::IndicatorType,
+        timeframe_indicator::TimeframeIndicator,
+    },
+    models::{
+        candle::Candle,
+        ring_buffer::{RingBuffer, RingView},
+    },
+};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    hash::Hash,
+    ops::{Bound, RangeBounds},
+};
+
+/// # CandleBuffer
+///
+/// Columnar store of the latest candles of a TimeSeries. Every candle field
+/// and every indicator is kept in its own contiguous column inside a ring
+/// buffer, so adding a candle takes constant time once the buffer is full
+/// and reading a column does not touch the rest of the candle data.
+/// Strategies, resolution strategies and the StrategyTester read candles
+/// through `CandleView`s into the buffer.
+#[derive(Debug, Clone)]
+pub struct CandleBuffer {
+    timestamps: RingBuffer<DateTime<Utc>>,
+    open: RingBuffer<f64>,
+    high: RingBuffer<f64>,
+    low: RingBuffer<f64>,
+    close: RingBuffer<f64>,
+    volume: RingBuffer<f64>,
+    indicators: IndexMap<IndicatorType, RingBuffer<Option<Indicator>>>,
+    timeframe_indicators: IndexMap<TimeframeIndicator, RingBuffer<Option<Indicator>>>,
+}
+
+impl CandleBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            timestamps: RingBuffer::new(capacity),
+            open: RingBuffer::new(capacity),
+            high: RingBuffer::new(capacity),
+            low: RingBuffer::new(capacity),
+            close: RingBuffer::new(capacity),
+            volume: RingBuffer::new(capacity),
+            indicators: IndexMap::new(),
+            timeframe_indicators: IndexMap::new(),
+        }
+    }
+
+    /// Builds a buffer holding the last `capacity` candles along with all
+    /// indicators populated on them.
+    pub fn from_candles(candles: &[Candle], capacity: usize) -> Self {
+        let mut buffer = Self::new(capacity);
+
+        let start = candles.len().saturating_sub(capacity);
+        for candle in &candles[start..] {
+            buffer.push(candle);
+        }
+
+        buffer
+    }
+
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.timestamps.capacity()
+    }
+
+    pub fn clear(&mut self) {
+        self.timestamps.clear();
+        self.open.clear();
+        self.high.clear();
+        self.low.clear();
+        self.close.clear();
+        self.volume.clear();
+        self.indicators.clear();
+        self.timeframe_indicators.clear();
+    }
+
+    /// Adds a candle along with its indicators, evicting the oldest candle
+    /// if the buffer is full.
+    pub fn push(&mut self, candle: &Candle) {
+        let (len, capacity) = (self.len(), self.capacity());
+        Self::push_indicators(&mut self.indicators, &candle.indicators, len, capacity);
+        Self::push_indicators(
+            &mut self.timeframe_indicators,
+            &candle.timeframe_indicators,
+            len,
+            capacity,
+        );
+
+        self.timestamps.push(candle.timestamp);
+        self.open.push(candle.open);
+        self.high.push(candle.high);
+        self.low.push(candle.low);
+        self.close.push(candle.close);
+        self.volume.push(candle.volume);
+    }
+
+    /// Sets the column of an indicator from the given candles, which are
+    /// the last candles of the buffer. Used after populating an indicator
+    /// for all candles and for indicators that update earlier candles when
+    /// a new one is added.
+    pub fn set_indicator(&mut self, indicator_type: IndicatorType, candles: &[Candle]) {
+        let (len, capacity) = (self.len(), self.capacity());
+        let column = self
+            .indicators
+            .entry(indicator_type)
+            .or_insert_with(|| Self::empty_column(len, capacity));
+        Self::set_last(column, candles, |c| c.indicators.get(&indicator_type));
+    }
+
+    /// Sets the column of an indicator calculated on a higher interval from
+    /// the given candles, which are the last candles of the buffer.
+    pub fn set_timeframe_indicator(&mut self, indicator: &TimeframeIndicator, candles: &[Candle]) {
+        let (len, capacity) = (self.len(), self.capacity());
+        let column = self
+            .timeframe_indicators
+            .entry(indicator.clone())
+            .or_insert_with(|| Self::empty_column(len, capacity));
+        Self::set_last(column, candles, |c| c.timeframe_indicators.get(indicator));
+    }
+
+    /// View of every candle in the buffer.
+    pub fn view(&self) -> CandleView<'_> {
+        CandleView {
+            buffer: self,
+            start: 0,
+            end: self.len(),
+        }
+    }
+
+    /// View of the last `n` candles in the buffer, or all of them if fewer
+    /// are available.
+    pub fn latest(&self, n: usize) -> CandleView<'_> {
+        self.view().latest(n)
+    }
+
+    fn row(&self, i: usize) -> Option<CandleRow<'_>> {
+        Some(CandleRow {
+            timestamp: *self.timestamps.get(i)?,
+            open: *self.open.get(i)?,
+            high: *self.high.get(i)?,
+            low: *self.low.get(i)?,
+            close: *self.close.get(i)?,
+            volume: *self.volume.get(i)?,
+            buffer: self,
+            i,
+        })
+    }
+
+    /// Column without values for the `len` candles already in the buffer.
+    fn empty_column(len: usize, capacity: usize) -> RingBuffer<Option<Indicator>> {
+        let mut column = RingBuffer::new(capacity);
+        for _ in 0..len {
+            column.push(None);
+        }
+
+        column
+    }
+
+    /// Pushes the value of every column, adding columns for indicators the
+    /// buffer doesn't hold yet with no values for the `len` earlier candles.
+    fn push_indicators<K: Hash + Eq + Clone>(
+        columns: &mut IndexMap<K, RingBuffer<Option<Indicator>>>,
+        values: &HashMap<K, Indicator>,
+        len: usize,
+        capacity: usize,
+    ) {
+        for key in values.keys() {
+            if !columns.contains_key(key) {
+                columns.insert(key.clone(), Self::empty_column(len, capacity));
+            }
+        }
+
+        for (key, column) in columns.iter_mut() {
+            column.push(values.get(key).cloned());
+        }
+    }
+
+    fn set_last<'c>(
+        column: &mut RingBuffer<Option<Indicator>>,
+        candles: &'c [Candle],
+        value: impl Fn(&'c Candle) -> Option<&'c Indicator>,
+    ) {
+        let n = candles.len().min(column.len());
+        let offset = column.len() - n;
+
+        for (i, candle) in candles[candles.len() - n..].iter().enumerate() {
+            if let Some(slot) = column.get_mut(offset + i) {
+                *slot = value(candle).cloned();
+            }
+        }
+    }
+}
+
+impl From<&[Candle]> for CandleBuffer {
+    fn from(candles: &[Candle]) -> Self {
+        Self::from_candles(candles, candles.len())
+    }
+}
+
+/// # CandleView
+///
+/// Cheap borrowed window over a CandleBuffer, handed to strategies instead
+/// of a slice of candles. Rows are read as `CandleRow`s and columns are
+/// returned as views into the buffer without copying any candle data.
+#[derive(Clone, Copy)]
+pub struct CandleView<'a> {
+    buffer: &'a CandleBuffer,
+    start: usize,
+    end: usize,
+}
+
+impl<'a> CandleView<'a> {
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn get(&self, i: usize) -> Option<CandleRow<'a>> {
+        if i >= self.len() {
+            return None;
+        }
+
+        self.buffer.row(self.start + i)
+    }
+
+    pub fn first(&self) -> Option<CandleRow<'a>> {
+        self.get(0)
+    }
+
+    pub fn last(&self) -> Option<CandleRow<'a>> {
+        self.get(self.len().checked_sub(1)?)
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = CandleRow<'a>> + 'a {
+        let buffer = self.buffer;
+        (self.start..self.end).filter_map(move |i| buffer.row(i))
+    }
+
+    /// View of the last `n` candles of this view.
+    pub fn latest(&self, n: usize) -> CandleView<'a> {
+        CandleView {
+            buffer: self.buffer,
+            start: self.end.saturating_sub(n).max(self.start),
+            end: self.end,
+        }
+    }
+
+    /// View of the candles in the range, relative to the start of this view.
+    pub fn window(&self, range: impl RangeBounds<usize>) -> CandleView<'a> {
+        let start = match range.start_bound() {
+            Bound::Included(i) => *i,
+            Bound::Excluded(i) => i + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(i) => i + 1,
+            Bound::Excluded(i) => *i,
+            Bound::Unbounded => self.len(),
+        };
+        let end = (self.start + end).min(self.end);
+
+        CandleView {
+            buffer: self.buffer,
+            start: (self.start + start).min(end),
+            end,
+        }
+    }
+
+    pub fn timestamps(&self) -> RingView<'a, DateTime<Utc>> {
+        self.buffer.timestamps.view(self.start..self.end)
+    }
+
+    pub fn open(&self) -> RingView<'a, f64> {
+        self.buffer.open.view(self.start..self.end)
+    }
+
+    pub fn high(&self) -> RingView<'a, f64> {
+        self.buffer.high.view(self.start..self.end)
+    }
+
+    pub fn low(&self) -> RingView<'a, f64> {
+        self.buffer.low.view(self.start..self.end)
+    }
+
+    pub fn close(&self) -> RingView<'a, f64> {
+        self.buffer.close.view(self.start..self.end)
+    }
+
+    pub fn volume(&self) -> RingView<'a, f64> {
+        self.buffer.volume.view(self.start..self.end)
+    }
+
+    /// Column of a candle field such as `close`, see `CANDLE_FIELDS`.
+    #[allow(dead_code)]
+    pub fn column(&self, name: &str) -> Option<RingView<'a, f64>> {
+        match name.trim().to_lowercase().as_str() {
+            "open" => Some(self.open()),
+            "high" => Some(self.high()),
+            "low" => Some(self.low()),
+            "close" => Some(self.close()),
+            "volume" => Some(self.volume()),
+            _ => None,
+        }
+    }
+
+    /// Column of an indicator, None if the indicator is not stored in the
+    /// buffer.
+    pub fn indicator(
+        &self,
+        indicator_type: &IndicatorType,
+    ) -> Option<RingView<'a, Option<Indicator>>> {
+        let column = self.buffer.indicators.get(indicator_type)?;
+        Some(column.view(self.start..self.end))
+    }
+
+    /// Values of an indicator output, None if the indicator is not stored
+    /// in the buffer.
+    pub fn field(&self, field: &IndicatorField) -> Option<impl Iterator<Item = Option<f64>> + 'a> {
+        let name = field.field;
+        let column = self.indicator(&field.indicator_type)?;
+
+        Some(
+            column
+                .iter()
+                .map(move |indicator| indicator.as_ref()?.field(name)),
+        )
+    }
+
+    /// Values of an indicator output given as a spec such as `bb(20).upper`.
+    #[allow(dead_code)]
+    pub fn value(&self, spec: &str) -> Result<impl Iterator<Item = Option<f64>> + 'a> {
+        let field: IndicatorField = spec.parse()?;
+
+        self.field(&field)
+            .ok_or(anyhow!("Indicator {} is not populated.", field))
+    }
+
+    /// Copies the candles of the view, e.g. for code that still needs owned
+    /// candles.
+    #[allow(dead_code)]
+    pub fn to_candles(&self) -> Vec<Candle> {
+        self.iter().map(|row| row.to_candle()).collect()
+    }
+}
+
+impl Debug for CandleView<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// # CandleRow
+///
+/// A single candle of a CandleView. The price and volume fields are copied
+/// out of their columns, while indicators are borrowed from the buffer.
+#[derive(Clone, Copy)]
+pub struct CandleRow<'a> {
+    pub timestamp: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    buffer: &'a CandleBuffer,
+    i: usize,
+}
+
+impl<'a> CandleRow<'a> {
+    pub fn indicator(&self, indicator_type: &IndicatorType) -> Option<&'a Indicator> {
+        self.buffer
+            .indicators
+            .get(indicator_type)?
+            .get(self.i)?
+            .as_ref()
+    }
+
+    pub fn timeframe_indicator(&self, indicator: &TimeframeIndicator) -> Option<&'a Indicator> {
+        self.buffer
+            .timeframe_indicators
+            .get(indicator)?
+            .get(self.i)?
+            .as_ref()
+    }
+
+    pub fn clone_indicator(&self, key: &IndicatorType) -> Result<Indicator> {
+        self.indicator(key)
+            .context(format!("Unable to find indicator with type: {:#?}", key))
+            .cloned()
+    }
+
+    #[allow(dead_code)]
+    pub fn clone_timeframe_indicator(&self, key: &TimeframeIndicator) -> Result<Indicator> {
+        self.timeframe_indicator(key)
+            .context(format!("Unable to find timeframe indicator: {:#?}", key))
+            .cloned()
+    }
+
+    /// Returns a candle field or indicator output by spec, see
+    /// `Candle::value`.
+    #[allow(dead_code)]
+    pub fn value(&self, spec: &str) -> Option<f64> {
+        if let Some(value) = self.candle_field(spec) {
+            return Some(value);
+        }
+
+        let field: IndicatorField = spec.parse().ok()?;
+        self.field_value(&field)
+    }
+
+    pub fn field_value(&self, field: &IndicatorField) -> Option<f64> {
+        self.indicator(&field.indicator_type)?.field(field.field)
+    }
+
+    /// Returns one of the `CANDLE_FIELDS` by name.
+    #[allow(dead_code)]
+    pub fn candle_field(&self, name: &str) -> Option<f64> {
+        match name.trim().to_lowercase().as_str() {
+            "open" => Some(self.open),
+            "high" => Some(self.high),
+            "low" => Some(self.low),
+            "close" => Some(self.close),
+            "volume" => Some(self.volume),
+            _ => None,
+        }
+    }
+
+    /// Copies the row into a Candle along with its indicators.
+    pub fn to_candle(&self) -> Candle {
+        let mut candle = Candle::new(
+            self.timestamp,
+            self.open,
+            self.close,
+            self.high,
+            self.low,
+            self.volume,
+        );
+
+        for (indicator_type, column) in &self.buffer.indicators {
+            if let Some(Some(indicator)) = column.get(self.i) {
+                candle.indicators.insert(*indicator_type, indicator.clone());
+            }
+        }
+
+        for (indicator, column) in &self.buffer.timeframe_indicators {
+            if let Some(Some(value)) = column.get(self.i) {
+                candle
+                    .timeframe_indicators
+                    .insert(indicator.clone(), value.clone());
+            }
+        }
+
+        candle
+    }
+}
+
+impl Debug for CandleRow<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CandleRow")
+            .field("timestamp", &self.timestamp)
+            .field("open", &self.open)
+            .field("high", &self.high)
+            .field("low", &self.low)
+            .field("close", &self.close)
+            .field("volume", &self.volume)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_field::IndicatorField, indicator_type::IndicatorType,
+            populates_candles::PopulatesCandlesWithSelf,
+        },
+        models::{
+            candle::Candle, candle_buffer::CandleBuffer, interval::Interval,
+            timeseries_builder::TimeSeriesBuilder,
+        },
+    };
+
+    #[test]
+    fn keeps_latest_candles_in_columns() {
+        let candles = Candle::dummy_data(10, "positive", 100.0);
+        let mut buffer = CandleBuffer::new(4);
+
+        for candle in &candles {
+            buffer.push(candle);
+        }
+
+        let closes: Vec<f64> = candles[6..].iter().map(|c| c.close).collect();
+        assert_eq!(buffer.len(), 4);
+        assert_eq!(buffer.view().close().to_vec(), closes);
+        assert_eq!(buffer.latest(2).close().to_vec(), closes[2..].to_vec());
+        assert_eq!(buffer.view().window(1..3).close().to_vec(), closes[1..3]);
+        assert_eq!(
+            buffer.view().last().unwrap().timestamp,
+            candles[9].timestamp
+        );
+        assert_eq!(buffer.view().get(4).map(|row| row.close), None);
+        assert_eq!(buffer.latest(20).len(), 4);
+    }
+
+    #[test]
+    fn indicator_columns_match_candles() {
+        let candles = Candle::dummy_data(40, "alternating", 100.0);
+        let sma = IndicatorType::SMA(5);
+        let pivots = IndicatorType::DynamicPivot(2);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .max_length(20)
+            .candles(candles[..25].to_vec())
+            .build();
+
+        sma.populate_candles(&mut ts).unwrap();
+        ts.add_indicator(pivots).unwrap();
+
+        for candle in &candles[25..] {
+            ts.add_candle(candle).unwrap();
+        }
+
+        let view = ts.buffer.view();
+        let start = ts.candles.len() - view.len();
+        assert_eq!(view.len(), 20);
+
+        // Pivots are populated on earlier candles as new ones are added
+        for indicator_type in [sma, pivots] {
+            for field in IndicatorField::all(indicator_type) {
+                let expected: Vec<Option<f64>> = ts.candles[start..]
+                    .iter()
+                    .map(|c| c.field_value(&field))
+                    .collect();
+                let values: Vec<Option<f64>> = view.field(&field).unwrap().collect();
+
+                assert_eq!(values, expected, "{}", field);
+            }
+        }
+
+        let expected: Vec<Option<f64>> = ts.candles[start..]
+            .iter()
+            .map(|c| c.value("sma(5)"))
+            .collect();
+        assert_eq!(view.value("sma(5)").unwrap().collect::<Vec<_>>(), expected);
+        assert!(view.value("ema(5)").is_err());
+
+        let last = view.last().unwrap();
+        let candle = last.to_candle();
+        assert_eq!(last.value("sma(5)"), candle.value("sma(5)"));
+        assert_eq!(last.value("close"), Some(candle.close));
+        assert_eq!(
+            candle.indicators.len(),
+            ts.candles.last().unwrap().indicators.len()
+        );
+        assert!(candle.indicators.contains_key(&sma));
+    }
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter, Result},
+    hash::{Hash, Hasher},
+};
+
+/// # HashableF64
+///
+/// Float wrapper which can be used as part of hashed keys such as
+/// `IndicatorType`. Equality and hashing are based on the bit representation
+/// of the value, so `NaN` equals itself while `0.0` and `-0.0` differ.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct HashableF64(pub f64);
+
+impl HashableF64 {
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+impl PartialEq for HashableF64 {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for HashableF64 {}
+
+impl Hash for HashableF64 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+impl From<f64> for HashableF64 {
+    fn from(value: f64) -> Self {
+        Self(value)
+    }
+}
+
+impl Display for HashableF64 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.0)
+    }
+}
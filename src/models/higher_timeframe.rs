@@ -0,0 +1,183 @@
+use crate::{
+    indicators::timeframe_indicator::TimeframeIndicator,
+    models::{
+        candle::Candle, interval::Interval, resampler::Resampler, timeseries::TimeSeries,
+        timeseries_builder::TimeSeriesBuilder,
+    },
+};
+use anyhow::Result;
+
+/// # HigherTimeframe
+///
+/// Candles of a higher Interval resampled from the candles of a TimeSeries,
+/// used to calculate `TimeframeIndicator`s. Higher interval candles are only
+/// added to the series once they have closed.
+#[derive(Debug, Clone)]
+pub struct HigherTimeframe {
+    pub ts: TimeSeries,
+    resampler: Resampler,
+}
+
+impl HigherTimeframe {
+    pub fn new(
+        symbol: &str,
+        lower_interval: &Interval,
+        interval: &Interval,
+        max_length: usize,
+        candles: &[Candle],
+    ) -> Result<Self> {
+        let resampler = Resampler::new(lower_interval, interval)?;
+        let ts = TimeSeriesBuilder::new()
+            .symbol(symbol.to_string())
+            .interval(interval.clone())
+            .max_length(max_length)
+            .validate_candles_on_add(false)
+            .build();
+
+        let mut timeframe = Self { ts, resampler };
+        timeframe.set_candles(candles);
+
+        Ok(timeframe)
+    }
+
+    pub fn interval(&self) -> &Interval {
+        &self.ts.interval
+    }
+
+    /// Adds a candle of the lower interval and populates the indicators of
+    /// the higher interval candle if it closed with it.
+    pub fn add_candle(&mut self, candle: &Candle) -> Result<()> {
+        if let Some(closed) = self.resampler.push(candle) {
+            self.ts.add_candle(&closed)?;
+        }
+
+        Ok(())
+    }
+
+    /// Rebuilds the higher interval candles from the given lower interval
+    /// candles, without populating indicators.
+    pub fn set_candles(&mut self, candles: &[Candle]) {
+        self.resampler.reset();
+
+        let closed = self.resampler.resample(candles);
+        let start = closed.len().saturating_sub(self.ts.max_length);
+
+        self.ts.set_candles(&closed[start..]);
+    }
+
+    pub fn clear_candles(&mut self) {
+        self.resampler.reset();
+        self.ts.clear_candles();
+    }
+
+    /// Sets the indicator values of the last closed higher interval candle on
+    /// the latest lower interval candle.
+    pub fn populate_last_candle(&self, candle: &mut Candle) {
+        if let Some(higher) = self.ts.candles.last() {
+            self.set_values(higher, candle);
+        }
+    }
+
+    /// Sets the indicator values of the last closed higher interval candle on
+    /// each of the lower interval candles.
+    pub fn populate_candles(&self, candles: &mut [Candle]) {
+        let mut higher_candles = self.ts.candles.iter().peekable();
+        let mut last = None;
+
+        for candle in candles.iter_mut() {
+            let close_time = self.resampler.from().next_start(candle.timestamp);
+
+            while let Some(higher) = higher_candles
+                .next_if(|higher| self.interval().next_start(higher.timestamp) <= close_time)
+            {
+                last = Some(higher);
+            }
+
+            if let Some(higher) = last {
+                self.set_values(higher, candle);
+            }
+        }
+    }
+
+    fn set_values(&self, higher: &Candle, candle: &mut Candle) {
+        for indicator_type in self.ts.indicators.iter() {
+            if let Some(indicator) = higher.indicators.get(indicator_type) {
+                let key = TimeframeIndicator::new(self.interval().clone(), *indicator_type);
+                candle.timeframe_indicators.insert(key, indicator.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{indicator_type::IndicatorType, timeframe_indicator::TimeframeIndicator},
+        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::math::sma,
+    };
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn hourly_candles(closes: &[f64]) -> Vec<Candle> {
+        let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| {
+                let timestamp = start + Duration::hours(i as i64);
+                Candle::new(
+                    timestamp,
+                    close - 1.0,
+                    *close,
+                    close + 2.0,
+                    close - 2.0,
+                    10.0,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn timeseries_uses_last_closed_value() {
+        let closes: Vec<f64> = (0..40).map(|i| 100.0 + ((i * 7) % 11) as f64).collect();
+        let candles = hourly_candles(&closes);
+        let indicator = TimeframeIndicator::new(Interval::Hour4, IndicatorType::SMA(2));
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Hour1)
+            .candles(candles.clone())
+            .build();
+        ts.add_timeframe_indicator(indicator.clone()).unwrap();
+
+        let mut incremental = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Hour1)
+            .candles(candles[..10].to_vec())
+            .build();
+        incremental
+            .add_timeframe_indicator(indicator.clone())
+            .unwrap();
+        for candle in candles[10..].iter() {
+            incremental.add_candle(candle).unwrap();
+        }
+
+        for (i, (candle, other)) in ts.candles.iter().zip(&incremental.candles).enumerate() {
+            let value = |candle: &Candle| {
+                candle
+                    .clone_timeframe_indicator(&indicator)
+                    .ok()
+                    .and_then(|sma| sma.as_sma())
+                    .map(|sma| sma.value)
+            };
+
+            // Only 4 hour candles closed by the current candle are used
+            let closed: Vec<f64> = closes[..=i].chunks_exact(4).map(|c| c[3]).collect();
+            let expected = (closed.len() >= 2).then(|| sma(&closed[closed.len() - 2..]));
+
+            assert_eq!(value(candle), expected);
+            assert_eq!(value(other), expected);
+        }
+    }
+}
@@ -1,9 +1,28 @@
-use chrono::Duration;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, Duration, Months, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
-use std::fmt::{Display, Formatter, Result};
+use std::{
+    fmt::{self, Display, Formatter},
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
 
+const MINUTES_PER_HOUR: u32 = 60;
+const MINUTES_PER_DAY: u32 = 24 * MINUTES_PER_HOUR;
+const MINUTES_PER_WEEK: u32 = 7 * MINUTES_PER_DAY;
+
+/// # Interval
+///
+/// Duration of the candles of a TimeSeries. Commonly used intervals have
+/// their own variants, any other whole number of minutes is represented by
+/// `Interval::Minutes`. Intervals can be parsed from strings such as `15m`,
+/// `4h`, `3d`, `1w` or `1M`.
+///
+/// Intervals are compared by their length, so `Interval::Minutes(240)` equals
+/// `Interval::Hour4`.
 #[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
 pub enum Interval {
     Minute1,
     Minute5,
@@ -15,10 +34,60 @@ pub enum Interval {
     Day1,
     Day5,
     Week1,
+    /// Any other number of minutes, e.g. 3 minutes, 2 hours or 3 days. Use
+    /// `Interval::from_minutes` to get the named variant when there is one.
+    Minutes(u32),
+    Month1,
+}
+
+/// Largest unit an Interval can be expressed in as a whole number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntervalUnit {
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+impl IntervalUnit {
+    pub fn name(&self) -> &'static str {
+        match self {
+            IntervalUnit::Minute => "minute",
+            IntervalUnit::Hour => "hour",
+            IntervalUnit::Day => "day",
+            IntervalUnit::Week => "week",
+            IntervalUnit::Month => "month",
+        }
+    }
+
+    fn suffix(&self) -> &'static str {
+        match self {
+            IntervalUnit::Minute => "m",
+            IntervalUnit::Hour => "h",
+            IntervalUnit::Day => "d",
+            IntervalUnit::Week => "w",
+            IntervalUnit::Month => "M",
+        }
+    }
+}
+
+impl PartialEq for Interval {
+    fn eq(&self, other: &Self) -> bool {
+        self.minutes() == other.minutes()
+    }
+}
+
+impl Eq for Interval {}
+
+impl Hash for Interval {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.minutes().hash(state);
+    }
 }
 
 impl Display for Interval {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match *self {
             Interval::Minute1 => write!(f, "1 Minute"),
             Interval::Minute5 => write!(f, "5 Minute"),
@@ -30,23 +99,156 @@ impl Display for Interval {
             Interval::Day1 => write!(f, "Daily"),
             Interval::Day5 => write!(f, "5 Day"),
             Interval::Week1 => write!(f, "Weekly"),
+            Interval::Month1 => write!(f, "Monthly"),
+            Interval::Minutes(_) => {
+                let (unit, count) = self.unit_count();
+                let name = unit.name();
+                write!(f, "{} {}{}", count, name[..1].to_uppercase(), &name[1..])
+            }
         }
     }
 }
 
+impl FromStr for Interval {
+    type Err = anyhow::Error;
+
+    /// Parses intervals such as `15m`, `4h`, `3d`, `2w` or `1M`, as well as
+    /// the variant names such as `Hour4`.
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+
+        let named = match s {
+            "Minute1" => Some(Interval::Minute1),
+            "Minute5" => Some(Interval::Minute5),
+            "Minute15" => Some(Interval::Minute15),
+            "Minute30" => Some(Interval::Minute30),
+            "Hour1" => Some(Interval::Hour1),
+            "Hour4" => Some(Interval::Hour4),
+            "Hour12" => Some(Interval::Hour12),
+            "Day1" => Some(Interval::Day1),
+            "Day5" => Some(Interval::Day5),
+            "Week1" => Some(Interval::Week1),
+            "Month1" => Some(Interval::Month1),
+            _ => None,
+        };
+        if let Some(interval) = named {
+            return Ok(interval);
+        }
+
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (count, unit) = s.split_at(split);
+        let count: u32 = count
+            .parse()
+            .map_err(|_| anyhow!("Invalid interval '{}'.", s))?;
+
+        let size = match unit {
+            "m" | "min" => 1,
+            "h" => MINUTES_PER_HOUR,
+            "d" | "D" => MINUTES_PER_DAY,
+            "w" | "W" => MINUTES_PER_WEEK,
+            "M" | "mo" if count == 1 => return Ok(Interval::Month1),
+            "M" | "mo" => return Err(anyhow!("Only monthly intervals of 1 month are supported.")),
+            _ => return Err(anyhow!("Invalid interval unit in '{}'.", s)),
+        };
+
+        let minutes = count
+            .checked_mul(size)
+            .ok_or(anyhow!("Interval '{}' is too long.", s))?;
+
+        Self::from_minutes(minutes)
+    }
+}
+
+impl From<Interval> for String {
+    fn from(interval: Interval) -> Self {
+        match interval {
+            Interval::Minutes(_) => interval.code(),
+            _ => format!("{:?}", interval),
+        }
+    }
+}
+
+impl TryFrom<String> for Interval {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
 impl Interval {
+    /// Returns the Interval of the given number of minutes, using the named
+    /// variant when there is one.
+    pub fn from_minutes(minutes: u32) -> Result<Self> {
+        let interval = match minutes {
+            0 => return Err(anyhow!("Intervals need to be at least 1 minute.")),
+            1 => Interval::Minute1,
+            5 => Interval::Minute5,
+            15 => Interval::Minute15,
+            30 => Interval::Minute30,
+            60 => Interval::Hour1,
+            240 => Interval::Hour4,
+            720 => Interval::Hour12,
+            MINUTES_PER_DAY => Interval::Day1,
+            7200 => Interval::Day5,
+            MINUTES_PER_WEEK => Interval::Week1,
+            minutes => Interval::Minutes(minutes),
+        };
+
+        Ok(interval)
+    }
+
+    /// Returns the number of minutes in the interval, or None for monthly
+    /// intervals since months differ in length.
+    pub fn minutes(&self) -> Option<u32> {
+        let minutes = match self {
+            Interval::Minute1 => 1,
+            Interval::Minute5 => 5,
+            Interval::Minute15 => 15,
+            Interval::Minute30 => 30,
+            Interval::Hour1 => 60,
+            Interval::Hour4 => 240,
+            Interval::Hour12 => 720,
+            Interval::Day1 => MINUTES_PER_DAY,
+            Interval::Day5 => 7200,
+            Interval::Week1 => MINUTES_PER_WEEK,
+            Interval::Minutes(minutes) => *minutes,
+            Interval::Month1 => return None,
+        };
+
+        Some(minutes)
+    }
+
+    /// Returns the largest unit the interval is a whole number of, and that
+    /// number. E.g. `(IntervalUnit::Hour, 4)` for 4 hours.
+    pub fn unit_count(&self) -> (IntervalUnit, u32) {
+        let Some(minutes) = self.minutes() else {
+            return (IntervalUnit::Month, 1);
+        };
+
+        [
+            (IntervalUnit::Week, MINUTES_PER_WEEK),
+            (IntervalUnit::Day, MINUTES_PER_DAY),
+            (IntervalUnit::Hour, MINUTES_PER_HOUR),
+        ]
+        .into_iter()
+        .find(|(_, size)| minutes % size == 0)
+        .map(|(unit, size)| (unit, minutes / size))
+        .unwrap_or((IntervalUnit::Minute, minutes))
+    }
+
+    /// Returns the short form of the interval, e.g. `15m` or `4h`.
+    pub fn code(&self) -> String {
+        let (unit, count) = self.unit_count();
+        format!("{}{}", count, unit.suffix())
+    }
+
+    /// Returns the duration of the interval. Months are approximated as 30
+    /// days, use `next_start` for the start of the following candle.
     pub fn to_duration(&self) -> Duration {
-        match self {
-            Interval::Minute1 => Duration::minutes(1),
-            Interval::Minute5 => Duration::minutes(5),
-            Interval::Minute15 => Duration::minutes(15),
-            Interval::Minute30 => Duration::minutes(30),
-            Interval::Hour1 => Duration::hours(1),
-            Interval::Hour4 => Duration::hours(4),
-            Interval::Hour12 => Duration::hours(12),
-            Interval::Day1 => Duration::days(1),
-            Interval::Day5 => Duration::days(5),
-            Interval::Week1 => Duration::weeks(1),
+        match self.minutes() {
+            Some(minutes) => Duration::minutes(minutes as i64),
+            None => Duration::days(30),
         }
     }
 
@@ -54,20 +256,125 @@ impl Interval {
         self.to_duration().num_milliseconds()
     }
 
+    /// Returns the start of the candle following the candle starting at the
+    /// given timestamp.
+    pub fn next_start(&self, start: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Interval::Month1 => start + Months::new(1),
+            _ => start + self.to_duration(),
+        }
+    }
+
+    /// Returns the start of the candle of this interval containing the
+    /// timestamp. Monthly candles start on the first of the month and weekly
+    /// candles on Mondays as on most exchanges, other candles at multiples of
+    /// the interval since the Unix epoch.
+    pub fn bucket_start(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        let Some(minutes) = self.minutes() else {
+            return Utc
+                .with_ymd_and_hms(timestamp.year(), timestamp.month(), 1, 0, 0, 0)
+                .unwrap();
+        };
+
+        // The Unix epoch was a Thursday
+        let origin = match minutes % MINUTES_PER_WEEK {
+            0 => Duration::days(4).num_milliseconds(),
+            _ => 0,
+        };
+
+        let offset = (timestamp.timestamp_millis() - origin).rem_euclid(self.to_millis());
+
+        timestamp - Duration::milliseconds(offset)
+    }
+
     /// Returns the acceptable difference in duration to still consider candles
     /// subsequent in a timeseries.
     pub fn max_diff(&self) -> Duration {
-        match self {
-            Interval::Minute1 => Duration::seconds(1),
-            Interval::Minute5 => Duration::seconds(5),
-            Interval::Minute15 => Duration::seconds(5),
-            Interval::Minute30 => Duration::seconds(5),
-            Interval::Hour1 => Duration::minutes(1),
-            Interval::Hour4 => Duration::minutes(1),
-            Interval::Hour12 => Duration::minutes(1),
-            Interval::Day1 => Duration::hours(1),
-            Interval::Day5 => Duration::hours(1),
-            Interval::Week1 => Duration::hours(1),
+        let duration = self.to_duration();
+
+        if duration < Duration::minutes(5) {
+            Duration::seconds(1)
+        } else if duration < Duration::hours(1) {
+            Duration::seconds(5)
+        } else if duration < Duration::days(1) {
+            Duration::minutes(1)
+        } else {
+            Duration::hours(1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::interval::{Interval, IntervalUnit};
+    use chrono::{TimeZone, Utc};
+    use std::{
+        collections::{hash_map::DefaultHasher, HashSet},
+        hash::{Hash, Hasher},
+    };
+
+    #[test]
+    fn parse_intervals() {
+        let parse = |s: &str| s.parse::<Interval>().unwrap();
+
+        assert_eq!(parse("15m"), Interval::Minute15);
+        assert_eq!(parse("4h"), Interval::Hour4);
+        assert_eq!(parse("60m"), Interval::Hour1);
+        assert_eq!(parse("1w"), Interval::Week1);
+        assert_eq!(parse("1M"), Interval::Month1);
+        assert_eq!(parse("2h"), Interval::Minutes(120));
+        assert_eq!(parse("3d"), Interval::Minutes(3 * 24 * 60));
+        assert_eq!(parse("Hour12"), Interval::Hour12);
+
+        for s in ["", "h", "0m", "4x", "2M", "-1d"] {
+            assert!(s.parse::<Interval>().is_err(), "{}", s);
         }
     }
+
+    #[test]
+    fn units_and_names() {
+        assert_eq!(Interval::Day5.unit_count(), (IntervalUnit::Day, 5));
+        assert_eq!(
+            Interval::Minutes(90).unit_count(),
+            (IntervalUnit::Minute, 90)
+        );
+        assert_eq!(Interval::Minutes(120).code(), "2h");
+        assert_eq!(Interval::Minutes(120).to_string(), "2 Hour");
+        assert_eq!(Interval::Month1.code(), "1M");
+
+        for interval in [Interval::Hour4, Interval::Minutes(180), Interval::Month1] {
+            let s: String = interval.clone().into();
+            assert_eq!(Interval::try_from(s).unwrap(), interval);
+        }
+    }
+
+    #[test]
+    fn equal_by_length() {
+        let hash = |interval: &Interval| {
+            let mut hasher = DefaultHasher::new();
+            interval.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        assert_eq!(Interval::Minutes(240), Interval::Hour4);
+        assert_eq!(hash(&Interval::Minutes(240)), hash(&Interval::Hour4));
+        assert_eq!(Interval::Minutes(7 * 24 * 60), Interval::Week1);
+        assert_ne!(Interval::Minutes(120), Interval::Hour1);
+        assert_ne!(Interval::Minutes(30 * 24 * 60), Interval::Month1);
+
+        let intervals = HashSet::from([Interval::Hour4, Interval::Day1]);
+        assert!(intervals.contains(&Interval::Minutes(240)));
+    }
+
+    #[test]
+    fn monthly_candles() {
+        let timestamp = Utc.with_ymd_and_hms(2024, 2, 17, 13, 0, 0).unwrap();
+        let start = Interval::Month1.bucket_start(timestamp);
+
+        assert_eq!(start, Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap());
+        assert_eq!(
+            Interval::Month1.next_start(start),
+            Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap()
+        );
+    }
 }
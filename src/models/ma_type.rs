@@ -1,4 +1,9 @@
+use anyhow::{anyhow, Error};
 use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter, Result},
+    str::FromStr,
+};
 
 #[allow(dead_code)]
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
@@ -6,4 +11,47 @@ pub enum MAType {
     SMA,
     EMA,
     VWMA,
+    WMA,
+    HMA,
+    DEMA,
+    TEMA,
+    KAMA,
+    RMA,
+}
+
+impl FromStr for MAType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sma" => Ok(MAType::SMA),
+            "ema" => Ok(MAType::EMA),
+            "vwma" => Ok(MAType::VWMA),
+            "wma" => Ok(MAType::WMA),
+            "hma" => Ok(MAType::HMA),
+            "dema" => Ok(MAType::DEMA),
+            "tema" => Ok(MAType::TEMA),
+            "kama" => Ok(MAType::KAMA),
+            "rma" => Ok(MAType::RMA),
+            _ => Err(anyhow!("Unknown moving average type '{}'.", s)),
+        }
+    }
+}
+
+impl Display for MAType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let s = match self {
+            MAType::SMA => "sma",
+            MAType::EMA => "ema",
+            MAType::VWMA => "vwma",
+            MAType::WMA => "wma",
+            MAType::HMA => "hma",
+            MAType::DEMA => "dema",
+            MAType::TEMA => "tema",
+            MAType::KAMA => "kama",
+            MAType::RMA => "rma",
+        };
+
+        write!(f, "{}", s)
+    }
 }
@@ -0,0 +1,40 @@
+use anyhow::{anyhow, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+/// State of the market on a candle, as labeled by a `RegimeClassifier`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum MarketRegime {
+    TrendingUp,
+    TrendingDown,
+    Ranging,
+    HighVolatility,
+}
+
+impl FromStr for MarketRegime {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().replace(['_', '-', ' '], "").as_str() {
+            "trendingup" => Ok(MarketRegime::TrendingUp),
+            "trendingdown" => Ok(MarketRegime::TrendingDown),
+            "ranging" => Ok(MarketRegime::Ranging),
+            "highvolatility" => Ok(MarketRegime::HighVolatility),
+            _ => Err(anyhow!("Unknown market regime {}", s)),
+        }
+    }
+}
+
+impl Display for MarketRegime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MarketRegime::TrendingUp => write!(f, "Trending Up"),
+            MarketRegime::TrendingDown => write!(f, "Trending Down"),
+            MarketRegime::Ranging => write!(f, "Ranging"),
+            MarketRegime::HighVolatility => write!(f, "High Volatility"),
+        }
+    }
+}
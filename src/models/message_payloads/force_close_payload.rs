@@ -0,0 +1,9 @@
+use actix::Message;
+use anyhow::Result;
+
+#[derive(Debug, Clone)]
+pub struct ForceClosePayload;
+
+impl Message for ForceClosePayload {
+    type Result = Result<()>;
+}
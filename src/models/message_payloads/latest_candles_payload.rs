@@ -1,11 +1,11 @@
-use crate::models::{candle::Candle, interval::Interval};
+use crate::models::{candle::Candle, interval::Interval, shared_candles::SharedCandles};
 use actix::Message;
 
 #[allow(dead_code)]
 pub struct LatestCandleResponse {
     pub symbol: String,
     pub interval: Interval,
-    pub candles: Vec<Candle>,
+    pub candles: SharedCandles,
 }
 
 impl Message for LatestCandleResponse {
@@ -18,7 +18,7 @@ impl LatestCandleResponse {
         LatestCandleResponse {
             symbol,
             interval,
-            candles,
+            candles: candles.into(),
         }
     }
 }
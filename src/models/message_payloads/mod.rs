@@ -1,10 +1,16 @@
 pub mod add_candles_payload;
 pub mod candle_added_payload;
 pub mod fill_historical_candles_payload;
+pub mod force_close_payload;
 pub mod latest_candles_payload;
+pub mod pause_payload;
 pub mod ping_payload;
 pub mod request_latest_candles_payload;
+pub mod request_setup_finder_state_payload;
+pub mod request_timeseries_state_payload;
+pub mod request_trade_state_payload;
 pub mod stop_payload;
+pub mod trade_spawned_payload;
 pub mod triggered_payload;
 pub mod ts_subscribe_payload;
 pub mod websocket_payload;
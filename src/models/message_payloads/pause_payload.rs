@@ -0,0 +1,10 @@
+use actix::Message;
+
+#[derive(Debug, Clone)]
+pub struct PausePayload {
+    pub paused: bool,
+}
+
+impl Message for PausePayload {
+    type Result = ();
+}
@@ -0,0 +1,18 @@
+use crate::models::{interval::Interval, trade::Trade};
+use actix::{Addr, Message};
+
+#[derive(Debug, Clone)]
+pub struct RequestSetupFinderStatePayload;
+
+impl Message for RequestSetupFinderStatePayload {
+    type Result = SetupFinderState;
+}
+
+#[derive(Debug, Clone)]
+pub struct SetupFinderState {
+    pub strategy: String,
+    pub interval: Interval,
+    pub paused: bool,
+    pub live_trading_enabled: bool,
+    pub trades: Vec<Addr<Trade>>,
+}
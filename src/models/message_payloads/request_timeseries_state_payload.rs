@@ -0,0 +1,22 @@
+use crate::models::interval::Interval;
+use actix::Message;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Clone)]
+pub struct RequestTimeSeriesStatePayload;
+
+impl Message for RequestTimeSeriesStatePayload {
+    type Result = TimeSeriesState;
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeSeriesState {
+    pub symbol: String,
+    pub interval: Interval,
+    pub n_candles: usize,
+    pub max_length: usize,
+    pub last_timestamp: Option<DateTime<Utc>>,
+    pub last_close: Option<f64>,
+    pub indicators: Vec<String>,
+}
@@ -0,0 +1,23 @@
+use crate::models::{interval::Interval, strategy_orientation::StrategyOrientation};
+use actix::Message;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Clone)]
+pub struct RequestTradeStatePayload;
+
+impl Message for RequestTradeStatePayload {
+    type Result = TradeState;
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeState {
+    pub id: usize,
+    pub symbol: String,
+    pub interval: Interval,
+    pub orientation: StrategyOrientation,
+    pub entry_timestamp: DateTime<Utc>,
+    pub entry_price: f64,
+    pub quantity: f64,
+    pub dollar_value: f64,
+}
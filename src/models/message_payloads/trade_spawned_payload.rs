@@ -0,0 +1,11 @@
+use crate::models::trade::Trade;
+use actix::{Addr, Message};
+
+#[derive(Debug, Clone)]
+pub struct TradeSpawnedPayload {
+    pub trade: Addr<Trade>,
+}
+
+impl Message for TradeSpawnedPayload {
+    type Result = ();
+}
@@ -1,13 +1,24 @@
+pub mod bar_builder;
+pub mod bar_type;
 pub mod calculation_mode;
 pub mod candle;
+pub mod candle_buffer;
+pub mod hashable_f64;
+pub mod higher_timeframe;
 pub mod interval;
 pub mod ma_type;
+pub mod market_regime;
 pub mod message_payloads;
 pub mod net_version;
+pub mod regime_classifier;
+pub mod resampler;
+pub mod ring_buffer;
 pub mod setups;
+pub mod shared_candles;
 pub mod strategy_orientation;
 pub mod timeseries;
 pub mod timeseries_builder;
+pub mod timeseries_columns;
 pub mod trade;
 pub mod trade_builder;
 pub mod traits;
@@ -0,0 +1,237 @@
+use crate::{
+    indicators::indicator_type::IndicatorType,
+    models::{
+        candle_buffer::CandleRow,
+        ma_type::MAType,
+        market_regime::MarketRegime,
+        traits::{has_min_length::HasMinLength, requires_indicators::RequiresIndicators},
+    },
+};
+
+/// # RegimeClassifier
+///
+/// Labels candles with the regime of the market, based on the volatility
+/// percentile (BBWP) and the trend strength given by the distance between a
+/// fast and a slow EMA, measured in ATRs.
+///
+/// ## Classification
+/// - High Volatility: BBWP at or above `high_volatility`, given in decimals.
+/// - Trending Up: Fast EMA at least `trend_strength` ATRs above the slow EMA.
+/// - Trending Down: Fast EMA at least `trend_strength` ATRs below the slow EMA.
+/// - Ranging: Otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegimeClassifier {
+    pub fast_len: usize,
+    pub slow_len: usize,
+    pub atr_len: usize,
+    pub trend_strength: f64,
+    pub bbwp_len: usize,
+    pub bbwp_lookback: usize,
+    pub high_volatility: f64,
+}
+
+impl Default for RegimeClassifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegimeClassifier {
+    pub fn new() -> Self {
+        Self::new_args(20, 50, 1.0, 0.9)
+    }
+
+    #[allow(dead_code)]
+    pub fn new_args(
+        fast_len: usize,
+        slow_len: usize,
+        trend_strength: f64,
+        high_volatility: f64,
+    ) -> Self {
+        Self {
+            fast_len,
+            slow_len,
+            atr_len: 14,
+            trend_strength,
+            bbwp_len: 13,
+            bbwp_lookback: 252,
+            high_volatility,
+        }
+    }
+
+    fn fast_type(&self) -> IndicatorType {
+        IndicatorType::EMA(self.fast_len)
+    }
+
+    fn slow_type(&self) -> IndicatorType {
+        IndicatorType::EMA(self.slow_len)
+    }
+
+    fn atr_type(&self) -> IndicatorType {
+        IndicatorType::ATR(self.atr_len)
+    }
+
+    fn bbwp_type(&self) -> IndicatorType {
+        IndicatorType::BBWP(self.bbwp_len, self.bbwp_lookback, 5, MAType::SMA)
+    }
+
+    fn ema(&self, candle: CandleRow, indicator_type: &IndicatorType) -> Option<f64> {
+        let ema = candle.clone_indicator(indicator_type).ok()?.as_ema()?;
+        Some(ema.value)
+    }
+
+    /// Returns the regime of the market on the candle, or None while the
+    /// indicators are warming up.
+    pub fn classify(&self, candle: CandleRow) -> Option<MarketRegime> {
+        let bbwp = candle.clone_indicator(&self.bbwp_type()).ok()?.as_bbwp()?;
+        let atr = candle.clone_indicator(&self.atr_type()).ok()?.as_atr()?;
+        let fast = self.ema(candle, &self.fast_type())?;
+        let slow = self.ema(candle, &self.slow_type())?;
+
+        if bbwp.value >= self.high_volatility {
+            return Some(MarketRegime::HighVolatility);
+        }
+
+        if atr.value <= 0.0 {
+            return Some(MarketRegime::Ranging);
+        }
+
+        let strength = (fast - slow) / atr.value;
+        let regime = if strength >= self.trend_strength {
+            MarketRegime::TrendingUp
+        } else if strength <= -self.trend_strength {
+            MarketRegime::TrendingDown
+        } else {
+            MarketRegime::Ranging
+        };
+
+        Some(regime)
+    }
+}
+
+impl HasMinLength for RegimeClassifier {
+    fn min_length(&self) -> usize {
+        self.required_indicators()
+            .iter()
+            .map(|i| i.min_length())
+            .max()
+            .unwrap_or(1)
+    }
+}
+
+impl RequiresIndicators for RegimeClassifier {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        vec![
+            self.fast_type(),
+            self.slow_type(),
+            self.atr_type(),
+            self.bbwp_type(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            atr::ATR, bbwp::BBWP, ema::EMA, indicator::Indicator, indicator_type::IndicatorType,
+        },
+        models::{
+            candle::Candle, candle_buffer::CandleBuffer, ma_type::MAType,
+            market_regime::MarketRegime, regime_classifier::RegimeClassifier,
+            traits::requires_indicators::RequiresIndicators,
+        },
+        utils::data::candle_strategy,
+    };
+    use std::collections::HashSet;
+
+    fn classify(classifier: &RegimeClassifier, candle: Candle) -> Option<MarketRegime> {
+        let buffer = CandleBuffer::from(&[candle][..]);
+        classifier.classify(buffer.view().last()?)
+    }
+
+    fn candle(fast: f64, slow: f64, atr: f64, bbwp: f64) -> Candle {
+        let mut candle = Candle::dummy_from_val(100.0);
+        let indicators = [
+            (
+                IndicatorType::EMA(20),
+                Indicator::EMA(Some(EMA {
+                    value: fast,
+                    len: 20,
+                })),
+            ),
+            (
+                IndicatorType::EMA(50),
+                Indicator::EMA(Some(EMA {
+                    value: slow,
+                    len: 50,
+                })),
+            ),
+            (
+                IndicatorType::ATR(14),
+                Indicator::ATR(Some(ATR {
+                    value: atr,
+                    len: 14,
+                })),
+            ),
+            (
+                IndicatorType::BBWP(13, 252, 5, MAType::SMA),
+                Indicator::BBWP(Some(BBWP {
+                    value: bbwp,
+                    len: 13,
+                    lookback: 252,
+                    sma: None,
+                })),
+            ),
+        ];
+        candle.indicators.extend(indicators);
+
+        candle
+    }
+
+    #[test]
+    fn classifies_candles() {
+        let classifier = RegimeClassifier::new();
+
+        let trending_up = candle(105.0, 100.0, 2.0, 0.5);
+        assert_eq!(
+            classify(&classifier, trending_up),
+            Some(MarketRegime::TrendingUp)
+        );
+
+        let trending_down = candle(95.0, 100.0, 2.0, 0.5);
+        assert_eq!(
+            classify(&classifier, trending_down),
+            Some(MarketRegime::TrendingDown)
+        );
+
+        let ranging = candle(101.0, 100.0, 2.0, 0.5);
+        assert_eq!(classify(&classifier, ranging), Some(MarketRegime::Ranging));
+
+        let volatile = candle(105.0, 100.0, 2.0, 0.95);
+        assert_eq!(
+            classify(&classifier, volatile),
+            Some(MarketRegime::HighVolatility)
+        );
+
+        assert_eq!(classify(&classifier, Candle::dummy_from_val(100.0)), None);
+        assert_eq!(
+            "high_volatility".parse::<MarketRegime>().unwrap(),
+            MarketRegime::HighVolatility
+        );
+        assert!("sideways".parse::<MarketRegime>().is_err());
+    }
+
+    #[test]
+    fn classifies_fixture_data() {
+        let classifier = RegimeClassifier::new();
+        let buffer = candle_strategy::indicator_candles(&classifier.required_indicators());
+
+        let regimes: HashSet<MarketRegime> = buffer
+            .view()
+            .iter()
+            .filter_map(|c| classifier.classify(c))
+            .collect();
+        assert!(regimes.len() > 1);
+    }
+}
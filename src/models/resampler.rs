@@ -0,0 +1,238 @@
+use crate::models::{
+    candle::Candle, interval::Interval, timeseries::TimeSeries,
+    timeseries_builder::TimeSeriesBuilder,
+};
+use anyhow::{anyhow, Result};
+
+/// # Resampler
+///
+/// Builds candles of an Interval from candles of a finer Interval that evenly
+/// divides it, aggregating the open, high, low, close and volume. Candles are
+/// grouped into buckets aligned by `Interval::bucket_start` and a resampled
+/// candle is returned once its bucket has closed, either by its last candle
+/// or by a candle of a later bucket after a gap. A bucket the candles start in
+/// the middle of is skipped since it would be incomplete.
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    from: Interval,
+    to: Interval,
+    /// Candle of the target interval currently being built.
+    pending: Option<Candle>,
+}
+
+impl Resampler {
+    pub fn new(from: &Interval, to: &Interval) -> Result<Self> {
+        if !Self::supports(from, to) {
+            return Err(anyhow!(
+                "Unable to build {} candles from {} candles.",
+                to,
+                from
+            ));
+        }
+
+        Ok(Self {
+            from: from.clone(),
+            to: to.clone(),
+            pending: None,
+        })
+    }
+
+    /// Returns whether candles of the `to` interval can be built from candles
+    /// of the `from` interval.
+    pub fn supports(from: &Interval, to: &Interval) -> bool {
+        match (from.minutes(), to.minutes()) {
+            (Some(from), Some(to)) => to >= from && to % from == 0,
+            // Months can be built from any candles that evenly divide a day
+            (Some(from), None) => (24 * 60) % from == 0,
+            (None, None) => true,
+            (None, Some(_)) => false,
+        }
+    }
+
+    pub fn from(&self) -> &Interval {
+        &self.from
+    }
+
+    /// Adds a candle of the finer interval, returning the resampled candle
+    /// if one was closed by it.
+    pub fn push(&mut self, candle: &Candle) -> Option<Candle> {
+        let start = self.to.bucket_start(candle.timestamp);
+
+        let cut_short = self
+            .pending
+            .as_ref()
+            .is_some_and(|pending| pending.timestamp != start);
+        let mut closed = if cut_short { self.pending.take() } else { None };
+
+        match self.pending.as_mut() {
+            Some(pending) => {
+                pending.high = pending.high.max(candle.high);
+                pending.low = pending.low.min(candle.low);
+                pending.close = candle.close;
+                pending.volume += candle.volume;
+            }
+            None if candle.timestamp == start => {
+                self.pending = Some(Candle::new(
+                    start,
+                    candle.open,
+                    candle.close,
+                    candle.high,
+                    candle.low,
+                    candle.volume,
+                ));
+            }
+            // Partial bucket
+            None => return closed,
+        }
+
+        let close_time = self.from.next_start(candle.timestamp);
+        if close_time >= self.to.next_start(start) {
+            closed = self.pending.take();
+        }
+
+        closed
+    }
+
+    /// Adds the candles in order and returns the resampled candles closed by
+    /// them.
+    pub fn resample(&mut self, candles: &[Candle]) -> Vec<Candle> {
+        candles
+            .iter()
+            .filter_map(|candle| self.push(candle))
+            .collect()
+    }
+
+    /// Discards the candle currently being built.
+    pub fn reset(&mut self) {
+        self.pending = None;
+    }
+
+    /// Builds a TimeSeries of the given interval from the closed candles of
+    /// a series of a finer interval. Indicators are not carried over.
+    pub fn resample_timeseries(ts: &TimeSeries, interval: &Interval) -> Result<TimeSeries> {
+        let mut resampler = Self::new(&ts.interval, interval)?;
+        let candles = resampler.resample(&ts.candles);
+
+        Ok(TimeSeriesBuilder::new()
+            .symbol(ts.symbol.clone())
+            .interval(interval.clone())
+            .net(ts.net)
+            .candles(candles)
+            .build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{
+        candle::Candle, interval::Interval, resampler::Resampler,
+        timeseries_builder::TimeSeriesBuilder,
+    };
+    use chrono::{DateTime, Datelike, Duration, TimeZone, Utc, Weekday};
+
+    fn candles(start: DateTime<Utc>, step: Duration, closes: &[f64]) -> Vec<Candle> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| {
+                let timestamp = start + step * i as i32;
+                Candle::new(
+                    timestamp,
+                    close - 1.0,
+                    *close,
+                    close + 2.0,
+                    close - 2.0,
+                    10.0,
+                )
+            })
+            .collect()
+    }
+
+    fn hourly_candles(closes: &[f64]) -> Vec<Candle> {
+        let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        candles(start, Duration::hours(1), closes)
+    }
+
+    #[test]
+    fn aggregates_closed_candles() {
+        let closes: Vec<f64> = (1..=12).map(|i| i as f64).collect();
+        let candles = hourly_candles(&closes);
+        let mut resampler = Resampler::new(&Interval::Hour1, &Interval::Hour4).unwrap();
+
+        // The last two hours are still being built
+        let resampled = resampler.resample(&candles[..10]);
+        assert_eq!(resampled.len(), 2);
+        assert_eq!(resampled[0].timestamp, candles[0].timestamp);
+        assert_eq!(resampled[0].open, 0.0);
+        assert_eq!(resampled[0].close, 4.0);
+        assert_eq!(resampled[0].high, 6.0);
+        assert_eq!(resampled[0].low, -1.0);
+        assert_eq!(resampled[0].volume, 40.0);
+        assert_eq!(resampled[1].timestamp, candles[4].timestamp);
+        assert_eq!(resampled[1].close, 8.0);
+
+        assert!(resampler.push(&candles[10]).is_none());
+
+        let closed = resampler.push(&candles[11]).unwrap();
+        assert_eq!(closed.timestamp, candles[8].timestamp);
+        assert_eq!(closed.open, 8.0);
+        assert_eq!(closed.close, 12.0);
+        assert_eq!(closed.volume, 40.0);
+    }
+
+    #[test]
+    fn skips_partial_and_closes_on_gap() {
+        let mut candles = hourly_candles(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0]);
+        candles.remove(7);
+
+        let mut resampler = Resampler::new(&Interval::Hour1, &Interval::Hour4).unwrap();
+
+        // The first candle starts in the middle of a 4 hour candle, and the
+        // second one is closed by the gap
+        let resampled = resampler.resample(&candles[1..]);
+        assert_eq!(resampled.len(), 1);
+        assert_eq!(resampled[0].timestamp, candles[4].timestamp);
+        assert_eq!(resampled[0].close, 7.0);
+        assert_eq!(resampled[0].volume, 30.0);
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        // Sunday the 1st of January 2023
+        let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let closes: Vec<f64> = (1..=16).map(|i| i as f64).collect();
+        let candles = candles(start, Duration::days(1), &closes);
+
+        let resampled = Resampler::new(&Interval::Day1, &Interval::Week1)
+            .unwrap()
+            .resample(&candles);
+
+        assert_eq!(resampled.len(), 2);
+        assert_eq!(resampled[0].timestamp.weekday(), Weekday::Mon);
+        assert_eq!(resampled[0].timestamp, candles[1].timestamp);
+        assert_eq!(resampled[0].close, 8.0);
+        assert_eq!(resampled[1].close, 15.0);
+    }
+
+    #[test]
+    fn resamples_timeseries() {
+        let ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Hour1)
+            .candles(hourly_candles(&[1.0; 24]))
+            .build();
+
+        let resampled = Resampler::resample_timeseries(&ts, &Interval::Hour12).unwrap();
+        assert_eq!(resampled.interval, Interval::Hour12);
+        assert_eq!(resampled.candles.len(), 2);
+        assert_eq!(resampled.candles[1].volume, 120.0);
+
+        assert!(Resampler::resample_timeseries(&ts, &Interval::Minute30).is_err());
+        assert!(Resampler::new(&Interval::Day5, &Interval::Week1).is_err());
+        assert!(Resampler::supports(&Interval::Hour4, &Interval::Month1));
+        assert!(!Resampler::supports(
+            &Interval::Minutes(420),
+            &Interval::Month1
+        ));
+    }
+}
@@ -0,0 +1,217 @@
+use std::ops::{Index, Range};
+
+/// # RingBuffer
+///
+/// Fixed capacity buffer where pushing to a full buffer overwrites the oldest
+/// value, so adding values never shifts the stored ones. Index 0 is always
+/// the oldest value.
+#[derive(Debug, Clone)]
+pub struct RingBuffer<T> {
+    values: Vec<T>,
+    capacity: usize,
+    start: usize,
+}
+
+#[allow(dead_code)]
+impl<T> RingBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            values: Vec::with_capacity(capacity),
+            capacity,
+            start: 0,
+        }
+    }
+
+    /// Adds a value, returning the oldest value if it was evicted.
+    pub fn push(&mut self, value: T) -> Option<T> {
+        if self.capacity == 0 {
+            return Some(value);
+        }
+
+        if self.values.len() < self.capacity {
+            self.values.push(value);
+            return None;
+        }
+
+        let evicted = std::mem::replace(&mut self.values[self.start], value);
+        self.start = (self.start + 1) % self.capacity;
+
+        Some(evicted)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.start = 0;
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        (i < self.len()).then(|| &self.values[self.physical(i)])
+    }
+
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        if i >= self.len() {
+            return None;
+        }
+
+        let i = self.physical(i);
+        Some(&mut self.values[i])
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.len().checked_sub(1).and_then(|i| self.get(i))
+    }
+
+    /// Returns the values as two slices, oldest first.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (newest, oldest) = self.values.split_at(self.start);
+        (oldest, newest)
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        let (first, second) = self.as_slices();
+        first.iter().chain(second.iter())
+    }
+
+    /// Borrowed view of the values in the given range without copying.
+    pub fn view(&self, range: Range<usize>) -> RingView<'_, T> {
+        let end = range.end.min(self.len());
+        let start = range.start.min(end);
+        let (first, second) = self.as_slices();
+
+        if end <= first.len() {
+            RingView::new(&first[start..end], &[])
+        } else if start >= first.len() {
+            RingView::new(&second[start - first.len()..end - first.len()], &[])
+        } else {
+            RingView::new(&first[start..], &second[..end - first.len()])
+        }
+    }
+
+    fn physical(&self, i: usize) -> usize {
+        if self.values.len() < self.capacity {
+            i
+        } else {
+            (self.start + i) % self.capacity
+        }
+    }
+}
+
+/// # RingView
+///
+/// Borrowed window of a RingBuffer. Since the buffer wraps around the window
+/// may consist of two contiguous slices.
+#[derive(Debug)]
+pub struct RingView<'a, T> {
+    first: &'a [T],
+    second: &'a [T],
+}
+
+impl<T> Clone for RingView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for RingView<'_, T> {}
+
+impl<T> Index<usize> for RingView<'_, T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        self.get(i).expect("Index out of bounds of RingView.")
+    }
+}
+
+#[allow(dead_code)]
+impl<'a, T> RingView<'a, T> {
+    pub fn new(first: &'a [T], second: &'a [T]) -> Self {
+        Self { first, second }
+    }
+
+    pub fn len(&self) -> usize {
+        self.first.len() + self.second.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, i: usize) -> Option<&'a T> {
+        if i < self.first.len() {
+            self.first.get(i)
+        } else {
+            self.second.get(i - self.first.len())
+        }
+    }
+
+    pub fn last(&self) -> Option<&'a T> {
+        self.second.last().or(self.first.last())
+    }
+
+    pub fn as_slices(&self) -> (&'a [T], &'a [T]) {
+        (self.first, self.second)
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &'a T> {
+        self.first.iter().chain(self.second.iter())
+    }
+
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::ring_buffer::RingBuffer;
+
+    #[test]
+    fn push_overwrites_oldest() {
+        let mut buffer = RingBuffer::new(3);
+
+        assert_eq!(buffer.push(1), None);
+        assert_eq!(buffer.push(2), None);
+        assert_eq!(buffer.push(3), None);
+        assert_eq!(buffer.push(4), Some(1));
+        assert_eq!(buffer.push(5), Some(2));
+
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.iter().cloned().collect::<Vec<_>>(), vec![3, 4, 5]);
+        assert_eq!(buffer.get(0), Some(&3));
+        assert_eq!(buffer.last(), Some(&5));
+        assert_eq!(buffer.get(3), None);
+    }
+
+    #[test]
+    fn views_across_wrap() {
+        let mut buffer = RingBuffer::new(5);
+
+        for i in 0..8 {
+            buffer.push(i);
+        }
+
+        assert_eq!(buffer.view(0..5).to_vec(), vec![3, 4, 5, 6, 7]);
+        assert_eq!(buffer.view(1..4).to_vec(), vec![4, 5, 6]);
+        assert_eq!(buffer.view(3..5).to_vec(), vec![6, 7]);
+        assert_eq!(buffer.view(0..2).to_vec(), vec![3, 4]);
+        assert_eq!(buffer.view(2..10).to_vec(), vec![5, 6, 7]);
+        assert_eq!(buffer.view(1..4).get(2), Some(&6));
+        assert_eq!(buffer.view(1..4).last(), Some(&6));
+        assert!(buffer.view(4..4).is_empty());
+    }
+}
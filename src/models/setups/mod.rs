@@ -3,3 +3,4 @@ pub mod setup;
 pub mod setup_builder;
 pub mod setup_finder;
 pub mod setup_finder_builder;
+pub mod strategy_state_store;
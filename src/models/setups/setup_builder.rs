@@ -1,5 +1,5 @@
 use crate::models::{
-    candle::Candle, interval::Interval, setups::setup::Setup,
+    candle::Candle, candle_buffer::CandleRow, interval::Interval, setups::setup::Setup,
     strategy_orientation::StrategyOrientation,
 };
 use anyhow::{anyhow, Result};
@@ -22,8 +22,8 @@ impl SetupBuilder {
         }
     }
 
-    pub fn candle(mut self, candle: &Candle) -> Self {
-        self.candle = Some(candle.clone());
+    pub fn candle(mut self, candle: CandleRow) -> Self {
+        self.candle = Some(candle.to_candle());
         self
     }
 
@@ -1,21 +1,53 @@
 use crate::{
     data_sources::datasource::DataSource,
+    metrics::bot_metrics::BotMetrics,
     models::{
         message_payloads::{
-            candle_added_payload::CandleAddedPayload, ping_payload::PingPayload,
+            candle_added_payload::CandleAddedPayload,
+            latest_candles_payload::LatestCandleResponse,
+            pause_payload::PausePayload,
+            ping_payload::PingPayload,
             request_latest_candles_payload::RequestLatestCandlesPayload,
-            triggered_payload::TriggeredPayload, ts_subscribe_payload::TSSubscribePayload,
+            request_setup_finder_state_payload::{
+                RequestSetupFinderStatePayload, SetupFinderState,
+            },
+            trade_spawned_payload::TradeSpawnedPayload,
+            triggered_payload::TriggeredPayload,
+            ts_subscribe_payload::TSSubscribePayload,
         },
+        setups::{setup_builder::SetupBuilder, strategy_state_store::StrategyStateStore},
         timeseries::TimeSeries,
         trade::Trade,
         trade_builder::TradeBuilder,
         traits::trading_strategy::TradingStrategy,
     },
-    notifications::notification_center::NotificationCenter,
+    notifications::{
+        notification_center::NotificationCenter, notification_event::NotificationEvent,
+    },
+};
+use actix::{
+    fut::wrap_future, Actor, ActorFutureExt, Addr, AsyncContext, Context, Handler, MessageResult,
 };
-use actix::{fut::wrap_future, Actor, Addr, AsyncContext, Context, Handler};
-use anyhow::Result;
+use anyhow::{Context as _, Result};
+use serde_json::Value;
 use tokio::try_join;
+use tracing::{error, info, info_span, warn, Instrument, Span};
+
+// Maximum number of Trades a SetupFinder may have open at the same time
+const MAX_OPEN_TRADES: usize = 1;
+
+// Number of closes included in the sparkline of setup notifications
+const SPARKLINE_LEN: usize = 30;
+
+/// Everything needed to act on a setup once the SetupFinder has moved on.
+struct SetupContext {
+    self_addr: Addr<SetupFinder>,
+    ts: Addr<TimeSeries>,
+    source: DataSource,
+    notifications_enabled: bool,
+    live_trading_enabled: bool,
+    n_open_trades: usize,
+}
 
 #[derive(Debug)]
 pub struct SetupFinder {
@@ -26,7 +58,9 @@ pub struct SetupFinder {
     live_trading_enabled: bool,
     only_trigger_once: bool,
     triggered: bool,
+    paused: bool,
     spawned_trade_addrs: Vec<Addr<Trade>>,
+    state_store: Option<StrategyStateStore>,
 }
 
 impl Actor for SetupFinder {
@@ -41,20 +75,28 @@ impl Handler<CandleAddedPayload> for SetupFinder {
             return ();
         }
 
+        if self.paused {
+            return;
+        }
+
+        let needed_candles = self.strategy.candles_needed_for_setup();
         let payload = RequestLatestCandlesPayload {
-            n: self.strategy.candles_needed_for_setup(),
+            n: needed_candles.max(SPARKLINE_LEN),
         };
 
-        let self_addr = ctx.address();
         let ts = self.ts_addr.clone();
         let mut strategy = self.strategy.clone_box();
-        let notifications_enabled = self.notifications_enabled;
-        let live_trading_enabled = self.live_trading_enabled;
-        let mut spawned_trades = self.spawned_trade_addrs.clone();
-        let source = self.source.clone();
 
         // Clear trades before potentially starting new one
         self.clear_closed_trades();
+        let setup_context = SetupContext {
+            self_addr: ctx.address(),
+            ts: ts.clone(),
+            source: self.source.clone(),
+            notifications_enabled: self.notifications_enabled,
+            live_trading_enabled: self.live_trading_enabled,
+            n_open_trades: self.spawned_trade_addrs.len(),
+        };
 
         let fut = async move {
             let candle_response = ts
@@ -63,85 +105,26 @@ impl Handler<CandleAddedPayload> for SetupFinder {
                 .unwrap_or_else(|e| panic!("Failed to send payload: {:#?}", e))
                 .unwrap_or_else(|e| panic!("Failed to unwrap LatestCandleResponse: {:#?}", e));
 
-            let sb = strategy.check_last_for_setup(&candle_response.candles);
-
-            if sb.is_none() {
-                return;
-            }
+            let sb = strategy
+                .check_last_for_setup(candle_response.candles.view().latest(needed_candles));
+            let state = strategy.state();
 
-            let sb = sb.unwrap();
-            let resolution_strategy = strategy.default_resolution_strategy();
-            let setup = sb
-                .symbol(&candle_response.symbol)
-                .interval(&candle_response.interval)
-                .build();
-
-            let setup = match setup {
-                Ok(setup) => setup,
-                Err(e) => {
-                    println!("Error: {:#?}", e);
-                    return;
-                }
-            };
-
-            self_addr.do_send(TriggeredPayload);
+            (sb, strategy, candle_response, state)
+        };
 
-            println!("Setup found: {:#?}", setup);
+        // Carry the state of the strategy over to the next candle before
+        // handling any other messages, while the setup is acted on in the
+        // background so trading and notifications don't block the mailbox
+        let actor_fut = wrap_future::<_, Self>(fut.instrument(self.span())).map(
+            |(sb, strategy, candle_response, state), actor, ctx| {
+                actor.update_strategy_state(state);
 
-            if live_trading_enabled {
-                if spawned_trades.len() > 0 {
-                    return;
+                if let Some(sb) = sb {
+                    let on_setup = Self::on_setup(sb, strategy, candle_response, setup_context);
+                    ctx.spawn(wrap_future(on_setup.instrument(actor.span())));
                 }
-
-                let wallet_fut = source.get_wallet();
-                let last_price_fut = source.get_symbol_price(&setup.symbol);
-
-                let (wallet, last_price) = try_join!(wallet_fut, last_price_fut)
-                    .expect("Unable to fetch data when creating Trade.");
-
-                // TODO: Implement system to enable variantions on position size
-                // Quantity is half of available balance
-                let dollar_value = wallet.total_available_balance / 2.0;
-                let quantity = dollar_value / last_price;
-
-                let trade = TradeBuilder::new()
-                    .setup(setup.clone())
-                    .quantity(quantity)
-                    .dollar_value(dollar_value)
-                    .source(source)
-                    .notifications_enabled(notifications_enabled)
-                    .trading_enabled(true)
-                    .resolution_strategy(resolution_strategy)
-                    .orientation(strategy.orientation())
-                    .timeseries_addr(ts.clone())
-                    .build()
-                    .expect("Unable to build Trade in SetupFinder");
-
-                let trade_addr = trade.start();
-
-                // Subscribe Trade to TimeSeries so it receives updates when
-                // candles are added
-                let ts_subscribe_payload = TSSubscribePayload {
-                    observer: trade_addr.clone().recipient(),
-                };
-
-                ts.do_send(ts_subscribe_payload);
-
-                spawned_trades.push(trade_addr)
-            }
-
-            if notifications_enabled {
-                match NotificationCenter::notify(&setup, &strategy).await {
-                    Ok(_) => (),
-                    Err(e) => {
-                        println!("Error when notifying: {:#?}", e);
-                        return;
-                    }
-                };
-            }
-        };
-
-        let actor_fut = wrap_future::<_, Self>(fut);
+            },
+        );
         ctx.wait(actor_fut);
     }
 }
@@ -154,6 +137,45 @@ impl Handler<TriggeredPayload> for SetupFinder {
     }
 }
 
+impl Handler<PausePayload> for SetupFinder {
+    type Result = ();
+
+    fn handle(&mut self, msg: PausePayload, _ctx: &mut Self::Context) -> Self::Result {
+        let _span = self.span().entered();
+        info!(paused = msg.paused, "Updating paused state");
+
+        self.paused = msg.paused;
+    }
+}
+
+impl Handler<TradeSpawnedPayload> for SetupFinder {
+    type Result = ();
+
+    fn handle(&mut self, msg: TradeSpawnedPayload, _ctx: &mut Self::Context) -> Self::Result {
+        self.spawned_trade_addrs.push(msg.trade);
+    }
+}
+
+impl Handler<RequestSetupFinderStatePayload> for SetupFinder {
+    type Result = MessageResult<RequestSetupFinderStatePayload>;
+
+    fn handle(
+        &mut self,
+        _msg: RequestSetupFinderStatePayload,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        self.clear_closed_trades();
+
+        MessageResult(SetupFinderState {
+            strategy: self.strategy.to_string(),
+            interval: self.strategy.interval(),
+            paused: self.paused,
+            live_trading_enabled: self.live_trading_enabled,
+            trades: self.spawned_trade_addrs.clone(),
+        })
+    }
+}
+
 impl SetupFinder {
     pub fn new(
         strategy: Box<dyn TradingStrategy>,
@@ -173,9 +195,171 @@ impl SetupFinder {
             spawned_trade_addrs: spawned_trade_addrs.to_vec(),
             source,
             triggered: false,
+            paused: false,
+            state_store: None,
         })
     }
 
+    /// Restores the state of the strategy from the store, if any state has
+    /// been stored, and keeps the store updated from then on.
+    pub fn persist_state(&mut self, store: StrategyStateStore) -> Result<()> {
+        if let Some(state) = store.load()? {
+            self.strategy
+                .restore_state(state)
+                .context("Unable to restore stored strategy state")?;
+        }
+
+        self.state_store = Some(store);
+
+        Ok(())
+    }
+
+    /// Notifies about the setup and spawns a Trade for it if live trading
+    /// is enabled.
+    async fn on_setup(
+        sb: SetupBuilder,
+        strategy: Box<dyn TradingStrategy>,
+        candle_response: LatestCandleResponse,
+        context: SetupContext,
+    ) {
+        let SetupContext {
+            self_addr,
+            ts,
+            source,
+            notifications_enabled,
+            live_trading_enabled,
+            n_open_trades,
+        } = context;
+
+        let resolution_strategy = strategy.default_resolution_strategy();
+        let setup = sb
+            .symbol(&candle_response.symbol)
+            .interval(&candle_response.interval)
+            .build();
+
+        let setup = match setup {
+            Ok(setup) => setup,
+            Err(e) => {
+                error!(error = ?e, "Unable to build setup");
+                return;
+            }
+        };
+
+        self_addr.do_send(TriggeredPayload);
+        BotMetrics::get()
+            .setups_found
+            .with_label_values(&[&strategy.to_string()])
+            .inc();
+
+        info!(
+            symbol = %setup.symbol,
+            timestamp = %setup.candle.timestamp,
+            orientation = ?setup.orientation,
+            "Setup found"
+        );
+
+        if live_trading_enabled {
+            if n_open_trades >= MAX_OPEN_TRADES {
+                warn!(
+                    max_open_trades = MAX_OPEN_TRADES,
+                    "Maximum number of open trades reached, skipping setup"
+                );
+
+                if notifications_enabled {
+                    let event = NotificationEvent::RiskLimitHit {
+                        strategy: strategy.to_string(),
+                        limit: format!(
+                            "Maximum number of open trades ({}) reached, skipping setup at {}.",
+                            MAX_OPEN_TRADES, setup.candle.timestamp
+                        ),
+                    };
+
+                    if let Err(e) = NotificationCenter::notify(&event).await {
+                        error!(error = ?e, "Unable to send notification");
+                    }
+                }
+
+                return;
+            }
+
+            let wallet_fut = source.get_wallet();
+            let last_price_fut = source.get_symbol_price(&setup.symbol);
+
+            let (wallet, last_price) = try_join!(wallet_fut, last_price_fut)
+                .expect("Unable to fetch data when creating Trade.");
+
+            // TODO: Implement system to enable variantions on position size
+            // Quantity is half of available balance
+            let dollar_value = wallet.total_available_balance / 2.0;
+            let quantity = dollar_value / last_price;
+
+            let trade = TradeBuilder::new()
+                .setup(setup.clone())
+                .quantity(quantity)
+                .dollar_value(dollar_value)
+                .source(source)
+                .notifications_enabled(notifications_enabled)
+                .trading_enabled(true)
+                .resolution_strategy(resolution_strategy)
+                .orientation(strategy.orientation())
+                .timeseries_addr(ts.clone())
+                .build()
+                .expect("Unable to build Trade in SetupFinder");
+
+            let trade_addr = trade.start();
+
+            // Subscribe Trade to TimeSeries so it receives updates when
+            // candles are added
+            let ts_subscribe_payload = TSSubscribePayload {
+                observer: trade_addr.clone().recipient(),
+            };
+
+            ts.do_send(ts_subscribe_payload);
+
+            self_addr.do_send(TradeSpawnedPayload { trade: trade_addr });
+        }
+
+        if notifications_enabled {
+            let event = NotificationEvent::SetupFound {
+                setup,
+                strategy: strategy.to_string(),
+                recent_closes: candle_response.candles.view().close().to_vec(),
+            };
+
+            if let Err(e) = NotificationCenter::notify(&event).await {
+                error!(error = ?e, "Unable to send notification");
+            }
+        }
+    }
+
+    /// Keeps the state of the strategy after it has been checked against the
+    /// latest candle, storing it if persistence is enabled.
+    fn update_strategy_state(&mut self, state: Option<Value>) {
+        let Some(state) = state else {
+            return;
+        };
+
+        let _span = self.span().entered();
+
+        if let Some(store) = &self.state_store {
+            if let Err(e) = store.save(&state) {
+                error!(error = ?e, "Unable to store strategy state");
+            }
+        }
+
+        if let Err(e) = self.strategy.restore_state(state) {
+            error!(error = ?e, "Unable to update strategy state");
+        }
+    }
+
+    fn span(&self) -> Span {
+        info_span!(
+            "SetupFinder",
+            strategy = %self.strategy,
+            interval = %self.strategy.interval()
+        )
+    }
+
     fn clear_closed_trades(&mut self) {
         let mut trade_addrs = vec![];
 
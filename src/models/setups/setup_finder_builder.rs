@@ -1,7 +1,9 @@
 use crate::{
     data_sources::datasource::DataSource,
     models::{
-        setups::setup_finder::SetupFinder, timeseries::TimeSeries, trade::Trade,
+        setups::{setup_finder::SetupFinder, strategy_state_store::StrategyStateStore},
+        timeseries::TimeSeries,
+        trade::Trade,
         traits::trading_strategy::TradingStrategy,
     },
 };
@@ -16,6 +18,7 @@ pub struct SetupFinderBuilder {
     live_trading_enabled: bool,
     only_trigger_once: bool,
     spawned_trades: Vec<Addr<Trade>>,
+    state_store: Option<StrategyStateStore>,
 }
 
 impl SetupFinderBuilder {
@@ -28,6 +31,7 @@ impl SetupFinderBuilder {
             live_trading_enabled: false,
             only_trigger_once: false,
             spawned_trades: vec![],
+            state_store: None,
         }
     }
 
@@ -67,6 +71,12 @@ impl SetupFinderBuilder {
         self
     }
 
+    /// Persists the state of the strategy so that it survives restarts.
+    pub fn state_store(mut self, store: StrategyStateStore) -> Self {
+        self.state_store = Some(store);
+        self
+    }
+
     pub fn build(self) -> Result<SetupFinder> {
         let strategy = self
             .strategy
@@ -82,7 +92,7 @@ impl SetupFinderBuilder {
             .source
             .context("Source is required to build SetupFinder")?;
 
-        Ok(SetupFinder::new(
+        let mut setup_finder = SetupFinder::new(
             strategy,
             ts,
             notifications_enabled,
@@ -90,6 +100,12 @@ impl SetupFinderBuilder {
             only_trigger_once,
             &spawned_trades,
             source,
-        )?)
+        )?;
+
+        if let Some(store) = self.state_store {
+            setup_finder.persist_state(store)?;
+        }
+
+        Ok(setup_finder)
     }
 }
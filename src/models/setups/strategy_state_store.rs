@@ -0,0 +1,140 @@
+use crate::models::traits::trading_strategy::TradingStrategy;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// # StrategyStateStore
+///
+/// Persists the state of a TradingStrategy as JSON so that stateful
+/// strategies can pick up where they left off after a restart.
+#[derive(Debug, Clone)]
+pub struct StrategyStateStore {
+    path: PathBuf,
+}
+
+impl StrategyStateStore {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Store at `data/state/<symbol>/<interval>/<strategy>_<orientation>.json`
+    /// so that instances of a strategy running side by side don't share
+    /// their state.
+    pub fn for_strategy(symbol: &str, strategy: &dyn TradingStrategy) -> Self {
+        let name = format!("{}_{}", strategy, strategy.orientation())
+            .to_lowercase()
+            .replace(' ', "_");
+        Self::new(format!(
+            "data/state/{}/{}/{}.json",
+            symbol.to_lowercase(),
+            strategy.interval().code(),
+            name
+        ))
+    }
+
+    #[allow(dead_code)]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the stored state, or None if no state has been stored yet.
+    pub fn load(&self) -> Result<Option<Value>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let json = fs::read_to_string(&self.path).context(format!(
+            "Unable to read strategy state {}",
+            self.path.display()
+        ))?;
+        let state = serde_json::from_str(&json)
+            .context(format!("Invalid strategy state {}", self.path.display()))?;
+
+        Ok(Some(state))
+    }
+
+    /// Stores the state, replacing the previous state in a single rename so
+    /// that a crash while writing can't leave a partial file behind.
+    pub fn save(&self, state: &Value) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(state)?)?;
+        fs::rename(&tmp, &self.path).context(format!(
+            "Unable to write strategy state {}",
+            self.path.display()
+        ))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{
+            setups::strategy_state_store::StrategyStateStore,
+            strategy_orientation::StrategyOrientation, traits::trading_strategy::TradingStrategy,
+        },
+        trading_strategies::public::rsi_basic::RsiBasic,
+    };
+    use serde_json::json;
+    use std::{fs, path::Path};
+
+    #[test]
+    fn saves_and_loads_state() {
+        let dir = std::env::temp_dir().join(format!("strategy-state-{}", std::process::id()));
+        let store = StrategyStateStore::new(dir.join("nested").join("strategy.json"));
+
+        assert!(store.load().unwrap().is_none());
+
+        let state = json!({ "armed_at": 3, "levels": [1.5, 2.0] });
+        store.save(&state).unwrap();
+        assert_eq!(store.load().unwrap(), Some(state));
+
+        store.save(&json!(null)).unwrap();
+        assert_eq!(store.load().unwrap(), Some(json!(null)));
+
+        fs::write(dir.join("nested").join("strategy.json"), "{ armed").unwrap();
+        assert!(store.load().is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keys_path_on_symbol_interval_and_orientation() {
+        let long = RsiBasic::new_args(14, 60.0, 40.0, StrategyOrientation::Long);
+        let short = RsiBasic::new_args(14, 60.0, 40.0, StrategyOrientation::Short);
+        let interval = long.interval().code();
+
+        let store = StrategyStateStore::for_strategy("BTCUSDT", &long);
+        let name = format!(
+            "{}_long.json",
+            long.to_string().to_lowercase().replace(' ', "_")
+        );
+        assert_eq!(
+            store.path(),
+            Path::new("data/state/btcusdt").join(&interval).join(name)
+        );
+
+        let paths = [
+            store.path().to_path_buf(),
+            StrategyStateStore::for_strategy("BTCUSDT", &short)
+                .path()
+                .to_path_buf(),
+            StrategyStateStore::for_strategy("ETHUSDT", &long)
+                .path()
+                .to_path_buf(),
+        ];
+        assert_ne!(paths[0], paths[1]);
+        assert_ne!(paths[0], paths[2]);
+        assert_ne!(paths[1], paths[2]);
+    }
+}
@@ -0,0 +1,69 @@
+use crate::models::{
+    candle::Candle,
+    candle_buffer::{CandleBuffer, CandleView},
+};
+use std::sync::Arc;
+
+/// # SharedCandles
+///
+/// Reference counted window over the candle buffer of a TimeSeries. Cloning
+/// it or sending it to several actors does not copy the candles, which are
+/// read through a `CandleView`.
+#[derive(Debug, Clone)]
+pub struct SharedCandles {
+    buffer: Arc<CandleBuffer>,
+    start: usize,
+}
+
+impl SharedCandles {
+    pub fn new(buffer: Arc<CandleBuffer>) -> Self {
+        Self { buffer, start: 0 }
+    }
+
+    /// Window over the last `n` candles of the snapshot.
+    pub fn latest(&self, n: usize) -> Self {
+        Self {
+            buffer: self.buffer.clone(),
+            start: self.buffer.len() - n.min(self.len()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.len() - self.start
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn view(&self) -> CandleView<'_> {
+        self.buffer.view().window(self.start..self.buffer.len())
+    }
+}
+
+impl From<Vec<Candle>> for SharedCandles {
+    fn from(candles: Vec<Candle>) -> Self {
+        Self::new(Arc::new(CandleBuffer::from(candles.as_slice())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{candle::Candle, shared_candles::SharedCandles};
+
+    #[test]
+    fn latest_shares_snapshot() {
+        let candles = Candle::dummy_data(10, "positive", 100.0);
+        let shared = SharedCandles::from(candles.clone());
+        let latest = shared.latest(3);
+
+        assert_eq!(shared.len(), 10);
+        assert_eq!(latest.len(), 3);
+        assert_eq!(
+            latest.view().first().unwrap().timestamp,
+            candles[7].timestamp
+        );
+        assert_eq!(latest.latest(20).len(), 3);
+    }
+}
@@ -1,35 +1,62 @@
 use crate::{
     data_sources::{bybit::rest::bybit_rest_api::BybitRestApi, datasource::DataSource, local},
-    indicators::{indicator_type::IndicatorType, populates_candles::PopulatesCandlesWithSelf},
+    indicators::{
+        indicator_state::IndicatorState, indicator_type::IndicatorType,
+        populates_candles::PopulatesCandlesWithSelf, timeframe_indicator::TimeframeIndicator,
+    },
+    metrics::bot_metrics::BotMetrics,
     models::{
         candle::Candle,
+        candle_buffer::CandleBuffer,
+        higher_timeframe::HigherTimeframe,
         interval::Interval,
         message_payloads::{
-            add_candles_payload::AddCandlesPayload, candle_added_payload::CandleAddedPayload,
+            add_candles_payload::AddCandlesPayload,
+            candle_added_payload::CandleAddedPayload,
             fill_historical_candles_payload::FillHistoricalCandlesPayload,
             latest_candles_payload::LatestCandleResponse,
             request_latest_candles_payload::RequestLatestCandlesPayload,
-            ts_subscribe_payload::TSSubscribePayload, websocket_payload::WebsocketPayload,
+            request_timeseries_state_payload::{RequestTimeSeriesStatePayload, TimeSeriesState},
+            ts_subscribe_payload::TSSubscribePayload,
+            websocket_payload::WebsocketPayload,
         },
         net_version::NetVersion,
+        resampler::Resampler,
+        shared_candles::SharedCandles,
         timeseries_builder::TimeSeriesBuilder,
+        timeseries_columns::TimeSeriesColumns,
     },
 };
 use actix::{
-    dev::ContextFutureSpawner, Actor, AsyncContext, Context as ActixContext, Handler, Recipient,
-    WrapFuture,
+    dev::ContextFutureSpawner, Actor, AsyncContext, Context as ActixContext, Handler,
+    MessageResult, Recipient, WrapFuture,
 };
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use indexmap::IndexSet;
+use std::{collections::HashMap, sync::Arc, time::Instant};
+use tracing::{debug, error, info_span, warn, Instrument, Span};
 
 #[derive(Debug, Clone)]
 pub struct TimeSeries {
     pub symbol: String,
     pub interval: Interval,
     pub max_length: usize,
+    /// Candles used to calculate indicators. Trimmed to `max_length` in
+    /// batches, so it may briefly hold more candles than that.
     pub candles: Vec<Candle>,
+    /// Columnar copy of the last `max_length` candles that strategies and
+    /// candle requests read from. Requests get a snapshot sharing the
+    /// buffer, which is only copied if a snapshot is still held when the
+    /// next candle is added.
+    pub buffer: Arc<CandleBuffer>,
     pub indicators: IndexSet<IndicatorType>,
+    /// Rolling indicator state along with the timestamp of the last candle
+    /// it includes.
+    pub indicator_states: HashMap<IndicatorType, (DateTime<Utc>, IndicatorState)>,
+    /// Higher interval candles built from the candles of the series, used
+    /// for `TimeframeIndicator`s.
+    pub timeframes: Vec<HigherTimeframe>,
     pub observers: Vec<Recipient<CandleAddedPayload>>,
     pub net: NetVersion,
     pub validate_candles_on_add: bool,
@@ -43,9 +70,12 @@ impl Handler<AddCandlesPayload> for TimeSeries {
     type Result = ();
 
     fn handle(&mut self, msg: AddCandlesPayload, _ctx: &mut ActixContext<Self>) -> Self::Result {
+        let _span = self.span().entered();
+
         match self.add_candles(&msg.candles) {
             Ok(_) => (),
-            Err(_e) => {
+            Err(e) => {
+                error!(error = ?e, "Unable to add candles");
                 // TODO: Reset/restart TimeSeries/SetupChaser, data possibly
                 // corrupted.
                 panic!("Unable to add candles to TimeSeries, data integrity threatened!");
@@ -58,14 +88,11 @@ impl Handler<WebsocketPayload> for TimeSeries {
     type Result = ();
 
     fn handle(&mut self, msg: WebsocketPayload, ctx: &mut ActixContext<Self>) -> Self::Result {
+        let _span = self.span().entered();
+
         if !msg.ok {
-            println!(
-                "Error: {}",
-                match msg.message {
-                    Some(message) => message,
-                    None => "Unknown error".to_string(),
-                }
-            );
+            let message = msg.message.unwrap_or("Unknown error".to_string());
+            error!(%message, "Received failed websocket payload");
 
             return;
         }
@@ -88,18 +115,19 @@ impl Handler<WebsocketPayload> for TimeSeries {
             };
             ctx.address().do_send(payload);
         } else {
-            println!("GAP at: {}", candle.timestamp);
+            warn!(timestamp = %candle.timestamp, "Gap detected, backfilling candles");
 
-            let interval_addition = self.interval.to_millis();
+            let last = self
+                .candles
+                .last()
+                .expect("Expected at least one candle")
+                .timestamp;
             let payload = FillHistoricalCandlesPayload {
-                from: self
-                    .candles
-                    .last()
-                    .expect("Expected at least one candle")
-                    .timestamp
-                    .timestamp_millis()
-                    + interval_addition,
-                to: candle.timestamp.timestamp_millis() + interval_addition,
+                from: self.interval.next_start(last).timestamp_millis(),
+                to: self
+                    .interval
+                    .next_start(candle.timestamp)
+                    .timestamp_millis(),
                 symbol: self.symbol.clone(),
                 interval: self.interval.clone(),
             };
@@ -127,17 +155,25 @@ impl Handler<FillHistoricalCandlesPayload> for TimeSeries {
         let net = self.net;
 
         let fut = async move {
-            let candles =
-                match BybitRestApi::get_kline_between(&symbol, &interval, &net, from, to).await {
-                    Ok(c) => c,
-                    _ => panic!("Unable to get candles in between."),
-                };
+            let candles = match Self::get_candles_between(&symbol, &interval, &net, from, to).await
+            {
+                Ok(c) => c,
+                _ => panic!("Unable to get candles in between."),
+            };
+
+            let labels = [symbol.as_str(), &interval.to_string()];
+            let metrics = BotMetrics::get();
+            metrics.backfills.with_label_values(&labels).inc();
+            metrics
+                .backfilled_candles
+                .with_label_values(&labels)
+                .inc_by(candles.len() as u64);
 
             let payload = AddCandlesPayload { candles };
             address.do_send(payload);
         };
 
-        fut.into_actor(self).spawn(ctx);
+        fut.instrument(self.span()).into_actor(self).spawn(ctx);
     }
 }
 
@@ -149,18 +185,13 @@ impl Handler<RequestLatestCandlesPayload> for TimeSeries {
         msg: RequestLatestCandlesPayload,
         _ctx: &mut ActixContext<Self>,
     ) -> Self::Result {
-        let candles = if self.candles.len() < msg.n {
-            // Return what's available
-            self.candles.clone()
-        } else {
-            // Return requested number of candles
-            self.candles[self.candles.len() - msg.n..].to_vec()
-        };
+        // Return what's available if fewer candles than requested
+        let snapshot = SharedCandles::new(self.buffer.clone());
 
         Ok(LatestCandleResponse {
             symbol: self.symbol.clone(),
             interval: self.interval.clone(),
-            candles,
+            candles: snapshot.latest(msg.n),
         })
     }
 }
@@ -174,10 +205,57 @@ impl Handler<TSSubscribePayload> for TimeSeries {
     }
 }
 
+impl Handler<RequestTimeSeriesStatePayload> for TimeSeries {
+    type Result = MessageResult<RequestTimeSeriesStatePayload>;
+
+    fn handle(
+        &mut self,
+        _msg: RequestTimeSeriesStatePayload,
+        _ctx: &mut ActixContext<Self>,
+    ) -> Self::Result {
+        let last = self.buffer.view().last();
+
+        MessageResult(TimeSeriesState {
+            symbol: self.symbol.clone(),
+            interval: self.interval.clone(),
+            n_candles: self.buffer.len(),
+            max_length: self.max_length,
+            last_timestamp: last.map(|c| c.timestamp),
+            last_close: last.map(|c| c.close),
+            indicators: self.indicators.iter().map(|i| format!("{:?}", i)).collect(),
+        })
+    }
+}
+
 impl TimeSeries {
+    fn span(&self) -> Span {
+        info_span!("TimeSeries", symbol = %self.symbol, interval = %self.interval)
+    }
+
     #[allow(dead_code)]
     pub fn set_max_length(&mut self, max_length: usize) {
         self.max_length = max_length;
+        self.trim_candles();
+        self.rebuild_buffer();
+    }
+
+    /// Fetches candles between the timestamps in milliseconds, resampled from
+    /// a finer interval if Bybit doesn't provide the interval.
+    async fn get_candles_between(
+        symbol: &str,
+        interval: &Interval,
+        net: &NetVersion,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<Candle>> {
+        let base = DataSource::Bybit.base_interval(interval)?;
+        let candles = BybitRestApi::get_kline_between(symbol, &base, net, from, to).await?;
+
+        if base == *interval {
+            return Ok(candles);
+        }
+
+        Ok(Resampler::new(&base, interval)?.resample(&candles))
     }
 
     fn validate_timeseries_integrity(&mut self, new_candle: DateTime<Utc>) -> bool {
@@ -187,13 +265,22 @@ impl TimeSeries {
         };
 
         let last_candle = &self.candles[self.candles.len() - 1];
-        let diff = new_candle.signed_duration_since(last_candle.timestamp);
+        let expected = self.interval.next_start(last_candle.timestamp);
+        let diff = new_candle.signed_duration_since(expected);
 
-        let step = self.interval.to_duration();
         let delta = self.interval.max_diff();
 
         // New is subsequent candle so timeseries integrity ok
-        return diff >= step - delta && diff < step + delta;
+        let integrity_ok = diff >= -delta && diff < delta;
+
+        if !integrity_ok {
+            BotMetrics::get()
+                .timeseries_gaps
+                .with_label_values(&[&self.symbol, &self.interval.to_string()])
+                .inc();
+        }
+
+        integrity_ok
     }
 
     fn add_candles(&mut self, candles: &[Candle]) -> Result<()> {
@@ -210,10 +297,30 @@ impl TimeSeries {
         let indicator_types = self.indicators.clone();
 
         for indicator_type in indicator_types {
+            let start = Instant::now();
             indicator_type.populate_last_candle(self)?;
+
+            BotMetrics::get()
+                .indicator_latency
+                .with_label_values(&[&format!("{:?}", indicator_type)])
+                .set(start.elapsed().as_secs_f64());
+        }
+
+        for timeframe in self.timeframes.iter_mut() {
+            timeframe.add_candle(candle)?;
+
+            if let Some(last) = self.candles.last_mut() {
+                timeframe.populate_last_candle(last);
+            }
         }
 
-        println!("Added candle: {:?}", self.candles.last());
+        debug!(
+            timestamp = %candle.timestamp,
+            close = candle.close,
+            "Added candle"
+        );
+
+        self.push_buffer_candle();
 
         // Notify observers
         let payload = CandleAddedPayload {
@@ -224,16 +331,61 @@ impl TimeSeries {
             observer.do_send(payload.clone());
         }
 
-        // Remove oldest candle if max length is exceeded
-        if self.candles.len() > self.max_length {
-            self.candles.remove(0);
-        }
+        self.trim_candles();
 
         Ok(())
     }
 
+    /// Removes the oldest candles once the max length has been exceeded by
+    /// a batch of candles, so that the remaining candles only have to be
+    /// moved once per batch instead of on every added candle.
+    fn trim_candles(&mut self) {
+        let batch = (self.max_length / 4).max(1);
+
+        if self.candles.len() >= self.max_length + batch {
+            let excess = self.candles.len() - self.max_length;
+            self.candles.drain(..excess);
+        }
+    }
+
+    /// Copies the last candle into the buffer once its indicators are
+    /// populated, along with earlier candles updated by lagging indicators.
+    fn push_buffer_candle(&mut self) {
+        let Some(last) = self.candles.last() else {
+            return;
+        };
+
+        let buffer = Arc::make_mut(&mut self.buffer);
+        buffer.push(last);
+
+        for indicator_type in self.indicators.iter() {
+            let lag = indicator_type.lag();
+
+            if lag > 0 {
+                let start = self.candles.len().saturating_sub(lag + 1);
+                buffer.set_indicator(*indicator_type, &self.candles[start..]);
+            }
+        }
+    }
+
+    fn rebuild_buffer(&mut self) {
+        self.buffer = Arc::new(CandleBuffer::from_candles(&self.candles, self.max_length));
+    }
+
+    /// Updates the buffer column of an indicator after it has been populated
+    /// for all candles.
+    pub fn sync_buffer_indicator(&mut self, indicator_type: IndicatorType) {
+        Arc::make_mut(&mut self.buffer).set_indicator(indicator_type, &self.candles);
+    }
+
     pub fn set_candles(&mut self, candles: &[Candle]) {
         self.candles = candles.to_vec();
+        self.indicator_states.clear();
+        self.rebuild_buffer();
+
+        for timeframe in self.timeframes.iter_mut() {
+            timeframe.set_candles(candles);
+        }
     }
 
     #[allow(dead_code)]
@@ -243,7 +395,13 @@ impl TimeSeries {
 
     #[allow(dead_code)]
     pub fn clear_candles(&mut self) {
-        self.candles.clear()
+        self.candles.clear();
+        self.indicator_states.clear();
+        self.rebuild_buffer();
+
+        for timeframe in self.timeframes.iter_mut() {
+            timeframe.clear_candles();
+        }
     }
 
     pub fn add_indicator(&mut self, indicator_type: IndicatorType) -> Result<()> {
@@ -258,6 +416,85 @@ impl TimeSeries {
         Ok(())
     }
 
+    /// Adds an indicator calculated on candles of a higher interval, which
+    /// are built from the candles of this series.
+    pub fn add_timeframe_indicator(&mut self, indicator: TimeframeIndicator) -> Result<()> {
+        let TimeframeIndicator {
+            interval,
+            indicator_type,
+        } = indicator;
+
+        let i = match self
+            .timeframes
+            .iter()
+            .position(|t| t.interval() == &interval)
+        {
+            Some(i) => i,
+            None => {
+                let timeframe = HigherTimeframe::new(
+                    &self.symbol,
+                    &self.interval,
+                    &interval,
+                    self.max_length,
+                    &self.candles,
+                )?;
+                self.timeframes.push(timeframe);
+                self.timeframes.len() - 1
+            }
+        };
+
+        let timeframe = &mut self.timeframes[i];
+        timeframe.ts.add_indicator(indicator_type)?;
+        timeframe.populate_candles(&mut self.candles);
+
+        let indicator = TimeframeIndicator {
+            interval,
+            indicator_type,
+        };
+        Arc::make_mut(&mut self.buffer).set_timeframe_indicator(&indicator, &self.candles);
+
+        Ok(())
+    }
+
+    /// Returns all candle fields and indicator outputs of the series in
+    /// columnar form.
+    #[allow(dead_code)]
+    pub fn columns(&self) -> TimeSeriesColumns {
+        TimeSeriesColumns::new(self)
+    }
+
+    /// Stores the rolling state of an indicator after it has been updated
+    /// with the last candle of the series.
+    pub fn set_indicator_state(&mut self, indicator_type: IndicatorType, state: IndicatorState) {
+        match self.candles.last() {
+            Some(candle) => {
+                self.indicator_states
+                    .insert(indicator_type, (candle.timestamp, state));
+            }
+            None => {
+                self.indicator_states.remove(&indicator_type);
+            }
+        }
+    }
+
+    /// Takes the rolling state of an indicator if it is ready to be updated
+    /// with the last candle, i.e. if it includes every candle up to the
+    /// second last one. Returns None if the state is missing or out of sync,
+    /// in which case the indicator needs to be recomputed.
+    pub fn take_indicator_state(
+        &mut self,
+        indicator_type: &IndicatorType,
+    ) -> Option<IndicatorState> {
+        let (timestamp, state) = self.indicator_states.remove(indicator_type)?;
+        let len = self.candles.len();
+
+        if len < 2 || self.candles[len - 2].timestamp != timestamp {
+            return None;
+        }
+
+        Some(state)
+    }
+
     pub fn dummy() -> Self {
         TimeSeriesBuilder::new()
             .symbol("BTCUSDT".to_string())
@@ -1,13 +1,14 @@
 use crate::{
     indicators::indicator_type::IndicatorType,
     models::{
-        candle::Candle, interval::Interval,
+        candle::Candle, candle_buffer::CandleBuffer, interval::Interval,
         message_payloads::candle_added_payload::CandleAddedPayload, net_version::NetVersion,
         timeseries::TimeSeries,
     },
 };
 use actix::Recipient;
 use indexmap::IndexSet;
+use std::{collections::HashMap, sync::Arc};
 
 #[derive(Debug, Clone)]
 pub struct TimeSeriesBuilder {
@@ -77,12 +78,17 @@ impl TimeSeriesBuilder {
     }
 
     pub fn build(self) -> TimeSeries {
+        let buffer = CandleBuffer::from_candles(&self.candles, self.max_length);
+
         TimeSeries {
             symbol: self.symbol.expect("Symbol is required"),
             interval: self.interval.expect("Interval is required"),
             max_length: self.max_length,
             candles: self.candles,
+            buffer: Arc::new(buffer),
             indicators: self.indicators,
+            indicator_states: HashMap::new(),
+            timeframes: vec![],
             observers: self.observers,
             net: self.net,
             validate_candles_on_add: self.validate_candles_on_add,
@@ -0,0 +1,162 @@
+use crate::{
+    indicators::indicator_field::IndicatorField,
+    models::{
+        candle::{Candle, CANDLE_FIELDS},
+        timeseries::TimeSeries,
+    },
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use std::io::Write;
+
+/// # TimeSeriesColumns
+///
+/// Columnar view of a TimeSeries with one column per candle field and per
+/// indicator output. Indicator columns are named by their field spec, e.g.
+/// `bb(20,2).upper`, and hold None where the indicator has no value yet.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeSeriesColumns {
+    pub timestamps: Vec<DateTime<Utc>>,
+    pub columns: IndexMap<String, Vec<Option<f64>>>,
+}
+
+#[allow(dead_code)]
+impl TimeSeriesColumns {
+    pub fn new(ts: &TimeSeries) -> Self {
+        let timestamps = ts.candles.iter().map(|c| c.timestamp).collect();
+        let mut columns = IndexMap::new();
+
+        for name in CANDLE_FIELDS {
+            let values = ts.candles.iter().map(|c| c.candle_field(name)).collect();
+            columns.insert(name.to_string(), values);
+        }
+
+        for indicator_type in ts.indicators.iter() {
+            for field in IndicatorField::all(*indicator_type) {
+                columns.insert(field.to_string(), Self::field_values(&ts.candles, &field));
+            }
+        }
+
+        Self {
+            timestamps,
+            columns,
+        }
+    }
+
+    /// Returns the values of a single indicator output for the given candles.
+    pub fn field_values(candles: &[Candle], field: &IndicatorField) -> Vec<Option<f64>> {
+        candles.iter().map(|c| c.field_value(field)).collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&[Option<f64>]> {
+        self.columns.get(name).map(|values| values.as_slice())
+    }
+
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.is_empty()
+    }
+
+    /// Writes the columns as CSV with a leading timestamp column. Missing
+    /// values are written as empty cells.
+    pub fn write_csv<W: Write>(&self, writer: W) -> Result<()> {
+        let mut writer = csv::Writer::from_writer(writer);
+
+        let mut header = vec!["timestamp".to_string()];
+        header.extend(self.columns.keys().cloned());
+        writer.write_record(&header)?;
+
+        for (i, timestamp) in self.timestamps.iter().enumerate() {
+            let mut row = vec![timestamp.to_rfc3339()];
+            row.extend(
+                self.columns
+                    .values()
+                    .map(|values| values[i].map(|v| v.to_string()).unwrap_or_default()),
+            );
+            writer.write_record(&row)?;
+        }
+
+        writer.flush()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_field::IndicatorField, indicator_type::IndicatorType,
+            populates_candles::PopulatesCandles, rsi::RSI, stochastic::Stochastic,
+        },
+        models::{
+            candle::Candle, interval::Interval, ma_type::MAType,
+            timeseries_builder::TimeSeriesBuilder,
+        },
+    };
+
+    #[test]
+    fn columns_from_timeseries() {
+        let candles = Candle::dummy_data(30, "alternating", 100.0);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        RSI::populate_candles(&mut ts).unwrap();
+        Stochastic::populate_candles(&mut ts).unwrap();
+
+        let columns = ts.columns();
+        assert_eq!(columns.len(), 30);
+
+        let names: Vec<&String> = columns.columns.keys().collect();
+        assert_eq!(
+            names,
+            vec![
+                "open",
+                "high",
+                "low",
+                "close",
+                "volume",
+                "rsi(14,rma).value",
+                "stoch(14,1,3,sma).k",
+                "stoch(14,1,3,sma).d"
+            ]
+        );
+
+        let rsi = columns.get("rsi(14,rma).value").unwrap();
+        assert!(rsi[0].is_none());
+
+        let last = ts.candles.last().unwrap();
+        let expected = last
+            .clone_indicator(&IndicatorType::RSI(14, MAType::RMA))
+            .unwrap()
+            .as_rsi()
+            .unwrap()
+            .value;
+        assert_eq!(rsi[29], Some(expected));
+        assert_eq!(last.value("rsi(14)"), Some(expected));
+        assert_eq!(last.value("close"), Some(last.close));
+        assert_eq!(last.value("rsi(20)"), None);
+
+        let d: IndicatorField = "stoch(14,1,3).d".parse().unwrap();
+        let stoch = last
+            .clone_indicator(&d.indicator_type)
+            .unwrap()
+            .as_stochastic()
+            .unwrap();
+        assert_eq!(last.field_value(&d), Some(stoch.d));
+
+        let mut csv = vec![];
+        columns.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 31);
+        assert!(csv.starts_with("timestamp,open,high,low,close,volume,\"rsi(14,rma).value\""));
+    }
+}
@@ -1,21 +1,43 @@
 use crate::{
     data_sources::datasource::DataSource,
+    metrics::bot_metrics::BotMetrics,
     models::{
         message_payloads::{
-            candle_added_payload::CandleAddedPayload, ping_payload::PingPayload,
-            request_latest_candles_payload::RequestLatestCandlesPayload, stop_payload::StopPayload,
+            candle_added_payload::CandleAddedPayload,
+            force_close_payload::ForceClosePayload,
+            ping_payload::PingPayload,
+            request_latest_candles_payload::RequestLatestCandlesPayload,
+            request_trade_state_payload::{RequestTradeStatePayload, TradeState},
+            stop_payload::StopPayload,
         },
         setups::setup::Setup,
+        strategy_orientation::StrategyOrientation,
         timeseries::TimeSeries,
     },
+    notifications::{
+        notification_center::NotificationCenter,
+        notification_event::{ExitReason, NotificationEvent},
+    },
     resolution_strategies::{
         is_resolution_strategy::IsResolutionStrategy, resolution_strategy::ResolutionStrategy,
     },
 };
-use actix::{Actor, ActorContext, Addr, AsyncContext, Context, Handler, WrapFuture};
+use actix::{
+    Actor, ActorContext, Addr, AsyncContext, Context, Handler, MessageResult, ResponseFuture,
+    WrapFuture,
+};
+use anyhow::{anyhow, Result};
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc,
+};
+use tracing::{error, info, info_span, warn, Instrument, Span};
+
+static NEXT_TRADE_ID: AtomicUsize = AtomicUsize::new(1);
 
 #[derive(Debug)]
 pub struct Trade {
+    pub id: usize,
     pub setup: Setup,
     pub quantity: f64,
     pub dollar_value: f64,
@@ -24,6 +46,9 @@ pub struct Trade {
     pub trading_enabled: bool,
     pub resolution_strategy: ResolutionStrategy,
     pub timeseries: Addr<TimeSeries>,
+    /// Set while the position is being sold, so that a take-profit or
+    /// stop-loss exit and a force close can't both sell it.
+    pub closing: Arc<AtomicBool>,
 }
 
 impl Actor for Trade {
@@ -32,24 +57,45 @@ impl Actor for Trade {
     fn started(&mut self, ctx: &mut Self::Context) {
         let source = self.source.clone();
         let symbol = self.setup.symbol.clone();
-        let dollar_value = self.dollar_value.clone();
+        let dollar_value = self.dollar_value;
+        let setup = self.setup.clone();
+        let notifications_enabled = self.notifications_enabled;
         self.resolution_strategy
             .set_initial_values(&self.setup)
             .expect("Unable to set initial values resolution strategy when starting Trade.");
+        BotMetrics::get().open_trades.inc();
 
         let fut = async move {
             let res = source.enter_trade(&symbol, dollar_value).await;
+            Self::record_order("entry", res.is_ok());
 
-            match res {
-                Ok(_) => println!("Successfully entered trade"),
-                Err(e) => println!("Unable to enter trade, error: {:#?}", e),
-            }
+            let event = match res {
+                Ok(_) => {
+                    info!(dollar_value, "Successfully entered trade");
+                    NotificationEvent::EntryFilled {
+                        setup,
+                        dollar_value,
+                    }
+                }
+                Err(e) => {
+                    error!(error = ?e, "Unable to enter trade");
+                    NotificationEvent::EntryFailed {
+                        setup,
+                        error: e.to_string(),
+                    }
+                }
+            };
 
-            // TODO: Setup system that manages scenario where Trade is unable
-            // to enter. Send notifications and or stop service.
+            if notifications_enabled {
+                Self::notify(&event).await;
+            }
         };
 
-        ctx.spawn(fut.into_actor(self));
+        ctx.spawn(fut.instrument(self.span()).into_actor(self));
+    }
+
+    fn stopped(&mut self, _ctx: &mut Self::Context) {
+        BotMetrics::get().open_trades.dec();
     }
 }
 
@@ -78,6 +124,10 @@ impl Handler<CandleAddedPayload> for Trade {
         let ts_addr = self.timeseries.clone();
         let source = self.source.clone();
         let symbol = self.setup.symbol.clone();
+        let setup = self.setup.clone();
+        let dollar_value = self.dollar_value;
+        let notifications_enabled = self.notifications_enabled;
+        let closing = self.closing.clone();
         let self_addr = ctx.address();
 
         // Multiply to avoid scenarios where quantity is slightly larger than
@@ -96,31 +146,188 @@ impl Handler<CandleAddedPayload> for Trade {
                 .expect("Unable to fetch timeseries data in Trade.")
                 .expect("Unable to parse LatestCandleResponse in Trade.");
 
-            let end = candle_response.candles.len();
+            let candles = candle_response.candles.view();
 
-            let tp_candles = &candle_response.candles[end - tp_candles_needed..end];
             let take_profit_reached = resolution_strategy
-                .take_profit_reached(&orientation, tp_candles)
+                .take_profit_reached(&orientation, candles.latest(tp_candles_needed))
                 .expect("Unable to perform take-profit check in Active Trade");
 
-            let sl_candles = &candle_response.candles[end - sl_candles_needed..end];
             let stop_loss_reached = resolution_strategy
-                .stop_loss_reached(&orientation, sl_candles)
+                .stop_loss_reached(&orientation, candles.latest(sl_candles_needed))
                 .expect("Unable to perform stop-loss check in Active Trade");
 
             if take_profit_reached || stop_loss_reached {
+                if !Self::start_closing(&closing) {
+                    info!("Trade is already closing");
+                    return;
+                }
+
                 let res = source.exit_trade(&symbol, quantity).await;
+                Self::record_order("exit", res.is_ok());
+                if res.is_err() {
+                    closing.store(false, Ordering::SeqCst);
+                }
 
-                match res {
-                    Ok(_) => println!("Trade successfully exited!"),
-                    Err(e) => println!("Trade exit failed with error: {:#?}", e),
+                let reason = if take_profit_reached {
+                    ExitReason::TakeProfit
+                } else {
+                    ExitReason::StopLoss
+                };
+                let exit_price = candles.last().expect("No candle provided to Trade.").close;
+                let event = Self::exit_event(setup, &res, reason, exit_price, dollar_value);
+
+                if notifications_enabled {
+                    Self::notify(&event).await;
                 }
-                // TODO: Handle/notify user in case selling was unsuccessful.
 
                 self_addr.do_send(StopPayload);
             }
         };
 
-        ctx.spawn(fut.into_actor(self));
+        ctx.spawn(fut.instrument(self.span()).into_actor(self));
+    }
+}
+
+impl Handler<ForceClosePayload> for Trade {
+    type Result = ResponseFuture<Result<()>>;
+
+    fn handle(&mut self, _msg: ForceClosePayload, ctx: &mut Self::Context) -> Self::Result {
+        let source = self.source.clone();
+        let symbol = self.setup.symbol.clone();
+        let setup = self.setup.clone();
+        let dollar_value = self.dollar_value;
+        let notifications_enabled = self.notifications_enabled;
+        let closing = self.closing.clone();
+        let self_addr = ctx.address();
+        let quantity = self.quantity * 0.99;
+
+        let fut = async move {
+            if !Self::start_closing(&closing) {
+                return Err(anyhow!("Trade is already closing"));
+            }
+
+            warn!("Force-closing trade");
+
+            let res = source.exit_trade(&symbol, quantity).await;
+            Self::record_order("exit", res.is_ok());
+            if res.is_err() {
+                closing.store(false, Ordering::SeqCst);
+            }
+
+            let exit_price = source
+                .get_symbol_price(&symbol)
+                .await
+                .unwrap_or(setup.candle.close);
+            let event = Self::exit_event(setup, &res, ExitReason::Manual, exit_price, dollar_value);
+
+            if notifications_enabled {
+                Self::notify(&event).await;
+            }
+
+            if res.is_ok() {
+                self_addr.do_send(StopPayload);
+            }
+
+            res
+        };
+
+        Box::pin(fut.instrument(self.span()))
+    }
+}
+
+impl Handler<RequestTradeStatePayload> for Trade {
+    type Result = MessageResult<RequestTradeStatePayload>;
+
+    fn handle(&mut self, _msg: RequestTradeStatePayload, _ctx: &mut Self::Context) -> Self::Result {
+        MessageResult(TradeState {
+            id: self.id,
+            symbol: self.setup.symbol.clone(),
+            interval: self.setup.interval.clone(),
+            orientation: self.setup.orientation,
+            entry_timestamp: self.setup.candle.timestamp,
+            entry_price: self.setup.candle.close,
+            quantity: self.quantity,
+            dollar_value: self.dollar_value,
+        })
+    }
+}
+
+impl Trade {
+    /// Returns a process wide unique id for a new Trade.
+    pub fn next_id() -> usize {
+        NEXT_TRADE_ID.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Marks the trade as closing, returning false if it already was. A
+    /// failed exit clears the flag again so that the exit can be retried.
+    fn start_closing(closing: &AtomicBool) -> bool {
+        !closing.swap(true, Ordering::SeqCst)
+    }
+
+    fn span(&self) -> Span {
+        info_span!(
+            "Trade",
+            id = self.id,
+            symbol = %self.setup.symbol,
+            interval = %self.setup.interval,
+            orientation = ?self.setup.orientation
+        )
+    }
+
+    fn exit_event(
+        setup: Setup,
+        res: &Result<()>,
+        reason: ExitReason,
+        exit_price: f64,
+        dollar_value: f64,
+    ) -> NotificationEvent {
+        match res {
+            Ok(_) => {
+                info!(%reason, exit_price, "Trade successfully exited");
+                let pnl = Self::realized_pnl(&setup, exit_price);
+
+                NotificationEvent::TradeExited {
+                    setup,
+                    reason,
+                    exit_price,
+                    pnl,
+                    dollar_pnl: pnl * dollar_value,
+                }
+            }
+            Err(e) => {
+                error!(error = ?e, "Trade exit failed");
+                NotificationEvent::ExitFailed {
+                    setup,
+                    error: e.to_string(),
+                }
+            }
+        }
+    }
+
+    /// Realized profit/loss in decimals when exiting at the given price.
+    fn realized_pnl(setup: &Setup, exit_price: f64) -> f64 {
+        let change = exit_price / setup.candle.close - 1.0;
+
+        match setup.orientation {
+            StrategyOrientation::Long => change,
+            StrategyOrientation::Short => -change,
+        }
+    }
+
+    fn record_order(order: &str, ok: bool) {
+        let metrics = BotMetrics::get();
+        let counter = if ok {
+            &metrics.orders_placed
+        } else {
+            &metrics.orders_failed
+        };
+
+        counter.with_label_values(&[order]).inc();
+    }
+
+    async fn notify(event: &NotificationEvent) {
+        if let Err(e) = NotificationCenter::notify(event).await {
+            error!(error = ?e, "Unable to send notification");
+        }
     }
 }
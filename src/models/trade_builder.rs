@@ -8,6 +8,7 @@ use crate::{
 };
 use actix::Addr;
 use anyhow::{anyhow, Result};
+use std::sync::{atomic::AtomicBool, Arc};
 
 #[derive(Debug)]
 pub struct TradeBuilder {
@@ -111,6 +112,7 @@ impl TradeBuilder {
             .ok_or(anyhow!("TimeSeries is required to build Trade."))?;
 
         let trade = Trade {
+            id: Trade::next_id(),
             setup,
             quantity,
             dollar_value,
@@ -119,6 +121,7 @@ impl TradeBuilder {
             trading_enabled,
             resolution_strategy,
             timeseries,
+            closing: Arc::new(AtomicBool::new(false)),
         };
 
         Ok(trade)
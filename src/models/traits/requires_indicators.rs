@@ -1,6 +1,12 @@
-use crate::indicators::indicator_type::IndicatorType;
+use crate::indicators::{indicator_type::IndicatorType, timeframe_indicator::TimeframeIndicator};
 
 pub trait RequiresIndicators {
     /// Required indicators for succesful use of resolution strategy
     fn required_indicators(&self) -> Vec<IndicatorType>;
+
+    /// Required indicators calculated on a higher interval than the one of
+    /// the TimeSeries used
+    fn required_timeframe_indicators(&self) -> Vec<TimeframeIndicator> {
+        vec![]
+    }
 }
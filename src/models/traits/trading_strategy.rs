@@ -1,12 +1,14 @@
 use crate::{
     models::{
-        candle::Candle, interval::Interval, setups::setup_builder::SetupBuilder,
+        candle_buffer::CandleView, interval::Interval, setups::setup_builder::SetupBuilder,
         strategy_orientation::StrategyOrientation, traits::has_min_length::HasMinLength,
         traits::requires_indicators::RequiresIndicators,
     },
     resolution_strategies::resolution_strategy::ResolutionStrategy,
 };
+use anyhow::Result;
 use chrono::Weekday;
+use serde_json::Value;
 use std::{
     collections::HashSet,
     fmt::{Debug, Display},
@@ -27,7 +29,7 @@ pub trait TradingStrategy: Display + Debug + RequiresIndicators + HasMinLength {
 
     /// Checks whether a new Setup has arisen upon the closure of the last
     /// candle provided.
-    fn check_last_for_setup(&mut self, candles: &[Candle]) -> Option<SetupBuilder>;
+    fn check_last_for_setup(&mut self, candles: CandleView) -> Option<SetupBuilder>;
 
     /// Returns a boxed clone of the current TradingStrategy
     fn clone_box(&self) -> Box<dyn TradingStrategy>;
@@ -51,4 +53,156 @@ pub trait TradingStrategy: Display + Debug + RequiresIndicators + HasMinLength {
 
     /// Returns the TradingDays to be used with this TradingStrategy
     fn trading_days(&self) -> HashSet<Weekday>;
+
+    /// Returns the internal state of the strategy, e.g. a condition armed on
+    /// an earlier candle. The state is carried over between candles when
+    /// looking for setups and persisted across restarts. Stateless
+    /// strategies return None.
+    fn state(&self) -> Option<Value> {
+        None
+    }
+
+    /// Restores state previously returned by `state`.
+    fn restore_state(&mut self, _state: Value) -> Result<()> {
+        Ok(())
+    }
+
+    /// Returns the number of candles of the strategy's interval needed for
+    /// all of its indicators to have values, including the indicators
+    /// calculated on higher intervals.
+    fn history_length(&self) -> usize {
+        let interval = self.interval();
+
+        self.required_timeframe_indicators()
+            .iter()
+            .map(|indicator| indicator.min_length_on(&interval))
+            .fold(self.min_length(), usize::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{indicator_type::IndicatorType, timeframe_indicator::TimeframeIndicator},
+        models::{
+            candle::Candle,
+            candle_buffer::CandleView,
+            interval::Interval,
+            setups::setup_builder::SetupBuilder,
+            strategy_orientation::StrategyOrientation,
+            timeseries_builder::TimeSeriesBuilder,
+            traits::{
+                has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+                trading_strategy::TradingStrategy,
+            },
+        },
+        resolution_strategies::{
+            instant_resolution::InstantResolution, resolution_strategy::ResolutionStrategy,
+        },
+    };
+    use chrono::{Duration, TimeZone, Utc, Weekday};
+    use std::{
+        collections::HashSet,
+        fmt::{Display, Formatter},
+    };
+
+    /// Hourly strategy using the daily SMA.
+    #[derive(Debug, Clone)]
+    struct DailySma;
+
+    impl DailySma {
+        fn daily_sma() -> TimeframeIndicator {
+            TimeframeIndicator::new(Interval::Day1, IndicatorType::SMA(5))
+        }
+    }
+
+    impl HasMinLength for DailySma {
+        fn min_length(&self) -> usize {
+            IndicatorType::SMA(2).min_length()
+        }
+    }
+
+    impl TradingStrategy for DailySma {
+        fn new() -> Self {
+            Self
+        }
+
+        fn candles_needed_for_setup(&self) -> usize {
+            1
+        }
+
+        fn check_last_for_setup(&mut self, _candles: CandleView) -> Option<SetupBuilder> {
+            None
+        }
+
+        fn clone_box(&self) -> Box<dyn TradingStrategy> {
+            Box::new(self.clone())
+        }
+
+        fn default_resolution_strategy(&self) -> ResolutionStrategy {
+            ResolutionStrategy::Instant(InstantResolution)
+        }
+
+        fn orientation(&self) -> StrategyOrientation {
+            StrategyOrientation::Long
+        }
+
+        fn interval(&self) -> Interval {
+            Interval::Hour1
+        }
+
+        fn trading_days(&self) -> HashSet<Weekday> {
+            HashSet::from([Weekday::Mon])
+        }
+    }
+
+    impl RequiresIndicators for DailySma {
+        fn required_indicators(&self) -> Vec<IndicatorType> {
+            vec![IndicatorType::SMA(2)]
+        }
+
+        fn required_timeframe_indicators(&self) -> Vec<TimeframeIndicator> {
+            vec![Self::daily_sma()]
+        }
+    }
+
+    impl Display for DailySma {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "DailySma")
+        }
+    }
+
+    /// Whether the last of `n` hourly candles has a daily SMA.
+    fn has_daily_sma(n: usize) -> bool {
+        let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let candles = (0..n)
+            .map(|i| Candle::from_val(start + Duration::hours(i as i64), 100.0 + i as f64, 1.0))
+            .collect();
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Hour1)
+            .candles(candles)
+            .build();
+        ts.add_timeframe_indicator(DailySma::daily_sma()).unwrap();
+
+        ts.buffer
+            .view()
+            .last()
+            .and_then(|c| c.clone_timeframe_indicator(&DailySma::daily_sma()).ok())
+            .and_then(|sma| sma.as_sma())
+            .is_some()
+    }
+
+    #[test]
+    fn history_length_covers_higher_timeframe_indicators() {
+        let strategy = DailySma::new();
+
+        assert_eq!(
+            strategy.history_length(),
+            (IndicatorType::SMA(5).min_length() + 1) * 24
+        );
+        assert!(has_daily_sma(strategy.history_length()));
+        assert!(!has_daily_sma(strategy.min_length() + 24));
+    }
 }
@@ -1,17 +1,36 @@
 use crate::{
     data_sources::datasource::DataSource,
+    metrics::bot_metrics::BotMetrics,
     models::{
-        interval::Interval, message_payloads::websocket_payload::WebsocketPayload,
-        net_version::NetVersion, timeseries::TimeSeries,
+        bar_builder::BarBuilder, candle::Candle, interval::Interval,
+        message_payloads::websocket_payload::WebsocketPayload, net_version::NetVersion,
+        resampler::Resampler, timeseries::TimeSeries,
+    },
+    notifications::{
+        notification_center::NotificationCenter, notification_event::NotificationEvent,
     },
 };
 use actix::{Actor, Addr, AsyncContext, Context, Handler, WrapFuture};
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{error, info_span, warn, Instrument, Span};
+
+// Delay before the first reconnection attempt, doubled on every failed attempt
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(300);
+// Connections that stayed up this long count as successful and reset the delay
+const STABLE_CONNECTION: Duration = Duration::from_secs(60);
 
 pub struct WebsocketClient {
     source: DataSource,
     interval: Interval,
+    /// Builds candles of the interval from a finer interval when the source
+    /// doesn't stream the interval itself.
+    resampler: Option<Resampler>,
+    /// Turns the candles into alternative bars such as Renko bricks.
+    bar_builder: Option<BarBuilder>,
     observers: Vec<Addr<TimeSeries>>,
     net: NetVersion,
+    notifications_enabled: bool,
 }
 
 impl Actor for WebsocketClient {
@@ -20,16 +39,58 @@ impl Actor for WebsocketClient {
     fn started(&mut self, ctx: &mut Self::Context) {
         let client = ctx.address();
         let source = self.source.clone();
-        let interval = self.interval.clone();
+        let interval = self.stream_interval();
         let net = self.net.clone();
+        let notifications_enabled = self.notifications_enabled;
         let fut = async move {
-            if let Err(e) = source.connect_ws(client, interval, &net).await {
-                // TODO: Add logic for error handling, restarting client etc.
-                println!("Error: {}", e);
+            let mut delay = INITIAL_RECONNECT_DELAY;
+
+            // Reconnect whenever the connection ends, whether it failed or
+            // was closed by the source
+            loop {
+                let connected_at = Instant::now();
+                let result = source
+                    .connect_ws(client.clone(), interval.clone(), &net)
+                    .await;
+
+                if result.is_ok() || connected_at.elapsed() >= STABLE_CONNECTION {
+                    delay = INITIAL_RECONNECT_DELAY;
+                }
+
+                let error = match result {
+                    Ok(()) => {
+                        warn!("Websocket connection closed");
+                        "Connection closed".to_string()
+                    }
+                    Err(e) => {
+                        error!(error = %e, "Websocket connection failed");
+                        e.to_string()
+                    }
+                };
+
+                if notifications_enabled {
+                    let event = NotificationEvent::WebsocketDisconnected {
+                        source: source.to_string(),
+                        error,
+                    };
+
+                    if let Err(e) = NotificationCenter::notify(&event).await {
+                        error!(error = ?e, "Unable to send notification");
+                    }
+                }
+
+                warn!(delay_secs = delay.as_secs(), "Reconnecting websocket");
+                sleep(delay).await;
+                delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+
+                BotMetrics::get()
+                    .websocket_reconnects
+                    .with_label_values(&[&source.to_string()])
+                    .inc();
             }
         };
 
-        ctx.spawn(fut.into_actor(self));
+        ctx.spawn(fut.instrument(self.span()).into_actor(self));
     }
 }
 
@@ -37,30 +98,108 @@ impl Handler<WebsocketPayload> for WebsocketClient {
     type Result = ();
 
     fn handle(&mut self, payload: WebsocketPayload, _ctx: &mut Context<Self>) -> Self::Result {
+        let _span = self.span().entered();
+
         if payload.ok {
-            for observer in &self.observers {
-                observer.do_send(payload.clone());
+            BotMetrics::get()
+                .candles_received
+                .with_label_values(&[
+                    &self.source.to_string(),
+                    &self.stream_interval().to_string(),
+                ])
+                .inc();
+
+            let payloads = match &payload.candle {
+                Some(candle) => self
+                    .transform(candle)
+                    .into_iter()
+                    .map(|candle| WebsocketPayload {
+                        candle: Some(candle),
+                        ..payload.clone()
+                    })
+                    .collect(),
+                None => vec![payload],
+            };
+
+            for payload in payloads {
+                for observer in &self.observers {
+                    observer.do_send(payload.clone());
+                }
             }
         } else {
             let err = match payload.message {
                 Some(message) => message,
                 None => "Unknown error".to_string(),
             };
-            println!("Error: {}", err);
+            error!(error = %err, "Received failed websocket payload");
         }
     }
 }
 
 impl WebsocketClient {
     pub fn new(source: DataSource, interval: Interval, net: NetVersion) -> Self {
+        let resampler = source
+            .base_interval(&interval)
+            .ok()
+            .filter(|base| *base != interval)
+            .and_then(|base| Resampler::new(&base, &interval).ok());
+
         Self {
             source,
             interval,
+            resampler,
+            bar_builder: None,
             net,
             observers: vec![],
+            notifications_enabled: false,
         }
     }
 
+    /// Interval streamed from the source, the finer interval when resampling.
+    fn stream_interval(&self) -> Interval {
+        match &self.resampler {
+            Some(resampler) => resampler.from().clone(),
+            None => self.interval.clone(),
+        }
+    }
+
+    fn span(&self) -> Span {
+        info_span!(
+            "WebsocketClient",
+            source = %self.source,
+            interval = %self.interval
+        )
+    }
+
+    /// Passes a received candle through the resampler and bar builder,
+    /// returning the candles to send to the observers.
+    fn transform(&mut self, candle: &Candle) -> Vec<Candle> {
+        let candle = match &mut self.resampler {
+            Some(resampler) => match resampler.push(candle) {
+                Some(candle) => candle,
+                None => return vec![],
+            },
+            None => candle.clone(),
+        };
+
+        match &mut self.bar_builder {
+            Some(bar_builder) => bar_builder.push(&candle),
+            None => vec![candle],
+        }
+    }
+
+    /// Sends bars built by the builder instead of candles. Pass the builder
+    /// used for the historical candles of the observed TimeSeries to continue
+    /// where it left off.
+    #[allow(dead_code)]
+    pub fn set_bar_builder(&mut self, bar_builder: BarBuilder) {
+        self.bar_builder = Some(bar_builder);
+    }
+
+    pub fn set_notifications_enabled(&mut self, enabled: bool) {
+        self.notifications_enabled = enabled;
+    }
+
     pub fn add_observer(&mut self, observer: Addr<TimeSeries>) {
         self.observers.push(observer);
     }
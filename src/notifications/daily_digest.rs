@@ -0,0 +1,126 @@
+use crate::notifications::notification_event::{ExitReason, NotificationEvent};
+use chrono::{DateTime, Utc};
+
+/// # DailyDigest
+///
+/// Collects the setups and trades notified during a day so that they can be
+/// summarized in a single digest email.
+#[derive(Debug, Clone)]
+pub struct DailyDigest {
+    pub events: Vec<(DateTime<Utc>, NotificationEvent)>,
+}
+
+impl DailyDigest {
+    pub const fn new() -> Self {
+        Self { events: Vec::new() }
+    }
+
+    pub fn record(&mut self, event: &NotificationEvent) {
+        let relevant = matches!(
+            event,
+            NotificationEvent::SetupFound { .. }
+                | NotificationEvent::EntryFilled { .. }
+                | NotificationEvent::EntryFailed { .. }
+                | NotificationEvent::TradeExited { .. }
+                | NotificationEvent::ExitFailed { .. }
+        );
+
+        if relevant {
+            self.events.push((Utc::now(), event.clone()));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Returns the collected digest and starts collecting a new one.
+    pub fn take(&mut self) -> DailyDigest {
+        DailyDigest {
+            events: std::mem::take(&mut self.events),
+        }
+    }
+
+    pub fn n_setups(&self) -> usize {
+        self.count(|e| matches!(e, NotificationEvent::SetupFound { .. }))
+    }
+
+    pub fn n_entries(&self) -> usize {
+        self.count(|e| matches!(e, NotificationEvent::EntryFilled { .. }))
+    }
+
+    pub fn n_failures(&self) -> usize {
+        self.count(|e| {
+            matches!(
+                e,
+                NotificationEvent::EntryFailed { .. } | NotificationEvent::ExitFailed { .. }
+            )
+        })
+    }
+
+    pub fn n_exits(&self, reason: ExitReason) -> usize {
+        self.count(
+            |e| matches!(e, NotificationEvent::TradeExited { reason: r, .. } if *r == reason),
+        )
+    }
+
+    /// Sum of realized profit/loss in USD for all exited trades.
+    pub fn total_dollar_pnl(&self) -> f64 {
+        self.events
+            .iter()
+            .filter_map(|(_, e)| match e {
+                NotificationEvent::TradeExited { dollar_pnl, .. } => Some(*dollar_pnl),
+                _ => None,
+            })
+            .sum()
+    }
+
+    fn count(&self, f: impl Fn(&NotificationEvent) -> bool) -> usize {
+        self.events.iter().filter(|(_, e)| f(e)).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::setups::setup::Setup,
+        notifications::{
+            daily_digest::DailyDigest,
+            notification_event::{ExitReason, NotificationEvent},
+        },
+    };
+
+    #[test]
+    fn digest_summarizes_trades() {
+        let mut digest = DailyDigest::new();
+        let exit = |reason, dollar_pnl| NotificationEvent::TradeExited {
+            setup: Setup::dummy(),
+            reason,
+            exit_price: 100.0,
+            pnl: 0.0,
+            dollar_pnl,
+        };
+
+        digest.record(&NotificationEvent::SetupFound {
+            setup: Setup::dummy(),
+            strategy: "Dummy".to_string(),
+            recent_closes: vec![],
+        });
+        digest.record(&exit(ExitReason::TakeProfit, 10.0));
+        digest.record(&exit(ExitReason::StopLoss, -4.0));
+        digest.record(&NotificationEvent::WebsocketDisconnected {
+            source: "Bybit".to_string(),
+            error: "Closed".to_string(),
+        });
+
+        assert_eq!(digest.events.len(), 3);
+        assert_eq!(digest.n_setups(), 1);
+        assert_eq!(digest.n_exits(ExitReason::TakeProfit), 1);
+        assert_eq!(digest.n_exits(ExitReason::StopLoss), 1);
+        assert_eq!(digest.total_dollar_pnl(), 6.0);
+
+        let taken = digest.take();
+        assert_eq!(taken.events.len(), 3);
+        assert!(digest.is_empty());
+    }
+}
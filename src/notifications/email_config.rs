@@ -0,0 +1,153 @@
+use anyhow::{anyhow, Context, Result};
+use lettre::{
+    message::Mailbox, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    Tokio1Executor,
+};
+use std::{env, str::FromStr};
+
+const DEFAULT_SMTP_HOST: &str = "smtp.gmail.com";
+
+/// Encryption used when connecting to the SMTP server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpTlsMode {
+    /// Unencrypted connection, only intended for local relays.
+    None,
+    /// Plain connection upgraded through STARTTLS (usually port 587).
+    StartTls,
+    /// Implicit TLS (usually port 465).
+    Tls,
+}
+
+impl FromStr for SmtpTlsMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "starttls" => Ok(Self::StartTls),
+            "tls" => Ok(Self::Tls),
+            _ => Err(anyhow!("Invalid SMTP TLS mode: {}", s)),
+        }
+    }
+}
+
+/// # EmailConfig
+///
+/// SMTP settings used when sending notification emails, read from the
+/// environment:
+///
+/// - `EMAIL_SMTP_HOST` - Defaults to smtp.gmail.com
+/// - `EMAIL_SMTP_PORT` - Defaults to the standard port of the TLS mode
+/// - `EMAIL_SMTP_TLS` - One of `none`, `starttls` or `tls` (default)
+/// - `EMAIL_SENDER`
+/// - `EMAIL_RECEIVERS` - Comma separated list, falls back to `EMAIL_RECEIVER`
+/// - `EMAIL_LOGIN_USERNAME` and `EMAIL_LOGIN_PASSWORD`
+#[derive(Debug, Clone)]
+pub struct EmailConfig {
+    pub host: String,
+    pub port: Option<u16>,
+    pub tls: SmtpTlsMode,
+    pub sender: Mailbox,
+    pub recipients: Vec<Mailbox>,
+    pub username: String,
+    pub password: String,
+}
+
+impl EmailConfig {
+    pub fn from_env() -> Result<Self> {
+        let host = env::var("EMAIL_SMTP_HOST").unwrap_or(DEFAULT_SMTP_HOST.to_string());
+        let port = match env::var("EMAIL_SMTP_PORT") {
+            Ok(port) => Some(port.parse::<u16>().context("Invalid EMAIL_SMTP_PORT")?),
+            Err(_) => None,
+        };
+        let tls = match env::var("EMAIL_SMTP_TLS") {
+            Ok(mode) => mode.parse()?,
+            Err(_) => SmtpTlsMode::Tls,
+        };
+
+        let sender = env::var("EMAIL_SENDER")?
+            .parse::<Mailbox>()
+            .context("Invalid EMAIL_SENDER address")?;
+        let recipients = env::var("EMAIL_RECEIVERS").or(env::var("EMAIL_RECEIVER"))?;
+        let recipients = Self::parse_recipients(&recipients)?;
+
+        Ok(Self {
+            host,
+            port,
+            tls,
+            sender,
+            recipients,
+            username: env::var("EMAIL_LOGIN_USERNAME")?,
+            password: env::var("EMAIL_LOGIN_PASSWORD")?,
+        })
+    }
+
+    pub fn parse_recipients(s: &str) -> Result<Vec<Mailbox>> {
+        let recipients = s
+            .split(',')
+            .map(|r| r.trim())
+            .filter(|r| !r.is_empty())
+            .map(|r| {
+                r.parse::<Mailbox>()
+                    .context(format!("Invalid recipient address: {}", r))
+            })
+            .collect::<Result<Vec<Mailbox>>>()?;
+
+        if recipients.is_empty() {
+            return Err(anyhow!("At least one email recipient is required."));
+        }
+
+        Ok(recipients)
+    }
+
+    pub fn transport(&self) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+        let builder = match self.tls {
+            SmtpTlsMode::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&self.host)
+            }
+            SmtpTlsMode::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.host)?
+            }
+            SmtpTlsMode::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&self.host)?,
+        };
+
+        let builder = match self.port {
+            Some(port) => builder.port(port),
+            None => builder,
+        };
+
+        let credentials = Credentials::new(self.username.clone(), self.password.clone());
+
+        Ok(builder.credentials(credentials).build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::notifications::email_config::{EmailConfig, SmtpTlsMode};
+
+    #[test]
+    fn parse_multiple_recipients() {
+        let recipients =
+            EmailConfig::parse_recipients("a@example.com, Bot <b@example.com>,").unwrap();
+
+        assert_eq!(recipients.len(), 2);
+        assert_eq!(recipients[1].email.to_string(), "b@example.com");
+    }
+
+    #[test]
+    fn parse_invalid_recipients() {
+        assert!(EmailConfig::parse_recipients("not-an-address").is_err());
+        assert!(EmailConfig::parse_recipients(" , ").is_err());
+    }
+
+    #[test]
+    fn parse_tls_mode() {
+        assert_eq!(
+            "STARTTLS".parse::<SmtpTlsMode>().unwrap(),
+            SmtpTlsMode::StartTls
+        );
+        assert_eq!("none".parse::<SmtpTlsMode>().unwrap(), SmtpTlsMode::None);
+        assert!("ssl3".parse::<SmtpTlsMode>().is_err());
+    }
+}
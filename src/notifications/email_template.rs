@@ -0,0 +1,203 @@
+use crate::{
+    indicators::indicator_field::IndicatorField,
+    models::candle::Candle,
+    notifications::{
+        daily_digest::DailyDigest,
+        notification_event::{ExitReason, NotificationEvent},
+    },
+};
+
+const SPARK_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+const STYLE: &str = "font-family:Helvetica,Arial,sans-serif;color:#222;";
+const TABLE_STYLE: &str = "border-collapse:collapse;margin:8px 0;";
+const CELL_STYLE: &str = "padding:4px 12px;border-bottom:1px solid #ddd;";
+
+/// Renders a unicode sparkline of the provided values, e.g. "▁▃▅█".
+pub fn sparkline(values: &[f64]) -> String {
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+
+    values
+        .iter()
+        .map(|v| {
+            if range <= 0.0 {
+                return SPARK_CHARS[SPARK_CHARS.len() / 2];
+            }
+
+            let i = ((v - min) / range * (SPARK_CHARS.len() - 1) as f64).round() as usize;
+            SPARK_CHARS[i.min(SPARK_CHARS.len() - 1)]
+        })
+        .collect()
+}
+
+/// HTML version of a notification email.
+pub fn event_html(event: &NotificationEvent) -> String {
+    let mut content = table(&event.fields());
+
+    if let NotificationEvent::SetupFound {
+        setup,
+        recent_closes,
+        ..
+    } = event
+    {
+        if !recent_closes.is_empty() {
+            content.push_str(&format!(
+                r#"<h3>Recent closes</h3><p style="font-size:20px;letter-spacing:1px;">{}</p>"#,
+                sparkline(recent_closes)
+            ));
+        }
+
+        let indicators = indicator_values(&setup.candle);
+        if !indicators.is_empty() {
+            content.push_str("<h3>Indicators at setup candle</h3>");
+            content.push_str(&table(&indicators));
+        }
+    }
+
+    layout(&event.subject(), &content)
+}
+
+/// Plain text version of the daily digest email.
+pub fn digest_text(digest: &DailyDigest) -> String {
+    digest_summary(digest)
+        .iter()
+        .map(|(label, value)| format!("{}: {}", label, value))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// HTML version of the daily digest email.
+pub fn digest_html(digest: &DailyDigest) -> String {
+    let mut content = table(&digest_summary(digest));
+
+    let rows: Vec<(String, String)> = digest
+        .events
+        .iter()
+        .map(|(time, event)| (time.format("%H:%M").to_string(), event.subject()))
+        .collect();
+
+    if !rows.is_empty() {
+        content.push_str("<h3>Events</h3>");
+        content.push_str(&table(&rows));
+    }
+
+    layout("Daily digest", &content)
+}
+
+fn digest_summary(digest: &DailyDigest) -> Vec<(&'static str, String)> {
+    vec![
+        ("Setups found", digest.n_setups().to_string()),
+        ("Trades entered", digest.n_entries().to_string()),
+        (
+            "Take-profits",
+            digest.n_exits(ExitReason::TakeProfit).to_string(),
+        ),
+        (
+            "Stop-losses",
+            digest.n_exits(ExitReason::StopLoss).to_string(),
+        ),
+        ("Failed orders", digest.n_failures().to_string()),
+        (
+            "Realized P&L",
+            format!("{:.2} USD", digest.total_dollar_pnl()),
+        ),
+    ]
+}
+
+fn indicator_values(candle: &Candle) -> Vec<(String, String)> {
+    let mut values: Vec<(String, String)> = candle
+        .indicators
+        .keys()
+        .flat_map(|indicator_type| IndicatorField::all(*indicator_type))
+        .filter_map(|field| {
+            let value = candle.field_value(&field)?;
+            Some((field.to_string(), format!("{:.4}", value)))
+        })
+        .collect();
+
+    values.sort();
+    values
+}
+
+fn table<L: AsRef<str>>(rows: &[(L, String)]) -> String {
+    let rows: String = rows
+        .iter()
+        .map(|(label, value)| {
+            format!(
+                r#"<tr><td style="{}"><b>{}</b></td><td style="{}">{}</td></tr>"#,
+                CELL_STYLE,
+                escape(label.as_ref()),
+                CELL_STYLE,
+                escape(value)
+            )
+        })
+        .collect();
+
+    format!(r#"<table style="{}">{}</table>"#, TABLE_STYLE, rows)
+}
+
+fn layout(title: &str, content: &str) -> String {
+    format!(
+        r#"<html><body style="{}"><h2>{}</h2>{}<p>Best of luck,<br>Rust-Bot</p></body></html>"#,
+        STYLE,
+        escape(title),
+        content
+    )
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{indicator::Indicator, indicator_type::IndicatorType, rsi::RSI},
+        models::{ma_type::MAType, setups::setup::Setup},
+        notifications::{
+            email_template::{event_html, sparkline},
+            notification_event::NotificationEvent,
+        },
+    };
+
+    #[test]
+    fn sparkline_scales_values() {
+        assert_eq!(
+            sparkline(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]),
+            "▁▂▃▄▅▆▇█"
+        );
+        assert_eq!(sparkline(&[5.0, 5.0]), "▅▅");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn setup_html_contains_indicators_and_sparkline() {
+        let mut setup = Setup::dummy();
+        let rsi = RSI {
+            value: 28.5,
+            len: 14,
+            avg_gain: 1.0,
+            avg_loss: 2.0,
+        };
+        setup.candle.indicators.insert(
+            IndicatorType::RSI(14, MAType::RMA),
+            Indicator::RSI(Some(rsi)),
+        );
+
+        let html = event_html(&NotificationEvent::SetupFound {
+            setup,
+            strategy: "<Dummy>".to_string(),
+            recent_closes: vec![1.0, 8.0],
+        });
+
+        assert!(html.contains("rsi(14,rma).value"));
+        assert!(html.contains("28.5000"));
+        assert!(html.contains("▁█"));
+        assert!(html.contains("&lt;Dummy&gt;"));
+    }
+}
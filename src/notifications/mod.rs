@@ -1 +1,6 @@
+pub mod daily_digest;
+pub mod email_config;
+pub mod email_template;
 pub mod notification_center;
+pub mod notification_event;
+pub mod notification_settings;
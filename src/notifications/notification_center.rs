@@ -1,73 +1,104 @@
-use crate::models::{setups::setup::Setup, traits::trading_strategy::TradingStrategy};
-use anyhow::{anyhow, Result};
-use lettre::{
-    message::header::ContentType, transport::smtp::authentication::Credentials, Message,
-    SmtpTransport, Transport,
+use crate::notifications::{
+    daily_digest::DailyDigest,
+    email_config::EmailConfig,
+    email_template::{digest_html, digest_text, event_html},
+    notification_event::NotificationEvent,
+    notification_settings::NotificationSettings,
 };
-use std::env;
+use actix::spawn;
+use anyhow::{anyhow, Result};
+use chrono::{Duration as ChronoDuration, Utc};
+use lettre::{message::MultiPart, AsyncTransport, Message};
+use std::sync::Mutex;
+use tokio::time::sleep;
+use tracing::{error, info};
+
+// Setups and trades collected for the next daily digest
+static DIGEST: Mutex<DailyDigest> = Mutex::new(DailyDigest::new());
 
 pub struct NotificationCenter;
 
 impl NotificationCenter {
-    pub async fn notify(setup: &Setup, strategy: &Box<dyn TradingStrategy>) -> Result<()> {
-        Self::notify_email(setup, strategy).await?;
+    pub async fn notify(event: &NotificationEvent) -> Result<()> {
+        if let Ok(mut digest) = DIGEST.lock() {
+            digest.record(event);
+        }
+
+        let settings = NotificationSettings::from_env();
+
+        if !settings.is_enabled(event.kind()) {
+            return Ok(());
+        }
+
+        Self::notify_email(event).await?;
 
         Ok(())
     }
 
-    pub async fn notify_email(setup: &Setup, strategy: &Box<dyn TradingStrategy>) -> Result<()> {
-        let sender = env::var("EMAIL_SENDER")?;
-        let sender = format!("{}", sender);
-        let receiver = env::var("EMAIL_RECEIVER")?;
-        let receiver = format!("{}", receiver);
-        let username = env::var("EMAIL_LOGIN_USERNAME")?;
-        let password = env::var("EMAIL_LOGIN_PASSWORD")?;
-
-        let email = Message::builder()
-            .from(sender.parse().unwrap())
-            .to(receiver.parse().unwrap())
-            .subject("Trade notification!")
-            .header(ContentType::TEXT_PLAIN)
-            .body(Self::get_body(setup, strategy));
-
-        if let Ok(email) = email {
-            let credentials = Credentials::new(username, password);
-            let mailer = SmtpTransport::relay("smtp.gmail.com")?
-                .credentials(credentials)
-                .build();
-
-            match mailer.send(&email) {
-                Ok(_) => {
-                    println!("Email sent successfully!");
-                    Ok(())
+    pub async fn notify_email(event: &NotificationEvent) -> Result<()> {
+        Self::send_email(&event.subject(), event.body(), event_html(event)).await
+    }
+
+    /// Spawns a task which sends a digest email summarizing the setups and
+    /// trades of the day shortly after midnight (UTC).
+    pub fn start_daily_digest() {
+        let fut = async move {
+            loop {
+                let now = Utc::now();
+                let next_midnight = (now + ChronoDuration::days(1))
+                    .date_naive()
+                    .and_hms_opt(0, 0, 0)
+                    .expect("Expected valid midnight.")
+                    .and_utc();
+                let until_midnight = (next_midnight - now)
+                    .to_std()
+                    .unwrap_or(std::time::Duration::from_secs(1));
+
+                sleep(until_midnight).await;
+
+                let digest = match DIGEST.lock() {
+                    Ok(mut digest) => digest.take(),
+                    Err(_) => continue,
+                };
+
+                if digest.is_empty() || !NotificationSettings::from_env().daily_digest {
+                    continue;
+                }
+
+                let subject = format!("Daily digest {}", now.date_naive());
+                let res =
+                    Self::send_email(&subject, digest_text(&digest), digest_html(&digest)).await;
+
+                if let Err(e) = res {
+                    error!(error = ?e, "Unable to send daily digest");
                 }
-                Err(e) => Err(anyhow!(e)),
             }
-        } else {
-            Err(anyhow!("Unable to create notification email."))
-        }
+        };
+
+        spawn(fut);
     }
 
-    fn get_body(setup: &Setup, strategy: &Box<dyn TradingStrategy>) -> String {
-        let s = format!(
-            r#"Trade Notification:
-
-        Strategy: {}
-        Symbol: {}
-        Date: {}
-        Timeframe: {}
-        Orientation: {}
-        Suggested entry: {}
-
-    Best of luck,
-    Rust-Bot"#,
-            strategy,
-            setup.symbol,
-            setup.candle.timestamp,
-            setup.interval,
-            setup.orientation,
-            setup.candle.close,
-        );
-        s.to_string()
+    async fn send_email(subject: &str, text: String, html: String) -> Result<()> {
+        let config = EmailConfig::from_env()?;
+
+        let mut builder = Message::builder()
+            .from(config.sender.clone())
+            .subject(subject);
+
+        for recipient in &config.recipients {
+            builder = builder.to(recipient.clone());
+        }
+
+        let email = builder
+            .multipart(MultiPart::alternative_plain_html(text, html))
+            .map_err(|e| anyhow!("Unable to create notification email: {}", e))?;
+
+        match config.transport()?.send(email).await {
+            Ok(_) => {
+                info!(subject, "Email sent successfully");
+                Ok(())
+            }
+            Err(e) => Err(anyhow!(e)),
+        }
     }
 }
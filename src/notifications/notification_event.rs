@@ -0,0 +1,213 @@
+use crate::models::setups::setup::Setup;
+use std::fmt::{Display, Formatter};
+
+/// Reason for a Trade being exited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitReason {
+    TakeProfit,
+    StopLoss,
+    Manual,
+}
+
+impl Display for ExitReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TakeProfit => write!(f, "Take-profit"),
+            Self::StopLoss => write!(f, "Stop-loss"),
+            Self::Manual => write!(f, "Manual close"),
+        }
+    }
+}
+
+/// The different kinds of events that can be notified, used to enable or
+/// disable notifications per event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotificationKind {
+    SetupFound,
+    EntryFilled,
+    EntryFailed,
+    TradeExited,
+    ExitFailed,
+    WebsocketDisconnected,
+    RiskLimitHit,
+}
+
+/// # NotificationEvent
+///
+/// Typed event covering the full trade lifecycle, from a setup being found
+/// until the resulting Trade has been exited.
+#[derive(Debug, Clone)]
+pub enum NotificationEvent {
+    SetupFound {
+        setup: Setup,
+        strategy: String,
+        recent_closes: Vec<f64>,
+    },
+    EntryFilled {
+        setup: Setup,
+        dollar_value: f64,
+    },
+    EntryFailed {
+        setup: Setup,
+        error: String,
+    },
+    TradeExited {
+        setup: Setup,
+        reason: ExitReason,
+        exit_price: f64,
+        pnl: f64, // Realized profit/loss in decimals, 0.01 = 1%
+        dollar_pnl: f64,
+    },
+    ExitFailed {
+        setup: Setup,
+        error: String,
+    },
+    WebsocketDisconnected {
+        source: String,
+        error: String,
+    },
+    RiskLimitHit {
+        strategy: String,
+        limit: String,
+    },
+}
+
+impl NotificationEvent {
+    pub fn kind(&self) -> NotificationKind {
+        match self {
+            Self::SetupFound { .. } => NotificationKind::SetupFound,
+            Self::EntryFilled { .. } => NotificationKind::EntryFilled,
+            Self::EntryFailed { .. } => NotificationKind::EntryFailed,
+            Self::TradeExited { .. } => NotificationKind::TradeExited,
+            Self::ExitFailed { .. } => NotificationKind::ExitFailed,
+            Self::WebsocketDisconnected { .. } => NotificationKind::WebsocketDisconnected,
+            Self::RiskLimitHit { .. } => NotificationKind::RiskLimitHit,
+        }
+    }
+
+    pub fn subject(&self) -> String {
+        match self {
+            Self::SetupFound { setup, .. } => format!("Setup found for {}!", setup.symbol),
+            Self::EntryFilled { setup, .. } => format!("Entered trade in {}", setup.symbol),
+            Self::EntryFailed { setup, .. } => format!("Failed to enter {}!", setup.symbol),
+            Self::TradeExited { setup, reason, .. } => {
+                format!("{} reached for {}", reason, setup.symbol)
+            }
+            Self::ExitFailed { setup, .. } => format!("Failed to exit {}!", setup.symbol),
+            Self::WebsocketDisconnected { source, .. } => {
+                format!("{} websocket disconnected!", source)
+            }
+            Self::RiskLimitHit { strategy, .. } => format!("Risk limit hit for {}", strategy),
+        }
+    }
+
+    /// Labelled values describing the event, shared by the plain text and
+    /// HTML templates.
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::SetupFound {
+                setup, strategy, ..
+            } => vec![
+                ("Strategy", strategy.clone()),
+                ("Symbol", setup.symbol.clone()),
+                ("Date", setup.candle.timestamp.to_string()),
+                ("Timeframe", setup.interval.to_string()),
+                ("Orientation", setup.orientation.to_string()),
+                ("Suggested entry", setup.candle.close.to_string()),
+            ],
+            Self::EntryFilled {
+                setup,
+                dollar_value,
+            } => vec![
+                ("Symbol", setup.symbol.clone()),
+                ("Orientation", setup.orientation.to_string()),
+                ("Setup price", setup.candle.close.to_string()),
+                ("Position size", format!("{:.2} USD", dollar_value)),
+            ],
+            Self::EntryFailed { setup, error } | Self::ExitFailed { setup, error } => vec![
+                ("Symbol", setup.symbol.clone()),
+                ("Orientation", setup.orientation.to_string()),
+                ("Setup date", setup.candle.timestamp.to_string()),
+                ("Error", error.clone()),
+            ],
+            Self::TradeExited {
+                setup,
+                reason,
+                exit_price,
+                pnl,
+                dollar_pnl,
+            } => vec![
+                ("Symbol", setup.symbol.clone()),
+                ("Orientation", setup.orientation.to_string()),
+                ("Exit reason", reason.to_string()),
+                ("Entry price", setup.candle.close.to_string()),
+                ("Exit price", exit_price.to_string()),
+                (
+                    "Realized P&L",
+                    format!("{:.2}% ({:.2} USD)", pnl * 100.0, dollar_pnl),
+                ),
+            ],
+            Self::WebsocketDisconnected { source, error } => {
+                vec![("Source", source.clone()), ("Error", error.clone())]
+            }
+            Self::RiskLimitHit { strategy, limit } => {
+                vec![("Strategy", strategy.clone()), ("Limit", limit.clone())]
+            }
+        }
+    }
+
+    pub fn body(&self) -> String {
+        let content = self
+            .fields()
+            .iter()
+            .map(|(label, value)| format!("        {}: {}", label, value))
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        format!(
+            r#"{}:
+
+{}
+
+    Best of luck,
+    Rust-Bot"#,
+            self.subject(),
+            content
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::setups::setup::Setup,
+        notifications::notification_event::{ExitReason, NotificationEvent, NotificationKind},
+    };
+
+    #[test]
+    fn trade_exited_body_contains_pnl() {
+        let event = NotificationEvent::TradeExited {
+            setup: Setup::dummy(),
+            reason: ExitReason::StopLoss,
+            exit_price: 99.0,
+            pnl: -0.1,
+            dollar_pnl: -10.0,
+        };
+
+        assert_eq!(event.kind(), NotificationKind::TradeExited);
+        assert!(event.subject().starts_with("Stop-loss reached"));
+        assert!(event.body().contains("Realized P&L: -10.00% (-10.00 USD)"));
+    }
+
+    #[test]
+    fn setup_found_body_contains_strategy() {
+        let event = NotificationEvent::SetupFound {
+            setup: Setup::dummy(),
+            strategy: "RSI Basic".to_string(),
+            recent_closes: vec![],
+        };
+
+        assert_eq!(event.kind(), NotificationKind::SetupFound);
+        assert!(event.body().contains("Strategy: RSI Basic"));
+    }
+}
@@ -0,0 +1,67 @@
+use crate::notifications::notification_event::NotificationKind;
+use std::env;
+
+/// # NotificationSettings
+///
+/// Per-event enable flags for notifications. All events are enabled by
+/// default and can be disabled through environment variables, e.g.
+/// `NOTIFY_ENTRY_FILLED=false`.
+#[derive(Debug, Clone, Copy)]
+pub struct NotificationSettings {
+    pub setup_found: bool,
+    pub entry_filled: bool,
+    pub entry_failed: bool,
+    pub trade_exited: bool,
+    pub exit_failed: bool,
+    pub websocket_disconnected: bool,
+    pub risk_limit_hit: bool,
+    pub daily_digest: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            setup_found: true,
+            entry_filled: true,
+            entry_failed: true,
+            trade_exited: true,
+            exit_failed: true,
+            websocket_disconnected: true,
+            risk_limit_hit: true,
+            daily_digest: true,
+        }
+    }
+}
+
+impl NotificationSettings {
+    pub fn from_env() -> Self {
+        let flag = |key: &str| {
+            env::var(key)
+                .map(|v| !matches!(v.to_lowercase().as_str(), "false" | "0" | "off"))
+                .unwrap_or(true)
+        };
+
+        Self {
+            setup_found: flag("NOTIFY_SETUP_FOUND"),
+            entry_filled: flag("NOTIFY_ENTRY_FILLED"),
+            entry_failed: flag("NOTIFY_ENTRY_FAILED"),
+            trade_exited: flag("NOTIFY_TRADE_EXITED"),
+            exit_failed: flag("NOTIFY_EXIT_FAILED"),
+            websocket_disconnected: flag("NOTIFY_WEBSOCKET_DISCONNECTED"),
+            risk_limit_hit: flag("NOTIFY_RISK_LIMIT_HIT"),
+            daily_digest: flag("NOTIFY_DAILY_DIGEST"),
+        }
+    }
+
+    pub fn is_enabled(&self, kind: NotificationKind) -> bool {
+        match kind {
+            NotificationKind::SetupFound => self.setup_found,
+            NotificationKind::EntryFilled => self.entry_filled,
+            NotificationKind::EntryFailed => self.entry_failed,
+            NotificationKind::TradeExited => self.trade_exited,
+            NotificationKind::ExitFailed => self.exit_failed,
+            NotificationKind::WebsocketDisconnected => self.websocket_disconnected,
+            NotificationKind::RiskLimitHit => self.risk_limit_hit,
+        }
+    }
+}
@@ -1,7 +1,11 @@
 use crate::{
-    indicators::indicator_type::IndicatorType,
+    indicators::{
+        dynamic_pivots::DynamicPivots, indicator::Indicator, indicator_type::IndicatorType,
+    },
     models::{
-        candle::Candle, setups::setup::Setup, strategy_orientation::StrategyOrientation,
+        candle_buffer::{CandleRow, CandleView},
+        setups::setup::Setup,
+        strategy_orientation::StrategyOrientation,
         traits::requires_indicators::RequiresIndicators,
     },
     resolution_strategies::is_resolution_strategy::IsResolutionStrategy,
@@ -26,7 +30,7 @@ impl IsResolutionStrategy for DynamicPivotResolution {
     fn stop_loss_reached(
         &self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView,
     ) -> Result<bool> {
         let len = candles.len();
 
@@ -35,33 +39,22 @@ impl IsResolutionStrategy for DynamicPivotResolution {
             return Err(anyhow!(msg));
         }
 
-        let ind_type = IndicatorType::DynamicPivot(self.len);
-        let pivots = candles[len - (self.len + 1)]
-            .indicators
-            .get(&ind_type)
-            .context(format!(
-                "Unable to find DynamicPivots indicator of length {}",
-                self.len
-            ))?
-            .as_dynamic_pivots()
-            .context("Unable to convert to Indicator::DynamicPivots")?;
-
-        match orientation {
-            StrategyOrientation::Long => {
-                let bound = pivots.low.context("No pivot provided for comparison")?;
-                Ok(candles[len - 1].close < bound)
-            }
-            StrategyOrientation::Short => {
-                let bound = pivots.high.context("No pivot provided for comparison")?;
-                Ok(candles[len - 1].close > bound)
-            }
-        }
+        // No stop-loss until a pivot has formed
+        let Some(pivots) = self.pivots(candles.get(len - (self.len + 1)))? else {
+            return Ok(false);
+        };
+
+        let last = candles.last().context("No candle provided.")?;
+        Ok(match orientation {
+            StrategyOrientation::Long => pivots.low.is_some_and(|bound| last.close < bound),
+            StrategyOrientation::Short => pivots.high.is_some_and(|bound| last.close > bound),
+        })
     }
 
     fn take_profit_reached(
         &self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView,
     ) -> Result<bool> {
         let len = candles.len();
 
@@ -70,27 +63,16 @@ impl IsResolutionStrategy for DynamicPivotResolution {
             return Err(anyhow!(msg));
         }
 
-        let ind_type = IndicatorType::DynamicPivot(self.len);
-        let pivots = candles[len - (self.len + 1)]
-            .indicators
-            .get(&ind_type)
-            .context(format!(
-                "Unable to find DynamicPivots indicator of length {}",
-                self.len
-            ))?
-            .as_dynamic_pivots()
-            .context("Unable to convert to Indicator::DynamicPivots")?;
-
-        match orientation {
-            StrategyOrientation::Long => {
-                let bound = pivots.high.context("No pivot provided for comparison")?;
-                Ok(candles[len - 1].high > bound)
-            }
-            StrategyOrientation::Short => {
-                let bound = pivots.low.context("No pivot provided for comparison")?;
-                Ok(candles[len - 1].low < bound)
-            }
-        }
+        // No take-profit until a pivot has formed
+        let Some(pivots) = self.pivots(candles.get(len - (self.len + 1)))? else {
+            return Ok(false);
+        };
+
+        let last = candles.last().context("No candle provided.")?;
+        Ok(match orientation {
+            StrategyOrientation::Long => pivots.high.is_some_and(|bound| last.high > bound),
+            StrategyOrientation::Short => pivots.low.is_some_and(|bound| last.low < bound),
+        })
     }
 
     fn set_initial_values(&mut self, _setup: &Setup) -> Result<()> {
@@ -109,4 +91,20 @@ impl DynamicPivotResolution {
     pub fn new() -> Self {
         Self { len: 15 }
     }
+
+    /// Returns the pivots of the candle, or None while the indicator is
+    /// still warming up.
+    fn pivots(&self, candle: Option<CandleRow>) -> Result<Option<DynamicPivots>> {
+        let indicator = candle
+            .and_then(|candle| candle.indicator(&IndicatorType::DynamicPivot(self.len)))
+            .context(format!(
+                "Unable to find DynamicPivots indicator of length {}",
+                self.len
+            ))?;
+
+        match indicator {
+            Indicator::DynamicPivot(pivots) => Ok(*pivots),
+            _ => Err(anyhow!("Unable to convert to Indicator::DynamicPivots")),
+        }
+    }
 }
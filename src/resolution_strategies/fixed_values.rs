@@ -1,12 +1,12 @@
 use crate::{
     indicators::indicator_type::IndicatorType,
     models::{
-        candle::Candle, setups::setup::Setup, strategy_orientation::StrategyOrientation,
+        candle_buffer::CandleView, setups::setup::Setup, strategy_orientation::StrategyOrientation,
         traits::requires_indicators::RequiresIndicators,
     },
     resolution_strategies::is_resolution_strategy::IsResolutionStrategy,
 };
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,13 +27,11 @@ impl IsResolutionStrategy for FixedValuesResolution {
     fn stop_loss_reached(
         &self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView,
     ) -> Result<bool> {
-        if candles.len() < 1 {
-            return Err(anyhow!("No candle provided for fixed value resolution."));
-        }
-
-        let candle = &candles[0];
+        let candle = candles
+            .first()
+            .context("No candle provided for fixed value resolution.")?;
 
         Ok(match orientation {
             StrategyOrientation::Long => candle.close < self.low,
@@ -44,13 +42,11 @@ impl IsResolutionStrategy for FixedValuesResolution {
     fn take_profit_reached(
         &self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView,
     ) -> Result<bool> {
-        if candles.len() < 1 {
-            return Err(anyhow!("No candle provided for fixed value resolution."));
-        }
-
-        let candle = &candles[0];
+        let candle = candles
+            .first()
+            .context("No candle provided for fixed value resolution.")?;
 
         Ok(match orientation {
             StrategyOrientation::Long => candle.high > self.high,
@@ -1,6 +1,8 @@
 use crate::models::traits::requires_indicators::RequiresIndicators;
 use crate::{
-    models::{candle::Candle, setups::setup::Setup, strategy_orientation::StrategyOrientation},
+    models::{
+        candle_buffer::CandleView, setups::setup::Setup, strategy_orientation::StrategyOrientation,
+    },
     resolution_strategies::is_resolution_strategy::IsResolutionStrategy,
 };
 use serde::{Deserialize, Serialize};
@@ -30,7 +32,7 @@ impl IsResolutionStrategy for InstantResolution {
     fn stop_loss_reached(
         &self,
         _orientation: &StrategyOrientation,
-        _candles: &[Candle],
+        _candles: CandleView,
     ) -> anyhow::Result<bool> {
         Ok(true)
     }
@@ -38,7 +40,7 @@ impl IsResolutionStrategy for InstantResolution {
     fn take_profit_reached(
         &self,
         _orientation: &StrategyOrientation,
-        _candles: &[Candle],
+        _candles: CandleView,
     ) -> anyhow::Result<bool> {
         Ok(true)
     }
@@ -1,5 +1,5 @@
 use crate::models::{
-    candle::Candle, setups::setup::Setup, strategy_orientation::StrategyOrientation,
+    candle_buffer::CandleView, setups::setup::Setup, strategy_orientation::StrategyOrientation,
     traits::requires_indicators::RequiresIndicators,
 };
 use anyhow::Result;
@@ -15,14 +15,14 @@ pub trait IsResolutionStrategy: RequiresIndicators {
     fn stop_loss_reached(
         &self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView,
     ) -> Result<bool>;
 
     /// Check whether take-profit has been reached for the last candle
     fn take_profit_reached(
         &self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView,
     ) -> Result<bool>;
 
     /// Set initial values from setup if applicable
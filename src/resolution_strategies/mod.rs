@@ -6,3 +6,4 @@ pub mod percentage;
 pub mod pmarp_or_bbwp_vs_percentage;
 pub mod pmarp_vs_percentage;
 pub mod resolution_strategy;
+pub mod sr_zones;
@@ -1,12 +1,12 @@
 use crate::{
     indicators::indicator_type::IndicatorType,
     models::{
-        candle::Candle, setups::setup::Setup, strategy_orientation::StrategyOrientation,
+        candle_buffer::CandleView, setups::setup::Setup, strategy_orientation::StrategyOrientation,
         traits::requires_indicators::RequiresIndicators,
     },
     resolution_strategies::is_resolution_strategy::IsResolutionStrategy,
 };
-use anyhow::{anyhow, Result};
+use anyhow::{Context, Result};
 
 #[derive(Debug, Clone)]
 pub struct PercentageResolution {
@@ -27,13 +27,11 @@ impl IsResolutionStrategy for PercentageResolution {
     fn stop_loss_reached(
         &self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView,
     ) -> Result<bool> {
-        if candles.is_empty() {
-            return Err(anyhow!("No candle provided for percentage resolution."));
-        }
-
-        let candle = &candles[0];
+        let candle = candles
+            .first()
+            .context("No candle provided for percentage resolution.")?;
 
         Ok(match orientation {
             StrategyOrientation::Long => {
@@ -48,13 +46,11 @@ impl IsResolutionStrategy for PercentageResolution {
     fn take_profit_reached(
         &self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView,
     ) -> Result<bool> {
-        if candles.is_empty() {
-            return Err(anyhow!("No candle provided for percentage resolution."));
-        }
-
-        let candle = &candles[0];
+        let candle = candles
+            .first()
+            .context("No candle provided for percentage resolution.")?;
 
         Ok(match orientation {
             StrategyOrientation::Long => {
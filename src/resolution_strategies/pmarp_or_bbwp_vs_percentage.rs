@@ -1,7 +1,7 @@
 use crate::{
     indicators::indicator_type::IndicatorType,
     models::{
-        candle::Candle, ma_type::MAType, setups::setup::Setup,
+        candle_buffer::CandleView, ma_type::MAType, setups::setup::Setup,
         strategy_orientation::StrategyOrientation, traits::requires_indicators::RequiresIndicators,
     },
     resolution_strategies::is_resolution_strategy::IsResolutionStrategy,
@@ -50,7 +50,7 @@ impl IsResolutionStrategy for PmarpOrBbwpVsPercentageResolution {
     fn stop_loss_reached(
         &self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView,
     ) -> Result<bool> {
         let len = candles.len();
 
@@ -61,7 +61,7 @@ impl IsResolutionStrategy for PmarpOrBbwpVsPercentageResolution {
         match orientation {
             StrategyOrientation::Long => {
                 let init_value = self.initial_value.context("Expected initial value")?;
-                Ok((1.0 - &candles[len - 1].close / init_value) * 100.0 > self.drawdown_threshold)
+                Ok((1.0 - candles.close()[len - 1] / init_value) * 100.0 > self.drawdown_threshold)
             }
             StrategyOrientation::Short => Err(anyhow!(ERR_MESSAGE)),
         }
@@ -70,7 +70,7 @@ impl IsResolutionStrategy for PmarpOrBbwpVsPercentageResolution {
     fn take_profit_reached(
         &self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView,
     ) -> Result<bool> {
         let len = candles.len();
         if len == 0 {
@@ -79,19 +79,28 @@ impl IsResolutionStrategy for PmarpOrBbwpVsPercentageResolution {
             ));
         }
 
-        let ind_type =
-            IndicatorType::PMARP(self.pmarp_len, self.pmarp_lookback, self.pmarp_ma_type);
-        let pmarp = candles[len - 1]
-            .indicators
-            .get(&ind_type)
+        let ind_type = IndicatorType::PMARP(
+            self.pmarp_len,
+            self.pmarp_lookback,
+            self.pmarp_ma_type,
+            MAType::SMA,
+        );
+        let pmarp = candles
+            .get(len - 1)
+            .and_then(|candle| candle.indicator(&ind_type))
             .context("Unable to get pmarp for pmarp vs % resolution.")?
             .as_pmarp()
             .context("Unable to convert indicator to pmarp in pmarp or bbwp vs % resolution")?;
 
-        let ind_type = IndicatorType::BBWP(self.bbwp_len, self.bbwp_lookback);
-        let bbwp = candles[len - 1]
-            .indicators
-            .get(&ind_type)
+        let ind_type = IndicatorType::BBWP(
+            self.bbwp_len,
+            self.bbwp_lookback,
+            self.bbwp_sma_len,
+            MAType::SMA,
+        );
+        let bbwp = candles
+            .get(len - 1)
+            .and_then(|candle| candle.indicator(&ind_type))
             .context("Unable to get bbwp for pmarp or bbwp vs % resolution.")?
             .as_bbwp()
             .context("Unable to convert indicator to bbwp in pmarp or bbwp vs % resolution")?;
@@ -114,8 +123,18 @@ impl IsResolutionStrategy for PmarpOrBbwpVsPercentageResolution {
 impl RequiresIndicators for PmarpOrBbwpVsPercentageResolution {
     fn required_indicators(&self) -> Vec<IndicatorType> {
         vec![
-            IndicatorType::PMARP(self.pmarp_len, self.pmarp_lookback, self.pmarp_ma_type),
-            IndicatorType::BBWP(self.bbwp_len, self.bbwp_lookback),
+            IndicatorType::PMARP(
+                self.pmarp_len,
+                self.pmarp_lookback,
+                self.pmarp_ma_type,
+                MAType::SMA,
+            ),
+            IndicatorType::BBWP(
+                self.bbwp_len,
+                self.bbwp_lookback,
+                self.bbwp_sma_len,
+                MAType::SMA,
+            ),
         ]
     }
 }
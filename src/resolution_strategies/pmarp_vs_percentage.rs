@@ -1,7 +1,7 @@
 use crate::{
     indicators::indicator_type::IndicatorType,
     models::{
-        candle::Candle, ma_type::MAType, setups::setup::Setup,
+        candle_buffer::CandleView, ma_type::MAType, setups::setup::Setup,
         strategy_orientation::StrategyOrientation, traits::requires_indicators::RequiresIndicators,
     },
     resolution_strategies::is_resolution_strategy::IsResolutionStrategy,
@@ -44,7 +44,7 @@ impl IsResolutionStrategy for PmarpVsPercentageResolution {
     fn stop_loss_reached(
         &self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView,
     ) -> Result<bool> {
         let len = candles.len();
 
@@ -55,7 +55,7 @@ impl IsResolutionStrategy for PmarpVsPercentageResolution {
         match orientation {
             StrategyOrientation::Long => {
                 let init_value = self.initial_value.context("Expected initial value")?;
-                Ok((1.0 - &candles[len - 1].close / init_value) * 100.0 > self.drawdown_threshold)
+                Ok((1.0 - candles.close()[len - 1] / init_value) * 100.0 > self.drawdown_threshold)
             }
             StrategyOrientation::Short => Err(anyhow!(ERR_MESSAGE)),
         }
@@ -64,18 +64,22 @@ impl IsResolutionStrategy for PmarpVsPercentageResolution {
     fn take_profit_reached(
         &self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView,
     ) -> Result<bool> {
         let len = candles.len();
         if len == 0 {
             return Err(anyhow!("No candle passed for pmarp vs % resolution."));
         }
 
-        let ind_type =
-            IndicatorType::PMARP(self.pmarp_len, self.pmarp_lookback, self.pmarp_ma_type);
-        let pmarp = candles[len - 1]
-            .indicators
-            .get(&ind_type)
+        let ind_type = IndicatorType::PMARP(
+            self.pmarp_len,
+            self.pmarp_lookback,
+            self.pmarp_ma_type,
+            MAType::SMA,
+        );
+        let pmarp = candles
+            .get(len - 1)
+            .and_then(|candle| candle.indicator(&ind_type))
             .context("Unable to get pmarp for pmarp vs % resolution.")?
             .as_pmarp()
             .context("Unable to convert indicator to pmarp in pmarp vs % resolution")?;
@@ -99,6 +103,7 @@ impl RequiresIndicators for PmarpVsPercentageResolution {
             self.pmarp_len,
             self.pmarp_lookback,
             self.pmarp_ma_type,
+            MAType::SMA,
         )]
     }
 }
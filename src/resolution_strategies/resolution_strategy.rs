@@ -1,14 +1,14 @@
 use crate::{
     indicators::indicator_type::IndicatorType,
     models::{
-        candle::Candle, setups::setup::Setup, strategy_orientation::StrategyOrientation,
+        candle_buffer::CandleView, setups::setup::Setup, strategy_orientation::StrategyOrientation,
         traits::requires_indicators::RequiresIndicators,
     },
     resolution_strategies::{
         dynamic_pivot::DynamicPivotResolution, fixed_values::FixedValuesResolution,
         instant_resolution::InstantResolution, is_resolution_strategy::IsResolutionStrategy,
         pmarp_or_bbwp_vs_percentage::PmarpOrBbwpVsPercentageResolution,
-        pmarp_vs_percentage::PmarpVsPercentageResolution,
+        pmarp_vs_percentage::PmarpVsPercentageResolution, sr_zones::SRZoneResolution,
     },
 };
 use anyhow::Result;
@@ -22,6 +22,7 @@ pub enum ResolutionStrategy {
     PmarpVsPercentage(PmarpVsPercentageResolution),
     PmarpOrBbwpVsPercentage(PmarpOrBbwpVsPercentageResolution),
     Instant(InstantResolution),
+    SRZones(SRZoneResolution),
 }
 
 impl IsResolutionStrategy for ResolutionStrategy {
@@ -32,6 +33,7 @@ impl IsResolutionStrategy for ResolutionStrategy {
             ResolutionStrategy::PmarpVsPercentage(pvp) => pvp.n_candles_stop_loss(),
             ResolutionStrategy::PmarpOrBbwpVsPercentage(pbvp) => pbvp.n_candles_stop_loss(),
             ResolutionStrategy::Instant(i) => i.n_candles_stop_loss(),
+            ResolutionStrategy::SRZones(sr) => sr.n_candles_stop_loss(),
         }
     }
 
@@ -42,13 +44,14 @@ impl IsResolutionStrategy for ResolutionStrategy {
             ResolutionStrategy::PmarpVsPercentage(pvp) => pvp.n_candles_take_profit(),
             ResolutionStrategy::PmarpOrBbwpVsPercentage(pvp) => pvp.n_candles_take_profit(),
             ResolutionStrategy::Instant(i) => i.n_candles_take_profit(),
+            ResolutionStrategy::SRZones(sr) => sr.n_candles_take_profit(),
         }
     }
 
     fn stop_loss_reached(
         &self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView,
     ) -> Result<bool> {
         match self {
             ResolutionStrategy::DynamicPivot(dp) => dp.stop_loss_reached(orientation, candles),
@@ -60,13 +63,14 @@ impl IsResolutionStrategy for ResolutionStrategy {
                 pvp.stop_loss_reached(orientation, candles)
             }
             ResolutionStrategy::Instant(i) => i.stop_loss_reached(orientation, candles),
+            ResolutionStrategy::SRZones(sr) => sr.stop_loss_reached(orientation, candles),
         }
     }
 
     fn take_profit_reached(
         &self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView,
     ) -> Result<bool> {
         match self {
             ResolutionStrategy::DynamicPivot(dp) => dp.take_profit_reached(orientation, candles),
@@ -78,6 +82,7 @@ impl IsResolutionStrategy for ResolutionStrategy {
                 pvp.take_profit_reached(orientation, candles)
             }
             ResolutionStrategy::Instant(i) => i.take_profit_reached(orientation, candles),
+            ResolutionStrategy::SRZones(sr) => sr.take_profit_reached(orientation, candles),
         }
     }
 
@@ -88,6 +93,7 @@ impl IsResolutionStrategy for ResolutionStrategy {
             ResolutionStrategy::PmarpVsPercentage(pvp) => pvp.set_initial_values(setup),
             ResolutionStrategy::PmarpOrBbwpVsPercentage(pvp) => pvp.set_initial_values(setup),
             ResolutionStrategy::Instant(i) => i.set_initial_values(setup),
+            ResolutionStrategy::SRZones(sr) => sr.set_initial_values(setup),
         }
     }
 }
@@ -100,6 +106,7 @@ impl RequiresIndicators for ResolutionStrategy {
             ResolutionStrategy::PmarpVsPercentage(pvp) => pvp.required_indicators(),
             ResolutionStrategy::PmarpOrBbwpVsPercentage(pvp) => pvp.required_indicators(),
             ResolutionStrategy::Instant(i) => i.required_indicators(),
+            ResolutionStrategy::SRZones(sr) => sr.required_indicators(),
         }
     }
 }
@@ -132,6 +139,7 @@ impl Display for ResolutionStrategy {
                 )
             }
             Self::Instant(_) => write!(f, "Instant"),
+            Self::SRZones(sr) => write!(f, "SRZones({},{},{})", sr.len, sr.lookback, sr.width),
         }
     }
 }
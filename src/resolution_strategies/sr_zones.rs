@@ -0,0 +1,114 @@
+use crate::{
+    indicators::indicator_type::IndicatorType,
+    models::{
+        candle_buffer::CandleView, setups::setup::Setup, strategy_orientation::StrategyOrientation,
+        traits::requires_indicators::RequiresIndicators,
+    },
+    resolution_strategies::is_resolution_strategy::IsResolutionStrategy,
+};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Resolves setups at the support and resistance zones nearest to the setup
+/// candle. Long setups take profit at the resistance zone above and stop out
+/// below the support zone, short setups the other way around.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SRZoneResolution {
+    pub len: usize,
+    pub lookback: usize,
+    pub width: f64,
+    pub target: Option<f64>,
+    pub stop: Option<f64>,
+}
+
+impl IsResolutionStrategy for SRZoneResolution {
+    fn n_candles_stop_loss(&self) -> usize {
+        1
+    }
+
+    fn n_candles_take_profit(&self) -> usize {
+        1
+    }
+
+    fn stop_loss_reached(
+        &self,
+        orientation: &StrategyOrientation,
+        candles: CandleView,
+    ) -> Result<bool> {
+        let candle = candles
+            .last()
+            .context("No candle provided for support/resistance resolution.")?;
+        let stop = self.stop.context("Stop loss zone not set.")?;
+
+        Ok(match orientation {
+            StrategyOrientation::Long => candle.close < stop,
+            StrategyOrientation::Short => candle.close > stop,
+        })
+    }
+
+    fn take_profit_reached(
+        &self,
+        orientation: &StrategyOrientation,
+        candles: CandleView,
+    ) -> Result<bool> {
+        let candle = candles
+            .last()
+            .context("No candle provided for support/resistance resolution.")?;
+        let target = self.target.context("Take profit zone not set.")?;
+
+        Ok(match orientation {
+            StrategyOrientation::Long => candle.high > target,
+            StrategyOrientation::Short => candle.low < target,
+        })
+    }
+
+    fn set_initial_values(&mut self, setup: &Setup) -> Result<()> {
+        let zones = setup
+            .candle
+            .indicators
+            .get(&self.indicator_type())
+            .context("Unable to find SRZones indicator on setup candle.")?
+            .as_sr_zones()
+            .context("Unable to convert to Indicator::SRZones")?;
+
+        let support = zones
+            .support
+            .ok_or(anyhow!("No support zone below setup."))?;
+        let resistance = zones
+            .resistance
+            .ok_or(anyhow!("No resistance zone above setup."))?;
+
+        let (target, stop) = match setup.orientation {
+            StrategyOrientation::Long => (resistance.low, support.low),
+            StrategyOrientation::Short => (support.high, resistance.high),
+        };
+
+        self.target = Some(target);
+        self.stop = Some(stop);
+
+        Ok(())
+    }
+}
+
+impl RequiresIndicators for SRZoneResolution {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        vec![self.indicator_type()]
+    }
+}
+
+impl SRZoneResolution {
+    #[allow(dead_code)]
+    pub fn new(len: usize, lookback: usize, width: f64) -> Self {
+        Self {
+            len,
+            lookback,
+            width,
+            target: None,
+            stop: None,
+        }
+    }
+
+    fn indicator_type(&self) -> IndicatorType {
+        IndicatorType::SRZones(self.len, self.lookback, self.width.into())
+    }
+}
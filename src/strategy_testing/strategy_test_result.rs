@@ -1,3 +1,6 @@
+use crate::models::market_regime::MarketRegime;
+use std::collections::HashMap;
+
 #[derive(Debug, Clone)]
 #[allow(dead_code)] // TODO: Remove once used
 pub struct StrategyTestResult {
@@ -18,4 +21,11 @@ pub struct StrategyTestResult {
     pub loss_bars_std: f64,
     pub initial_account: f64,
     pub ending_account: f64,
+    /// Results of the setups taken in each market regime, only filled when
+    /// testing with a `RegimeClassifier`.
+    pub by_regime: HashMap<MarketRegime, StrategyTestResult>,
+    /// Results of the setups taken while testing with a `RegimeClassifier`
+    /// that couldn't classify their candle yet. Together with `by_regime`
+    /// this covers every setup.
+    pub unclassified: Option<Box<StrategyTestResult>>,
 }
@@ -1,7 +1,9 @@
 use crate::{
+    models::market_regime::MarketRegime,
     strategy_testing::strategy_test_result::StrategyTestResult,
     utils::math::{sma, std},
 };
+use std::collections::HashMap;
 
 const INITIAL_ACCOUNT_SIZE: f64 = 100_000.0;
 
@@ -15,6 +17,8 @@ pub struct StrategyTestResultBuilder {
     pub win_bars: Vec<usize>,
     pub losses: Vec<f64>,
     pub loss_bars: Vec<usize>,
+    pub regimes: HashMap<MarketRegime, StrategyTestResultBuilder>,
+    pub unclassified: Option<Box<StrategyTestResultBuilder>>,
 }
 
 impl StrategyTestResultBuilder {
@@ -28,6 +32,8 @@ impl StrategyTestResultBuilder {
             win_bars: Vec::new(),
             losses: Vec::new(),
             loss_bars: Vec::new(),
+            regimes: HashMap::new(),
+            unclassified: None,
         }
     }
 
@@ -47,6 +53,25 @@ impl StrategyTestResultBuilder {
         self.account_size += self.account_size * outcome;
     }
 
+    /// Adds an outcome of a setup taken in the given market regime, which is
+    /// also tracked separately for the regime.
+    pub fn add_regime_outcome(&mut self, regime: MarketRegime, outcome: f64, n_bars: usize) {
+        self.add_outcome(outcome, n_bars);
+        self.regimes
+            .entry(regime)
+            .or_insert_with(StrategyTestResultBuilder::new)
+            .add_outcome(outcome, n_bars);
+    }
+
+    /// Adds an outcome of a setup taken before the market regime could be
+    /// classified, e.g. while the indicators of the classifier warm up.
+    pub fn add_unclassified_outcome(&mut self, outcome: f64, n_bars: usize) {
+        self.add_outcome(outcome, n_bars);
+        self.unclassified
+            .get_or_insert_with(|| Box::new(StrategyTestResultBuilder::new()))
+            .add_outcome(outcome, n_bars);
+    }
+
     pub fn build(self) -> StrategyTestResult {
         let total_wins = self.wins.iter().sum::<f64>();
         let total_losses = self.losses.iter().sum::<f64>();
@@ -97,6 +122,12 @@ impl StrategyTestResultBuilder {
             losses_std: std(&self.losses, sma(&self.losses)),
             win_bars_std: std(&f_win_bars, sma(&f_win_bars)),
             loss_bars_std: std(&f_loss_bars, sma(&f_loss_bars)),
+            by_regime: self
+                .regimes
+                .into_iter()
+                .map(|(regime, builder)| (regime, builder.build()))
+                .collect(),
+            unclassified: self.unclassified.map(|builder| Box::new(builder.build())),
         }
     }
 }
@@ -1,5 +1,8 @@
 use crate::{
-    models::{candle::Candle, interval::Interval, traits::trading_strategy::TradingStrategy},
+    models::{
+        candle_buffer::CandleView, interval::Interval, regime_classifier::RegimeClassifier,
+        traits::trading_strategy::TradingStrategy,
+    },
     resolution_strategies::{
         is_resolution_strategy::IsResolutionStrategy, resolution_strategy::ResolutionStrategy,
     },
@@ -9,42 +12,67 @@ use crate::{
     },
 };
 use anyhow::{Context, Result};
+use tracing::{debug, info, info_span};
 
 pub struct StrategyTester;
 
 impl StrategyTester {
+    #[allow(dead_code)]
     pub fn test_strategy(
         strat: &mut Box<dyn TradingStrategy>,
-        candles: &[Candle],
+        candles: CandleView,
+    ) -> Result<StrategyTestResult> {
+        Self::run(strat, candles, None)
+    }
+
+    /// Tests the strategy and breaks the results down by the market regime
+    /// on the candle of each setup. The candles need the indicators required
+    /// by the classifier populated.
+    pub fn test_strategy_by_regime(
+        strat: &mut Box<dyn TradingStrategy>,
+        candles: CandleView,
+        classifier: &RegimeClassifier,
+    ) -> Result<StrategyTestResult> {
+        Self::run(strat, candles, Some(classifier))
+    }
+
+    fn run(
+        strat: &mut Box<dyn TradingStrategy>,
+        candles: CandleView,
+        classifier: Option<&RegimeClassifier>,
     ) -> Result<StrategyTestResult> {
         let orientation = strat.orientation();
         let needed_candles = strat.candles_needed_for_setup();
         let mut result_builder = StrategyTestResultBuilder::new();
         let mut next_i = 0;
 
-        println!("Starting Strategy test for {}", strat);
+        let _span = info_span!("StrategyTester", strategy = %strat).entered();
+        info!(n_candles = candles.len(), "Starting strategy test");
 
         // Loop over the needed candles to determine a setup and gather results.
-        for (i, window) in candles.windows(needed_candles).enumerate() {
-            // Bump index up to compensate for window size
-            let i = i + needed_candles;
+        for i in needed_candles..=candles.len() {
+            let window = candles.window(i - needed_candles..i);
 
             if i % 1000 == 0 {
-                println!("Testing Iteration {:#?}", i);
+                debug!(iteration = i, "Testing iteration");
             }
 
+            // Check every candle, as is done live, so that stateful strategies
+            // see the same candles in backtests
+            let sb = strat.check_last_for_setup(window);
+
             if i < needed_candles || i < next_i {
                 continue;
             }
 
-            let sb = strat.check_last_for_setup(&window);
-
             if sb.is_none() {
                 continue;
             };
 
             let sb = sb.context("Expected SetupBuilder.")?;
             let setup = sb.symbol("TESTING").interval(&Interval::Day1).build()?;
+            let regime =
+                classifier.map(|c| candles.get(i - 1).and_then(|candle| c.classify(candle)));
 
             // Initialize resolution strategy
             let mut resolution_strategy = strat.default_resolution_strategy();
@@ -67,26 +95,38 @@ impl StrategyTester {
                     break;
                 };
 
-                let tp_candles = &candles[end - tp_candles_needed..end];
+                let tp_candles = candles.window(end - tp_candles_needed..end);
                 let take_profit_reached =
                     resolution_strategy.take_profit_reached(&orientation, tp_candles)?;
 
                 if take_profit_reached {
-                    let increase =
-                        tp_candles[tp_candles.len() - 1].close / setup.candle.close - 1.0;
-                    result_builder.add_outcome(increase, n_bars);
+                    let close = tp_candles.last().context("Expected candle.")?.close;
+                    let increase = close / setup.candle.close - 1.0;
+                    match regime {
+                        Some(Some(regime)) => {
+                            result_builder.add_regime_outcome(regime, increase, n_bars)
+                        }
+                        Some(None) => result_builder.add_unclassified_outcome(increase, n_bars),
+                        None => result_builder.add_outcome(increase, n_bars),
+                    }
 
                     break;
                 }
 
-                let sl_candles = &candles[end - sl_candles_needed..end];
+                let sl_candles = candles.window(end - sl_candles_needed..end);
                 let stop_loss_reached =
                     resolution_strategy.stop_loss_reached(&orientation, sl_candles)?;
 
                 if stop_loss_reached {
-                    let decrease =
-                        sl_candles[sl_candles.len() - 1].close / setup.candle.close - 1.0;
-                    result_builder.add_outcome(decrease, n_bars);
+                    let close = sl_candles.last().context("Expected candle.")?.close;
+                    let decrease = close / setup.candle.close - 1.0;
+                    match regime {
+                        Some(Some(regime)) => {
+                            result_builder.add_regime_outcome(regime, decrease, n_bars)
+                        }
+                        Some(None) => result_builder.add_unclassified_outcome(decrease, n_bars),
+                        None => result_builder.add_outcome(decrease, n_bars),
+                    }
                     break;
                 }
             }
@@ -96,7 +136,7 @@ impl StrategyTester {
             next_i = i + n_bars;
         }
 
-        println!("Strategy testing complete, results:",);
+        info!("Strategy testing complete");
         Ok(result_builder.build())
     }
 
@@ -104,8 +144,248 @@ impl StrategyTester {
     pub fn by_strategies(
         _trading_strat: &Box<dyn TradingStrategy>,
         _resolution_strat: &ResolutionStrategy,
-        _candles: &[Candle],
+        _candles: CandleView,
     ) -> StrategyTestResult {
         todo!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::indicator_type::IndicatorType,
+        models::{
+            candle_buffer::CandleView,
+            interval::Interval,
+            market_regime::MarketRegime,
+            regime_classifier::RegimeClassifier,
+            setups::setup_builder::SetupBuilder,
+            strategy_orientation::StrategyOrientation,
+            traits::{
+                has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+                trading_strategy::TradingStrategy,
+            },
+        },
+        resolution_strategies::{
+            dynamic_pivot::DynamicPivotResolution, is_resolution_strategy::IsResolutionStrategy,
+            resolution_strategy::ResolutionStrategy,
+        },
+        strategy_testing::strategy_tester::StrategyTester,
+        trading_strategies::{
+            ensemble::{ensemble_mode::EnsembleMode, ensemble_strategy::EnsembleStrategy},
+            public::{
+                bbwp_squeeze_breakout::BbwpSqueezeBreakout,
+                bollinger_mean_reversion::BollingerMeanReversion,
+                donchian_breakout::DonchianBreakout, ema_trend_pullback::EmaTrendPullback,
+                rsi_basic::RsiBasic, silver_cross::SilverCross, stochastic_cross::StochasticCross,
+            },
+        },
+        utils::data::candle_strategy,
+    };
+    use chrono::Weekday;
+    use serde_json::Value;
+    use std::{
+        collections::HashSet,
+        fmt::{Display, Formatter},
+    };
+
+    /// Takes a setup on every candle and counts the candles it was checked on.
+    #[derive(Debug, Clone)]
+    struct CountsChecks {
+        checks: usize,
+    }
+
+    impl HasMinLength for CountsChecks {
+        fn min_length(&self) -> usize {
+            1
+        }
+    }
+
+    impl TradingStrategy for CountsChecks {
+        fn new() -> Self {
+            Self { checks: 0 }
+        }
+
+        fn candles_needed_for_setup(&self) -> usize {
+            self.default_resolution_strategy().n_candles_take_profit()
+        }
+
+        fn check_last_for_setup(&mut self, candles: CandleView) -> Option<SetupBuilder> {
+            self.checks += 1;
+
+            Some(
+                SetupBuilder::new()
+                    .candle(candles.last()?)
+                    .orientation(&self.orientation()),
+            )
+        }
+
+        fn clone_box(&self) -> Box<dyn TradingStrategy> {
+            Box::new(self.clone())
+        }
+
+        fn default_resolution_strategy(&self) -> ResolutionStrategy {
+            ResolutionStrategy::DynamicPivot(DynamicPivotResolution::new())
+        }
+
+        fn orientation(&self) -> StrategyOrientation {
+            StrategyOrientation::Long
+        }
+
+        fn interval(&self) -> Interval {
+            Interval::Day1
+        }
+
+        fn trading_days(&self) -> HashSet<Weekday> {
+            HashSet::from([Weekday::Mon])
+        }
+
+        fn state(&self) -> Option<Value> {
+            Some(Value::from(self.checks))
+        }
+    }
+
+    impl RequiresIndicators for CountsChecks {
+        fn required_indicators(&self) -> Vec<IndicatorType> {
+            self.default_resolution_strategy().required_indicators()
+        }
+    }
+
+    impl Display for CountsChecks {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "CountsChecks")
+        }
+    }
+
+    #[test]
+    fn checks_every_candle_while_setups_play_out() {
+        let mut strategy: Box<dyn TradingStrategy> = Box::new(CountsChecks::new());
+        let buffer = candle_strategy::strategy_candles(strategy.as_ref());
+        let candles = buffer.view();
+
+        let n_windows = candles.len() - strategy.candles_needed_for_setup() + 1;
+
+        let result = StrategyTester::test_strategy(&mut strategy, candles).unwrap();
+
+        // Stateful strategies see every candle as they would live, including
+        // the candles on which they can't take a setup
+        assert_eq!(strategy.state(), Some(Value::from(n_windows)));
+        assert!(result.n_setups > 0);
+        assert!(result.n_setups < n_windows / 2);
+    }
+
+    #[test]
+    fn breaks_results_down_by_regime() {
+        let classifier = RegimeClassifier::new_args(5, 100, 1.0, 0.9);
+        let mut strategy: Box<dyn TradingStrategy> = Box::new(BollingerMeanReversion::new_args(
+            StrategyOrientation::Long,
+            20,
+            1.5,
+        ));
+
+        let mut indicators = strategy.required_indicators();
+        for indicator_type in classifier.required_indicators() {
+            if !indicators.contains(&indicator_type) {
+                indicators.push(indicator_type);
+            }
+        }
+        let buffer = candle_strategy::indicator_candles(&indicators);
+        let candles = buffer.view();
+
+        let total = StrategyTester::test_strategy(&mut strategy, candles).unwrap();
+        let result =
+            StrategyTester::test_strategy_by_regime(&mut strategy, candles, &classifier).unwrap();
+
+        assert_eq!(result.n_setups, 6);
+        assert_eq!(result.n_setups, total.n_setups);
+        assert!(total.by_regime.is_empty());
+        assert!(total.unclassified.is_none());
+
+        let n_setups = |regime| result.by_regime.get(&regime).map(|r| r.n_setups);
+        assert_eq!(n_setups(MarketRegime::TrendingUp), Some(2));
+        assert_eq!(n_setups(MarketRegime::TrendingDown), Some(1));
+        assert_eq!(n_setups(MarketRegime::Ranging), Some(1));
+        assert_eq!(n_setups(MarketRegime::HighVolatility), Some(1));
+
+        // The first setup is taken while the 100 EMA is still warming up
+        let unclassified = result.unclassified.unwrap();
+        assert_eq!(unclassified.n_setups, 1);
+        assert_eq!(unclassified.accuracy, 0.0);
+    }
+
+    #[test]
+    fn backtests_strategy_library() {
+        use StrategyOrientation::{Long, Short};
+
+        let ensemble = |orientation| -> Box<dyn TradingStrategy> {
+            let mode = EnsembleMode::Vote {
+                min_votes: 2,
+                within: 3,
+            };
+            let members: Vec<Box<dyn TradingStrategy>> = vec![
+                Box::new(BollingerMeanReversion::new_args(orientation, 20, 1.5)),
+                Box::new(StochasticCross::new_args(orientation, 14, 0.2, 0.8)),
+            ];
+
+            Box::new(EnsembleStrategy::new_args(mode, members).unwrap())
+        };
+
+        // Strategy along with its number of setups and wins on the fixture
+        let expected: Vec<(Box<dyn TradingStrategy>, usize, usize)> = vec![
+            (Box::new(SilverCross::new_args(Long, 5, 20)), 5, 3),
+            (Box::new(SilverCross::new_args(Short, 5, 20)), 4, 2),
+            (Box::new(RsiBasic::new_args(14, 60.0, 40.0, Long)), 5, 2),
+            (Box::new(RsiBasic::new_args(14, 60.0, 40.0, Short)), 7, 4),
+            (
+                Box::new(BollingerMeanReversion::new_args(Long, 20, 1.5)),
+                6,
+                2,
+            ),
+            (
+                Box::new(BollingerMeanReversion::new_args(Short, 20, 1.5)),
+                5,
+                2,
+            ),
+            (
+                Box::new(BbwpSqueezeBreakout::new_args(Long, 13, 100, 0.25)),
+                1,
+                0,
+            ),
+            (
+                Box::new(BbwpSqueezeBreakout::new_args(Short, 13, 100, 0.25)),
+                1,
+                1,
+            ),
+            (
+                Box::new(StochasticCross::new_args(Long, 14, 0.2, 0.8)),
+                5,
+                3,
+            ),
+            (
+                Box::new(StochasticCross::new_args(Short, 14, 0.2, 0.8)),
+                6,
+                3,
+            ),
+            (Box::new(EmaTrendPullback::new_args(Long, 10, 50)), 7, 4),
+            (Box::new(EmaTrendPullback::new_args(Short, 10, 50)), 4, 2),
+            (Box::new(DonchianBreakout::new_args(Long, 20)), 11, 7),
+            (Box::new(DonchianBreakout::new_args(Short, 20)), 10, 6),
+            (ensemble(Long), 4, 2),
+            (ensemble(Short), 5, 2),
+        ];
+
+        for (mut strategy, n_setups, n_wins) in expected {
+            let buffer = candle_strategy::strategy_candles(strategy.as_ref());
+            let result = StrategyTester::test_strategy(&mut strategy, buffer.view()).unwrap();
+            let wins = (result.accuracy * result.n_setups as f64).round() as usize;
+
+            assert_eq!(
+                (result.n_setups, wins),
+                (n_setups, n_wins),
+                "{} {:?}",
+                strategy,
+                strategy.orientation()
+            );
+        }
+    }
+}
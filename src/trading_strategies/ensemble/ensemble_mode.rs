@@ -0,0 +1,126 @@
+use anyhow::{anyhow, Result};
+use std::fmt::{Display, Formatter};
+
+/// # EnsembleMode
+///
+/// How the signals of the members of an `EnsembleStrategy` are combined.
+/// A member signals on a candle when it finds a setup in the orientation of
+/// the ensemble, and signals count for `within` candles including the one
+/// they occurred on.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EnsembleMode {
+    /// At least `min_votes` members have signaled, at least one of them on
+    /// the current candle.
+    Vote { min_votes: usize, within: usize },
+    /// The first member has signaled and every other member has confirmed
+    /// it by signaling on the same candle or after it.
+    Confirmation { within: usize },
+    /// The first member signals on the current candle while every other
+    /// member, acting as a regime filter, has signaled.
+    Filter { within: usize },
+}
+
+impl EnsembleMode {
+    /// Number of candles a signal counts for.
+    pub fn within(&self) -> usize {
+        match self {
+            EnsembleMode::Vote { within, .. }
+            | EnsembleMode::Confirmation { within }
+            | EnsembleMode::Filter { within } => *within,
+        }
+    }
+
+    /// Checks that the mode can be used with `n_members` strategies.
+    pub fn validate(&self, n_members: usize) -> Result<()> {
+        if self.within() == 0 {
+            return Err(anyhow!("Signals must count for at least one candle."));
+        }
+
+        match self {
+            EnsembleMode::Vote { min_votes, .. } if *min_votes == 0 || *min_votes > n_members => {
+                Err(anyhow!(
+                    "Expected between 1 and {} votes, got {}.",
+                    n_members,
+                    min_votes
+                ))
+            }
+            EnsembleMode::Confirmation { .. } | EnsembleMode::Filter { .. } if n_members < 2 => {
+                Err(anyhow!("{} needs at least two strategies.", self))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns whether the signals form a setup on the current candle, given
+    /// the number of candles since each member last signaled.
+    pub fn is_setup(&self, bars_since_signal: &[Option<usize>]) -> bool {
+        let any_current = bars_since_signal.contains(&Some(0));
+
+        match self {
+            EnsembleMode::Vote { min_votes, .. } => {
+                let votes = bars_since_signal.iter().filter(|b| b.is_some()).count();
+                any_current && votes >= *min_votes
+            }
+            EnsembleMode::Confirmation { .. } => match bars_since_signal.split_first() {
+                Some((Some(primary), others)) => {
+                    any_current && others.iter().all(|b| b.is_some_and(|b| b <= *primary))
+                }
+                _ => false,
+            },
+            EnsembleMode::Filter { .. } => match bars_since_signal.split_first() {
+                Some((Some(0), filters)) => filters.iter().all(|b| b.is_some()),
+                _ => false,
+            },
+        }
+    }
+
+    /// Whether the signals are used up by a setup. Filters describe a regime
+    /// rather than an event, so they keep counting.
+    pub fn consumes_signals(&self) -> bool {
+        !matches!(self, EnsembleMode::Filter { .. })
+    }
+}
+
+impl Display for EnsembleMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnsembleMode::Vote { min_votes, within } => write!(f, "Vote({},{})", min_votes, within),
+            EnsembleMode::Confirmation { within } => write!(f, "Confirmation({})", within),
+            EnsembleMode::Filter { within } => write!(f, "Filter({})", within),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trading_strategies::ensemble::ensemble_mode::EnsembleMode;
+
+    #[test]
+    fn combines_signals() {
+        let vote = EnsembleMode::Vote {
+            min_votes: 2,
+            within: 3,
+        };
+        assert!(vote.is_setup(&[Some(0), None, Some(2)]));
+        assert!(!vote.is_setup(&[Some(1), None, Some(2)]));
+        assert!(!vote.is_setup(&[Some(0), None, None]));
+
+        let confirmation = EnsembleMode::Confirmation { within: 3 };
+        assert!(confirmation.is_setup(&[Some(2), Some(0)]));
+        assert!(confirmation.is_setup(&[Some(0), Some(0)]));
+        assert!(!confirmation.is_setup(&[Some(0), Some(1)]));
+        assert!(!confirmation.is_setup(&[None, Some(0)]));
+
+        let filter = EnsembleMode::Filter { within: 10 };
+        assert!(filter.is_setup(&[Some(0), Some(7)]));
+        assert!(!filter.is_setup(&[Some(1), Some(0)]));
+        assert!(!filter.is_setup(&[Some(0), None]));
+        assert!(!filter.consumes_signals());
+
+        assert!(vote.validate(3).is_ok());
+        assert!(vote.validate(1).is_err());
+        assert!(confirmation.validate(1).is_err());
+        assert!(EnsembleMode::Filter { within: 0 }.validate(2).is_err());
+    }
+}
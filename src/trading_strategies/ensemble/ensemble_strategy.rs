@@ -0,0 +1,460 @@
+use crate::{
+    indicators::{indicator_type::IndicatorType, timeframe_indicator::TimeframeIndicator},
+    models::{
+        candle_buffer::CandleView,
+        interval::Interval,
+        setups::setup_builder::SetupBuilder,
+        strategy_orientation::StrategyOrientation,
+        traits::{
+            has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+            trading_strategy::TradingStrategy,
+        },
+    },
+    resolution_strategies::{
+        instant_resolution::InstantResolution, resolution_strategy::ResolutionStrategy,
+    },
+    trading_strategies::ensemble::ensemble_mode::EnsembleMode,
+};
+use anyhow::{anyhow, Context, Result};
+use chrono::Weekday;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter},
+};
+
+/// State of an ensemble as persisted between candles and restarts.
+#[derive(Debug, Serialize, Deserialize)]
+struct EnsembleState {
+    bars_since_signal: Vec<Option<usize>>,
+    members: Vec<Option<Value>>,
+}
+
+/// # EnsembleStrategy
+///
+/// Strategy combining the signals of several strategies, e.g. taking a
+/// setup only when 2 of 3 strategies agree within a few candles, or using
+/// one strategy as a regime filter for another. See `EnsembleMode` for the
+/// ways signals can be combined.
+///
+/// The first member is the primary strategy, which decides the orientation,
+/// interval and resolution strategy of the ensemble. Members only signal
+/// when they find setups in the orientation of the ensemble. All members
+/// are checked on every candle, so stateful members work as they would on
+/// their own.
+#[derive(Debug)]
+pub struct EnsembleStrategy {
+    mode: EnsembleMode,
+    members: Vec<Box<dyn TradingStrategy>>,
+    bars_since_signal: Vec<Option<usize>>,
+}
+
+impl EnsembleStrategy {
+    #[allow(dead_code)]
+    pub fn new_args(mode: EnsembleMode, members: Vec<Box<dyn TradingStrategy>>) -> Result<Self> {
+        let primary = members
+            .first()
+            .context("An ensemble needs at least one strategy.")?;
+        mode.validate(members.len())?;
+
+        let interval = primary.interval();
+        if let Some(member) = members.iter().find(|m| m.interval() != interval) {
+            return Err(anyhow!(
+                "{} uses interval {} while {} uses {}.",
+                member,
+                member.interval(),
+                primary,
+                interval
+            ));
+        }
+
+        Ok(Self {
+            mode,
+            bars_since_signal: vec![None; members.len()],
+            members,
+        })
+    }
+}
+
+impl Clone for EnsembleStrategy {
+    fn clone(&self) -> Self {
+        Self {
+            mode: self.mode,
+            members: self.members.iter().map(|m| m.clone_box()).collect(),
+            bars_since_signal: self.bars_since_signal.clone(),
+        }
+    }
+}
+
+impl HasMinLength for EnsembleStrategy {
+    fn min_length(&self) -> usize {
+        self.members
+            .iter()
+            .map(|m| m.min_length())
+            .max()
+            .unwrap_or(1)
+    }
+}
+
+impl TradingStrategy for EnsembleStrategy {
+    /// Returns an ensemble without members, which never triggers. Ensembles
+    /// are meant to be built with `new_args`.
+    fn new() -> Self {
+        Self {
+            mode: EnsembleMode::Vote {
+                min_votes: 1,
+                within: 1,
+            },
+            members: vec![],
+            bars_since_signal: vec![],
+        }
+    }
+
+    fn candles_needed_for_setup(&self) -> usize {
+        self.members
+            .iter()
+            .map(|m| m.candles_needed_for_setup())
+            .max()
+            .unwrap_or(1)
+    }
+
+    fn check_last_for_setup(&mut self, candles: CandleView) -> Option<SetupBuilder> {
+        let current = candles.last()?;
+        let orientation = self.orientation();
+        let within = self.mode.within();
+
+        for (member, bars) in self.members.iter_mut().zip(&mut self.bars_since_signal) {
+            *bars = bars.map(|b| b + 1).filter(|b| *b < within);
+
+            let sb = member.check_last_for_setup(candles.latest(member.candles_needed_for_setup()));
+
+            if sb.is_some_and(|sb| sb.orientation == Some(orientation)) {
+                *bars = Some(0);
+            }
+        }
+
+        if !self.mode.is_setup(&self.bars_since_signal) {
+            return None;
+        }
+
+        if self.mode.consumes_signals() {
+            self.bars_since_signal.fill(None);
+        }
+
+        let sb = SetupBuilder::new()
+            .candle(current)
+            .orientation(&orientation);
+
+        Some(sb)
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+
+    fn default_resolution_strategy(&self) -> ResolutionStrategy {
+        self.members
+            .first()
+            .map_or(ResolutionStrategy::Instant(InstantResolution), |m| {
+                m.default_resolution_strategy()
+            })
+    }
+
+    fn orientation(&self) -> StrategyOrientation {
+        self.members
+            .first()
+            .map_or(StrategyOrientation::Long, |m| m.orientation())
+    }
+
+    fn interval(&self) -> Interval {
+        self.members
+            .first()
+            .map_or(Interval::Day1, |m| m.interval())
+    }
+
+    /// Days on which any of the members trade.
+    fn trading_days(&self) -> HashSet<Weekday> {
+        self.members.iter().flat_map(|m| m.trading_days()).collect()
+    }
+
+    fn state(&self) -> Option<Value> {
+        let state = EnsembleState {
+            bars_since_signal: self.bars_since_signal.clone(),
+            members: self.members.iter().map(|m| m.state()).collect(),
+        };
+
+        serde_json::to_value(state).ok()
+    }
+
+    fn restore_state(&mut self, state: Value) -> Result<()> {
+        let state: EnsembleState = serde_json::from_value(state)?;
+
+        let n = self.members.len();
+        if state.bars_since_signal.len() != n || state.members.len() != n {
+            return Err(anyhow!(
+                "Expected state of an ensemble of {} strategies.",
+                n
+            ));
+        }
+
+        for (member, member_state) in self.members.iter_mut().zip(state.members) {
+            if let Some(member_state) = member_state {
+                member.restore_state(member_state)?;
+            }
+        }
+        self.bars_since_signal = state.bars_since_signal;
+
+        Ok(())
+    }
+}
+
+impl RequiresIndicators for EnsembleStrategy {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        let mut indicators = vec![];
+
+        for indicator_type in self.members.iter().flat_map(|m| m.required_indicators()) {
+            if !indicators.contains(&indicator_type) {
+                indicators.push(indicator_type);
+            }
+        }
+
+        indicators
+    }
+
+    fn required_timeframe_indicators(&self) -> Vec<TimeframeIndicator> {
+        let mut indicators = vec![];
+
+        for indicator in self
+            .members
+            .iter()
+            .flat_map(|m| m.required_timeframe_indicators())
+        {
+            if !indicators.contains(&indicator) {
+                indicators.push(indicator);
+            }
+        }
+
+        indicators
+    }
+}
+
+impl Display for EnsembleStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let members: Vec<String> = self.members.iter().map(|m| m.to_string()).collect();
+        write!(f, "Ensemble {} [{}]", self.mode, members.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::indicator_type::IndicatorType,
+        models::{
+            candle::Candle,
+            candle_buffer::{CandleBuffer, CandleView},
+            interval::Interval,
+            setups::setup_builder::SetupBuilder,
+            strategy_orientation::StrategyOrientation,
+            traits::{
+                has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+                trading_strategy::TradingStrategy,
+            },
+        },
+        resolution_strategies::{
+            instant_resolution::InstantResolution, resolution_strategy::ResolutionStrategy,
+        },
+        trading_strategies::{
+            ensemble::{ensemble_mode::EnsembleMode, ensemble_strategy::EnsembleStrategy},
+            public::{rsi_basic::RsiBasic, stochastic_cross::StochasticCross},
+        },
+    };
+    use chrono::{Duration, TimeZone, Utc, Weekday};
+    use std::{
+        collections::HashSet,
+        fmt::{Display, Formatter},
+    };
+
+    /// Strategy signaling on candles with the given closes.
+    #[derive(Debug, Clone)]
+    struct SignalOn {
+        closes: Vec<f64>,
+        indicator: IndicatorType,
+    }
+
+    impl HasMinLength for SignalOn {
+        fn min_length(&self) -> usize {
+            1
+        }
+    }
+
+    impl TradingStrategy for SignalOn {
+        fn new() -> Self {
+            Self {
+                closes: vec![],
+                indicator: IndicatorType::SMA(1),
+            }
+        }
+
+        fn candles_needed_for_setup(&self) -> usize {
+            1
+        }
+
+        fn check_last_for_setup(&mut self, candles: CandleView) -> Option<SetupBuilder> {
+            let current = candles.last()?;
+            self.closes.contains(&current.close).then(|| {
+                SetupBuilder::new()
+                    .candle(current)
+                    .orientation(&StrategyOrientation::Long)
+            })
+        }
+
+        fn clone_box(&self) -> Box<dyn TradingStrategy> {
+            Box::new(self.clone())
+        }
+
+        fn default_resolution_strategy(&self) -> ResolutionStrategy {
+            ResolutionStrategy::Instant(InstantResolution)
+        }
+
+        fn orientation(&self) -> StrategyOrientation {
+            StrategyOrientation::Long
+        }
+
+        fn interval(&self) -> Interval {
+            Interval::Day1
+        }
+
+        fn trading_days(&self) -> HashSet<Weekday> {
+            HashSet::from([Weekday::Mon])
+        }
+    }
+
+    impl RequiresIndicators for SignalOn {
+        fn required_indicators(&self) -> Vec<IndicatorType> {
+            vec![self.indicator]
+        }
+    }
+
+    impl Display for SignalOn {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "SignalOn")
+        }
+    }
+
+    fn signal_on(closes: &[f64]) -> Box<dyn TradingStrategy> {
+        Box::new(SignalOn {
+            closes: closes.to_vec(),
+            indicator: IndicatorType::SMA(closes.len()),
+        })
+    }
+
+    /// Checks the ensemble on candles closing at 0, 1, 2, ... and returns the
+    /// closes of the candles with setups.
+    fn setups(ensemble: &mut EnsembleStrategy, n: usize) -> Vec<f64> {
+        let start = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        let candles: Vec<Candle> = (0..n)
+            .map(|i| Candle::from_val(start + Duration::days(i as i64), i as f64, 1.0))
+            .collect();
+        let buffer = CandleBuffer::from(&candles[..]);
+
+        (1..=n)
+            .filter_map(|end| ensemble.check_last_for_setup(buffer.view().window(..end)))
+            .map(|sb| sb.candle.unwrap().close)
+            .collect()
+    }
+
+    #[test]
+    fn combines_member_signals() {
+        let vote = |min_votes, within| EnsembleMode::Vote { min_votes, within };
+        let members = || vec![signal_on(&[1.0]), signal_on(&[2.0]), signal_on(&[5.0])];
+
+        let mut ensemble = EnsembleStrategy::new_args(vote(2, 2), members()).unwrap();
+        assert_eq!(setups(&mut ensemble, 8), vec![2.0]);
+
+        let mut ensemble = EnsembleStrategy::new_args(vote(2, 1), members()).unwrap();
+        assert!(setups(&mut ensemble, 8).is_empty());
+
+        let mut ensemble = EnsembleStrategy::new_args(vote(3, 5), members()).unwrap();
+        assert_eq!(setups(&mut ensemble, 8), vec![5.0]);
+
+        let confirmation = EnsembleMode::Confirmation { within: 3 };
+        let members = vec![signal_on(&[1.0, 6.0]), signal_on(&[0.0, 3.0, 9.0])];
+        let mut ensemble = EnsembleStrategy::new_args(confirmation, members).unwrap();
+        assert_eq!(setups(&mut ensemble, 12), vec![3.0]);
+
+        let filter = EnsembleMode::Filter { within: 5 };
+        let members = vec![signal_on(&[2.0, 3.0, 8.0]), signal_on(&[0.0])];
+        let mut ensemble = EnsembleStrategy::new_args(filter, members).unwrap();
+        assert_eq!(setups(&mut ensemble, 12), vec![2.0, 3.0]);
+    }
+
+    #[test]
+    fn merges_members() {
+        let mode = EnsembleMode::Vote {
+            min_votes: 1,
+            within: 1,
+        };
+        let stochastic = StochasticCross::new_args(StrategyOrientation::Long, 14, 0.2, 0.8);
+        let mut expected = vec![IndicatorType::SMA(2)];
+        expected.extend(stochastic.required_indicators());
+
+        let ensemble = EnsembleStrategy::new_args(
+            mode,
+            vec![
+                signal_on(&[1.0, 2.0]),
+                Box::new(stochastic),
+                signal_on(&[3.0, 4.0]),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(ensemble.required_indicators(), expected);
+        assert_eq!(ensemble.candles_needed_for_setup(), 2);
+        assert_eq!(ensemble.trading_days().len(), 7);
+        assert!(matches!(
+            ensemble.default_resolution_strategy(),
+            ResolutionStrategy::Instant(_)
+        ));
+        assert_eq!(
+            ensemble.to_string(),
+            "Ensemble Vote(1,1) [SignalOn, Stochastic Cross, SignalOn]"
+        );
+
+        // Members need the same interval, modes need enough members
+        let minute = Box::new(RsiBasic::new());
+        assert!(EnsembleStrategy::new_args(mode, vec![signal_on(&[]), minute]).is_err());
+        assert!(EnsembleStrategy::new_args(mode, vec![]).is_err());
+        let filter = EnsembleMode::Filter { within: 1 };
+        assert!(EnsembleStrategy::new_args(filter, vec![signal_on(&[])]).is_err());
+    }
+
+    #[test]
+    fn state_carries_over_clones() {
+        let mode = EnsembleMode::Confirmation { within: 3 };
+        let members = vec![signal_on(&[1.0]), signal_on(&[3.0])];
+        let mut ensemble = EnsembleStrategy::new_args(mode, members).unwrap();
+
+        let start = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        let candles: Vec<Candle> = (0..5)
+            .map(|i| Candle::from_val(start + Duration::days(i), i as f64, 1.0))
+            .collect();
+        let buffer = CandleBuffer::from(&candles[..]);
+
+        // Checked on clones with the state carried over, as in SetupFinder
+        let mut found = vec![];
+        for end in 1..=candles.len() {
+            let mut clone = ensemble.clone_box();
+            if let Some(sb) = clone.check_last_for_setup(buffer.view().window(..end)) {
+                found.push(sb.candle.unwrap().close);
+            }
+            ensemble.restore_state(clone.state().unwrap()).unwrap();
+        }
+        assert_eq!(found, vec![3.0]);
+
+        // State of a differently sized ensemble is rejected
+        let members = vec![signal_on(&[]), signal_on(&[]), signal_on(&[])];
+        let mut other = EnsembleStrategy::new_args(mode, members).unwrap();
+        assert!(other.restore_state(ensemble.state().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod ensemble_mode;
+pub mod ensemble_strategy;
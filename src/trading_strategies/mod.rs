@@ -1,2 +1,6 @@
+pub mod ensemble;
 pub mod private;
 pub mod public;
+pub mod regimes;
+pub mod rules;
+pub mod scripting;
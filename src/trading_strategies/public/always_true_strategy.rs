@@ -1,7 +1,7 @@
 use crate::{
     indicators::indicator_type::IndicatorType,
     models::{
-        candle::Candle,
+        candle_buffer::CandleView,
         interval::Interval,
         setups::setup_builder::SetupBuilder,
         strategy_orientation::StrategyOrientation,
@@ -14,7 +14,9 @@ use crate::{
         instant_resolution::InstantResolution, resolution_strategy::ResolutionStrategy,
     },
 };
+use anyhow::{Context, Result};
 use chrono::Weekday;
+use serde_json::Value;
 use std::{
     collections::HashSet,
     fmt::{Display, Formatter},
@@ -71,13 +73,13 @@ impl TradingStrategy for AlwaysTrueStrategy {
         1
     }
 
-    fn check_last_for_setup(&mut self, candles: &[Candle]) -> Option<SetupBuilder> {
+    fn check_last_for_setup(&mut self, candles: CandleView) -> Option<SetupBuilder> {
         if self.triggered {
             return None;
         }
 
         let sb = SetupBuilder::new()
-            .candle(&candles[0])
+            .candle(candles.first()?)
             .orientation(&StrategyOrientation::Long);
 
         self.triggered = true;
@@ -104,6 +106,18 @@ impl TradingStrategy for AlwaysTrueStrategy {
     fn trading_days(&self) -> HashSet<Weekday> {
         self.trading_days.clone()
     }
+
+    fn state(&self) -> Option<Value> {
+        Some(Value::Bool(self.triggered))
+    }
+
+    fn restore_state(&mut self, state: Value) -> Result<()> {
+        self.triggered = state
+            .as_bool()
+            .context("Expected triggered flag as strategy state")?;
+
+        Ok(())
+    }
 }
 
 impl AlwaysTrueStrategy {
@@ -133,3 +147,43 @@ impl Display for AlwaysTrueStrategy {
         write!(f, "AlwaysTrueStrategy")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{
+            candle::Candle, candle_buffer::CandleBuffer,
+            setups::strategy_state_store::StrategyStateStore,
+            traits::trading_strategy::TradingStrategy,
+        },
+        trading_strategies::public::always_true_strategy::AlwaysTrueStrategy,
+    };
+    use chrono::Utc;
+
+    #[test]
+    fn state_carries_over_clones_and_restarts() {
+        let buffer = CandleBuffer::from(&[Candle::from_val(Utc::now(), 100.0, 1.0)][..]);
+        let mut strategy: Box<dyn TradingStrategy> = Box::new(AlwaysTrueStrategy::new());
+
+        // Checked on clones with the state carried over, as in SetupFinder
+        let mut n_setups = 0;
+        for _ in 0..3 {
+            let mut clone = strategy.clone_box();
+            n_setups += clone.check_last_for_setup(buffer.view()).is_some() as usize;
+            strategy.restore_state(clone.state().unwrap()).unwrap();
+        }
+        assert_eq!(n_setups, 1);
+
+        let path = std::env::temp_dir().join(format!("always-true-{}.json", std::process::id()));
+        let store = StrategyStateStore::new(&path);
+        store.save(&strategy.state().unwrap()).unwrap();
+
+        let mut restarted = AlwaysTrueStrategy::new();
+        restarted
+            .restore_state(store.load().unwrap().unwrap())
+            .unwrap();
+        assert!(restarted.check_last_for_setup(buffer.view()).is_none());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
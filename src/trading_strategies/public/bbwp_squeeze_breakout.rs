@@ -0,0 +1,234 @@
+use crate::{
+    indicators::{bollinger_bands::BollingerBands, indicator_type::IndicatorType},
+    models::{
+        candle_buffer::{CandleRow, CandleView},
+        interval::Interval,
+        ma_type::MAType,
+        setups::setup_builder::SetupBuilder,
+        strategy_orientation::StrategyOrientation,
+        traits::{
+            has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+            trading_strategy::TradingStrategy,
+        },
+    },
+    resolution_strategies::{
+        dynamic_pivot::DynamicPivotResolution, resolution_strategy::ResolutionStrategy,
+    },
+};
+use chrono::{Datelike, Weekday};
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter},
+};
+
+/// # BBWP Squeeze Breakout
+///
+/// Strategy trading the expansion following a volatility squeeze, where the
+/// Bollinger Band Width Percentile is low and price breaks out of the
+/// Bollinger Bands.
+///
+/// ## Directionality
+/// - Long
+/// - Short
+///
+/// ## Interval
+/// - Daily
+///
+/// ## Entry Conditions
+/// - Previous BBWP at or below the squeeze threshold, given in decimals.
+/// - Long: Current close breaks above the upper Bollinger Band.
+/// - Short: Current close breaks below the lower Bollinger Band.
+///
+/// ## Resolution
+/// - Dynamic pivots of length 15.
+///
+/// ## Trading days
+/// - All
+#[derive(Debug, Clone)]
+pub struct BbwpSqueezeBreakout {
+    pub bbwp_len: usize,
+    pub bbwp_lookback: usize,
+    pub squeeze_threshold: f64,
+    pub bb_len: usize,
+    pub bb_std: f64,
+    pub orientation: StrategyOrientation,
+    pub trading_days: HashSet<Weekday>,
+}
+
+impl BbwpSqueezeBreakout {
+    #[allow(dead_code)]
+    pub fn new_args(
+        orientation: StrategyOrientation,
+        bbwp_len: usize,
+        bbwp_lookback: usize,
+        squeeze_threshold: f64,
+    ) -> Self {
+        Self {
+            bbwp_len,
+            bbwp_lookback,
+            squeeze_threshold,
+            bb_len: 20,
+            bb_std: 2.0,
+            orientation,
+            trading_days: Self::build_trading_days(),
+        }
+    }
+
+    fn bbwp_type(&self) -> IndicatorType {
+        IndicatorType::BBWP(self.bbwp_len, self.bbwp_lookback, 5, MAType::SMA)
+    }
+
+    fn bb_type(&self) -> IndicatorType {
+        IndicatorType::BollingerBands(self.bb_len, self.bb_std.into())
+    }
+
+    fn bbwp(&self, candle: CandleRow) -> Option<f64> {
+        let bbwp = candle.clone_indicator(&self.bbwp_type()).ok()?.as_bbwp()?;
+        Some(bbwp.value)
+    }
+
+    fn bands(&self, candle: CandleRow) -> Option<BollingerBands> {
+        candle
+            .clone_indicator(&self.bb_type())
+            .ok()?
+            .as_bollinger_bands()
+    }
+
+    fn build_trading_days() -> HashSet<Weekday> {
+        let mut set = HashSet::new();
+
+        set.insert(Weekday::Mon);
+        set.insert(Weekday::Tue);
+        set.insert(Weekday::Wed);
+        set.insert(Weekday::Thu);
+        set.insert(Weekday::Fri);
+        set.insert(Weekday::Sat);
+        set.insert(Weekday::Sun);
+
+        set
+    }
+}
+
+impl HasMinLength for BbwpSqueezeBreakout {
+    fn min_length(&self) -> usize {
+        let warmup = self
+            .bbwp_type()
+            .min_length()
+            .max(self.bb_type().min_length());
+        warmup + self.candles_needed_for_setup()
+    }
+}
+
+impl TradingStrategy for BbwpSqueezeBreakout {
+    fn new() -> Self {
+        Self::new_args(StrategyOrientation::Long, 13, 252, 0.1)
+    }
+
+    fn candles_needed_for_setup(&self) -> usize {
+        2
+    }
+
+    fn check_last_for_setup(&mut self, candles: CandleView) -> Option<SetupBuilder> {
+        if candles.len() < 2 {
+            return None;
+        }
+
+        let current = candles.last()?;
+
+        let is_active_day = self.trading_days.contains(&current.timestamp.weekday());
+        if !is_active_day {
+            return None;
+        }
+
+        let prev = candles.get(candles.len() - 2)?;
+        if self.bbwp(prev)? > self.squeeze_threshold {
+            return None;
+        }
+
+        let bands = self.bands(current)?;
+        let is_breakout = match self.orientation {
+            StrategyOrientation::Long => current.close > bands.upper,
+            StrategyOrientation::Short => current.close < bands.lower,
+        };
+
+        if !is_breakout {
+            return None;
+        }
+
+        let sb = SetupBuilder::new()
+            .candle(current)
+            .orientation(&self.orientation);
+
+        Some(sb)
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+
+    fn default_resolution_strategy(&self) -> ResolutionStrategy {
+        ResolutionStrategy::DynamicPivot(DynamicPivotResolution::new())
+    }
+
+    fn orientation(&self) -> StrategyOrientation {
+        self.orientation
+    }
+
+    fn interval(&self) -> Interval {
+        Interval::Day1
+    }
+
+    fn trading_days(&self) -> HashSet<Weekday> {
+        self.trading_days.clone()
+    }
+}
+
+impl RequiresIndicators for BbwpSqueezeBreakout {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        let mut indicators = vec![self.bbwp_type(), self.bb_type()];
+        indicators.extend(self.default_resolution_strategy().required_indicators());
+
+        indicators
+    }
+}
+
+impl Display for BbwpSqueezeBreakout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BBWP Squeeze Breakout")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{
+            strategy_orientation::StrategyOrientation, traits::trading_strategy::TradingStrategy,
+        },
+        trading_strategies::public::bbwp_squeeze_breakout::BbwpSqueezeBreakout,
+        utils::data::candle_strategy,
+    };
+
+    #[test]
+    fn finds_setups_on_fixture_data() {
+        let mut long = BbwpSqueezeBreakout::new_args(StrategyOrientation::Long, 13, 100, 0.25);
+        let mut short = BbwpSqueezeBreakout::new_args(StrategyOrientation::Short, 13, 100, 0.25);
+        let buffer = candle_strategy::strategy_candles(&long);
+        let candles = buffer.view();
+
+        // The BBWP of candle 41 is 0.0 and candle 42 closes at 1069.98 above
+        // the upper band at 1069.40. Candle 43 closes at 1072.00, below the
+        // upper band at 1073.31.
+        assert!(long.check_last_for_setup(candles.window(41..=42)).is_some());
+        assert!(long.check_last_for_setup(candles.window(42..=43)).is_none());
+
+        // The BBWP of candle 74 is 0.01 and candle 75 closes at 1045.66 below
+        // the lower band at 1047.96. Candle 76 closes below the lower band as
+        // well, but the BBWP of candle 75 is 0.26, above the threshold.
+        assert!(short
+            .check_last_for_setup(candles.window(74..=75))
+            .is_some());
+        assert!(short
+            .check_last_for_setup(candles.window(75..=76))
+            .is_none());
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{
+    indicators::{bollinger_bands::BollingerBands, indicator_type::IndicatorType},
+    models::{
+        candle_buffer::{CandleRow, CandleView},
+        interval::Interval,
+        setups::setup_builder::SetupBuilder,
+        strategy_orientation::StrategyOrientation,
+        traits::{
+            has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+            trading_strategy::TradingStrategy,
+        },
+    },
+    resolution_strategies::{
+        dynamic_pivot::DynamicPivotResolution, resolution_strategy::ResolutionStrategy,
+    },
+};
+use chrono::{Datelike, Weekday};
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter},
+};
+
+/// # Bollinger Band Mean Reversion
+///
+/// Strategy betting on price returning towards the mean after closing
+/// outside of the Bollinger Bands.
+///
+/// ## Directionality
+/// - Long
+/// - Short
+///
+/// ## Interval
+/// - Daily
+///
+/// ## Entry Conditions
+/// - Long: Previous close below the lower band and current close back above it.
+/// - Short: Previous close above the upper band and current close back below it.
+///
+/// ## Resolution
+/// - Dynamic pivots of length 15.
+///
+/// ## Trading days
+/// - All
+#[derive(Debug, Clone)]
+pub struct BollingerMeanReversion {
+    pub len: usize,
+    pub n_std: f64,
+    pub orientation: StrategyOrientation,
+    pub trading_days: HashSet<Weekday>,
+}
+
+impl BollingerMeanReversion {
+    #[allow(dead_code)]
+    pub fn new_args(orientation: StrategyOrientation, len: usize, n_std: f64) -> Self {
+        Self {
+            len,
+            n_std,
+            orientation,
+            trading_days: Self::build_trading_days(),
+        }
+    }
+
+    fn indicator_type(&self) -> IndicatorType {
+        IndicatorType::BollingerBands(self.len, self.n_std.into())
+    }
+
+    fn bands(&self, candle: CandleRow) -> Option<BollingerBands> {
+        candle
+            .clone_indicator(&self.indicator_type())
+            .ok()?
+            .as_bollinger_bands()
+    }
+
+    fn build_trading_days() -> HashSet<Weekday> {
+        let mut set = HashSet::new();
+
+        set.insert(Weekday::Mon);
+        set.insert(Weekday::Tue);
+        set.insert(Weekday::Wed);
+        set.insert(Weekday::Thu);
+        set.insert(Weekday::Fri);
+        set.insert(Weekday::Sat);
+        set.insert(Weekday::Sun);
+
+        set
+    }
+}
+
+impl HasMinLength for BollingerMeanReversion {
+    fn min_length(&self) -> usize {
+        self.indicator_type().min_length() + self.candles_needed_for_setup()
+    }
+}
+
+impl TradingStrategy for BollingerMeanReversion {
+    fn new() -> Self {
+        Self::new_args(StrategyOrientation::Long, 20, 2.0)
+    }
+
+    fn candles_needed_for_setup(&self) -> usize {
+        2
+    }
+
+    fn check_last_for_setup(&mut self, candles: CandleView) -> Option<SetupBuilder> {
+        if candles.len() < 2 {
+            return None;
+        }
+
+        let current = candles.last()?;
+
+        let is_active_day = self.trading_days.contains(&current.timestamp.weekday());
+        if !is_active_day {
+            return None;
+        }
+
+        let prev = candles.get(candles.len() - 2)?;
+        let prev_bands = self.bands(prev)?;
+        let bands = self.bands(current)?;
+
+        let is_reversion = match self.orientation {
+            StrategyOrientation::Long => {
+                prev.close < prev_bands.lower && current.close > bands.lower
+            }
+            StrategyOrientation::Short => {
+                prev.close > prev_bands.upper && current.close < bands.upper
+            }
+        };
+
+        if !is_reversion {
+            return None;
+        }
+
+        let sb = SetupBuilder::new()
+            .candle(current)
+            .orientation(&self.orientation);
+
+        Some(sb)
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+
+    fn default_resolution_strategy(&self) -> ResolutionStrategy {
+        ResolutionStrategy::DynamicPivot(DynamicPivotResolution::new())
+    }
+
+    fn orientation(&self) -> StrategyOrientation {
+        self.orientation
+    }
+
+    fn interval(&self) -> Interval {
+        Interval::Day1
+    }
+
+    fn trading_days(&self) -> HashSet<Weekday> {
+        self.trading_days.clone()
+    }
+}
+
+impl RequiresIndicators for BollingerMeanReversion {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        let mut indicators = vec![self.indicator_type()];
+        indicators.extend(self.default_resolution_strategy().required_indicators());
+
+        indicators
+    }
+}
+
+impl Display for BollingerMeanReversion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Bollinger Mean Reversion")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{
+            strategy_orientation::StrategyOrientation, traits::trading_strategy::TradingStrategy,
+        },
+        trading_strategies::public::bollinger_mean_reversion::BollingerMeanReversion,
+        utils::data::candle_strategy,
+    };
+
+    #[test]
+    fn finds_setups_on_fixture_data() {
+        let mut long = BollingerMeanReversion::new_args(StrategyOrientation::Long, 20, 1.5);
+        let mut short = BollingerMeanReversion::new_args(StrategyOrientation::Short, 20, 1.5);
+        let buffer = candle_strategy::strategy_candles(&long);
+        let candles = buffer.view();
+
+        // Candle 61 closes at 1050.90 below the lower band at 1054.00 and
+        // candle 62 closes back inside at 1058.03 above 1053.26. Candle 63
+        // doesn't follow a close below the band.
+        assert!(long.check_last_for_setup(candles.window(61..=62)).is_some());
+        assert!(long.check_last_for_setup(candles.window(62..=63)).is_none());
+
+        // Candle 32 closes at 1056.77 above the upper band at 1051.50 and
+        // candle 33 closes back inside at 1050.62 below 1052.55
+        assert!(short
+            .check_last_for_setup(candles.window(32..=33))
+            .is_some());
+        assert!(short
+            .check_last_for_setup(candles.window(33..=34))
+            .is_none());
+    }
+}
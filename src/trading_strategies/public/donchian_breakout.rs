@@ -0,0 +1,230 @@
+use crate::{
+    indicators::indicator_type::IndicatorType,
+    models::{
+        candle_buffer::CandleView,
+        interval::Interval,
+        setups::setup_builder::SetupBuilder,
+        strategy_orientation::StrategyOrientation,
+        traits::{
+            has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+            trading_strategy::TradingStrategy,
+        },
+    },
+    resolution_strategies::{
+        dynamic_pivot::DynamicPivotResolution, resolution_strategy::ResolutionStrategy,
+    },
+};
+use chrono::{Datelike, Weekday};
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter},
+};
+
+/// # Donchian Breakout
+///
+/// Classic channel breakout strategy entering when price closes beyond the
+/// Donchian channel, i.e. the highest high or lowest low of the preceding
+/// candles. The channel is calculated from the candles passed to the
+/// strategy, so no indicators are needed for the entry.
+///
+/// ## Directionality
+/// - Long
+/// - Short
+///
+/// ## Interval
+/// - Daily
+///
+/// ## Entry Conditions
+/// - Long: Close above the highest high of the previous `len` candles.
+/// - Short: Close below the lowest low of the previous `len` candles.
+///
+/// ## Resolution
+/// - Dynamic pivots of length 15.
+///
+/// ## Trading days
+/// - All
+#[derive(Debug, Clone)]
+pub struct DonchianBreakout {
+    pub len: usize,
+    pub orientation: StrategyOrientation,
+    pub trading_days: HashSet<Weekday>,
+}
+
+impl DonchianBreakout {
+    #[allow(dead_code)]
+    pub fn new_args(orientation: StrategyOrientation, len: usize) -> Self {
+        Self {
+            len,
+            orientation,
+            trading_days: Self::build_trading_days(),
+        }
+    }
+
+    fn build_trading_days() -> HashSet<Weekday> {
+        let mut set = HashSet::new();
+
+        set.insert(Weekday::Mon);
+        set.insert(Weekday::Tue);
+        set.insert(Weekday::Wed);
+        set.insert(Weekday::Thu);
+        set.insert(Weekday::Fri);
+        set.insert(Weekday::Sat);
+        set.insert(Weekday::Sun);
+
+        set
+    }
+}
+
+impl HasMinLength for DonchianBreakout {
+    fn min_length(&self) -> usize {
+        let resolution_len = self
+            .required_indicators()
+            .iter()
+            .map(|i| i.min_length())
+            .max()
+            .unwrap_or(1);
+
+        self.candles_needed_for_setup().max(resolution_len)
+    }
+}
+
+impl TradingStrategy for DonchianBreakout {
+    fn new() -> Self {
+        Self::new_args(StrategyOrientation::Long, 20)
+    }
+
+    fn candles_needed_for_setup(&self) -> usize {
+        self.len + 1
+    }
+
+    fn check_last_for_setup(&mut self, candles: CandleView) -> Option<SetupBuilder> {
+        let n = candles.len();
+        if n < self.len + 1 {
+            return None;
+        }
+
+        let current = candles.last()?;
+
+        let is_active_day = self.trading_days.contains(&current.timestamp.weekday());
+        if !is_active_day {
+            return None;
+        }
+
+        let channel = candles.window(n - 1 - self.len..n - 1);
+        let is_breakout = match self.orientation {
+            StrategyOrientation::Long => channel.high().iter().all(|high| current.close > *high),
+            StrategyOrientation::Short => channel.low().iter().all(|low| current.close < *low),
+        };
+
+        if !is_breakout {
+            return None;
+        }
+
+        let sb = SetupBuilder::new()
+            .candle(current)
+            .orientation(&self.orientation);
+
+        Some(sb)
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+
+    fn default_resolution_strategy(&self) -> ResolutionStrategy {
+        ResolutionStrategy::DynamicPivot(DynamicPivotResolution::new())
+    }
+
+    fn orientation(&self) -> StrategyOrientation {
+        self.orientation
+    }
+
+    fn interval(&self) -> Interval {
+        Interval::Day1
+    }
+
+    fn trading_days(&self) -> HashSet<Weekday> {
+        self.trading_days.clone()
+    }
+}
+
+impl RequiresIndicators for DonchianBreakout {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        self.default_resolution_strategy().required_indicators()
+    }
+}
+
+impl Display for DonchianBreakout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Donchian Breakout")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::indicator_type::IndicatorType,
+        models::{
+            candle::Candle,
+            candle_buffer::CandleBuffer,
+            strategy_orientation::StrategyOrientation,
+            traits::{has_min_length::HasMinLength, trading_strategy::TradingStrategy},
+        },
+        trading_strategies::public::donchian_breakout::DonchianBreakout,
+        utils::data::candle_strategy,
+    };
+    use chrono::{Duration, TimeZone, Utc};
+
+    #[test]
+    fn detects_breakouts() {
+        let start = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        let candles: Vec<Candle> = [100.0, 102.0, 101.0, 103.0, 99.0, 98.0]
+            .iter()
+            .enumerate()
+            .map(|(i, close)| Candle::from_val(start + Duration::days(i as i64), *close, 1.0))
+            .collect();
+        let buffer = CandleBuffer::from(&candles[..]);
+        let candles = buffer.view();
+
+        let mut long = DonchianBreakout::new_args(StrategyOrientation::Long, 3);
+        let mut short = DonchianBreakout::new_args(StrategyOrientation::Short, 3);
+        assert_eq!(
+            long.min_length(),
+            IndicatorType::DynamicPivot(15).min_length()
+        );
+        assert_eq!(
+            DonchianBreakout::new_args(StrategyOrientation::Long, 40).min_length(),
+            41
+        );
+
+        assert!(long.check_last_for_setup(candles.window(..3)).is_none());
+        assert!(long.check_last_for_setup(candles.window(..4)).is_some());
+        assert!(long.check_last_for_setup(candles.window(1..5)).is_none());
+        assert!(short.check_last_for_setup(candles.window(1..5)).is_some());
+        assert!(short.check_last_for_setup(candles.window(..4)).is_none());
+    }
+
+    #[test]
+    fn finds_setups_on_fixture_data() {
+        let mut long = DonchianBreakout::new_args(StrategyOrientation::Long, 20);
+        let mut short = DonchianBreakout::new_args(StrategyOrientation::Short, 20);
+        let buffer = candle_strategy::strategy_candles(&long);
+        let candles = buffer.view();
+
+        // Candle 31 closes at 1055.17 above the highest high of the 20
+        // candles before it, 1054.32. Candle 32 closes at 1056.77, below the
+        // high of candle 31 at 1057.61.
+        assert!(long.check_last_for_setup(candles.window(11..=31)).is_some());
+        assert!(long.check_last_for_setup(candles.window(12..=32)).is_none());
+
+        // Candle 75 closes at 1045.66 below the lowest low of the 20 candles
+        // before it, 1046.58. Candle 76 closes at 1042.01, above the low of
+        // candle 75 at 1041.18.
+        assert!(short
+            .check_last_for_setup(candles.window(55..=75))
+            .is_some());
+        assert!(short
+            .check_last_for_setup(candles.window(56..=76))
+            .is_none());
+    }
+}
@@ -0,0 +1,209 @@
+use crate::{
+    indicators::indicator_type::IndicatorType,
+    models::{
+        candle_buffer::{CandleRow, CandleView},
+        interval::Interval,
+        setups::setup_builder::SetupBuilder,
+        strategy_orientation::StrategyOrientation,
+        traits::{
+            has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+            trading_strategy::TradingStrategy,
+        },
+    },
+    resolution_strategies::{
+        dynamic_pivot::DynamicPivotResolution, resolution_strategy::ResolutionStrategy,
+    },
+};
+use chrono::{Datelike, Weekday};
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter},
+};
+
+/// # EMA Trend Pullback
+///
+/// Strategy joining an established trend after price pulls back through the
+/// fast EMA and reclaims it.
+///
+/// ## Directionality
+/// - Long
+/// - Short
+///
+/// ## Interval
+/// - Daily
+///
+/// ## Entry Conditions
+/// - Long: Fast EMA above the trend EMA, previous close below the fast EMA
+///   and current close back above it.
+/// - Short: Fast EMA below the trend EMA, previous close above the fast EMA
+///   and current close back below it.
+///
+/// ## Resolution
+/// - Dynamic pivots of length 15.
+///
+/// ## Trading days
+/// - All
+#[derive(Debug, Clone)]
+pub struct EmaTrendPullback {
+    pub fast_len: usize,
+    pub trend_len: usize,
+    pub orientation: StrategyOrientation,
+    pub trading_days: HashSet<Weekday>,
+}
+
+impl EmaTrendPullback {
+    #[allow(dead_code)]
+    pub fn new_args(orientation: StrategyOrientation, fast_len: usize, trend_len: usize) -> Self {
+        Self {
+            fast_len,
+            trend_len,
+            orientation,
+            trading_days: Self::build_trading_days(),
+        }
+    }
+
+    fn ema(&self, candle: CandleRow, len: usize) -> Option<f64> {
+        let ema = candle.clone_indicator(&IndicatorType::EMA(len)).ok()?;
+        Some(ema.as_ema()?.value)
+    }
+
+    fn build_trading_days() -> HashSet<Weekday> {
+        let mut set = HashSet::new();
+
+        set.insert(Weekday::Mon);
+        set.insert(Weekday::Tue);
+        set.insert(Weekday::Wed);
+        set.insert(Weekday::Thu);
+        set.insert(Weekday::Fri);
+        set.insert(Weekday::Sat);
+        set.insert(Weekday::Sun);
+
+        set
+    }
+}
+
+impl HasMinLength for EmaTrendPullback {
+    fn min_length(&self) -> usize {
+        IndicatorType::EMA(self.trend_len).min_length() + self.candles_needed_for_setup()
+    }
+}
+
+impl TradingStrategy for EmaTrendPullback {
+    fn new() -> Self {
+        Self::new_args(StrategyOrientation::Long, 21, 200)
+    }
+
+    fn candles_needed_for_setup(&self) -> usize {
+        2
+    }
+
+    fn check_last_for_setup(&mut self, candles: CandleView) -> Option<SetupBuilder> {
+        if candles.len() < 2 {
+            return None;
+        }
+
+        let current = candles.last()?;
+
+        let is_active_day = self.trading_days.contains(&current.timestamp.weekday());
+        if !is_active_day {
+            return None;
+        }
+
+        let prev = candles.get(candles.len() - 2)?;
+        let prev_fast = self.ema(prev, self.fast_len)?;
+        let fast = self.ema(current, self.fast_len)?;
+        let trend = self.ema(current, self.trend_len)?;
+
+        let is_pullback = match self.orientation {
+            StrategyOrientation::Long => {
+                fast > trend && prev.close < prev_fast && current.close > fast
+            }
+            StrategyOrientation::Short => {
+                fast < trend && prev.close > prev_fast && current.close < fast
+            }
+        };
+
+        if !is_pullback {
+            return None;
+        }
+
+        let sb = SetupBuilder::new()
+            .candle(current)
+            .orientation(&self.orientation);
+
+        Some(sb)
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+
+    fn default_resolution_strategy(&self) -> ResolutionStrategy {
+        ResolutionStrategy::DynamicPivot(DynamicPivotResolution::new())
+    }
+
+    fn orientation(&self) -> StrategyOrientation {
+        self.orientation
+    }
+
+    fn interval(&self) -> Interval {
+        Interval::Day1
+    }
+
+    fn trading_days(&self) -> HashSet<Weekday> {
+        self.trading_days.clone()
+    }
+}
+
+impl RequiresIndicators for EmaTrendPullback {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        let mut indicators = vec![
+            IndicatorType::EMA(self.fast_len),
+            IndicatorType::EMA(self.trend_len),
+        ];
+        indicators.extend(self.default_resolution_strategy().required_indicators());
+
+        indicators
+    }
+}
+
+impl Display for EmaTrendPullback {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EMA Trend Pullback")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{
+            strategy_orientation::StrategyOrientation, traits::trading_strategy::TradingStrategy,
+        },
+        trading_strategies::public::ema_trend_pullback::EmaTrendPullback,
+        utils::data::candle_strategy,
+    };
+
+    #[test]
+    fn finds_setups_on_fixture_data() {
+        let mut long = EmaTrendPullback::new_args(StrategyOrientation::Long, 10, 50);
+        let mut short = EmaTrendPullback::new_args(StrategyOrientation::Short, 10, 50);
+        let buffer = candle_strategy::strategy_candles(&long);
+        let candles = buffer.view();
+
+        // With the 10 EMA at 1061.60 above the 50 EMA at 1043.15, candle 54
+        // closes at 1057.80 below the 10 EMA at 1060.36 and candle 55 closes
+        // back above it at 1067.19. Candle 56 follows a close above the EMA.
+        assert!(long.check_last_for_setup(candles.window(54..=55)).is_some());
+        assert!(long.check_last_for_setup(candles.window(55..=56)).is_none());
+
+        // With the 10 EMA at 1011.66 below the 50 EMA at 1030.45, candle 98
+        // closes at 1012.60 above the 10 EMA at 1011.69 and candle 99 closes
+        // back below it at 1011.49
+        assert!(short
+            .check_last_for_setup(candles.window(98..=99))
+            .is_some());
+        assert!(short
+            .check_last_for_setup(candles.window(99..=100))
+            .is_none());
+    }
+}
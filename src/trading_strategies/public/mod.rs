@@ -1,3 +1,8 @@
 pub mod always_true_strategy;
+pub mod bbwp_squeeze_breakout;
+pub mod bollinger_mean_reversion;
+pub mod donchian_breakout;
+pub mod ema_trend_pullback;
 pub mod rsi_basic;
 pub mod silver_cross;
+pub mod stochastic_cross;
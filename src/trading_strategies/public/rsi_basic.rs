@@ -1,8 +1,9 @@
 use crate::{
     indicators::{indicator_type::IndicatorType, rsi::RSI},
     models::{
-        candle::Candle,
+        candle_buffer::CandleView,
         interval::Interval,
+        ma_type::MAType,
         setups::setup_builder::SetupBuilder,
         strategy_orientation::StrategyOrientation,
         traits::{
@@ -10,7 +11,9 @@ use crate::{
             trading_strategy::TradingStrategy,
         },
     },
-    resolution_strategies::resolution_strategy::ResolutionStrategy,
+    resolution_strategies::{
+        dynamic_pivot::DynamicPivotResolution, resolution_strategy::ResolutionStrategy,
+    },
 };
 use chrono::{Datelike, Weekday};
 use std::{
@@ -26,7 +29,11 @@ use std::{
 /// ## Example:
 ///
 /// If the upper band is set to 70.0 and the current RSI is above 70.0, a short
-/// setup will be triggered when the RSI goes below 70.
+/// setup will be triggered when the RSI goes below 70. Only setups in the
+/// orientation of the strategy are triggered.
+///
+/// ## Resolution
+/// - Dynamic pivots of length 15.
 #[derive(Debug, Clone)]
 pub struct RsiBasic {
     pub len: usize,
@@ -55,14 +62,12 @@ impl RsiBasic {
 
     fn get_orientation(&self, prev: &RSI, current: &RSI) -> Option<StrategyOrientation> {
         let long_condition = prev.value < self.lower_band && current.value > self.lower_band;
-        let _short_condition = prev.value > self.upper_band && current.value < self.upper_band;
-
-        if long_condition {
-            Some(StrategyOrientation::Long)
-        // } else if short_condition {
-        //     Some(StrategyOrientation::Short)
-        } else {
-            None
+        let short_condition = prev.value > self.upper_band && current.value < self.upper_band;
+
+        match self.orientation {
+            StrategyOrientation::Long if long_condition => Some(StrategyOrientation::Long),
+            StrategyOrientation::Short if short_condition => Some(StrategyOrientation::Short),
+            _ => None,
         }
     }
 
@@ -83,7 +88,7 @@ impl RsiBasic {
 
 impl HasMinLength for RsiBasic {
     fn min_length(&self) -> usize {
-        self.len
+        IndicatorType::RSI(self.len, MAType::RMA).min_length() + self.candles_needed_for_setup()
     }
 }
 
@@ -99,11 +104,10 @@ impl TradingStrategy for RsiBasic {
     }
 
     fn candles_needed_for_setup(&self) -> usize {
-        // TODO: Add real value
-        self.len
+        2
     }
 
-    fn check_last_for_setup(&mut self, candles: &[Candle]) -> Option<SetupBuilder> {
+    fn check_last_for_setup(&mut self, candles: CandleView) -> Option<SetupBuilder> {
         if candles.len() < 2 {
             return None;
         }
@@ -117,14 +121,14 @@ impl TradingStrategy for RsiBasic {
 
         let prev = candles.get(candles.len() - 2)?;
 
-        let key = IndicatorType::RSI(self.len);
+        let key = IndicatorType::RSI(self.len, MAType::RMA);
         let prev_rsi = prev.clone_indicator(&key).ok()?.as_rsi()?;
         let current_rsi = current.clone_indicator(&key).ok()?.as_rsi()?;
 
         let orientation = self.get_orientation(&prev_rsi, &current_rsi)?;
 
         let sb = SetupBuilder::new()
-            .candle(&current)
+            .candle(current)
             .orientation(&orientation);
 
         Some(sb)
@@ -135,11 +139,11 @@ impl TradingStrategy for RsiBasic {
     }
 
     fn default_resolution_strategy(&self) -> ResolutionStrategy {
-        todo!()
+        ResolutionStrategy::DynamicPivot(DynamicPivotResolution::new())
     }
 
     fn orientation(&self) -> StrategyOrientation {
-        todo!()
+        self.orientation
     }
 
     fn interval(&self) -> Interval {
@@ -153,7 +157,10 @@ impl TradingStrategy for RsiBasic {
 
 impl RequiresIndicators for RsiBasic {
     fn required_indicators(&self) -> Vec<IndicatorType> {
-        vec![IndicatorType::RSI(self.len)]
+        let mut indicators = vec![IndicatorType::RSI(self.len, MAType::RMA)];
+        indicators.extend(self.default_resolution_strategy().required_indicators());
+
+        indicators
     }
 }
 
@@ -162,3 +169,44 @@ impl Display for RsiBasic {
         write!(f, "RSI Basic")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::indicator_type::IndicatorType,
+        models::{
+            ma_type::MAType,
+            strategy_orientation::StrategyOrientation,
+            traits::{has_min_length::HasMinLength, trading_strategy::TradingStrategy},
+        },
+        trading_strategies::public::rsi_basic::RsiBasic,
+        utils::data::candle_strategy,
+    };
+
+    #[test]
+    fn finds_setups_on_fixture_data() {
+        let mut long = RsiBasic::new_args(14, 60.0, 40.0, StrategyOrientation::Long);
+        let mut short = RsiBasic::new_args(14, 60.0, 40.0, StrategyOrientation::Short);
+        assert_eq!(
+            long.min_length(),
+            IndicatorType::RSI(14, MAType::RMA).min_length() + 2
+        );
+
+        let buffer = candle_strategy::strategy_candles(&long);
+        let candles = buffer.view();
+
+        // The RSI returns from 36.01 to 41.15 on candle 88 and drops back to
+        // 37.04 on candle 89
+        assert!(long.check_last_for_setup(candles.window(87..=88)).is_some());
+        assert!(long.check_last_for_setup(candles.window(88..=89)).is_none());
+
+        // The RSI returns from 62.77 to 56.39 on candle 21 and rises to 60.58
+        // on candle 22 without having been above 60 before
+        assert!(short
+            .check_last_for_setup(candles.window(20..=21))
+            .is_some());
+        assert!(short
+            .check_last_for_setup(candles.window(21..=22))
+            .is_none());
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     indicators::indicator_type::IndicatorType,
     models::{
-        candle::Candle,
+        candle_buffer::{CandleRow, CandleView},
         interval::Interval,
         setups::setup_builder::SetupBuilder,
         strategy_orientation::StrategyOrientation,
@@ -10,9 +10,11 @@ use crate::{
             trading_strategy::TradingStrategy,
         },
     },
-    resolution_strategies::resolution_strategy::ResolutionStrategy,
+    resolution_strategies::{
+        dynamic_pivot::DynamicPivotResolution, resolution_strategy::ResolutionStrategy,
+    },
 };
-use chrono::Weekday;
+use chrono::{Datelike, Weekday};
 use std::{
     collections::HashSet,
     fmt::{Display, Formatter},
@@ -22,6 +24,13 @@ use std::{
 ///
 /// Strategy built on the silver cross event where the 21 SMA crosses the 55
 /// SMA in either orientation.
+///
+/// ## Entry Conditions
+/// - Long: The short SMA crosses above the long SMA.
+/// - Short: The short SMA crosses below the long SMA.
+///
+/// ## Resolution
+/// - Dynamic pivots of length 15.
 #[derive(Debug, Clone)]
 pub struct SilverCross {
     pub short_len: usize,
@@ -41,6 +50,11 @@ impl SilverCross {
         }
     }
 
+    fn sma(&self, candle: CandleRow, len: usize) -> Option<f64> {
+        let sma = candle.clone_indicator(&IndicatorType::SMA(len)).ok()?;
+        Some(sma.as_sma()?.value)
+    }
+
     fn build_trading_days() -> HashSet<Weekday> {
         let mut set = HashSet::new();
 
@@ -58,12 +72,11 @@ impl SilverCross {
 
 impl HasMinLength for SilverCross {
     fn min_length(&self) -> usize {
-        self.long_len
+        self.long_len + self.candles_needed_for_setup()
     }
 }
 
 impl TradingStrategy for SilverCross {
-    #[allow(dead_code)] // TODO: Remove once used
     fn new() -> Self {
         SilverCross {
             orientation: StrategyOrientation::Long,
@@ -74,12 +87,40 @@ impl TradingStrategy for SilverCross {
     }
 
     fn candles_needed_for_setup(&self) -> usize {
-        // TODO: Add real value
-        self.long_len
+        2
     }
 
-    fn check_last_for_setup(&mut self, _candles: &[Candle]) -> Option<SetupBuilder> {
-        todo!()
+    fn check_last_for_setup(&mut self, candles: CandleView) -> Option<SetupBuilder> {
+        if candles.len() < 2 {
+            return None;
+        }
+
+        let current = candles.last()?;
+
+        let is_active_day = self.trading_days.contains(&current.timestamp.weekday());
+        if !is_active_day {
+            return None;
+        }
+
+        let prev = candles.get(candles.len() - 2)?;
+
+        let prev_diff = self.sma(prev, self.short_len)? - self.sma(prev, self.long_len)?;
+        let diff = self.sma(current, self.short_len)? - self.sma(current, self.long_len)?;
+
+        let is_cross = match self.orientation {
+            StrategyOrientation::Long => prev_diff <= 0.0 && diff > 0.0,
+            StrategyOrientation::Short => prev_diff >= 0.0 && diff < 0.0,
+        };
+
+        if !is_cross {
+            return None;
+        }
+
+        let sb = SetupBuilder::new()
+            .candle(current)
+            .orientation(&self.orientation);
+
+        Some(sb)
     }
 
     fn clone_box(&self) -> Box<dyn TradingStrategy> {
@@ -87,11 +128,11 @@ impl TradingStrategy for SilverCross {
     }
 
     fn default_resolution_strategy(&self) -> ResolutionStrategy {
-        todo!()
+        ResolutionStrategy::DynamicPivot(DynamicPivotResolution::new())
     }
 
     fn orientation(&self) -> StrategyOrientation {
-        todo!()
+        self.orientation
     }
 
     fn interval(&self) -> crate::models::interval::Interval {
@@ -99,16 +140,19 @@ impl TradingStrategy for SilverCross {
     }
 
     fn trading_days(&self) -> HashSet<Weekday> {
-        Self::build_trading_days()
+        self.trading_days.clone()
     }
 }
 
 impl RequiresIndicators for SilverCross {
     fn required_indicators(&self) -> Vec<IndicatorType> {
-        vec![
+        let mut indicators = vec![
             IndicatorType::SMA(self.short_len),
             IndicatorType::SMA(self.long_len),
-        ]
+        ];
+        indicators.extend(self.default_resolution_strategy().required_indicators());
+
+        indicators
     }
 }
 
@@ -117,3 +161,39 @@ impl Display for SilverCross {
         write!(f, "Silver Cross")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{
+            strategy_orientation::StrategyOrientation,
+            traits::{has_min_length::HasMinLength, trading_strategy::TradingStrategy},
+        },
+        trading_strategies::public::silver_cross::SilverCross,
+        utils::data::candle_strategy,
+    };
+
+    #[test]
+    fn finds_setups_on_fixture_data() {
+        let mut long = SilverCross::new_args(StrategyOrientation::Long, 5, 20);
+        let mut short = SilverCross::new_args(StrategyOrientation::Short, 5, 20);
+        assert_eq!(long.min_length(), 22);
+
+        let buffer = candle_strategy::strategy_candles(&long);
+        let candles = buffer.view();
+
+        // SMA 5 - SMA 20 goes from -0.96 to 1.84 on candle 58 and stays
+        // positive at 2.65 on candle 59
+        assert!(long.check_last_for_setup(candles.window(57..=58)).is_some());
+        assert!(long.check_last_for_setup(candles.window(58..=59)).is_none());
+
+        // SMA 5 - SMA 20 goes from 2.23 to -1.43 on candle 53 and stays
+        // negative at -3.92 on candle 54
+        assert!(short
+            .check_last_for_setup(candles.window(52..=53))
+            .is_some());
+        assert!(short
+            .check_last_for_setup(candles.window(53..=54))
+            .is_none());
+    }
+}
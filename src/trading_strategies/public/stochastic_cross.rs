@@ -0,0 +1,220 @@
+use crate::{
+    indicators::{indicator_type::IndicatorType, stochastic::Stochastic},
+    models::{
+        candle_buffer::{CandleRow, CandleView},
+        interval::Interval,
+        ma_type::MAType,
+        setups::setup_builder::SetupBuilder,
+        strategy_orientation::StrategyOrientation,
+        traits::{
+            has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+            trading_strategy::TradingStrategy,
+        },
+    },
+    resolution_strategies::{
+        dynamic_pivot::DynamicPivotResolution, resolution_strategy::ResolutionStrategy,
+    },
+};
+use chrono::{Datelike, Weekday};
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter},
+};
+
+/// # Stochastic Cross
+///
+/// Strategy entering when %K crosses %D out of oversold or overbought
+/// territory.
+///
+/// ## Directionality
+/// - Long
+/// - Short
+///
+/// ## Interval
+/// - Daily
+///
+/// ## Entry Conditions
+/// - Long: %K crosses above %D while the previous %K is below the lower band.
+/// - Short: %K crosses below %D while the previous %K is above the upper band.
+///
+/// Bands are given in decimals, e.g. 0.2 and 0.8.
+///
+/// ## Resolution
+/// - Dynamic pivots of length 15.
+///
+/// ## Trading days
+/// - All
+#[derive(Debug, Clone)]
+pub struct StochasticCross {
+    pub k_len: usize,
+    pub k_smoothing: usize,
+    pub d_smoothing: usize,
+    pub lower_band: f64,
+    pub upper_band: f64,
+    pub orientation: StrategyOrientation,
+    pub trading_days: HashSet<Weekday>,
+}
+
+impl StochasticCross {
+    #[allow(dead_code)]
+    pub fn new_args(
+        orientation: StrategyOrientation,
+        k_len: usize,
+        lower_band: f64,
+        upper_band: f64,
+    ) -> Self {
+        Self {
+            k_len,
+            k_smoothing: 3,
+            d_smoothing: 3,
+            lower_band,
+            upper_band,
+            orientation,
+            trading_days: Self::build_trading_days(),
+        }
+    }
+
+    fn indicator_type(&self) -> IndicatorType {
+        IndicatorType::Stochastic(self.k_len, self.k_smoothing, self.d_smoothing, MAType::SMA)
+    }
+
+    fn stochastic(&self, candle: CandleRow) -> Option<Stochastic> {
+        candle
+            .clone_indicator(&self.indicator_type())
+            .ok()?
+            .as_stochastic()
+    }
+
+    fn build_trading_days() -> HashSet<Weekday> {
+        let mut set = HashSet::new();
+
+        set.insert(Weekday::Mon);
+        set.insert(Weekday::Tue);
+        set.insert(Weekday::Wed);
+        set.insert(Weekday::Thu);
+        set.insert(Weekday::Fri);
+        set.insert(Weekday::Sat);
+        set.insert(Weekday::Sun);
+
+        set
+    }
+}
+
+impl HasMinLength for StochasticCross {
+    fn min_length(&self) -> usize {
+        self.indicator_type().min_length() + self.candles_needed_for_setup()
+    }
+}
+
+impl TradingStrategy for StochasticCross {
+    fn new() -> Self {
+        Self::new_args(StrategyOrientation::Long, 14, 0.2, 0.8)
+    }
+
+    fn candles_needed_for_setup(&self) -> usize {
+        2
+    }
+
+    fn check_last_for_setup(&mut self, candles: CandleView) -> Option<SetupBuilder> {
+        if candles.len() < 2 {
+            return None;
+        }
+
+        let current = candles.last()?;
+
+        let is_active_day = self.trading_days.contains(&current.timestamp.weekday());
+        if !is_active_day {
+            return None;
+        }
+
+        let prev = self.stochastic(candles.get(candles.len() - 2)?)?;
+        let stoch = self.stochastic(current)?;
+
+        let is_cross = match self.orientation {
+            StrategyOrientation::Long => {
+                prev.k < self.lower_band && prev.k <= prev.d && stoch.k > stoch.d
+            }
+            StrategyOrientation::Short => {
+                prev.k > self.upper_band && prev.k >= prev.d && stoch.k < stoch.d
+            }
+        };
+
+        if !is_cross {
+            return None;
+        }
+
+        let sb = SetupBuilder::new()
+            .candle(current)
+            .orientation(&self.orientation);
+
+        Some(sb)
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+
+    fn default_resolution_strategy(&self) -> ResolutionStrategy {
+        ResolutionStrategy::DynamicPivot(DynamicPivotResolution::new())
+    }
+
+    fn orientation(&self) -> StrategyOrientation {
+        self.orientation
+    }
+
+    fn interval(&self) -> Interval {
+        Interval::Day1
+    }
+
+    fn trading_days(&self) -> HashSet<Weekday> {
+        self.trading_days.clone()
+    }
+}
+
+impl RequiresIndicators for StochasticCross {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        let mut indicators = vec![self.indicator_type()];
+        indicators.extend(self.default_resolution_strategy().required_indicators());
+
+        indicators
+    }
+}
+
+impl Display for StochasticCross {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Stochastic Cross")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{
+            strategy_orientation::StrategyOrientation, traits::trading_strategy::TradingStrategy,
+        },
+        trading_strategies::public::stochastic_cross::StochasticCross,
+        utils::data::candle_strategy,
+    };
+
+    #[test]
+    fn finds_setups_on_fixture_data() {
+        let mut long = StochasticCross::new_args(StrategyOrientation::Long, 14, 0.2, 0.8);
+        let mut short = StochasticCross::new_args(StrategyOrientation::Short, 14, 0.2, 0.8);
+        let buffer = candle_strategy::strategy_candles(&long);
+        let candles = buffer.view();
+
+        // K is 0.072 below D at 0.103 on candle 78 and crosses above it on
+        // candle 79, 0.144 to 0.101. K is already above D on candle 80.
+        assert!(long.check_last_for_setup(candles.window(78..=79)).is_some());
+        assert!(long.check_last_for_setup(candles.window(79..=80)).is_none());
+
+        // K is 0.934 above D at 0.909 on candle 17 and crosses below it on
+        // candle 18, 0.871 to 0.906. K is already below D on candle 19.
+        assert!(short
+            .check_last_for_setup(candles.window(17..=18))
+            .is_some());
+        assert!(short
+            .check_last_for_setup(candles.window(18..=19))
+            .is_none());
+    }
+}
@@ -0,0 +1 @@
+pub mod regime_filtered_strategy;
@@ -0,0 +1,202 @@
+use crate::{
+    indicators::{indicator_type::IndicatorType, timeframe_indicator::TimeframeIndicator},
+    models::{
+        candle_buffer::CandleView,
+        interval::Interval,
+        market_regime::MarketRegime,
+        regime_classifier::RegimeClassifier,
+        setups::setup_builder::SetupBuilder,
+        strategy_orientation::StrategyOrientation,
+        traits::{
+            has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+            trading_strategy::TradingStrategy,
+        },
+    },
+    resolution_strategies::resolution_strategy::ResolutionStrategy,
+    trading_strategies::public::rsi_basic::RsiBasic,
+};
+use anyhow::Result;
+use chrono::Weekday;
+use serde_json::Value;
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt::{Display, Formatter},
+};
+
+/// # RegimeFilteredStrategy
+///
+/// Restricts the setups of a strategy to the market regimes it is meant for,
+/// e.g. only taking mean reversion setups while the market is ranging. The
+/// regime is classified on the candle of the setup, no setups are taken
+/// while the classifier is warming up.
+///
+/// The strategy is checked on every candle regardless of the regime, so
+/// stateful strategies work as they would on their own.
+#[derive(Debug)]
+pub struct RegimeFilteredStrategy {
+    strategy: Box<dyn TradingStrategy>,
+    classifier: RegimeClassifier,
+    allowed: BTreeSet<MarketRegime>,
+}
+
+impl RegimeFilteredStrategy {
+    #[allow(dead_code)]
+    pub fn new_args(
+        strategy: Box<dyn TradingStrategy>,
+        classifier: RegimeClassifier,
+        allowed: &[MarketRegime],
+    ) -> Self {
+        Self {
+            strategy,
+            classifier,
+            allowed: allowed.iter().copied().collect(),
+        }
+    }
+}
+
+impl Clone for RegimeFilteredStrategy {
+    fn clone(&self) -> Self {
+        Self {
+            strategy: self.strategy.clone_box(),
+            classifier: self.classifier.clone(),
+            allowed: self.allowed.clone(),
+        }
+    }
+}
+
+impl HasMinLength for RegimeFilteredStrategy {
+    fn min_length(&self) -> usize {
+        self.strategy.min_length().max(self.classifier.min_length())
+    }
+}
+
+impl TradingStrategy for RegimeFilteredStrategy {
+    /// RSI Basic, only taking setups while the market is ranging.
+    fn new() -> Self {
+        Self::new_args(
+            Box::new(RsiBasic::new()),
+            RegimeClassifier::new(),
+            &[MarketRegime::Ranging],
+        )
+    }
+
+    fn candles_needed_for_setup(&self) -> usize {
+        self.strategy.candles_needed_for_setup()
+    }
+
+    fn check_last_for_setup(&mut self, candles: CandleView) -> Option<SetupBuilder> {
+        let sb = self.strategy.check_last_for_setup(candles)?;
+        let regime = self.classifier.classify(candles.last()?)?;
+
+        self.allowed.contains(&regime).then_some(sb)
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+
+    fn default_resolution_strategy(&self) -> ResolutionStrategy {
+        self.strategy.default_resolution_strategy()
+    }
+
+    fn orientation(&self) -> StrategyOrientation {
+        self.strategy.orientation()
+    }
+
+    fn interval(&self) -> Interval {
+        self.strategy.interval()
+    }
+
+    fn trading_days(&self) -> HashSet<Weekday> {
+        self.strategy.trading_days()
+    }
+
+    fn state(&self) -> Option<Value> {
+        self.strategy.state()
+    }
+
+    fn restore_state(&mut self, state: Value) -> Result<()> {
+        self.strategy.restore_state(state)
+    }
+}
+
+impl RequiresIndicators for RegimeFilteredStrategy {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        let mut indicators = self.strategy.required_indicators();
+
+        for indicator_type in self.classifier.required_indicators() {
+            if !indicators.contains(&indicator_type) {
+                indicators.push(indicator_type);
+            }
+        }
+
+        indicators
+    }
+
+    fn required_timeframe_indicators(&self) -> Vec<TimeframeIndicator> {
+        self.strategy.required_timeframe_indicators()
+    }
+}
+
+impl Display for RegimeFilteredStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let allowed: Vec<String> = self.allowed.iter().map(|r| r.to_string()).collect();
+        write!(f, "{} [{}]", self.strategy, allowed.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{
+            market_regime::MarketRegime,
+            regime_classifier::RegimeClassifier,
+            strategy_orientation::StrategyOrientation,
+            traits::{requires_indicators::RequiresIndicators, trading_strategy::TradingStrategy},
+        },
+        trading_strategies::{
+            public::bollinger_mean_reversion::BollingerMeanReversion,
+            regimes::regime_filtered_strategy::RegimeFilteredStrategy,
+        },
+        utils::data::candle_strategy,
+    };
+
+    #[test]
+    fn only_takes_setups_in_allowed_regimes() {
+        let inner = BollingerMeanReversion::new_args(StrategyOrientation::Long, 20, 1.5);
+        let classifier = RegimeClassifier::new_args(5, 20, 1.0, 0.9);
+        let mut strategy = RegimeFilteredStrategy::new_args(
+            Box::new(inner.clone()),
+            classifier.clone(),
+            &[MarketRegime::Ranging, MarketRegime::TrendingDown],
+        );
+
+        let buffer = candle_strategy::strategy_candles(&strategy);
+        let candles = buffer.view();
+        let n = strategy.candles_needed_for_setup();
+        let mut unfiltered = inner.clone();
+        let mut found = 0;
+
+        for end in n..=candles.len() {
+            let window = candles.window(end - n..end);
+            let expected = unfiltered.check_last_for_setup(window).is_some()
+                && matches!(
+                    classifier.classify(window.last().unwrap()),
+                    Some(MarketRegime::Ranging | MarketRegime::TrendingDown)
+                );
+
+            let sb = strategy.check_last_for_setup(window);
+            assert_eq!(sb.is_some(), expected);
+            found += sb.is_some() as usize;
+        }
+        assert!(found > 0);
+
+        for indicator_type in inner.required_indicators() {
+            assert!(strategy.required_indicators().contains(&indicator_type));
+        }
+        assert_eq!(
+            strategy.to_string(),
+            "Bollinger Mean Reversion [Trending Down, Ranging]"
+        );
+    }
+}
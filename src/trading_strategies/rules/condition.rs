@@ -0,0 +1,209 @@
+use crate::{
+    indicators::indicator_type::IndicatorType,
+    models::candle_buffer::CandleView,
+    trading_strategies::rules::operand::{Operand, RawOperand},
+};
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// # Condition
+///
+/// Entry rule of a `RuleStrategy`. Conditions compare two operands on the
+/// last candle, or on the last two candles for crosses, and can be combined
+/// with `all`, `any` and `not`. In a strategy config they are written as
+/// tables, e.g. `{ crosses_above = ["rsi(14)", 30] }`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Condition<T = Operand> {
+    All(Vec<Condition<T>>),
+    Any(Vec<Condition<T>>),
+    Not(Box<Condition<T>>),
+    Above(T, T),
+    Below(T, T),
+    CrossesAbove(T, T),
+    CrossesBelow(T, T),
+}
+
+impl Condition<RawOperand> {
+    /// Resolves the operands of the condition.
+    pub fn compile(&self, declared: &BTreeMap<String, IndicatorType>) -> Result<Condition> {
+        let parse = |raw| Operand::parse(raw, declared);
+
+        let condition = match self {
+            Condition::All(conditions) => Condition::All(
+                conditions
+                    .iter()
+                    .map(|c| c.compile(declared))
+                    .collect::<Result<_>>()?,
+            ),
+            Condition::Any(conditions) => Condition::Any(
+                conditions
+                    .iter()
+                    .map(|c| c.compile(declared))
+                    .collect::<Result<_>>()?,
+            ),
+            Condition::Not(condition) => Condition::Not(Box::new(condition.compile(declared)?)),
+            Condition::Above(a, b) => Condition::Above(parse(a)?, parse(b)?),
+            Condition::Below(a, b) => Condition::Below(parse(a)?, parse(b)?),
+            Condition::CrossesAbove(a, b) => Condition::CrossesAbove(parse(a)?, parse(b)?),
+            Condition::CrossesBelow(a, b) => Condition::CrossesBelow(parse(a)?, parse(b)?),
+        };
+
+        Ok(condition)
+    }
+}
+
+impl Condition {
+    /// Returns whether the condition holds for the last candle. Conditions
+    /// on missing values, e.g. indicators still warming up, don't hold, and
+    /// neither do their negations.
+    pub fn is_met(&self, candles: CandleView) -> bool {
+        self.evaluate(candles) == Some(true)
+    }
+
+    /// Evaluates the condition on the last candle, None if it can't be
+    /// decided because of missing values. `all` and `any` are only decided
+    /// by their known conditions when those settle the result, e.g. `all`
+    /// with a false condition is false.
+    fn evaluate(&self, candles: CandleView) -> Option<bool> {
+        let compare = |a: &Operand, b: &Operand, back: usize| -> Option<(f64, f64)> {
+            Some((a.value(candles, back)?, b.value(candles, back)?))
+        };
+
+        match self {
+            Condition::All(conditions) => {
+                let results: Vec<_> = conditions.iter().map(|c| c.evaluate(candles)).collect();
+
+                if results.contains(&Some(false)) {
+                    Some(false)
+                } else {
+                    results
+                        .into_iter()
+                        .collect::<Option<Vec<_>>>()
+                        .map(|_| true)
+                }
+            }
+            Condition::Any(conditions) => {
+                let results: Vec<_> = conditions.iter().map(|c| c.evaluate(candles)).collect();
+
+                if results.contains(&Some(true)) {
+                    Some(true)
+                } else {
+                    results
+                        .into_iter()
+                        .collect::<Option<Vec<_>>>()
+                        .map(|_| false)
+                }
+            }
+            Condition::Not(condition) => condition.evaluate(candles).map(|met| !met),
+            Condition::Above(a, b) => compare(a, b, 0).map(|(a, b)| a > b),
+            Condition::Below(a, b) => compare(a, b, 0).map(|(a, b)| a < b),
+            Condition::CrossesAbove(a, b) => compare(a, b, 0)
+                .zip(compare(a, b, 1))
+                .map(|((a, b), (prev_a, prev_b))| a > b && prev_a <= prev_b),
+            Condition::CrossesBelow(a, b) => compare(a, b, 0)
+                .zip(compare(a, b, 1))
+                .map(|((a, b), (prev_a, prev_b))| a < b && prev_a >= prev_b),
+        }
+    }
+
+    /// Returns all operands of the condition.
+    pub fn operands(&self) -> Vec<Operand> {
+        match self {
+            Condition::All(conditions) | Condition::Any(conditions) => {
+                conditions.iter().flat_map(|c| c.operands()).collect()
+            }
+            Condition::Not(condition) => condition.operands(),
+            Condition::Above(a, b)
+            | Condition::Below(a, b)
+            | Condition::CrossesAbove(a, b)
+            | Condition::CrossesBelow(a, b) => vec![*a, *b],
+        }
+    }
+
+    /// Returns the number of candles needed to evaluate the condition.
+    pub fn candles_needed(&self) -> usize {
+        match self {
+            Condition::All(conditions) | Condition::Any(conditions) => conditions
+                .iter()
+                .map(|c| c.candles_needed())
+                .max()
+                .unwrap_or(1),
+            Condition::Not(condition) => condition.candles_needed(),
+            Condition::Above(a, b) | Condition::Below(a, b) => a.offset.max(b.offset) + 1,
+            Condition::CrossesAbove(a, b) | Condition::CrossesBelow(a, b) => {
+                a.offset.max(b.offset) + 2
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{candle::Candle, candle_buffer::CandleBuffer},
+        trading_strategies::rules::{condition::Condition, operand::RawOperand},
+    };
+    use chrono::{Duration, TimeZone, Utc};
+    use std::collections::BTreeMap;
+
+    fn candles(closes: &[f64]) -> CandleBuffer {
+        let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| Candle::from_val(start + Duration::days(i as i64), *close, 1.0))
+            .collect::<Vec<_>>()
+            .as_slice()
+            .into()
+    }
+
+    fn compile(toml: &str) -> Condition {
+        let raw: Condition<RawOperand> =
+            toml::from_str::<BTreeMap<String, Condition<RawOperand>>>(toml)
+                .unwrap()
+                .remove("entry")
+                .unwrap();
+
+        raw.compile(&BTreeMap::new()).unwrap()
+    }
+
+    #[test]
+    fn evaluates_conditions() {
+        let crosses = compile(r#"entry = { crosses_above = ["close", 100] }"#);
+        assert!(crosses.is_met(candles(&[99.0, 101.0]).view()));
+        assert!(!crosses.is_met(candles(&[101.0, 102.0]).view()));
+        assert!(!crosses.is_met(candles(&[101.0]).view()));
+        assert_eq!(crosses.candles_needed(), 2);
+
+        let rising = compile(
+            r#"entry = { all = [
+                { above = ["close", "close[1]"] },
+                { not = { above = ["close", 105] } },
+            ] }"#,
+        );
+        assert!(rising.is_met(candles(&[101.0, 102.0]).view()));
+        assert!(!rising.is_met(candles(&[102.0, 101.0]).view()));
+        assert!(!rising.is_met(candles(&[102.0, 106.0]).view()));
+
+        // Negations of conditions on missing values don't hold either
+        let not_crossing = compile(r#"entry = { not = { crosses_above = ["close", 100] } }"#);
+        assert!(not_crossing.is_met(candles(&[101.0, 102.0]).view()));
+        assert!(!not_crossing.is_met(candles(&[101.0]).view()));
+
+        let not_both = compile(
+            r#"entry = { not = { all = [{ below = ["close", 50] }, { above = ["close[1]", 50] }] } }"#,
+        );
+        assert!(not_both.is_met(candles(&[60.0]).view()));
+        assert!(!not_both.is_met(candles(&[40.0]).view()));
+
+        let either = compile(
+            r#"entry = { any = [{ below = ["close", 50] }, { crosses_below = ["close", "open[2]"] }] }"#,
+        );
+        assert_eq!(either.candles_needed(), 4);
+        assert!(either.is_met(candles(&[40.0]).view()));
+        assert!(!either.is_met(candles(&[60.0]).view()));
+    }
+}
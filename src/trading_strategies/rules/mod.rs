@@ -0,0 +1,3 @@
+pub mod condition;
+pub mod operand;
+pub mod rule_strategy;
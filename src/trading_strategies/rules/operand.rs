@@ -0,0 +1,172 @@
+use crate::{
+    indicators::{indicator_field::IndicatorField, indicator_type::IndicatorType},
+    models::candle_buffer::CandleView,
+};
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Value an Operand is read from.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OperandSource {
+    Value(f64),
+    Open,
+    High,
+    Low,
+    Close,
+    Volume,
+    Indicator(IndicatorField),
+}
+
+/// # Operand
+///
+/// Side of a rule condition. Operands are either numbers or specs such as
+/// `close`, `rsi(14)`, `bb(20).upper` or the name of an indicator declared
+/// by the strategy. A `[n]` suffix reads the value `n` candles back, e.g.
+/// `close[1]` for the previous close.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Operand {
+    pub source: OperandSource,
+    pub offset: usize,
+}
+
+/// Operand as written in a strategy config, before declared indicator names
+/// are resolved.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum RawOperand {
+    Number(f64),
+    Spec(String),
+}
+
+impl Operand {
+    /// Resolves a raw operand, looking up indicator names in `declared`
+    /// before parsing them as indicator specs.
+    pub fn parse(raw: &RawOperand, declared: &BTreeMap<String, IndicatorType>) -> Result<Self> {
+        let spec = match raw {
+            RawOperand::Number(value) => {
+                return Ok(Self {
+                    source: OperandSource::Value(*value),
+                    offset: 0,
+                })
+            }
+            RawOperand::Spec(spec) => spec.trim(),
+        };
+
+        let (spec, offset) = match spec.strip_suffix(']') {
+            Some(rest) => {
+                let (spec, offset) = rest
+                    .rsplit_once('[')
+                    .context(format!("Missing opening bracket in '{}'.", spec))?;
+                let offset = offset
+                    .trim()
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid candle offset in '{}'.", spec))?;
+
+                (spec.trim(), offset)
+            }
+            None => (spec, 0),
+        };
+
+        let source = match spec.to_lowercase().as_str() {
+            "open" => OperandSource::Open,
+            "high" => OperandSource::High,
+            "low" => OperandSource::Low,
+            "close" => OperandSource::Close,
+            "volume" => OperandSource::Volume,
+            _ => match spec.parse::<f64>() {
+                Ok(value) => OperandSource::Value(value),
+                Err(_) => OperandSource::Indicator(Self::parse_field(spec, declared)?),
+            },
+        };
+
+        Ok(Self { source, offset })
+    }
+
+    fn parse_field(
+        spec: &str,
+        declared: &BTreeMap<String, IndicatorType>,
+    ) -> Result<IndicatorField> {
+        let (name, field) = match spec.split_once('.') {
+            Some((name, field)) if !name.contains('(') => (name, Some(field)),
+            _ => (spec, None),
+        };
+
+        match declared.get(name.trim()) {
+            Some(indicator_type) => {
+                let field = field.unwrap_or(indicator_type.definition().outputs[0]);
+                IndicatorField::new(*indicator_type, field)
+            }
+            None => spec.parse(),
+        }
+    }
+
+    /// Returns the indicator the operand reads from, if any.
+    pub fn indicator_type(&self) -> Option<IndicatorType> {
+        match self.source {
+            OperandSource::Indicator(field) => Some(field.indicator_type),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the operand `back` candles before the last one.
+    pub fn value(&self, candles: CandleView, back: usize) -> Option<f64> {
+        if let OperandSource::Value(value) = self.source {
+            return Some(value);
+        }
+
+        let i = candles.len().checked_sub(1 + self.offset + back)?;
+        let candle = candles.get(i)?;
+
+        match self.source {
+            OperandSource::Value(value) => Some(value),
+            OperandSource::Open => Some(candle.open),
+            OperandSource::High => Some(candle.high),
+            OperandSource::Low => Some(candle.low),
+            OperandSource::Close => Some(candle.close),
+            OperandSource::Volume => Some(candle.volume),
+            OperandSource::Indicator(field) => candle.field_value(&field),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::indicator_type::IndicatorType,
+        models::ma_type::MAType,
+        trading_strategies::rules::operand::{Operand, OperandSource, RawOperand},
+    };
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn parse_operands() {
+        let declared = BTreeMap::from([("fast".to_string(), IndicatorType::EMA(12))]);
+        let parse = |s: &str| Operand::parse(&RawOperand::Spec(s.to_string()), &declared);
+
+        let close = parse("close[2]").unwrap();
+        assert_eq!(close.source, OperandSource::Close);
+        assert_eq!(close.offset, 2);
+
+        let upper = parse("bb(20).upper").unwrap();
+        assert_eq!(
+            upper.indicator_type(),
+            Some(IndicatorType::BollingerBands(20, 2.0.into()))
+        );
+
+        let rsi = parse("rsi(14,rma)[1]").unwrap();
+        assert_eq!(
+            rsi.indicator_type(),
+            Some(IndicatorType::RSI(14, MAType::RMA))
+        );
+        assert_eq!(rsi.offset, 1);
+
+        let fast = parse("fast.value").unwrap();
+        assert_eq!(fast.indicator_type(), Some(IndicatorType::EMA(12)));
+        assert_eq!(parse("70").unwrap().source, OperandSource::Value(70.0));
+
+        for s in ["slow", "fast.upper", "close[x]", "close]"] {
+            assert!(parse(s).is_err(), "{}", s);
+        }
+    }
+}
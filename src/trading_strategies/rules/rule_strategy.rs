@@ -0,0 +1,350 @@
+use crate::{
+    indicators::indicator_type::IndicatorType,
+    models::{
+        candle_buffer::CandleView,
+        interval::Interval,
+        setups::setup_builder::SetupBuilder,
+        strategy_orientation::StrategyOrientation,
+        traits::{
+            has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+            trading_strategy::TradingStrategy,
+        },
+    },
+    resolution_strategies::{
+        instant_resolution::InstantResolution, resolution_strategy::ResolutionStrategy,
+    },
+    trading_strategies::rules::{condition::Condition, operand::RawOperand},
+};
+use anyhow::{anyhow, Context, Result};
+use chrono::{Datelike, Weekday};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::{Display, Formatter},
+    fs,
+    path::Path,
+};
+
+/// Strategy config as written in a TOML file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleStrategyConfig {
+    name: String,
+    interval: Interval,
+    orientation: StrategyOrientation,
+    #[serde(default)]
+    trading_days: Vec<Weekday>,
+    resolution: ResolutionStrategy,
+    #[serde(default)]
+    indicators: BTreeMap<String, String>,
+    entry: Condition<RawOperand>,
+}
+
+/// # RuleStrategy
+///
+/// Strategy defined by entry rules in a config file rather than in Rust.
+/// Indicators used by the rules or the resolution strategy are added to the
+/// TimeSeries automatically, and can also be declared under a name to keep
+/// the rules readable.
+///
+/// ## Example:
+///
+/// ```toml
+/// name = "RSI reversal"
+/// interval = "4h"
+/// orientation = "Long"
+/// trading_days = ["Mon", "Tue", "Wed", "Thu", "Fri"]
+/// resolution = { DynamicPivot = { len = 15 } }
+///
+/// [indicators]
+/// trend = "ema(200)"
+///
+/// [entry]
+/// all = [
+///     { crosses_above = ["rsi(14)", 30] },
+///     { above = ["close", "trend"] },
+/// ]
+/// ```
+///
+/// Leaving out the trading days trades every day of the week.
+#[derive(Debug, Clone)]
+pub struct RuleStrategy {
+    pub name: String,
+    pub interval: Interval,
+    pub orientation: StrategyOrientation,
+    pub trading_days: HashSet<Weekday>,
+    pub resolution_strategy: ResolutionStrategy,
+    pub indicators: Vec<IndicatorType>,
+    pub entry: Condition,
+}
+
+impl RuleStrategy {
+    /// Loads a strategy from a TOML config file.
+    #[allow(dead_code)]
+    pub fn load(path: impl AsRef<Path>) -> Result<Box<dyn TradingStrategy>> {
+        let path = path.as_ref();
+        let config = fs::read_to_string(path)
+            .context(format!("Unable to read strategy config {}", path.display()))?;
+
+        Ok(Box::new(Self::from_toml(&config)?))
+    }
+
+    /// Parses a strategy from the contents of a TOML config file.
+    pub fn from_toml(config: &str) -> Result<Self> {
+        let config: RuleStrategyConfig =
+            toml::from_str(config).map_err(|e| anyhow!("Invalid strategy config: {}", e))?;
+
+        let declared = config
+            .indicators
+            .iter()
+            .map(|(name, spec)| {
+                let indicator_type = spec
+                    .parse()
+                    .context(format!("Invalid indicator '{}'.", name))?;
+                Ok((name.clone(), indicator_type))
+            })
+            .collect::<Result<BTreeMap<String, IndicatorType>>>()?;
+
+        let entry = config
+            .entry
+            .compile(&declared)
+            .context("Invalid entry conditions.")?;
+
+        let operands = entry.operands();
+        let used = operands.iter().filter_map(|o| o.indicator_type());
+        let resolution = config.resolution.required_indicators();
+
+        let mut indicators: Vec<IndicatorType> = declared.values().copied().collect();
+        for indicator_type in used.chain(resolution) {
+            if !indicators.contains(&indicator_type) {
+                indicators.push(indicator_type);
+            }
+        }
+
+        let trading_days = match config.trading_days.is_empty() {
+            true => Self::build_trading_days(),
+            false => config.trading_days.into_iter().collect(),
+        };
+
+        Ok(Self {
+            name: config.name,
+            interval: config.interval,
+            orientation: config.orientation,
+            trading_days,
+            resolution_strategy: config.resolution,
+            indicators,
+            entry,
+        })
+    }
+
+    fn build_trading_days() -> HashSet<Weekday> {
+        let mut set = HashSet::new();
+
+        set.insert(Weekday::Mon);
+        set.insert(Weekday::Tue);
+        set.insert(Weekday::Wed);
+        set.insert(Weekday::Thu);
+        set.insert(Weekday::Fri);
+        set.insert(Weekday::Sat);
+        set.insert(Weekday::Sun);
+
+        set
+    }
+}
+
+impl HasMinLength for RuleStrategy {
+    fn min_length(&self) -> usize {
+        let warmup = self
+            .indicators
+            .iter()
+            .map(|i| i.min_length())
+            .max()
+            .unwrap_or(0);
+
+        warmup + self.candles_needed_for_setup()
+    }
+}
+
+impl TradingStrategy for RuleStrategy {
+    /// Returns a strategy without entry rules, which never triggers. Rule
+    /// strategies are meant to be loaded from config files.
+    fn new() -> Self {
+        Self {
+            name: "Rule strategy".to_string(),
+            interval: Interval::Day1,
+            orientation: StrategyOrientation::Long,
+            trading_days: Self::build_trading_days(),
+            resolution_strategy: ResolutionStrategy::Instant(InstantResolution),
+            indicators: vec![],
+            entry: Condition::Any(vec![]),
+        }
+    }
+
+    fn candles_needed_for_setup(&self) -> usize {
+        self.entry.candles_needed()
+    }
+
+    fn check_last_for_setup(&mut self, candles: CandleView) -> Option<SetupBuilder> {
+        let current = candles.last()?;
+
+        let is_active_day = self.trading_days.contains(&current.timestamp.weekday());
+        if !is_active_day || !self.entry.is_met(candles) {
+            return None;
+        }
+
+        let sb = SetupBuilder::new()
+            .candle(current)
+            .orientation(&self.orientation);
+
+        Some(sb)
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+
+    fn default_resolution_strategy(&self) -> ResolutionStrategy {
+        self.resolution_strategy.clone()
+    }
+
+    fn orientation(&self) -> StrategyOrientation {
+        self.orientation
+    }
+
+    fn interval(&self) -> Interval {
+        self.interval.clone()
+    }
+
+    fn trading_days(&self) -> HashSet<Weekday> {
+        self.trading_days.clone()
+    }
+}
+
+impl RequiresIndicators for RuleStrategy {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        self.indicators.clone()
+    }
+}
+
+impl Display for RuleStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::indicator_type::IndicatorType,
+        models::{
+            candle::Candle,
+            interval::Interval,
+            ma_type::MAType,
+            strategy_orientation::StrategyOrientation,
+            timeseries_builder::TimeSeriesBuilder,
+            traits::{
+                has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+                trading_strategy::TradingStrategy,
+            },
+        },
+        resolution_strategies::resolution_strategy::ResolutionStrategy,
+        strategy_testing::strategy_tester::StrategyTester,
+        trading_strategies::rules::rule_strategy::RuleStrategy,
+    };
+    use chrono::{Duration, TimeZone, Utc, Weekday};
+
+    const CONFIG: &str = r#"
+        name = "Close above SMA"
+        interval = "1d"
+        orientation = "Long"
+        trading_days = ["Mon", "Tue", "Wed", "Thu", "Fri"]
+        resolution = { FixedValues = { high = 110.0, low = 90.0 } }
+
+        [indicators]
+        trend = "sma(3)"
+
+        [entry]
+        all = [
+            { crosses_above = ["close", "trend"] },
+            { above = ["close", "close[1]"] },
+            { below = ["rsi(14)", 80] },
+        ]
+    "#;
+
+    #[test]
+    fn parses_config() {
+        let strategy = RuleStrategy::from_toml(CONFIG).unwrap();
+
+        assert_eq!(strategy.to_string(), "Close above SMA");
+        assert_eq!(strategy.interval(), Interval::Day1);
+        assert_eq!(strategy.trading_days().len(), 5);
+        assert!(!strategy.trading_days().contains(&Weekday::Sat));
+        assert!(matches!(
+            strategy.default_resolution_strategy(),
+            ResolutionStrategy::FixedValues(_)
+        ));
+        assert_eq!(
+            strategy.required_indicators(),
+            vec![IndicatorType::SMA(3), IndicatorType::RSI(14, MAType::RMA)]
+        );
+        assert_eq!(strategy.candles_needed_for_setup(), 2);
+        assert_eq!(strategy.min_length(), 17);
+
+        let invalid = [
+            CONFIG.replace("sma(3)", "foo(3)"),
+            CONFIG.replace("\"trend\"", "\"slow\""),
+            CONFIG.replace("crosses_above", "crosses"),
+            CONFIG.replace("interval = \"1d\"", ""),
+        ];
+        for config in invalid {
+            assert!(RuleStrategy::from_toml(&config).is_err());
+        }
+    }
+
+    #[test]
+    fn loads_example_config() {
+        let strategy = RuleStrategy::load("strategies/rsi_reversal.toml").unwrap();
+
+        assert_eq!(strategy.to_string(), "RSI reversal");
+        assert_eq!(strategy.interval(), Interval::Hour4);
+        assert!(strategy
+            .required_indicators()
+            .contains(&IndicatorType::DynamicPivot(15)));
+    }
+
+    #[test]
+    fn finds_setups_in_strategy_tester() {
+        let config = CONFIG
+            .replace("{ below = [\"rsi(14)\", 80] },", "")
+            .replace(
+                "{ FixedValues = { high = 110.0, low = 90.0 } }",
+                "{ DynamicPivot = { len = 1 } }",
+            );
+        let strategy = RuleStrategy::from_toml(&config).unwrap();
+        assert!(matches!(strategy.orientation(), StrategyOrientation::Long));
+
+        // Weekly candles on Mondays, dipping below and crossing back above
+        // the 3 candle SMA once before reaching the take-profit
+        let start = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        let candles: Vec<Candle> = [100.0, 100.0, 100.0, 98.0, 96.0, 101.0, 102.0, 111.0]
+            .iter()
+            .enumerate()
+            .map(|(i, close)| Candle::from_val(start + Duration::weeks(i as i64), *close, 1.0))
+            .collect();
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Week1)
+            .candles(candles)
+            .build();
+        for indicator_type in strategy.required_indicators() {
+            ts.add_indicator(indicator_type).unwrap();
+        }
+
+        let mut strategy: Box<dyn TradingStrategy> = Box::new(strategy);
+        let result = StrategyTester::test_strategy(&mut strategy, ts.buffer.view()).unwrap();
+
+        assert_eq!(result.n_setups, 1);
+        assert_eq!(result.accuracy, 1.0);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod script_engine;
+pub mod script_strategy;
@@ -0,0 +1,87 @@
+use crate::{indicators::indicator_field::IndicatorField, models::candle::Candle};
+use rhai::{module_resolvers::DummyModuleResolver, Dynamic, Engine, EvalAltResult};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::{debug, info};
+
+/// Maximum number of operations a single script call may run.
+const MAX_OPERATIONS: u64 = 1_000_000;
+/// Maximum wall clock time a single script call may run.
+pub const TIME_LIMIT: Duration = Duration::from_millis(250);
+
+/// # ScriptEngine
+///
+/// Sandboxed Rhai engine used to run strategy scripts. Scripts have no
+/// access to the file system, network or other modules, `eval` is disabled
+/// and calls are terminated once they exceed the operation or time limit.
+///
+/// Candles are passed to scripts as read-only copies exposing `open`,
+/// `high`, `low`, `close`, `volume` and `timestamp` (Unix milliseconds),
+/// along with `value(spec)` returning an indicator output such as
+/// `candle.value("bb(20).upper")`, or `()` if the indicator has no value
+/// for the candle.
+pub struct ScriptEngine {
+    pub engine: Engine,
+    deadline: Arc<Mutex<Instant>>,
+}
+
+impl ScriptEngine {
+    pub fn new() -> Self {
+        let deadline = Arc::new(Mutex::new(Instant::now()));
+        let mut engine = Engine::new();
+
+        engine
+            .set_module_resolver(DummyModuleResolver::new())
+            .disable_symbol("eval")
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_call_levels(32)
+            .set_max_expr_depths(64, 32)
+            .set_max_string_size(10_000)
+            .set_max_array_size(10_000)
+            .set_max_map_size(1_000)
+            .on_print(|s| info!(script_output = s, "Script print"))
+            .on_debug(|s, _, _| debug!(script_output = s, "Script debug"));
+
+        let limit = deadline.clone();
+        engine.on_progress(move |_| {
+            let deadline = *limit.lock().unwrap_or_else(|e| e.into_inner());
+
+            match Instant::now() > deadline {
+                true => Some("Script time limit exceeded".into()),
+                false => None,
+            }
+        });
+
+        engine
+            .register_type_with_name::<Candle>("Candle")
+            .register_get("open", |c: &mut Candle| c.open)
+            .register_get("high", |c: &mut Candle| c.high)
+            .register_get("low", |c: &mut Candle| c.low)
+            .register_get("close", |c: &mut Candle| c.close)
+            .register_get("volume", |c: &mut Candle| c.volume)
+            .register_get("timestamp", |c: &mut Candle| c.timestamp.timestamp_millis())
+            .register_fn("value", Self::value);
+
+        Self { engine, deadline }
+    }
+
+    /// Starts the time limit for the next script call.
+    pub fn start_call(&self) {
+        *self.deadline.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now() + TIME_LIMIT;
+    }
+
+    fn value(candle: &mut Candle, spec: &str) -> Result<Dynamic, Box<EvalAltResult>> {
+        let field: IndicatorField = spec
+            .parse()
+            .map_err(|e| format!("Invalid indicator '{}': {}", spec, e))?;
+
+        let value = candle
+            .indicators
+            .get(&field.indicator_type)
+            .and_then(|indicator| indicator.field(field.field));
+
+        Ok(value.map_or(Dynamic::UNIT, Dynamic::from_float))
+    }
+}
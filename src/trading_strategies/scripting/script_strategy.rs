@@ -0,0 +1,502 @@
+use crate::{
+    indicators::indicator_type::IndicatorType,
+    models::{
+        candle_buffer::CandleView,
+        interval::Interval,
+        setups::setup_builder::SetupBuilder,
+        strategy_orientation::StrategyOrientation,
+        traits::{
+            has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+            trading_strategy::TradingStrategy,
+        },
+    },
+    resolution_strategies::{
+        instant_resolution::InstantResolution, resolution_strategy::ResolutionStrategy,
+    },
+    trading_strategies::scripting::script_engine::ScriptEngine,
+};
+use anyhow::{anyhow, Context, Result};
+use chrono::{Datelike, Weekday};
+use rhai::{Array, Dynamic, Scope, AST};
+use serde::Deserialize;
+use std::{
+    collections::HashSet,
+    fmt::{Debug, Display, Formatter},
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::SystemTime,
+};
+use tracing::{info, warn};
+
+/// Settings returned by the `config` function of a strategy script.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScriptConfig {
+    name: String,
+    interval: Interval,
+    orientation: StrategyOrientation,
+    resolution: ResolutionStrategy,
+    /// Number of candles passed to `check`.
+    #[serde(default = "ScriptConfig::default_candles")]
+    candles: usize,
+    #[serde(default)]
+    trading_days: Vec<Weekday>,
+    #[serde(default)]
+    indicators: Vec<String>,
+}
+
+impl ScriptConfig {
+    fn default_candles() -> usize {
+        2
+    }
+}
+
+/// Compiled script along with its config and the modification time of the
+/// file it was compiled from.
+struct LoadedScript {
+    ast: AST,
+    config: ScriptConfig,
+    modified: Option<SystemTime>,
+}
+
+/// # ScriptStrategy
+///
+/// Strategy implemented in a Rhai script, so its logic can be changed
+/// without recompiling or restarting the bot. The script defines a `config`
+/// function returning the settings of the strategy and a `check` function
+/// returning whether the last of the given candles is a setup:
+///
+/// ```rhai
+/// fn config() {
+///     #{
+///         name: "RSI cross",
+///         interval: "4h",
+///         orientation: "Long",
+///         resolution: #{ DynamicPivot: #{ len: 15 } },
+///         indicators: ["rsi(14)"],
+///     }
+/// }
+///
+/// fn check(candles) {
+///     let prev = candles[-2].value("rsi(14)");
+///     let last = candles[-1].value("rsi(14)");
+///
+///     prev != () && last != () && prev < 30.0 && last > 30.0
+/// }
+/// ```
+///
+/// The script file is reloaded when it changes, which is shared between all
+/// clones of the strategy. Invalid changes are logged and the previous
+/// version kept. Since indicators and the interval are set up when the
+/// TimeSeries is created, changes to them only apply after a restart.
+#[derive(Clone)]
+pub struct ScriptStrategy {
+    path: PathBuf,
+    engine: Arc<ScriptEngine>,
+    script: Arc<RwLock<LoadedScript>>,
+    interval: Interval,
+    indicators: Vec<IndicatorType>,
+}
+
+impl ScriptStrategy {
+    /// Loads a strategy from a Rhai script file.
+    #[allow(dead_code)]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let engine = ScriptEngine::new();
+        let script = Self::load_script(&engine, &path)?;
+
+        Ok(Self::from_script(path, engine, script))
+    }
+
+    fn from_script(path: PathBuf, engine: ScriptEngine, script: LoadedScript) -> Self {
+        Self {
+            path,
+            interval: script.config.interval.clone(),
+            indicators: Self::indicators(&script.config).unwrap_or_default(),
+            engine: Arc::new(engine),
+            script: Arc::new(RwLock::new(script)),
+        }
+    }
+
+    fn load_script(engine: &ScriptEngine, path: &Path) -> Result<LoadedScript> {
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+        let source = fs::read_to_string(path)
+            .context(format!("Unable to read strategy script {}", path.display()))?;
+
+        let mut script = Self::compile(engine, &source)?;
+        script.modified = modified;
+
+        Ok(script)
+    }
+
+    fn compile(engine: &ScriptEngine, source: &str) -> Result<LoadedScript> {
+        let ast = engine
+            .engine
+            .compile(source)
+            .map_err(|e| anyhow!("Unable to compile strategy script: {}", e))?;
+
+        engine.start_call();
+        let config: Dynamic = engine
+            .engine
+            .call_fn(&mut Scope::new(), &ast, "config", ())
+            .map_err(|e| anyhow!("Unable to get strategy script config: {}", e))?;
+        let config: ScriptConfig = rhai::serde::from_dynamic(&config)
+            .map_err(|e| anyhow!("Invalid strategy script config: {}", e))?;
+
+        Self::indicators(&config)?;
+
+        Ok(LoadedScript {
+            ast,
+            config,
+            modified: None,
+        })
+    }
+
+    /// Returns the indicators declared by the script along with the ones
+    /// required by its resolution strategy.
+    fn indicators(config: &ScriptConfig) -> Result<Vec<IndicatorType>> {
+        let mut indicators = config
+            .indicators
+            .iter()
+            .map(|spec| spec.parse())
+            .collect::<Result<Vec<IndicatorType>>>()?;
+
+        for indicator_type in config.resolution.required_indicators() {
+            if !indicators.contains(&indicator_type) {
+                indicators.push(indicator_type);
+            }
+        }
+
+        Ok(indicators)
+    }
+
+    /// Reloads the script if its file has changed since it was loaded.
+    fn reload_if_changed(&self) {
+        let modified = fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        let Ok(mut script) = self.script.write() else {
+            return;
+        };
+
+        if modified.is_none() || modified == script.modified {
+            return;
+        }
+
+        let name = script.config.name.clone();
+        script.modified = modified;
+
+        let reloaded = match Self::load_script(&self.engine, &self.path) {
+            Ok(reloaded) => reloaded,
+            Err(e) => {
+                warn!(strategy = %name, error = %e, "Keeping previous strategy script");
+                return;
+            }
+        };
+
+        let indicators = Self::indicators(&reloaded.config).unwrap_or_default();
+        if reloaded.config.interval != self.interval
+            || indicators.iter().any(|i| !self.indicators.contains(i))
+        {
+            warn!(
+                strategy = %name,
+                "Interval and indicator changes apply after restarting the strategy"
+            );
+        }
+
+        info!(strategy = %reloaded.config.name, "Reloaded strategy script");
+        *script = reloaded;
+    }
+
+    fn read_config(&self) -> Option<ScriptConfig> {
+        self.script.read().ok().map(|s| s.config.clone())
+    }
+}
+
+impl HasMinLength for ScriptStrategy {
+    fn min_length(&self) -> usize {
+        let warmup = self
+            .indicators
+            .iter()
+            .map(|i| i.min_length())
+            .max()
+            .unwrap_or(0);
+
+        warmup + self.candles_needed_for_setup()
+    }
+}
+
+impl TradingStrategy for ScriptStrategy {
+    /// Returns a strategy running a script which never triggers. Script
+    /// strategies are meant to be loaded from files.
+    fn new() -> Self {
+        let engine = ScriptEngine::new();
+        let config = ScriptConfig {
+            name: "Script strategy".to_string(),
+            interval: Interval::Day1,
+            orientation: StrategyOrientation::Long,
+            resolution: ResolutionStrategy::Instant(InstantResolution),
+            candles: ScriptConfig::default_candles(),
+            trading_days: vec![],
+            indicators: vec![],
+        };
+        let script = LoadedScript {
+            ast: AST::empty(),
+            config,
+            modified: None,
+        };
+
+        Self::from_script(PathBuf::new(), engine, script)
+    }
+
+    fn candles_needed_for_setup(&self) -> usize {
+        self.read_config().map_or(1, |config| config.candles.max(1))
+    }
+
+    fn check_last_for_setup(&mut self, candles: CandleView) -> Option<SetupBuilder> {
+        self.reload_if_changed();
+
+        let current = candles.last()?;
+        let script = self.script.read().ok()?;
+        let config = &script.config;
+
+        let is_active_day = config.trading_days.is_empty()
+            || config.trading_days.contains(&current.timestamp.weekday());
+        if !is_active_day || script.ast.iter_functions().all(|f| f.name != "check") {
+            return None;
+        }
+
+        let array: Array = candles
+            .iter()
+            .map(|candle| Dynamic::from(candle.to_candle()))
+            .collect();
+
+        self.engine.start_call();
+        let is_setup =
+            self.engine
+                .engine
+                .call_fn::<bool>(&mut Scope::new(), &script.ast, "check", (array,));
+
+        match is_setup {
+            Ok(true) => Some(
+                SetupBuilder::new()
+                    .candle(current)
+                    .orientation(&config.orientation),
+            ),
+            Ok(false) => None,
+            Err(e) => {
+                warn!(strategy = %config.name, error = %e, "Strategy script failed");
+                None
+            }
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+
+    fn default_resolution_strategy(&self) -> ResolutionStrategy {
+        self.read_config()
+            .map_or(ResolutionStrategy::Instant(InstantResolution), |config| {
+                config.resolution
+            })
+    }
+
+    fn orientation(&self) -> StrategyOrientation {
+        self.read_config()
+            .map_or(StrategyOrientation::Long, |config| config.orientation)
+    }
+
+    fn interval(&self) -> Interval {
+        self.interval.clone()
+    }
+
+    fn trading_days(&self) -> HashSet<Weekday> {
+        let days = self
+            .read_config()
+            .map(|c| c.trading_days)
+            .unwrap_or_default();
+
+        match days.is_empty() {
+            true => HashSet::from([
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+                Weekday::Sat,
+                Weekday::Sun,
+            ]),
+            false => days.into_iter().collect(),
+        }
+    }
+}
+
+impl RequiresIndicators for ScriptStrategy {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        self.indicators.clone()
+    }
+}
+
+impl Debug for ScriptStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptStrategy")
+            .field("path", &self.path)
+            .field("interval", &self.interval)
+            .field("indicators", &self.indicators)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Display for ScriptStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.read_config() {
+            Some(config) => write!(f, "{}", config.name),
+            None => write!(f, "Script strategy"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{indicator_type::IndicatorType, populates_candles::PopulatesCandlesWithSelf},
+        models::{
+            candle::Candle,
+            candle_buffer::CandleBuffer,
+            interval::Interval,
+            timeseries_builder::TimeSeriesBuilder,
+            traits::{requires_indicators::RequiresIndicators, trading_strategy::TradingStrategy},
+        },
+        trading_strategies::scripting::{
+            script_engine::TIME_LIMIT, script_strategy::ScriptStrategy,
+        },
+    };
+    use chrono::{Duration, TimeZone, Utc};
+    use std::{
+        fs::{self, File},
+        path::PathBuf,
+        time::{Instant, SystemTime},
+    };
+
+    const SCRIPT: &str = r#"
+        fn config() {
+            #{
+                name: "Close above SMA",
+                interval: "1d",
+                orientation: "Long",
+                resolution: #{ FixedValues: #{ high: 110.0, low: 90.0 } },
+                indicators: ["sma(3)"],
+            }
+        }
+
+        fn check(candles) {
+            let sma = candles[-1].value("sma(3)");
+            sma != () && candles[-1].close > sma
+        }
+    "#;
+
+    fn candles(closes: &[f64]) -> CandleBuffer {
+        let start = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        let candles = closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| Candle::from_val(start + Duration::days(i as i64), *close, 1.0))
+            .collect();
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+        IndicatorType::SMA(3).populate_candles(&mut ts).unwrap();
+
+        (*ts.buffer).clone()
+    }
+
+    /// Writes the script to a temporary file, bumping its modification time
+    /// so that rewrites within the timestamp resolution are noticed.
+    fn write_script(path: &PathBuf, script: &str, n: u64) {
+        fs::write(path, script).unwrap();
+        let modified = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000 + n);
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}-{}.rhai", name, std::process::id()))
+    }
+
+    #[test]
+    fn runs_and_reloads_script() {
+        let path = temp_path("script-strategy");
+        write_script(&path, SCRIPT, 0);
+
+        let mut strategy = ScriptStrategy::load(&path).unwrap();
+        assert_eq!(strategy.to_string(), "Close above SMA");
+        assert_eq!(strategy.required_indicators(), vec![IndicatorType::SMA(3)]);
+        assert_eq!(strategy.candles_needed_for_setup(), 2);
+
+        let rising = candles(&[100.0, 100.0, 100.0, 103.0]);
+        let falling = candles(&[100.0, 100.0, 100.0, 97.0]);
+        assert!(strategy.check_last_for_setup(rising.view()).is_some());
+        assert!(strategy.check_last_for_setup(falling.view()).is_none());
+
+        // Reloads are shared with clones, invalid scripts are ignored
+        let mut clone = strategy.clone_box();
+        write_script(&path, &SCRIPT.replace("> sma", "< sma"), 1);
+        assert!(clone.check_last_for_setup(rising.view()).is_none());
+        assert!(strategy.check_last_for_setup(falling.view()).is_some());
+
+        write_script(&path, "fn check(candles) {", 2);
+        assert!(strategy.check_last_for_setup(falling.view()).is_some());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn loads_example_script() {
+        let strategy = ScriptStrategy::load("strategies/rsi_cross.rhai").unwrap();
+
+        assert_eq!(strategy.to_string(), "RSI cross");
+        assert_eq!(strategy.interval(), Interval::Hour4);
+        assert_eq!(strategy.trading_days().len(), 5);
+        assert!(strategy
+            .required_indicators()
+            .contains(&IndicatorType::DynamicPivot(15)));
+    }
+
+    #[test]
+    fn sandboxes_scripts() {
+        let path = temp_path("script-sandbox");
+        let candles = candles(&[100.0, 101.0]);
+
+        for check in [
+            "loop {}",
+            "let x = 0; while true { x += 1; }",
+            r#"eval("true")"#,
+            r#"import "other" as other; true"#,
+            r#"candles[-1].value("foo(1)") == ()"#,
+        ] {
+            let script = SCRIPT.replace(
+                "let sma = candles[-1].value(\"sma(3)\");\n            sma != () && candles[-1].close > sma",
+                check,
+            );
+            assert_ne!(script, SCRIPT);
+            write_script(&path, &script, 0);
+
+            let start = Instant::now();
+            let result =
+                ScriptStrategy::load(&path).map(|mut s| s.check_last_for_setup(candles.view()));
+
+            assert!(matches!(result, Err(_) | Ok(None)), "{}", check);
+            assert!(start.elapsed() < TIME_LIMIT * 4, "{}", check);
+        }
+
+        fs::remove_file(&path).unwrap();
+    }
+}
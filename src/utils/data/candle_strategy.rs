@@ -0,0 +1,154 @@
+// Proptest helpers for generating random candles and checking that indicators
+// give the same results when populated candle by candle.
+use crate::{
+    indicators::{indicator_type::IndicatorType, populates_candles::PopulatesCandlesWithSelf},
+    models::{
+        candle::Candle, candle_buffer::CandleBuffer, interval::Interval,
+        timeseries_builder::TimeSeriesBuilder, traits::trading_strategy::TradingStrategy,
+    },
+    utils::data::dummy_data::PRICE_CHANGES,
+};
+use chrono::{Duration, TimeZone, Utc};
+use proptest::{prelude::*, test_runner::TestCaseError};
+
+/// Random walk of candles with between `min` and `max` candles.
+pub fn candles(min: usize, max: usize) -> impl Strategy<Value = Vec<Candle>> {
+    let step = (-0.05..0.05f64, 0.0..0.02f64, 0.0..0.02f64, 1.0..1000.0f64);
+
+    prop::collection::vec(step, min..max).prop_map(|steps| {
+        let mut timestamp = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let mut close = 100.0;
+
+        steps
+            .into_iter()
+            .map(|(change, up, down, volume)| {
+                let open: f64 = close;
+                close = open * (1.0 + change);
+                timestamp += Duration::days(1);
+
+                let high = open.max(close) * (1.0 + up);
+                let low = open.min(close) * (1.0 - down);
+
+                Candle::new(timestamp, open, close, high, low, volume)
+            })
+            .collect()
+    })
+}
+
+/// Daily candles following `PRICE_CHANGES` from 1000, with wicks of half the
+/// change on both sides. Used for testing indicators and strategies on
+/// fixed data.
+pub fn price_change_candles() -> Vec<Candle> {
+    let mut timestamp = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let mut close = 1000.0;
+
+    PRICE_CHANGES
+        .iter()
+        .map(|change| {
+            let open: f64 = close;
+            close = open + change;
+            timestamp += Duration::days(1);
+
+            let wick = change.abs() / 2.0;
+            let high = open.max(close) + wick;
+            let low = open.min(close) - wick;
+
+            Candle::new(timestamp, open, close, high, low, 1000.0)
+        })
+        .collect()
+}
+
+/// Daily candles from (high, low, close), each opening at the previous close.
+/// Used for indicators with values worked out by hand.
+pub fn hlc_candles(bars: &[(f64, f64, f64)]) -> Vec<Candle> {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let mut open = bars.first().map_or(0.0, |(_, _, close)| *close);
+
+    bars.iter()
+        .enumerate()
+        .map(|(i, (high, low, close))| {
+            let timestamp = start + Duration::days(i as i64);
+            let candle = Candle::new(timestamp, open, *close, *high, *low, 1000.0);
+            open = *close;
+
+            candle
+        })
+        .collect()
+}
+
+/// Buffer of `price_change_candles` with the indicators required by the
+/// strategy populated, used for backtesting strategies on fixed data.
+pub fn strategy_candles(strategy: &dyn TradingStrategy) -> CandleBuffer {
+    indicator_candles(&strategy.required_indicators())
+}
+
+/// Buffer of `price_change_candles` with the given indicators populated.
+pub fn indicator_candles(indicator_types: &[IndicatorType]) -> CandleBuffer {
+    let mut ts = TimeSeriesBuilder::new()
+        .symbol("DUMMY".to_string())
+        .interval(Interval::Day1)
+        .candles(price_change_candles())
+        .build();
+
+    for indicator_type in indicator_types {
+        ts.add_indicator(*indicator_type).unwrap();
+    }
+
+    CandleBuffer::from(&ts.candles[..])
+}
+
+/// Candles with the indicator populated on all of them at once.
+pub fn populated(candles: &[Candle], indicator_type: IndicatorType) -> Vec<Candle> {
+    let mut ts = TimeSeriesBuilder::new()
+        .symbol("DUMMY".to_string())
+        .interval(Interval::Day1)
+        .candles(candles.to_vec())
+        .build();
+    indicator_type.populate_candles(&mut ts).unwrap();
+
+    ts.candles
+}
+
+/// Checks that two optional values are equal up to floating point error.
+pub fn assert_close(actual: Option<f64>, expected: Option<f64>) -> Result<(), TestCaseError> {
+    match (actual, expected) {
+        (Some(a), Some(b)) => {
+            let tolerance = 1e-9 * a.abs().max(b.abs()).max(1.0);
+            prop_assert!((a - b).abs() <= tolerance, "{} != {}", a, b);
+        }
+        (a, b) => prop_assert_eq!(a, b),
+    }
+
+    Ok(())
+}
+
+/// Populates the indicator on the first `split` candles and adds the rest one
+/// by one, then checks that every candle matches populating all candles at
+/// once.
+pub fn assert_incremental_matches(
+    candles: &[Candle],
+    split: usize,
+    indicator_type: IndicatorType,
+) -> Result<(), TestCaseError> {
+    let split = split.min(candles.len());
+    let full = populated(candles, indicator_type);
+
+    let mut incremental = TimeSeriesBuilder::new()
+        .symbol("DUMMY".to_string())
+        .interval(Interval::Day1)
+        .candles(candles[..split].to_vec())
+        .build();
+    incremental.add_indicator(indicator_type).unwrap();
+
+    for candle in &candles[split..] {
+        incremental.add_candle(candle).unwrap();
+    }
+
+    for (a, b) in incremental.candles.iter().zip(full.iter()) {
+        let a = a.indicators.get(&indicator_type).map(|i| i.fields());
+        let b = b.indicators.get(&indicator_type).map(|i| i.fields());
+        prop_assert_eq!(a, b);
+    }
+
+    Ok(())
+}
@@ -1 +1,3 @@
+#[cfg(test)]
+pub mod candle_strategy;
 pub mod dummy_data;
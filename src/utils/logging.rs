@@ -0,0 +1,39 @@
+use anyhow::{anyhow, Result};
+use std::env;
+use tracing_appender::{non_blocking::WorkerGuard, rolling};
+use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+const DEFAULT_LOG_DIR: &str = "logs";
+const DEFAULT_LOG_LEVEL: &str = "info";
+
+/// Initializes structured logging. Human readable logs are written to stdout
+/// and JSON logs to a daily rotating file in `LOG_DIR` (defaults to `logs`).
+/// Levels are configured through `RUST_LOG`, e.g.
+/// `RUST_LOG=rust_bot=debug,actix=warn`.
+///
+/// The returned guard must be held for as long as logs should be written,
+/// dropping it flushes and stops the file writer.
+pub fn init_logging() -> Result<WorkerGuard> {
+    let log_dir = env::var("LOG_DIR").unwrap_or(DEFAULT_LOG_DIR.to_string());
+    let file_appender = rolling::daily(log_dir, "rust_bot.log");
+    let (file_writer, guard) = tracing_appender::non_blocking(file_appender);
+
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_LEVEL));
+
+    let stdout_layer = fmt::layer().with_target(false);
+    let file_layer = fmt::layer()
+        .json()
+        .with_current_span(true)
+        .with_span_list(true)
+        .with_writer(file_writer);
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(stdout_layer)
+        .with(file_layer)
+        .try_init()
+        .map_err(|e| anyhow!("Unable to initialize logging: {}", e))?;
+
+    Ok(guard)
+}
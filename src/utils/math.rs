@@ -27,6 +27,67 @@ pub fn ema_rolling(prev_ema: f64, price: f64, len: f64) -> f64 {
     (price * smoothing) + (prev_ema * (1.0 - smoothing))
 }
 
+/// Linearly weighted average where the last value has weight `len` and the
+/// first weight 1.
+pub fn wma(segment: &[f64]) -> f64 {
+    let len = segment.len();
+
+    if len == 0 {
+        return 0.0;
+    }
+
+    let weighted: f64 = segment
+        .iter()
+        .enumerate()
+        .map(|(i, value)| value * (i + 1) as f64)
+        .sum();
+
+    weighted / ((len * (len + 1)) as f64 / 2.0)
+}
+
+/// Lengths of the half and square root weighted averages of a Hull moving
+/// average.
+pub fn hma_lens(len: usize) -> (usize, usize) {
+    (
+        (len / 2).max(1),
+        ((len as f64).sqrt().floor() as usize).max(1),
+    )
+}
+
+pub fn dema(ema: f64, ema_of_ema: f64) -> f64 {
+    2.0 * ema - ema_of_ema
+}
+
+pub fn tema(ema: f64, ema_of_ema: f64, ema_of_ema_of_ema: f64) -> f64 {
+    3.0 * (ema - ema_of_ema) + ema_of_ema_of_ema
+}
+
+/// Kaufman efficiency ratio, the net change over the segment divided by the
+/// sum of the absolute changes within it.
+pub fn efficiency_ratio(segment: &[f64]) -> f64 {
+    let (Some(first), Some(last)) = (segment.first(), segment.last()) else {
+        return 0.0;
+    };
+
+    let noise: f64 = segment.windows(2).map(|w| (w[1] - w[0]).abs()).sum();
+
+    if noise == 0.0 {
+        0.0
+    } else {
+        (last - first).abs() / noise
+    }
+}
+
+/// Kaufman adaptive moving average step, smoothing between a 2 and a 30
+/// period EMA depending on the efficiency ratio.
+pub fn kama_rolling(prev_kama: f64, price: f64, efficiency_ratio: f64) -> f64 {
+    let fast = 2.0 / (2.0 + 1.0);
+    let slow = 2.0 / (30.0 + 1.0);
+    let smoothing = (efficiency_ratio * (fast - slow) + slow).powi(2);
+
+    prev_kama + smoothing * (price - prev_kama)
+}
+
 pub fn vwma(segment: &[(f64, f64)]) -> f64 {
     let len = segment.len();
 
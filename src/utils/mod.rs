@@ -1,5 +1,6 @@
 pub mod constants;
 pub mod data;
+pub mod logging;
 pub mod math;
 pub mod string;
 